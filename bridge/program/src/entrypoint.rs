@@ -10,7 +10,7 @@ use solana_program::{
 use solana_program::program_error::{PrintProgramError, ProgramError};
 
 use crate::processor;
-use lib::error::LibError;
+use lib::error::{LibError, set_error_info, BRIDGE_PROGRAM_CODE};
 
 entrypoint!(process_instruction);
 
@@ -23,6 +23,7 @@ fn process_instruction<'a>(
         Ok(()) => Ok(()),
         Err(e) => {
             e.print::<LibError>();
+            set_error_info(BRIDGE_PROGRAM_CODE, &e);
             return Err(e);
         }
     }
@@ -1,6 +1,12 @@
+//! Rarimo bridge program.
+//!
+//! With the `no-entrypoint` feature the program entrypoint is not compiled, so the crate can be used
+//! as a host library: `processor::process_instruction` can be called directly (e.g. from the off-chain
+//! tests or other programs) and `merkle::OperationContent` used to build the signed admin operations.
 #![feature(destructuring_assignment)]
 #![feature(array_methods)]
 pub mod state;
+#[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod processor;
-mod merkle;
\ No newline at end of file
+pub mod merkle;
@@ -4,35 +4,45 @@ use borsh::{
     BorshDeserialize, BorshSerialize,
 };
 use mpl_token_metadata::{
-    instruction::{create_master_edition_v3, create_metadata_accounts_v3, verify_collection},
+    instruction::{create_master_edition_v3, create_metadata_accounts_v3, update_metadata_accounts_v2, verify_collection, verify_sized_collection_item},
     state::{DataV2, TokenStandard},
 };
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
-    entrypoint::ProgramResult, hash, msg,
-    program::{invoke, invoke_signed}, pubkey::Pubkey, secp256k1_recover::{SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH}, system_instruction,
-    sysvar::{rent::Rent, Sysvar},
+    entrypoint::ProgramResult, log::sol_log_data, msg,
+    program::{invoke, invoke_signed, set_return_data}, program_error::ProgramError, program_option::COption, pubkey::Pubkey, secp256k1_recover::{SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH},
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
-use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_associated_token_account::get_associated_token_address;
 use spl_token::{
-    instruction::{initialize_mint, mint_to, transfer},
+    instruction::{initialize_mint, mint_to, sync_native, transfer, transfer_checked},
     solana_program::program_pack::Pack,
     state::Mint,
 };
-use spl_token::instruction::burn;
+use spl_token::instruction::{burn, close_account};
 
 use crate::{
     state::BridgeAdmin,
     state::Withdraw,
+    state::WithdrawAnnouncement,
+    state::SupportedNetworks,
+    state::TokenConfig,
+    state::CollectionDelegate,
+    state::LegacyBridgeAdmin,
+    state::BaselineBridgeAdmin,
+    state::BaselineWithdraw,
+    state::AuthorizedOrigins,
+    state::MintCap,
+    state::StoredRoots,
 };
-use crate::merkle::{Data, TransferData, Content};
 use solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
-use lib::merkle::{get_merkle_root};
-use lib::ecdsa::verify_ecdsa_signature;
-use lib::instructions::bridge::{BridgeInstruction, SignedMetadata};
+use lib::merkle::{amount_bytes, get_merkle_root, normalize_metadata_field, HashAlgo, MerkleProof, TransferData, Content};
+use lib::ecdsa::{verify_ecdsa_signature, ETH_ADDRESS_LENGTH};
+use lib::instructions::bridge::{BridgeInstruction, DepositBumps, SignedMetadata, MAX_WITHDRAW_FEE_BPS, WithdrawArgs, WithdrawReceipt, WithdrawValidation};
 use lib::instructions::InstructionValidation;
 use lib::error::LibError;
-use crate::state::{BRIDGE_ADMIN_SIZE, WITHDRAW_SIZE};
+use crate::state::{BRIDGE_ADMIN_SIZE, WITHDRAW_SIZE, BASELINE_BRIDGE_ADMIN_SIZE, BASELINE_WITHDRAW_SIZE, WITHDRAW_ANNOUNCEMENT_SIZE, SUPPORTED_NETWORKS_SIZE, TOKEN_CONFIG_SIZE, COLLECTION_DELEGATE_SIZE, LEGACY_BRIDGE_ADMIN_SIZE, AUTHORIZED_ORIGINS_SIZE, MINT_CAP_SIZE, OperationType, stored_roots_size};
+use crate::merkle::{metadata_data, OperationContent};
 
 pub fn process_instruction<'a>(
     program_id: &'a Pubkey,
@@ -43,7 +53,7 @@ pub fn process_instruction<'a>(
     match instruction {
         BridgeInstruction::InitializeAdmin(args) => {
             msg!("Instruction: Create Bridge Admin");
-            process_init_admin(program_id, accounts, args.seeds, args.public_key, args.commission_program)
+            process_init_admin(program_id, accounts, args.seeds, args.public_key, args.commission_program, args.hash_algo)
         }
         BridgeInstruction::TransferOwnership(args) => {
             msg!("Instruction: Transfer Bridge Admin ownership");
@@ -52,35 +62,42 @@ pub fn process_instruction<'a>(
         BridgeInstruction::DepositNative(args) => {
             msg!("Instruction: Deposit SOL");
             args.validate()?;
-            process_deposit_native(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.amount)
+            process_deposit_native(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.amount, args.bumps)
         }
         BridgeInstruction::DepositFT(args) => {
             msg!("Instruction: Deposit FT");
             args.validate()?;
-            process_deposit_ft(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.amount, args.token_seed)
+            process_deposit_ft(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.amount, args.token_seed, args.bumps)
         }
         BridgeInstruction::DepositNFT(args) => {
             msg!("Instruction: Deposit NFT");
             args.validate()?;
-            process_deposit_nft(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.token_seed)
+            process_deposit_nft(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.token_seed, args.bumps)
+        }
+        BridgeInstruction::DepositNFTBatch(args) => {
+            msg!("Instruction: Deposit NFT batch");
+            args.validate()?;
+            process_deposit_nft_batch(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.mints, args.token_seeds)
         }
 
         BridgeInstruction::WithdrawNative(args) => {
             msg!("Instruction: Withdraw SOL");
             args.validate()?;
-            process_withdraw_native(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount)
+            process_withdraw_native(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.eip712_domain_separator, None)
         }
 
         BridgeInstruction::WithdrawFT(args) => {
             msg!("Instruction: Withdraw FT");
             args.validate()?;
-            process_withdraw_ft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.token_seed, args.signed_meta)
+            args.validate_token_type(&lib::TokenType::FT)?;
+            process_withdraw_ft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.claim_amount, args.token_seed, args.signed_meta, args.eip712_domain_separator, args.strict_ata, args.no_metadata, None)
         }
 
         BridgeInstruction::WithdrawNFT(args) => {
             msg!("Instruction: Withdraw NFT");
             args.validate()?;
-            process_withdraw_nft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.token_seed, args.signed_meta)
+            args.validate_token_type(&lib::TokenType::NFT)?;
+            process_withdraw_nft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.token_seed, args.signed_meta, args.eip712_domain_separator, args.strict_ata, None)
         }
 
         BridgeInstruction::MintCollection(args) => {
@@ -88,6 +105,165 @@ pub fn process_instruction<'a>(
             args.validate()?;
             process_create_collection(program_id, accounts, args.seeds, args.data, args.token_seed)
         }
+
+        BridgeInstruction::SetMinDeposit(args) => {
+            msg!("Instruction: Set min deposit");
+            process_set_min_deposit(program_id, accounts, args.seeds, args.min_deposit, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::InitBridgeTokenAccount(args) => {
+            msg!("Instruction: Init bridge token account");
+            process_init_bridge_token_account(program_id, accounts, args.seeds)
+        }
+
+        BridgeInstruction::SetStrictTokenAccounts(args) => {
+            msg!("Instruction: Set strict token accounts");
+            process_set_strict_token_accounts(program_id, accounts, args.seeds, args.strict, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::SetWithdrawDelay(args) => {
+            msg!("Instruction: Set withdraw delay");
+            process_set_withdraw_delay(program_id, accounts, args.seeds, args.delay, args.threshold, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::AnnounceWithdraw(args) => {
+            msg!("Instruction: Announce withdraw");
+            process_announce_withdraw(program_id, accounts, args.seeds, args.origin, args.amount, args.leaf, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::UpdateTokenMetadata(args) => {
+            msg!("Instruction: Update token metadata");
+            args.validate()?;
+            process_update_token_metadata(program_id, accounts, args.seeds, args.token_seed, args.data, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::EmergencyWithdraw(args) => {
+            msg!("Instruction: Emergency withdraw");
+            process_emergency_withdraw(program_id, accounts, args.seeds, args.mints, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::SetNetworks(args) => {
+            msg!("Instruction: Set networks");
+            args.validate()?;
+            process_set_networks(program_id, accounts, args.seeds, args.networks, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::SetTokenConfig(args) => {
+            msg!("Instruction: Set token config");
+            process_set_token_config(program_id, accounts, args.seeds, args.mint, args.deposit_enabled, args.withdraw_enabled, args.target_decimals, args.min_deposit, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::ValidateWithdraw(args) => {
+            msg!("Instruction: Validate withdraw");
+            args.validate()?;
+            process_validate_withdraw(program_id, accounts, args.token_type, args.withdraw)
+        }
+
+        BridgeInstruction::MigrateState => {
+            msg!("Instruction: Migrate state");
+            process_migrate_state(program_id, accounts)
+        }
+
+        BridgeInstruction::ReclaimMint(args) => {
+            msg!("Instruction: Reclaim mint");
+            process_reclaim_mint(program_id, accounts, args.seeds, args.token_seed, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::SweepDust(args) => {
+            msg!("Instruction: Sweep dust");
+            process_sweep_dust(program_id, accounts, args.seeds, args.mints, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::RegisterCollectionDelegate(args) => {
+            msg!("Instruction: Register collection delegate");
+            process_register_collection_delegate(program_id, accounts, args.seeds, args.collection_mint, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::MigrateAdmin(args) => {
+            msg!("Instruction: Migrate admin");
+            process_migrate_admin(program_id, accounts, args.seeds, args.commission_program, args.signature, args.recovery_id)
+        }
+
+        BridgeInstruction::SetWithdrawFee(args) => {
+            msg!("Instruction: Set withdraw fee");
+            args.validate()?;
+            process_set_withdraw_fee(program_id, accounts, args.seeds, args.withdraw_fee_bps, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::SetEthAddress(args) => {
+            msg!("Instruction: Set eth address");
+            process_set_eth_address(program_id, accounts, args.seeds, args.eth_address, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::AuthorizeOrigins(args) => {
+            msg!("Instruction: Authorize origins");
+            process_authorize_origins(program_id, accounts, args.seeds, args.root, args.signature, args.recovery_id)
+        }
+
+        BridgeInstruction::ClaimAuthorized(args) => {
+            msg!("Instruction: Claim authorized");
+            args.validate()?;
+            process_claim_authorized(program_id, accounts, args.token_type, args.withdraw)
+        }
+
+        BridgeInstruction::SetSponsorTokenAccounts(args) => {
+            msg!("Instruction: Set sponsor token accounts");
+            process_set_sponsor_token_accounts(program_id, accounts, args.seeds, args.sponsor, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::DepositNativeWithCommission(args) => {
+            msg!("Instruction: Deposit SOL with commission");
+            args.validate()?;
+            process_deposit_native_with_commission(program_id, accounts, args.seeds, args.network_to, args.receiver_address, args.amount, args.bumps, args.commission_token, args.deposit_nonce)
+        }
+
+        BridgeInstruction::SetMaxSingleWithdraw(args) => {
+            msg!("Instruction: Set max single withdraw");
+            process_set_max_single_withdraw(program_id, accounts, args.seeds, args.max_single_withdraw, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::SetNetworkFormats(args) => {
+            msg!("Instruction: Set network formats");
+            args.validate()?;
+            process_set_network_formats(program_id, accounts, args.seeds, args.formats, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::StoreRoots(args) => {
+            msg!("Instruction: Store roots");
+            args.validate()?;
+            process_store_roots(program_id, accounts, args.seeds, args.roots, args.capacity, args.signature, args.recovery_id)
+        }
+
+        BridgeInstruction::ClaimStored(args) => {
+            msg!("Instruction: Claim stored");
+            args.validate()?;
+            process_claim_stored(program_id, accounts, args.token_type, args.root, args.withdraw)
+        }
+
+        BridgeInstruction::SetBackupKey(args) => {
+            msg!("Instruction: Set backup key");
+            process_set_backup_key(program_id, accounts, args.seeds, args.backup_key, args.promote_timelock, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::PromoteBackup(args) => {
+            msg!("Instruction: Promote backup");
+            process_promote_backup(program_id, accounts, args.seeds, args.confirm, args.signature, args.recovery_id)
+        }
+
+        BridgeInstruction::CancelPromotion(args) => {
+            msg!("Instruction: Cancel promotion");
+            process_cancel_promotion(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::VetoWithdraw(args) => {
+            msg!("Instruction: Veto withdraw");
+            process_veto_withdraw(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path)
+        }
+
+        BridgeInstruction::RevokeRoot(args) => {
+            msg!("Instruction: Revoke root");
+            process_revoke_root(program_id, accounts, args.seeds, args.root, args.signature, args.recovery_id, args.path)
+        }
     }
 }
 
@@ -97,7 +273,9 @@ pub fn process_init_admin<'a>(
     seeds: [u8; 32],
     public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     commission_program: Pubkey,
+    hash_algo: HashAlgo,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 4, "InitializeAdmin")?;
     let account_info_iter = &mut accounts.iter();
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
@@ -110,6 +288,8 @@ pub fn process_init_admin<'a>(
         return Err(LibError::WrongSeeds.into());
     }
 
+    lib::expect_empty_admin(bridge_admin_info)?;
+
     lib::call_create_account(
         fee_payer_info,
         bridge_admin_info,
@@ -126,8 +306,10 @@ pub fn process_init_admin<'a>(
     }
 
     bridge_admin.public_key = public_key;
+    bridge_admin.version = lib::STATE_VERSION;
     bridge_admin.is_initialized = true;
     bridge_admin.commission_program = commission_program;
+    bridge_admin.hash_algo = hash_algo;
     bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
     Ok(())
 }
@@ -140,6 +322,7 @@ pub fn process_transfer_ownership<'a>(
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "TransferOwnership")?;
     let account_info_iter = &mut accounts.iter();
     let bridge_admin_info = next_account_info(account_info_iter)?;
 
@@ -153,11 +336,23 @@ pub fn process_transfer_ownership<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
 
-    verify_ecdsa_signature(solana_program::keccak::hash(new_public_key.as_slice()).as_ref(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
 
+    bridge_admin.verify_signature(solana_program::keccak::hash([lib::BRIDGE_DOMAIN, new_public_key.as_slice()].concat().as_slice()).as_ref(), signature.as_slice(), recovery_id)?;
+
+    let old_public_key = bridge_admin.public_key;
+
+    // New owner is the full public key, the Ethereum address mode should be enabled again by SetEthAddress
     bridge_admin.public_key = new_public_key;
+    bridge_admin.eth_address = None;
     bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+
+    lib::KeyRotated {
+        admin: bridge_admin_key,
+        old_key: old_public_key,
+        new_key: new_public_key,
+    }.log();
     Ok(())
 }
 
@@ -166,10 +361,12 @@ pub fn process_deposit_native<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     seeds: [u8; 32],
-    network: String,
+    network: lib::Network,
     receiver: String,
     amount: u64,
+    bumps: Option<DepositBumps>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 6, "DepositNative")?;
     let account_info_iter = &mut accounts.iter();
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
@@ -177,6 +374,73 @@ pub fn process_deposit_native<'a>(
     let system_program = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
     let sysvar_info = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+
+    lib::expect_signer(owner_info)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if *bridge_admin_info.key != bridge_admin_key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_network_supported(program_id, &bridge_admin_key, networks_info, &network, &receiver, bumps.as_ref().map(|b| b.networks))?;
+
+    if amount < bridge_admin.min_deposit {
+        return Err(LibError::BelowMinimum.into());
+    }
+
+    verify_commission_charged(program_id, bridge_admin_info, sysvar_info, &bridge_admin, owner_info.key, lib::TokenType::Native, amount)?;
+
+    let transfer_tokens_instruction = solana_program::system_instruction::transfer(
+        owner_info.key,
+        bridge_admin_info.key,
+        amount,
+    );
+
+    msg!("Transferring token");
+    invoke(
+        &transfer_tokens_instruction,
+        &[
+            owner_info.clone(),
+            bridge_admin_info.clone(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn process_deposit_native_with_commission<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    network: lib::Network,
+    receiver: String,
+    amount: u64,
+    bumps: Option<DepositBumps>,
+    commission_token: lib::CommissionToken,
+    deposit_nonce: u64,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 9, "DepositNativeWithCommission")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+    let commission_program_info = next_account_info(account_info_iter)?;
+    let commission_admin_info = next_account_info(account_info_iter)?;
+    let charge_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    lib::expect_signer(owner_info)?;
 
     let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
     if *bridge_admin_info.key != bridge_admin_key {
@@ -188,7 +452,62 @@ pub fn process_deposit_native<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
-    verify_commission_charged( bridge_admin_info, sysvar_info, &bridge_admin, lib::TokenType::Native, amount)?;
+    lib::check_version(bridge_admin.version)?;
+
+    verify_network_supported(program_id, &bridge_admin_key, networks_info, &network, &receiver, bumps.as_ref().map(|b| b.networks))?;
+
+    if amount < bridge_admin.min_deposit {
+        return Err(LibError::BelowMinimum.into());
+    }
+
+    if *commission_program_info.key != bridge_admin.commission_program {
+        return Err(LibError::WrongCommissionProgram.into());
+    }
+
+    let commission_key = lib::instructions::commission::commission_admin_address(commission_program_info.key, &bridge_admin_key)?;
+    if *commission_admin_info.key != commission_key {
+        return Err(LibError::WrongCommissionAccount.into());
+    }
+
+    // The charge is a part of this instruction, so the failed charge reverts the whole deposit
+    let charge_instruction = match commission_token {
+        lib::CommissionToken::FT(mint) => lib::instructions::commission::charge_commission_ft(
+            *commission_program_info.key,
+            commission_key,
+            bridge_admin_key,
+            *owner_info.key,
+            mint,
+            commission_token,
+            lib::TokenType::Native,
+            amount,
+            deposit_nonce,
+        ),
+        _ => lib::instructions::commission::charge_commission_native(
+            *commission_program_info.key,
+            commission_key,
+            bridge_admin_key,
+            *owner_info.key,
+            commission_token,
+            lib::TokenType::Native,
+            amount,
+            deposit_nonce,
+        ),
+    };
+
+    let mut charge_accounts = vec![
+        commission_admin_info.clone(),
+        bridge_admin_info.clone(),
+        owner_info.clone(),
+        charge_info.clone(),
+        system_program.clone(),
+        rent_info.clone(),
+        token_program.clone(),
+    ];
+    charge_accounts.extend(account_info_iter.cloned());
+    charge_accounts.push(commission_program_info.clone());
+
+    msg!("Charging commission");
+    invoke(&charge_instruction, charge_accounts.as_slice())?;
 
     let transfer_tokens_instruction = solana_program::system_instruction::transfer(
         owner_info.key,
@@ -212,11 +531,13 @@ pub fn process_deposit_ft<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     seeds: [u8; 32],
-    network: String,
+    network: lib::Network,
     receiver: String,
     amount: u64,
     token_seed: Option<[u8; 32]>,
+    bumps: Option<DepositBumps>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 12, "DepositFT")?;
     let account_info_iter = &mut accounts.iter();
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
@@ -230,6 +551,10 @@ pub fn process_deposit_ft<'a>(
     let rent_info = next_account_info(account_info_iter)?;
     let sysvar_info = next_account_info(account_info_iter)?;
     let _associated_program = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+    let token_config_info = next_account_info(account_info_iter)?;
+
+    lib::expect_signer(owner_info)?;
 
     let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
     if *bridge_admin_info.key != bridge_admin_key {
@@ -241,7 +566,23 @@ pub fn process_deposit_ft<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
-    verify_commission_charged(bridge_admin_info, sysvar_info, &bridge_admin, lib::TokenType::FT, amount)?;
+    lib::check_version(bridge_admin.version)?;
+
+    verify_network_supported(program_id, &bridge_admin_key, networks_info, &network, &receiver, bumps.as_ref().map(|b| b.networks))?;
+
+    let token_config = get_token_config(program_id, &bridge_admin_key, token_config_info, mint_info.key, bumps.as_ref().map(|b| b.token_config))?;
+    if !token_config.deposit_enabled {
+        return Err(LibError::TokenDisabled.into());
+    }
+
+    if amount < token_config.min_deposit {
+        return Err(LibError::BelowMinimum.into());
+    }
+
+    verify_commission_charged(program_id, bridge_admin_info, sysvar_info, &bridge_admin, owner_info.key, lib::TokenType::FT, amount)?;
+
+    let mint = Mint::unpack_from_slice(&mut mint_info.data.borrow().as_ref())?;
+    let normalized_amount = lib::normalize_amount(amount, mint.decimals, token_config.target_decimals.unwrap_or(mint.decimals))?;
 
     if *bridge_associated_info.key !=
         get_associated_token_address(&bridge_admin_key, mint_info.key) {
@@ -249,10 +590,16 @@ pub fn process_deposit_ft<'a>(
     }
 
     if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+        if bridge_admin.strict_token_accounts {
+            msg!("Bridge associated account should be created by InitBridgeTokenAccount");
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
         msg!("Creating bridge admin associated account");
-        lib::call_create_associated_account(
-            owner_info,
+        create_bridge_associated_account(
+            &bridge_admin,
             bridge_admin_info,
+            owner_info,
             mint_info,
             bridge_associated_info,
             rent_info,
@@ -261,6 +608,7 @@ pub fn process_deposit_ft<'a>(
         )?;
     }
 
+    verify_token_account_owner(bridge_associated_info)?;
 
     if let Some(token_seed) = token_seed {
         let (mint_key, _) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
@@ -277,15 +625,25 @@ pub fn process_deposit_ft<'a>(
         )?;
     } else {
         msg!("Transferring token");
-        call_transfer_token(
+        call_transfer_token_checked(
             owner_associated_info,
+            mint_info,
             bridge_associated_info,
             owner_info,
             amount,
+            mint.decimals,
             &[],
         )?;
     }
 
+    lib::Deposited {
+        mint: *mint_info.key,
+        amount,
+        normalized_amount,
+        network_to: network,
+        receiver_address: receiver,
+    }.log();
+
     Ok(())
 }
 
@@ -293,10 +651,12 @@ pub fn process_deposit_nft<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     seeds: [u8; 32],
-    network: String,
+    network: lib::Network,
     receiver: String,
     token_seed: Option<[u8; 32]>,
+    bumps: Option<DepositBumps>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 12, "DepositNFT")?;
     let account_info_iter = &mut accounts.iter();
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
@@ -310,6 +670,69 @@ pub fn process_deposit_nft<'a>(
     let rent_info = next_account_info(account_info_iter)?;
     let sysvar_info = next_account_info(account_info_iter)?;
     let _associated_program = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+    let token_config_info = next_account_info(account_info_iter)?;
+
+    lib::expect_signer(owner_info)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if *bridge_admin_info.key != bridge_admin_key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_network_supported(program_id, &bridge_admin_key, networks_info, &network, &receiver, bumps.as_ref().map(|b| b.networks))?;
+
+    if !get_token_config(program_id, &bridge_admin_key, token_config_info, mint_info.key, bumps.as_ref().map(|b| b.token_config))?.deposit_enabled {
+        return Err(LibError::TokenDisabled.into());
+    }
+
+    verify_commission_charged(program_id, bridge_admin_info, sysvar_info, &bridge_admin, owner_info.key, lib::TokenType::NFT, 1)?;
+
+    deposit_nft_token(
+        program_id,
+        bridge_admin_info,
+        &bridge_admin,
+        mint_info,
+        owner_associated_info,
+        bridge_associated_info,
+        owner_info,
+        rent_info,
+        system_program,
+        token_program,
+        token_seed,
+    )
+}
+
+pub fn process_deposit_nft_batch<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    network: lib::Network,
+    receiver: String,
+    mints: Vec<Pubkey>,
+    token_seeds: Vec<Option<[u8; 32]>>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 8, "DepositNFTBatch")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let sysvar_info = next_account_info(account_info_iter)?;
+    let _associated_program = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+
+    lib::expect_signer(owner_info)?;
 
     let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
     if *bridge_admin_info.key != bridge_admin_key {
@@ -321,18 +744,79 @@ pub fn process_deposit_nft<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
-    verify_commission_charged( bridge_admin_info, sysvar_info, &bridge_admin, lib::TokenType::NFT, 1)?;
+    lib::check_version(bridge_admin.version)?;
+
+    verify_network_supported(program_id, &bridge_admin_key, networks_info, &network, &receiver, None)?;
+
+    verify_commission_charged(program_id, bridge_admin_info, sysvar_info, &bridge_admin, owner_info.key, lib::TokenType::NFT, mints.len() as u64)?;
+
+    for (mint, token_seed) in mints.iter().zip(token_seeds) {
+        let mint_info = next_account_info(account_info_iter)?;
+        let owner_associated_info = next_account_info(account_info_iter)?;
+        let bridge_associated_info = next_account_info(account_info_iter)?;
+        let token_config_info = next_account_info(account_info_iter)?;
+
+        if *mint_info.key != *mint {
+            return Err(LibError::WrongMint.into());
+        }
+
+        if !get_token_config(program_id, &bridge_admin_key, token_config_info, mint, None)?.deposit_enabled {
+            return Err(LibError::TokenDisabled.into());
+        }
+
+        let owner_associated = spl_token::state::Account::unpack_from_slice(&mut owner_associated_info.data.borrow().as_ref())?;
+        if owner_associated.mint != *mint || owner_associated.amount != 1 {
+            return Err(LibError::WrongBalance.into());
+        }
+
+        deposit_nft_token(
+            program_id,
+            bridge_admin_info,
+            &bridge_admin,
+            mint_info,
+            owner_associated_info,
+            bridge_associated_info,
+            owner_info,
+            rent_info,
+            system_program,
+            token_program,
+            token_seed,
+        )?;
+    }
+
+    msg!("Deposited {} NFTs to {} on {}", mints.len(), receiver, network);
+    Ok(())
+}
 
+fn deposit_nft_token<'a>(
+    program_id: &Pubkey,
+    bridge_admin_info: &AccountInfo<'a>,
+    bridge_admin: &BridgeAdmin,
+    mint_info: &AccountInfo<'a>,
+    owner_associated_info: &AccountInfo<'a>,
+    bridge_associated_info: &AccountInfo<'a>,
+    owner_info: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    token_seed: Option<[u8; 32]>,
+) -> ProgramResult {
     if *bridge_associated_info.key !=
-        get_associated_token_address(&bridge_admin_key, mint_info.key) {
+        get_associated_token_address(bridge_admin_info.key, mint_info.key) {
         return Err(LibError::WrongTokenAccount.into());
     }
 
     if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+        if bridge_admin.strict_token_accounts {
+            msg!("Bridge associated account should be created by InitBridgeTokenAccount");
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
         msg!("Creating bridge admin associated account");
-        lib::call_create_associated_account(
-            owner_info,
+        create_bridge_associated_account(
+            bridge_admin,
             bridge_admin_info,
+            owner_info,
             mint_info,
             bridge_associated_info,
             rent_info,
@@ -341,6 +825,8 @@ pub fn process_deposit_nft<'a>(
         )?;
     }
 
+    verify_token_account_owner(bridge_associated_info)?;
+
     if let Some(token_seed) = token_seed {
         let (mint_key, _) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
         if mint_key != *mint_info.key {
@@ -374,10 +860,13 @@ pub fn process_withdraw_native<'a>(
     seeds: [u8; 32],
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
-    path: Vec<[u8; 32]>,
+    path: MerkleProof,
     origin: [u8; 32],
     amount: u64,
+    eip712_domain_separator: Option<[u8; 32]>,
+    authorized_root: Option<[u8; 32]>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "WithdrawNative")?;
     let account_info_iter = &mut accounts.iter();
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
@@ -397,6 +886,9 @@ pub fn process_withdraw_native<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+    verify_withdraw_not_spent(withdraw_info)?;
+
     let content = Content::new(
         origin,
         owner_info.key.to_bytes(),
@@ -407,12 +899,18 @@ pub fn process_withdraw_native<'a>(
             ),
         ),
     );
-    let root = get_merkle_root(content.hash(), &path)?;
+    let leaf = content.leaf_hash_with(bridge_admin.hash_algo, eip712_domain_separator)?;
+    let root = path.root_from_leaf_with(bridge_admin.hash_algo, leaf)?;
+
+    verify_withdraw_root(&bridge_admin, root, signature, recovery_id, authorized_root)?;
+    verify_max_single_withdraw(&bridge_admin, amount)?;
+    verify_withdraw_delay(program_id, &bridge_admin_key, &bridge_admin, account_info_iter, origin, amount, leaf)?;
 
-    verify_ecdsa_signature(root.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+    // Fee lamports stay on the bridge admin account
+    let (net_amount, fee) = withdraw_amounts(&bridge_admin, amount)?;
 
     // TODO check rent
-    if **bridge_admin_info.try_borrow_lamports()? < amount {
+    if **bridge_admin_info.try_borrow_lamports()? < net_amount {
         return Err(LibError::WrongBalance.into());
     }
 
@@ -434,8 +932,7 @@ pub fn process_withdraw_native<'a>(
     )?;
 
     msg!("Transferring token");
-    **bridge_admin_info.try_borrow_mut_lamports()? -= amount;
-    **owner_info.try_borrow_mut_lamports()? += amount;
+    transfer_lamports(bridge_admin_info, owner_info, net_amount)?;
 
     msg!("Initializing withdraw account");
     let mut withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow_mut().as_ref())?;
@@ -443,15 +940,19 @@ pub fn process_withdraw_native<'a>(
         return Err(LibError::AlreadyInUse.into());
     }
 
+    withdraw.version = lib::STATE_VERSION;
     withdraw.is_initialized = true;
     withdraw.token_type = lib::TokenType::Native;
     withdraw.origin = origin;
     withdraw.mint = Option::None;
     withdraw.amount = amount;
+    withdraw.claimed = amount;
     withdraw.receiver_address = *owner_info.key;
     withdraw.serialize(&mut *withdraw_info.data.borrow_mut())?;
     msg!("Withdraw account created");
-    Ok(())
+
+    log_withdraw_address(&withdraw_key);
+    set_withdraw_receipt(origin, net_amount, fee, None)
 }
 
 pub fn process_withdraw_ft<'a>(
@@ -460,13 +961,20 @@ pub fn process_withdraw_ft<'a>(
     seeds: [u8; 32],
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
-    path: Vec<[u8; 32]>,
+    path: MerkleProof,
     origin: [u8; 32],
     amount: u64,
+    claim_amount: Option<u64>,
     token_seed: Option<[u8; 32]>,
     signed_meta: Option<SignedMetadata>,
+    eip712_domain_separator: Option<[u8; 32]>,
+    strict_ata: bool,
+    no_metadata: bool,
+    authorized_root: Option<[u8; 32]>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 14, "WithdrawFT")?;
     let account_info_iter = &mut accounts.iter();
+    log_compute_units("start");
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
@@ -481,6 +989,8 @@ pub fn process_withdraw_ft<'a>(
     let rent_info = next_account_info(account_info_iter)?;
     let _metadata_program = next_account_info(account_info_iter)?;
     let _associated_program = next_account_info(account_info_iter)?;
+    let token_config_info = next_account_info(account_info_iter)?;
+    let mint_cap_info = next_account_info(account_info_iter)?;
 
     let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
     if *bridge_admin_info.key != bridge_admin_key {
@@ -492,10 +1002,23 @@ pub fn process_withdraw_ft<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
-    if *metadata_info.key != mpl_token_metadata::pda::find_metadata_account(mint_info.key).0 {
+    lib::check_version(bridge_admin.version)?;
+    verify_withdraw_not_spent(withdraw_info)?;
+
+    if !get_token_config(program_id, &bridge_admin_key, token_config_info, mint_info.key, None)?.withdraw_enabled {
+        return Err(LibError::TokenDisabled.into());
+    }
+
+    if !no_metadata && *metadata_info.key != mpl_token_metadata::pda::find_metadata_account(mint_info.key).0 {
         return Err(LibError::WrongMetadataAccount.into());
     }
 
+    // Supply cap is signed only by the withdrawal that creates the mint
+    let created_max_supply = match (token_seed, &signed_meta) {
+        (Some(_), Some(signed_meta)) if mint_info.data_is_empty() => signed_meta.max_supply,
+        _ => None,
+    };
+
     if let Some(token_seed) = token_seed {
         try_mint_token_with_meta(
             program_id,
@@ -508,30 +1031,81 @@ pub fn process_withdraw_ft<'a>(
             rent_info,
             system_program,
             seeds,
+            Some(mint_cap_info),
         )?;
     }
 
-    let metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut metadata_info.data.borrow_mut().as_ref())?;
+    // MintCap created in this instruction is not read back, the signed cap is used directly
+    let max_supply = match created_max_supply {
+        Some(max_supply) => Some(max_supply),
+        None => get_mint_cap(program_id, &bridge_admin_key, mint_cap_info, mint_info.key)?,
+    };
+
+    log_compute_units("token created");
 
     let mint: spl_token::state::Mint = Mint::unpack_from_slice(&mut mint_info.data.borrow_mut().as_ref())?;
 
+    let transfer = ft_transfer_data(mint_info, metadata_info, amount, mint.decimals, no_metadata, account_info_iter)?;
+
     let content = Content::new(
         origin,
         owner_info.key.to_bytes(),
         program_id.to_bytes(),
-        Box::new(
-            TransferData::new_ft_transfer(
-                mint_info.key.to_bytes(),
-                amount,
-                metadata.data.name.trim_matches(char::from(0)).to_string(),
-                metadata.data.symbol.trim_matches(char::from(0)).to_string(),
-                metadata.data.uri.trim_matches(char::from(0)).to_string(),
-                mint.decimals,
-            ),
-        ),
+        Box::new(transfer.with_max_supply(created_max_supply)),
     );
 
-    verify_ecdsa_signature(get_merkle_root(content.hash(), &path)?.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+    // Receiver bound by the signed content, the tokens are delivered only to its associated account
+    let receiver = Pubkey::new_from_array(content.receiver);
+
+    let leaf = content.leaf_hash_with(bridge_admin.hash_algo, eip712_domain_separator)?;
+    verify_withdraw_root(&bridge_admin, path.root_from_leaf_with(bridge_admin.hash_algo, leaf)?, signature, recovery_id, authorized_root)?;
+    verify_max_single_withdraw(&bridge_admin, amount)?;
+    verify_withdraw_delay(program_id, &bridge_admin_key, &bridge_admin, account_info_iter, origin, amount, leaf)?;
+    log_compute_units("signature verified");
+
+    let (withdraw_key, bump_seed) = Pubkey::find_program_address(&[origin.as_slice()], program_id);
+    if withdraw_key != *withdraw_info.key {
+        return Err(LibError::WrongNonce.into());
+    }
+
+    let withdraw_created = withdraw_info.data_is_empty();
+    if withdraw_created {
+        msg!("Creating withdraw account");
+        lib::call_create_account(
+            owner_info,
+            withdraw_info,
+            rent_info,
+            system_program,
+            WITHDRAW_SIZE,
+            program_id,
+            &[origin.as_slice(), &[bump_seed]],
+        )?;
+    } else if !Withdraw::is_current_version(withdraw_info.data.borrow().as_ref()) {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    let mut withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow_mut().as_ref())?;
+    if withdraw_created {
+        msg!("Initializing withdraw account");
+        withdraw.version = lib::STATE_VERSION;
+        withdraw.is_initialized = true;
+        withdraw.token_type = lib::TokenType::FT;
+        withdraw.origin = origin;
+        withdraw.mint = Option::Some(mint_info.key.clone());
+        withdraw.amount = amount;
+        withdraw.claimed = 0;
+        withdraw.receiver_address = receiver;
+    } else if !withdraw.is_initialized ||
+        withdraw.token_type != lib::TokenType::FT ||
+        withdraw.mint != Option::Some(*mint_info.key) ||
+        withdraw.amount != amount ||
+        withdraw.receiver_address != receiver {
+        // Partial withdrawal: all signed data should be the same as for the previous claims
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    let claim_amount = ft_claim_amount(withdraw.amount, withdraw.claimed, claim_amount)?;
+    withdraw.claimed += claim_amount;
 
     if *bridge_associated_info.key !=
         get_associated_token_address(&bridge_admin_key, mint_info.key) {
@@ -539,6 +1113,11 @@ pub fn process_withdraw_ft<'a>(
     }
 
     if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+        if bridge_admin.strict_token_accounts {
+            msg!("Bridge associated account should be created by InitBridgeTokenAccount");
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
         msg!("Create bridge associated account");
         lib::call_create_associated_account(
             owner_info,
@@ -551,6 +1130,7 @@ pub fn process_withdraw_ft<'a>(
         )?;
     }
 
+    verify_token_account_owner(bridge_associated_info)?;
     let bridge_associated = spl_token::state::Account::unpack_from_slice(&mut bridge_associated_info.data.borrow_mut().as_ref())?;
 
     if *owner_associated_info.key !=
@@ -559,6 +1139,11 @@ pub fn process_withdraw_ft<'a>(
     }
 
     if owner_associated_info.data.borrow().as_ref().len() == 0 {
+        if strict_ata {
+            msg!("Owner associated account should exist in the strict mode");
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
         msg!("Create owner associated account");
         lib::call_create_associated_account(
             owner_info,
@@ -571,58 +1156,71 @@ pub fn process_withdraw_ft<'a>(
         )?;
     }
 
+    let owner_associated = spl_token::state::Account::unpack_from_slice(&mut owner_associated_info.data.borrow().as_ref())?;
+    if owner_associated.owner != receiver || owner_associated.mint != *mint_info.key {
+        return Err(LibError::WrongTokenAccount.into());
+    }
 
-    if bridge_associated.amount < amount {
-        msg!("Minting token to bridge admin");
-        call_mint_to(
-            mint_info,
-            bridge_associated_info,
-            bridge_admin_info,
-            seeds,
-            amount - bridge_associated.amount,
-        )?;
+    // Transfer to the frozen account fails only after the token is minted to the bridge.
+    // The account frozen by the bridge-controlled freeze authority is thawed instead.
+    if owner_associated.is_frozen() {
+        if mint.freeze_authority != COption::Some(bridge_admin_key) {
+            return Err(LibError::AccountFrozen.into());
+        }
+
+        msg!("Thawing owner associated account");
+        call_thaw_account(owner_associated_info, mint_info, bridge_admin_info, seeds)?;
+    }
+
+    log_compute_units("token accounts ready");
+
+    // Zero if the bridge already holds enough tokens
+    let shortfall = claim_amount.saturating_sub(bridge_associated.amount);
+    if shortfall > 0 {
+        if *mint_info.key == spl_token::native_mint::id() {
+            // Bridge does not control wSOL mint, so the lacking part is wrapped from the bridge admin lamports
+            msg!("Wrapping SOL to bridge associated account");
+            transfer_lamports(bridge_admin_info, bridge_associated_info, shortfall)?;
+            call_sync_native(bridge_associated_info)?;
+        } else {
+            if mint.mint_authority != COption::Some(bridge_admin_key) {
+                return Err(LibError::WrongMintAuthority.into());
+            }
+
+            msg!("Minting token to bridge admin");
+            call_mint_to(
+                mint_info,
+                bridge_associated_info,
+                bridge_admin_info,
+                seeds,
+                shortfall,
+                max_supply,
+            )?;
+        }
     }
 
+    // Fee tokens stay on the bridge associated account
+    let (net_amount, fee) = withdraw_amounts(&bridge_admin, claim_amount)?;
+
     msg!("Transferring token");
-    call_transfer_token(
+    call_transfer_token_checked(
         bridge_associated_info,
+        mint_info,
         owner_associated_info,
         bridge_admin_info,
-        amount,
+        net_amount,
+        mint.decimals,
         &[&[seeds.as_slice()]],
     )?;
 
-    let (withdraw_key, bump_seed) = Pubkey::find_program_address(&[origin.as_slice()], program_id);
-    if withdraw_key != *withdraw_info.key {
-        return Err(LibError::WrongNonce.into());
-    }
-
-    msg!("Creating withdraw account");
-    lib::call_create_account(
-        owner_info,
-        withdraw_info,
-        rent_info,
-        system_program,
-        WITHDRAW_SIZE,
-        program_id,
-        &[origin.as_slice(), &[bump_seed]],
-    )?;
-
-    msg!("Initializing withdraw account");
-    let mut withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow_mut().as_ref())?;
-    if withdraw.is_initialized {
-        return Err(LibError::AlreadyInUse.into());
-    }
+    log_compute_units("token transferred");
 
-    withdraw.is_initialized = true;
-    withdraw.token_type = lib::TokenType::FT;
-    withdraw.origin = origin;
-    withdraw.mint = Option::Some(mint_info.key.clone());
-    withdraw.amount = amount;
-    withdraw.receiver_address = *owner_info.key;
     withdraw.serialize(&mut *withdraw_info.data.borrow_mut())?;
-    msg!("Withdraw account created");
-    Ok(())
+    msg!("Withdraw account updated, claimed {} of {}", withdraw.claimed, withdraw.amount);
+
+    log_compute_units("withdraw account saved");
+    log_withdraw_address(&withdraw_key);
+    set_withdraw_receipt(origin, net_amount, fee, Some(*mint_info.key))
 }
 
 pub fn process_withdraw_nft<'a>(
@@ -631,12 +1229,17 @@ pub fn process_withdraw_nft<'a>(
     seeds: [u8; 32],
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
-    path: Vec<[u8; 32]>,
+    path: MerkleProof,
     origin: [u8; 32],
     token_seed: Option<[u8; 32]>,
     signed_meta: Option<SignedMetadata>,
+    eip712_domain_separator: Option<[u8; 32]>,
+    strict_ata: bool,
+    authorized_root: Option<[u8; 32]>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 13, "WithdrawNFT")?;
     let account_info_iter = &mut accounts.iter();
+    log_compute_units("start");
 
     let bridge_admin_info = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
@@ -651,6 +1254,7 @@ pub fn process_withdraw_nft<'a>(
     let rent_info = next_account_info(account_info_iter)?;
     let _metadata_program = next_account_info(account_info_iter)?;
     let _associated_program = next_account_info(account_info_iter)?;
+    let token_config_info = next_account_info(account_info_iter)?;
 
     let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
     if *bridge_admin_info.key != bridge_admin_key {
@@ -662,6 +1266,13 @@ pub fn process_withdraw_nft<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+    verify_withdraw_not_spent(withdraw_info)?;
+
+    if !get_token_config(program_id, &bridge_admin_key, token_config_info, mint_info.key, None)?.withdraw_enabled {
+        return Err(LibError::TokenDisabled.into());
+    }
+
     if *metadata_info.key != mpl_token_metadata::pda::find_metadata_account(mint_info.key).0 {
         return Err(LibError::WrongMetadataAccount.into());
     }
@@ -678,31 +1289,37 @@ pub fn process_withdraw_nft<'a>(
             rent_info,
             system_program,
             seeds,
+            None,
         )?;
     }
 
+    log_compute_units("token created");
+
     let metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut metadata_info.data.borrow_mut().as_ref())?;
+    verify_token_standard(&metadata, lib::TokenType::NFT)?;
 
     // Default metadata - from token
     let mut name = metadata.data.name;
     let mut symbol = metadata.data.symbol;
-    let mut uri = metadata.data.uri;
+    let uri = metadata.data.uri;
 
     let mut collection: Option<[u8; 32]> = None;
+    let mut collection_metadata_info: Option<&AccountInfo<'a>> = None;
 
-    if metadata.collection.is_some() {
-        let collection_key = metadata.collection.unwrap().key;
+    if let Some(metadata_collection) = &metadata.collection {
+        let collection_key = metadata_collection.key;
 
-        let collection_metadata_info = next_account_info(account_info_iter)?;
-        if *collection_metadata_info.key != mpl_token_metadata::pda::find_metadata_account(&collection_key).0 {
+        let info = next_account_info(account_info_iter)?;
+        if *info.key != mpl_token_metadata::pda::find_metadata_account(&collection_key).0 {
             return Err(LibError::WrongMetadataAccount.into());
         }
 
         // If collection exists, use its metadata (name and symbol) instead of token metadata
-        let collection_metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut collection_metadata_info.data.borrow_mut().as_ref())?;
+        let collection_metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut info.data.borrow_mut().as_ref())?;
         name = collection_metadata.data.name;
         symbol = collection_metadata.data.symbol;
-        collection = Some(collection_key.to_bytes())
+        collection = Some(collection_key.to_bytes());
+        collection_metadata_info = Some(info);
     }
 
     let content = Content::new(
@@ -713,14 +1330,35 @@ pub fn process_withdraw_nft<'a>(
             TransferData::new_nft_transfer(
                 mint_info.key.to_bytes(),
                 collection,
-                name.trim_matches(char::from(0)).to_string(),
-                symbol.trim_matches(char::from(0)).to_string(),
-                uri.trim_matches(char::from(0)).to_string(),
+                normalize_metadata_field(&name),
+                normalize_metadata_field(&symbol),
+                normalize_metadata_field(&uri),
             )
         ),
     );
 
-    verify_ecdsa_signature(get_merkle_root(content.hash(), &path)?.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+    // Receiver bound by the signed content, the tokens are delivered only to its associated account
+    let receiver = Pubkey::new_from_array(content.receiver);
+
+    let leaf = content.leaf_hash_with(bridge_admin.hash_algo, eip712_domain_separator)?;
+    verify_withdraw_root(&bridge_admin, path.root_from_leaf_with(bridge_admin.hash_algo, leaf)?, signature, recovery_id, authorized_root)?;
+    verify_withdraw_delay(program_id, &bridge_admin_key, &bridge_admin, account_info_iter, origin, 1, leaf)?;
+    log_compute_units("signature verified");
+
+    if let (Some(metadata_collection), Some(collection_metadata_info)) = (&metadata.collection, collection_metadata_info) {
+        if !metadata_collection.verified {
+            try_verify_delegated_collection(
+                program_id,
+                bridge_admin_info,
+                seeds,
+                account_info_iter,
+                metadata_info,
+                collection_metadata_info,
+                owner_info,
+                &metadata_collection.key,
+            )?;
+        }
+    }
 
     if *bridge_associated_info.key !=
         get_associated_token_address(&bridge_admin_key, mint_info.key) {
@@ -728,6 +1366,11 @@ pub fn process_withdraw_nft<'a>(
     }
 
     if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+        if bridge_admin.strict_token_accounts {
+            msg!("Bridge associated account should be created by InitBridgeTokenAccount");
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
         msg!("Create bridge associated account");
         lib::call_create_associated_account(
             owner_info,
@@ -740,109 +1383,2599 @@ pub fn process_withdraw_nft<'a>(
         )?;
     }
 
-    let bridge_associated = spl_token::state::Account::unpack_from_slice(&mut bridge_associated_info.data.borrow_mut().as_ref())?;
+    verify_token_account_owner(bridge_associated_info)?;
+    let bridge_associated = spl_token::state::Account::unpack_from_slice(&mut bridge_associated_info.data.borrow_mut().as_ref())?;
+
+    if *owner_associated_info.key !=
+        get_associated_token_address(&owner_info.key, mint_info.key) {
+        return Err(LibError::WrongTokenAccount.into());
+    }
+
+    if owner_associated_info.data.borrow().as_ref().len() == 0 {
+        if strict_ata {
+            msg!("Owner associated account should exist in the strict mode");
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
+        msg!("Deposit owner associated account");
+        lib::call_create_associated_account(
+            owner_info,
+            owner_info,
+            mint_info,
+            owner_associated_info,
+            rent_info,
+            system_program,
+            token_program,
+        )?;
+    }
+
+    let owner_associated = spl_token::state::Account::unpack_from_slice(&mut owner_associated_info.data.borrow().as_ref())?;
+    if owner_associated.owner != receiver || owner_associated.mint != *mint_info.key {
+        return Err(LibError::WrongTokenAccount.into());
+    }
+
+    // Transfer to the frozen account fails only after the token is minted to the bridge
+    if owner_associated.is_frozen() {
+        return Err(LibError::AccountFrozen.into());
+    }
+
+    log_compute_units("token accounts ready");
+
+    if bridge_associated.amount == 0 {
+        let mint: spl_token::state::Mint = Mint::unpack_from_slice(&mut mint_info.data.borrow_mut().as_ref())?;
+        if mint.mint_authority != COption::Some(bridge_admin_key) {
+            return Err(LibError::WrongMintAuthority.into());
+        }
+
+        msg!("Minting token to bridge admin");
+        call_mint_to(
+            mint_info,
+            bridge_associated_info,
+            bridge_admin_info,
+            seeds,
+            1,
+            None,
+        )?;
+    }
+
+    msg!("Transferring token");
+    call_transfer_token(
+        bridge_associated_info,
+        owner_associated_info,
+        bridge_admin_info,
+        1,
+        &[&[seeds.as_slice()]],
+    )?;
+
+    log_compute_units("token transferred");
+
+    let (withdraw_key, bump_seed) = Pubkey::find_program_address(&[origin.as_slice()], program_id);
+    if withdraw_key != *withdraw_info.key {
+        return Err(LibError::WrongNonce.into());
+    }
+
+    msg!("Creating withdraw account");
+    lib::call_create_account(
+        owner_info,
+        withdraw_info,
+        rent_info,
+        system_program,
+        WITHDRAW_SIZE,
+        program_id,
+        &[origin.as_slice(), &[bump_seed]],
+    )?;
+
+    msg!("Initializing withdraw account");
+    let mut withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow_mut().as_ref())?;
+    if withdraw.is_initialized {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    withdraw.version = lib::STATE_VERSION;
+    withdraw.is_initialized = true;
+    withdraw.token_type = lib::TokenType::NFT;
+    withdraw.origin = origin;
+    withdraw.mint = Option::Some(mint_info.key.clone());
+    withdraw.amount = 1;
+    withdraw.claimed = 1;
+    withdraw.receiver_address = receiver;
+    withdraw.serialize(&mut *withdraw_info.data.borrow_mut())?;
+    msg!("Withdraw account created");
+
+    log_compute_units("withdraw account saved");
+    log_withdraw_address(&withdraw_key);
+    set_withdraw_receipt(origin, 1, 0, Some(*mint_info.key))
+}
+
+pub fn process_set_min_deposit<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    min_deposit: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetMinDeposit")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetMinDeposit,
+        amount_bytes(min_deposit),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.min_deposit = min_deposit;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_init_bridge_token_account<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 8, "InitBridgeTokenAccount")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let bridge_associated_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let _associated_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if *bridge_admin_info.key != bridge_admin_key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    if *bridge_associated_info.key !=
+        get_associated_token_address(&bridge_admin_key, mint_info.key) {
+        return Err(LibError::WrongTokenAccount.into());
+    }
+
+    if bridge_associated_info.data.borrow().as_ref().len() != 0 {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    msg!("Creating bridge admin associated account");
+    lib::call_create_associated_account(
+        payer_info,
+        bridge_admin_info,
+        mint_info,
+        bridge_associated_info,
+        rent_info,
+        system_program,
+        token_program,
+    )
+}
+
+pub fn process_set_strict_token_accounts<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    strict: bool,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetStrictTokenAccounts")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetStrictTokenAccounts,
+        vec![strict as u8],
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.strict_token_accounts = strict;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_sponsor_token_accounts<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    sponsor: bool,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetSponsorTokenAccounts")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetSponsorTokenAccounts,
+        vec![sponsor as u8],
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.sponsor_token_accounts = sponsor;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_max_single_withdraw<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    max_single_withdraw: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetMaxSingleWithdraw")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetMaxSingleWithdraw,
+        amount_bytes(max_single_withdraw),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.max_single_withdraw = max_single_withdraw;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_backup_key<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    backup_key: Option<[u8; SECP256K1_PUBLIC_KEY_LENGTH]>,
+    promote_timelock: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetBackupKey")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let mut data = backup_key.map(Vec::from).unwrap_or_default();
+    data.extend(amount_bytes(promote_timelock));
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetBackupKey,
+        data,
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.backup_key = backup_key;
+    bridge_admin.promote_timelock = promote_timelock;
+    bridge_admin.promote_requested_slot = None;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_promote_backup<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    confirm: bool,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "PromoteBackup")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    // The nonce is incremented by every step, so the request can not be replayed after the cancel
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        lib::PROMOTE_BACKUP_TAG.as_bytes(),
+        bridge_admin_key.as_ref(),
+        bridge_admin.nonce.to_be_bytes().as_slice(),
+        &[confirm as u8],
+    ]);
+    bridge_admin.verify_backup_signature(hash.as_ref(), signature.as_slice(), recovery_id)?;
+
+    let slot = Clock::get()?.slot;
+    match (confirm, bridge_admin.promote_requested_slot) {
+        (false, None) => {
+            msg!("Backup key promotion requested at slot {}", slot);
+            bridge_admin.promote_requested_slot = Some(slot);
+        }
+        (false, Some(_)) => return Err(LibError::AlreadyInUse.into()),
+        (true, None) => return Err(LibError::NoPendingPromotion.into()),
+        (true, Some(requested_slot)) => {
+            if slot.saturating_sub(requested_slot) < bridge_admin.promote_timelock {
+                msg!("Promotion was requested at slot {}, timelock is {} slots", requested_slot, bridge_admin.promote_timelock);
+                return Err(LibError::PromotionTimelocked.into());
+            }
+
+            msg!("Backup key promoted");
+            // Checked by verify_backup_signature
+            bridge_admin.public_key = bridge_admin.backup_key.take().unwrap();
+            bridge_admin.eth_address = None;
+            bridge_admin.promote_requested_slot = None;
+        }
+    }
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_cancel_promotion<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "CancelPromotion")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let requested_slot = bridge_admin.promote_requested_slot.ok_or(LibError::NoPendingPromotion)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::CancelPromotion,
+        amount_bytes(requested_slot),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.promote_requested_slot = None;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_withdraw_delay<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    delay: u64,
+    threshold: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetWithdrawDelay")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetWithdrawDelay,
+        [amount_bytes(delay), amount_bytes(threshold)].concat(),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.withdraw_delay = delay;
+    bridge_admin.withdraw_delay_threshold = threshold;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_withdraw_fee<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    withdraw_fee_bps: u16,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetWithdrawFee")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetWithdrawFee,
+        withdraw_fee_bps.to_be_bytes().to_vec(),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.withdraw_fee_bps = withdraw_fee_bps;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_eth_address<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    eth_address: Option<[u8; ETH_ADDRESS_LENGTH]>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "SetEthAddress")?;
+    let account_info_iter = &mut accounts.iter();
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    // Empty operation data switches back to the public key mode
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetEthAddress,
+        eth_address.map(Vec::from).unwrap_or_default(),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.eth_address = eth_address;
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_authorize_origins<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    root: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "AuthorizeOrigins")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let authorized_origins_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (authorized_origins_key, bump_seed) = Pubkey::find_program_address(
+        &[lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), root.as_slice()],
+        program_id,
+    );
+    if authorized_origins_key != *authorized_origins_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if authorized_origins_info.data.borrow().as_ref().len() != 0 {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    // The seed is hashed as the operation tag, so the signature differs from the other domain signed operations
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(),
+        bridge_admin_key.as_ref(),
+        root.as_slice(),
+    ]);
+    bridge_admin.verify_signature(hash.as_ref(), signature.as_slice(), recovery_id)?;
+
+    msg!("Creating authorized origins account");
+    lib::call_create_account(
+        payer_info,
+        authorized_origins_info,
+        rent_info,
+        system_program,
+        AUTHORIZED_ORIGINS_SIZE,
+        program_id,
+        &[lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), root.as_slice(), &[bump_seed]],
+    )?;
+
+    let authorized = AuthorizedOrigins {
+        root,
+        public_key: bridge_admin.public_key,
+        revoked: false,
+        is_initialized: true,
+    };
+    authorized.serialize(&mut *authorized_origins_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_claim_authorized<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    token_type: lib::TokenType,
+    args: WithdrawArgs,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "ClaimAuthorized")?;
+    let authorized_origins_info = &accounts[0];
+
+    if *authorized_origins_info.owner != *program_id {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    let authorized: AuthorizedOrigins = BorshDeserialize::deserialize(&mut authorized_origins_info.data.borrow().as_ref())?;
+    if !authorized.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&args.seeds], &program_id)?;
+    let (authorized_origins_key, _) = Pubkey::find_program_address(
+        &[lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), authorized.root.as_slice()],
+        program_id,
+    );
+    if authorized_origins_key != *authorized_origins_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if authorized.revoked {
+        return Err(LibError::RootRevoked.into());
+    }
+
+    // The rest accounts are the same as for the regular withdrawal
+    let accounts = &accounts[1..];
+    verify_root_key(accounts, &bridge_admin_key, &authorized.public_key)?;
+    let root = Some(authorized.root);
+    match token_type {
+        lib::TokenType::Native => process_withdraw_native(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.eip712_domain_separator, root),
+        lib::TokenType::FT => process_withdraw_ft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.claim_amount, args.token_seed, args.signed_meta, args.eip712_domain_separator, args.strict_ata, args.no_metadata, root),
+        lib::TokenType::NFT => process_withdraw_nft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.token_seed, args.signed_meta, args.eip712_domain_separator, args.strict_ata, root),
+    }
+}
+
+pub fn process_revoke_root<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    root: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 6, "RevokeRoot")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let authorized_origins_info = next_account_info(account_info_iter)?;
+    let stored_roots_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (authorized_origins_key, bump_seed) = Pubkey::find_program_address(
+        &[lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), root.as_slice()],
+        program_id,
+    );
+    if authorized_origins_key != *authorized_origins_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let (stored_roots_key, _) = Pubkey::find_program_address(
+        &[lib::STORED_ROOTS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref()],
+        program_id,
+    );
+    if stored_roots_key != *stored_roots_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::RevokeRoot,
+        root.to_vec(),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+
+    // The root that is not authorized yet gets the tombstone too, otherwise its signature could be used later
+    if authorized_origins_info.data.borrow().as_ref().len() == 0 {
+        msg!("Creating authorized origins account");
+        lib::call_create_account(
+            payer_info,
+            authorized_origins_info,
+            rent_info,
+            system_program,
+            AUTHORIZED_ORIGINS_SIZE,
+            program_id,
+            &[lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), root.as_slice(), &[bump_seed]],
+        )?;
+    }
+
+    let authorized = AuthorizedOrigins {
+        root,
+        public_key: bridge_admin.public_key,
+        revoked: true,
+        is_initialized: true,
+    };
+    authorized.serialize(&mut *authorized_origins_info.data.borrow_mut())?;
+
+    // The StoredRoots account exists after the first StoreRoots
+    if *stored_roots_info.owner == *program_id {
+        let mut stored: StoredRoots = BorshDeserialize::deserialize(&mut stored_roots_info.data.borrow().as_ref())?;
+        stored.remove(&root);
+        lib::serialize_checked(&stored, stored_roots_info)?;
+    }
+
+    Ok(())
+}
+
+pub fn process_store_roots<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    roots: Vec<[u8; 32]>,
+    capacity: u16,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "StoreRoots")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let stored_roots_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (stored_roots_key, bump_seed) = Pubkey::find_program_address(
+        &[lib::STORED_ROOTS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref()],
+        program_id,
+    );
+    if stored_roots_key != *stored_roots_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if stored_roots_info.data.borrow().as_ref().len() == 0 {
+        msg!("Creating stored roots account");
+        lib::call_create_account(
+            payer_info,
+            stored_roots_info,
+            rent_info,
+            system_program,
+            stored_roots_size(capacity),
+            program_id,
+            &[lib::STORED_ROOTS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), &[bump_seed]],
+        )?;
+    }
+
+    let mut stored: StoredRoots = BorshDeserialize::deserialize(&mut stored_roots_info.data.borrow().as_ref())?;
+    if !stored.is_initialized {
+        stored.capacity = capacity;
+        stored.is_initialized = true;
+    }
+
+    // The batch counter is signed, so the previous batch can not be replayed to evict the newer roots
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        lib::STORED_ROOTS_PDA_SEED.as_bytes(),
+        bridge_admin_key.as_ref(),
+        capacity.to_be_bytes().as_slice(),
+        stored.batches.to_be_bytes().as_slice(),
+        solana_program::keccak::hash(roots.concat().as_slice()).as_ref(),
+    ]);
+    bridge_admin.verify_signature(hash.as_ref(), signature.as_slice(), recovery_id)?;
+
+    // Roots signed by the replaced admin key are not kept
+    if stored.public_key != bridge_admin.public_key {
+        stored.roots.clear();
+        stored.next = 0;
+        stored.public_key = bridge_admin.public_key;
+    }
+
+    stored.resize(capacity);
+    if stored_roots_info.data_len() != stored_roots_size(capacity) {
+        lib::realloc_with_rent(stored_roots_info, payer_info, system_program, stored_roots_size(capacity))?;
+    }
+
+    for root in roots {
+        stored.push(root);
+    }
+
+    stored.batches += 1;
+    lib::serialize_checked(&stored, stored_roots_info)
+}
+
+pub fn process_claim_stored<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    token_type: lib::TokenType,
+    root: [u8; 32],
+    args: WithdrawArgs,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "ClaimStored")?;
+    let stored_roots_info = &accounts[0];
+
+    if *stored_roots_info.owner != *program_id {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    let stored: StoredRoots = BorshDeserialize::deserialize(&mut stored_roots_info.data.borrow().as_ref())?;
+    if !stored.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&args.seeds], &program_id)?;
+    let (stored_roots_key, _) = Pubkey::find_program_address(
+        &[lib::STORED_ROOTS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref()],
+        program_id,
+    );
+    if stored_roots_key != *stored_roots_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if !stored.contains(&root) {
+        return Err(LibError::WrongMerkleRoot.into());
+    }
+
+    // The rest accounts are the same as for the regular withdrawal
+    let accounts = &accounts[1..];
+    verify_root_key(accounts, &bridge_admin_key, &stored.public_key)?;
+    let root = Some(root);
+    match token_type {
+        lib::TokenType::Native => process_withdraw_native(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.eip712_domain_separator, root),
+        lib::TokenType::FT => process_withdraw_ft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.amount, args.claim_amount, args.token_seed, args.signed_meta, args.eip712_domain_separator, args.strict_ata, args.no_metadata, root),
+        lib::TokenType::NFT => process_withdraw_nft(program_id, accounts, args.seeds, args.signature, args.recovery_id, args.path, args.origin, args.token_seed, args.signed_meta, args.eip712_domain_separator, args.strict_ata, root),
+    }
+}
+
+pub fn process_announce_withdraw<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    origin: [u8; 32],
+    amount: u64,
+    leaf: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: MerkleProof,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "AnnounceWithdraw")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let announcement_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    // Only the signed withdrawal can be announced. The leaf is verified at the withdrawal, so it is the part of the address
+    // with the origin and amount: the announcement with the wrong origin or amount does not block the real one.
+    let root = path.root_from_leaf_with(bridge_admin.hash_algo, leaf)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
+
+    let amount_seed = amount.to_be_bytes();
+    let (announcement_key, bump_seed) = lib::instructions::bridge::find_withdraw_announcement(program_id, &bridge_admin_key, origin, amount, leaf);
+    if announcement_key != *announcement_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if announcement_info.data_len() != 0 {
+        let announcement: WithdrawAnnouncement = BorshDeserialize::deserialize(&mut announcement_info.data.borrow().as_ref())?;
+        if announcement.vetoed {
+            return Err(LibError::WithdrawVetoed.into());
+        }
+
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    lib::call_create_account(
+        payer_info,
+        announcement_info,
+        rent_info,
+        system_program,
+        WITHDRAW_ANNOUNCEMENT_SIZE,
+        program_id,
+        &[lib::WITHDRAW_ANNOUNCEMENT_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), origin.as_slice(), amount_seed.as_slice(), leaf.as_slice(), &[bump_seed]],
+    )?;
+
+    let mut announcement: WithdrawAnnouncement = BorshDeserialize::deserialize(&mut announcement_info.data.borrow_mut().as_ref())?;
+    if announcement.is_initialized {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    announcement.origin = origin;
+    announcement.amount = amount;
+    announcement.leaf = leaf;
+    announcement.announce_slot = Clock::get()?.slot;
+    announcement.is_initialized = true;
+    announcement.serialize(&mut *announcement_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_veto_withdraw<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "VetoWithdraw")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let announcement_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    if announcement_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut announcement: WithdrawAnnouncement = BorshDeserialize::deserialize(&mut announcement_info.data.borrow().as_ref())?;
+    if !announcement.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    if announcement.vetoed {
+        return Err(LibError::WithdrawVetoed.into());
+    }
+
+    let (announcement_key, _) = lib::instructions::bridge::find_withdraw_announcement(program_id, &bridge_admin_key, announcement.origin, announcement.amount, announcement.leaf);
+    if announcement_key != *announcement_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::VetoWithdraw,
+        announcement_key.to_bytes().to_vec(),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+
+    // The announcement is kept: the closed one could be announced again by anyone with the signed leaf
+    announcement.vetoed = true;
+    announcement.serialize(&mut *announcement_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_update_token_metadata<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    token_seed: [u8; 32],
+    data: SignedMetadata,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 4, "UpdateTokenMetadata")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let _metadata_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (mint_key, _) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
+    if mint_key != *mint_info.key {
+        return Err(LibError::WrongTokenSeed.into());
+    }
+
+    if *metadata_info.key != mpl_token_metadata::pda::find_metadata_account(mint_info.key).0 {
+        return Err(LibError::WrongMetadataAccount.into());
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::UpdateTokenMetadata,
+        metadata_data(&token_seed, &data),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    let metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut metadata_info.data.borrow_mut().as_ref())?;
+
+    msg!("Updating metadata account");
+    call_update_metadata(
+        metadata_info,
+        bridge_admin_info,
+        DataV2 {
+            name: data.name,
+            symbol: data.symbol,
+            uri: data.uri,
+            seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+            creators: metadata.data.creators,
+            collection: metadata.collection,
+            uses: metadata.uses,
+        },
+        seeds,
+    )?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_emergency_withdraw<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    mints: Vec<Pubkey>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3, "EmergencyWithdraw")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let _token_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let mut data = Vec::from(destination_info.key.as_ref());
+    for mint in &mints {
+        data.append(&mut Vec::from(mint.as_ref()));
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::EmergencyWithdraw,
+        data,
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    for mint in &mints {
+        let mint_info = next_account_info(account_info_iter)?;
+        let bridge_associated_info = next_account_info(account_info_iter)?;
+        let destination_associated_info = next_account_info(account_info_iter)?;
+
+        if *mint_info.key != *mint {
+            return Err(LibError::WrongMint.into());
+        }
+
+        if *bridge_associated_info.key != get_associated_token_address(&bridge_admin_key, mint) {
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
+        if *destination_associated_info.key != get_associated_token_address(destination_info.key, mint) {
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
+        if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+            continue;
+        }
+
+        verify_token_account_owner(bridge_associated_info)?;
+        let bridge_associated = spl_token::state::Account::unpack_from_slice(&mut bridge_associated_info.data.borrow().as_ref())?;
+        if bridge_associated.amount == 0 {
+            continue;
+        }
+
+        let mint_state: spl_token::state::Mint = Mint::unpack_from_slice(&mut mint_info.data.borrow().as_ref())?;
+
+        msg!("Transferring {} of {}", bridge_associated.amount, mint);
+        call_transfer_token_checked(
+            bridge_associated_info,
+            mint_info,
+            destination_associated_info,
+            bridge_admin_info,
+            bridge_associated.amount,
+            mint_state.decimals,
+            &[&[seeds.as_slice()]],
+        )?;
+    }
+
+    // Before the serialization: writing the state advances the account data slice, so its length changes
+    let rent_exempt = Rent::get()?.minimum_balance(bridge_admin_info.data_len());
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+
+    let amount = bridge_admin_info.lamports().saturating_sub(rent_exempt);
+    if amount > 0 {
+        msg!("Transferring {} lamports", amount);
+        transfer_lamports(bridge_admin_info, destination_info, amount)?;
+    }
+
+    Ok(())
+}
+
+pub fn process_set_networks<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    networks: Vec<lib::Network>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "SetNetworks")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (networks_key, bump_seed) = Pubkey::find_program_address(&[lib::NETWORKS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref()], program_id);
+    if networks_key != *networks_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    // Every network is hashed separately, so the list can not be re-split into other names
+    let mut data = Vec::new();
+    for network in &networks {
+        data.append(&mut Vec::from(solana_program::keccak::hash(network.as_bytes()).as_ref()));
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetNetworks,
+        data,
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    if networks_info.data.borrow().as_ref().len() == 0 {
+        msg!("Creating supported networks account");
+        lib::call_create_account(
+            payer_info,
+            networks_info,
+            rent_info,
+            system_program,
+            SUPPORTED_NETWORKS_SIZE,
+            program_id,
+            &[lib::NETWORKS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), &[bump_seed]],
+        )?;
+    } else if networks_info.data_len() < SUPPORTED_NETWORKS_SIZE {
+        msg!("Extending supported networks account");
+        lib::realloc_with_rent(networks_info, payer_info, system_program, SUPPORTED_NETWORKS_SIZE)?;
+    }
+
+    let mut supported: SupportedNetworks = BorshDeserialize::deserialize(&mut networks_info.data.borrow_mut().as_ref())?;
+    supported.networks = networks;
+    supported.is_initialized = true;
+    lib::serialize_checked(&supported, networks_info)?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_network_formats<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    formats: Vec<lib::NetworkFormat>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 4, "SetNetworkFormats")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let networks_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    verify_pda_address(program_id, &[lib::NETWORKS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref()], None, networks_info)?;
+
+    // Formats are stored in the supported networks account created by SetNetworks
+    if networks_info.data.borrow().as_ref().len() == 0 {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    let mut data = Vec::new();
+    for format in &formats {
+        data.append(&mut Vec::from(solana_program::keccak::hash(format.network.as_bytes()).as_ref()));
+        data.push(format.format as u8);
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetNetworkFormats,
+        data,
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    if networks_info.data_len() < SUPPORTED_NETWORKS_SIZE {
+        msg!("Extending supported networks account");
+        lib::realloc_with_rent(networks_info, payer_info, system_program, SUPPORTED_NETWORKS_SIZE)?;
+    }
+
+    let mut supported: SupportedNetworks = BorshDeserialize::deserialize(&mut networks_info.data.borrow_mut().as_ref())?;
+    supported.formats = formats;
+    lib::serialize_checked(&supported, networks_info)?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_set_token_config<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    mint: Pubkey,
+    deposit_enabled: bool,
+    withdraw_enabled: bool,
+    target_decimals: Option<u8>,
+    min_deposit: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "SetTokenConfig")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let token_config_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (token_config_key, bump_seed) = Pubkey::find_program_address(
+        &[lib::TOKEN_CONFIG_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), mint.as_ref()],
+        program_id,
+    );
+    if token_config_key != *token_config_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut data = Vec::from(mint.as_ref());
+    data.push(deposit_enabled as u8);
+    data.push(withdraw_enabled as u8);
+    // Not set target decimals keep the content of the configs signed before
+    if let Some(target_decimals) = target_decimals {
+        data.push(target_decimals);
+    }
+    // The same for the zero minimum, the data lengths of the optional parts differ
+    if min_deposit != 0 {
+        data.extend(amount_bytes(min_deposit));
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SetTokenConfig,
+        data,
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    if token_config_info.data.borrow().as_ref().len() == 0 {
+        msg!("Creating token config account");
+        lib::call_create_account(
+            payer_info,
+            token_config_info,
+            rent_info,
+            system_program,
+            TOKEN_CONFIG_SIZE,
+            program_id,
+            &[lib::TOKEN_CONFIG_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), mint.as_ref(), &[bump_seed]],
+        )?;
+    } else if token_config_info.data_len() < TOKEN_CONFIG_SIZE {
+        msg!("Extending token config account");
+        lib::realloc_with_rent(token_config_info, payer_info, system_program, TOKEN_CONFIG_SIZE)?;
+    }
+
+    let mut config = read_token_config(token_config_info)?;
+    config.mint = mint;
+    config.deposit_enabled = deposit_enabled;
+    config.withdraw_enabled = withdraw_enabled;
+    config.target_decimals = target_decimals;
+    config.min_deposit = min_deposit;
+    config.is_initialized = true;
+    config.serialize(&mut *token_config_info.data.borrow_mut())?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_reclaim_mint<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    token_seed: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "ReclaimMint")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let bridge_associated_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+    let _token_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (mint_key, _) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
+    if mint_key != *mint_info.key {
+        return Err(LibError::WrongTokenSeed.into());
+    }
+
+    if *bridge_associated_info.key != get_associated_token_address(&bridge_admin_key, mint_info.key) {
+        return Err(LibError::WrongTokenAccount.into());
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::ReclaimMint,
+        [token_seed.as_slice(), receiver_info.key.as_ref()].concat(),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    let mint: spl_token::state::Mint = Mint::unpack_from_slice(&mut mint_info.data.borrow().as_ref())?;
+    if mint.supply != 0 {
+        return Err(LibError::NonZeroSupply.into());
+    }
+
+    if bridge_associated_info.data.borrow().as_ref().len() != 0 {
+        verify_token_account_owner(bridge_associated_info)?;
+
+        msg!("Closing bridge associated account");
+        call_close_account(
+            bridge_associated_info,
+            receiver_info,
+            bridge_admin_info,
+            seeds,
+        )?;
+    }
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_sweep_dust<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    mints: Vec<Pubkey>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3 + mints.len(), "SweepDust")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+    let _token_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let mut data = Vec::from(receiver_info.key.as_ref());
+    for mint in &mints {
+        data.append(&mut Vec::from(mint.as_ref()));
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::SweepDust,
+        data,
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    for mint in &mints {
+        let bridge_associated_info = next_account_info(account_info_iter)?;
+
+        if *bridge_associated_info.key != get_associated_token_address(&bridge_admin_key, mint) {
+            return Err(LibError::WrongTokenAccount.into());
+        }
+
+        if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+            continue;
+        }
+
+        verify_token_account_owner(bridge_associated_info)?;
+        let bridge_associated = spl_token::state::Account::unpack_from_slice(&mut bridge_associated_info.data.borrow().as_ref())?;
+        if bridge_associated.amount != 0 {
+            continue;
+        }
+
+        msg!("Closing bridge associated account of {}", mint);
+        call_close_account(
+            bridge_associated_info,
+            receiver_info,
+            bridge_admin_info,
+            seeds,
+        )?;
+    }
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_register_collection_delegate<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    collection_mint: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 6, "RegisterCollectionDelegate")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let collection_delegate_info = next_account_info(account_info_iter)?;
+    let authority_record_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let mut bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (collection_delegate_key, bump_seed) = Pubkey::find_program_address(
+        &[lib::COLLECTION_DELEGATE_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), collection_mint.as_ref()],
+        program_id,
+    );
+    if collection_delegate_key != *collection_delegate_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    // The record is created by the collection update authority approving the bridge admin
+    let (authority_record_key, _) = mpl_token_metadata::pda::find_collection_authority_account(&collection_mint, &bridge_admin_key);
+    if authority_record_key != *authority_record_info.key ||
+        *authority_record_info.owner != mpl_token_metadata::id() ||
+        authority_record_info.data_is_empty() {
+        return Err(LibError::NotCollectionAuthority.into());
+    }
+
+    verify_operation_signed(
+        program_id,
+        &bridge_admin,
+        OperationType::RegisterCollectionDelegate,
+        Vec::from(collection_mint.as_ref()),
+        signature,
+        recovery_id,
+        &path,
+    )?;
+
+    if collection_delegate_info.data.borrow().as_ref().len() == 0 {
+        msg!("Creating collection delegate account");
+        lib::call_create_account(
+            payer_info,
+            collection_delegate_info,
+            rent_info,
+            system_program,
+            COLLECTION_DELEGATE_SIZE,
+            program_id,
+            &[lib::COLLECTION_DELEGATE_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), collection_mint.as_ref(), &[bump_seed]],
+        )?;
+    }
+
+    let mut delegate: CollectionDelegate = BorshDeserialize::deserialize(&mut collection_delegate_info.data.borrow_mut().as_ref())?;
+    delegate.collection_mint = collection_mint;
+    delegate.is_initialized = true;
+    delegate.serialize(&mut *collection_delegate_info.data.borrow_mut())?;
+
+    bridge_admin.nonce += 1;
+    bridge_admin.serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_migrate_admin<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    seeds: [u8; 32],
+    commission_program: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3, "MigrateAdmin")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&seeds], &program_id)?;
+    if bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if bridge_admin_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Both the first and the deployed layouts have no version byte, they are recognized by the size
+    let (public_key, is_initialized) = match bridge_admin_info.data_len() {
+        LEGACY_BRIDGE_ADMIN_SIZE => {
+            let legacy: LegacyBridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow().as_ref())?;
+            (legacy.public_key, legacy.is_initialized)
+        }
+        BASELINE_BRIDGE_ADMIN_SIZE => {
+            let baseline: BaselineBridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow().as_ref())?;
+            (baseline.public_key, baseline.is_initialized)
+        }
+        _ => {
+            msg!("Bridge admin is not in the legacy or deployed layout");
+            return Err(LibError::UnsupportedVersion.into());
+        }
+    };
+
+    if !is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    // Legacy and deployed admins have no nonce, the migration can be done only once because the account size changes
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        bridge_admin_key.as_ref(),
+        commission_program.as_ref(),
+    ]);
+
+    verify_ecdsa_signature(hash.as_ref(), signature.as_slice(), recovery_id, public_key)?;
+
+    lib::realloc_with_rent(bridge_admin_info, payer_info, system_program, BRIDGE_ADMIN_SIZE)?;
+
+    BridgeAdmin::migrated(public_key, commission_program).serialize(&mut *bridge_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_migrate_state<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3, "MigrateState")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let account_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if account_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Accounts of the deployed layout have no version byte, their type is recognized by the size
+    match account_info.data_len() {
+        BASELINE_BRIDGE_ADMIN_SIZE => {
+            let baseline: BaselineBridgeAdmin = BorshDeserialize::deserialize(&mut account_info.data.borrow().as_ref())?;
+            if !baseline.is_initialized {
+                return Err(LibError::NotInitialized.into());
+            }
+
+            msg!("Migrating bridge admin to the version {}", lib::STATE_VERSION);
+            lib::realloc_with_rent(account_info, payer_info, system_program, BRIDGE_ADMIN_SIZE)?;
+            BridgeAdmin::migrated(baseline.public_key, baseline.commission_program).serialize(&mut *account_info.data.borrow_mut())?;
+            Ok(())
+        }
+        // BridgeAdmin of the current layout can have the same size, the withdraw is recognized by its address
+        BASELINE_WITHDRAW_SIZE if is_baseline_withdraw(program_id, account_info) => {
+            let baseline: BaselineWithdraw = BorshDeserialize::deserialize(&mut account_info.data.borrow().as_ref())?;
+            if !baseline.is_initialized {
+                return Err(LibError::NotInitialized.into());
+            }
+
+            // Withdrawals of the deployed version were always made for the whole amount
+            let withdraw = Withdraw {
+                version: lib::STATE_VERSION,
+                token_type: baseline.token_type,
+                mint: baseline.mint,
+                amount: baseline.amount,
+                origin: baseline.origin,
+                receiver_address: baseline.receiver_address,
+                is_initialized: true,
+                claimed: baseline.amount,
+            };
+
+            msg!("Migrating withdraw account to the version {}", lib::STATE_VERSION);
+            lib::realloc_with_rent(account_info, payer_info, system_program, WITHDRAW_SIZE)?;
+            withdraw.serialize(&mut *account_info.data.borrow_mut())?;
+            Ok(())
+        }
+        _ => match lib::state_version(account_info, BASELINE_BRIDGE_ADMIN_SIZE)? {
+            lib::STATE_VERSION => {
+                msg!("Account {} is already of the current version", account_info.key);
+                Ok(())
+            }
+            version => lib::check_version(version),
+        }
+    }
+}
+
+pub fn process_validate_withdraw<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    token_type: lib::TokenType,
+    args: WithdrawArgs,
+) -> ProgramResult {
+    let result = validate_withdraw(program_id, accounts, token_type, &args);
+    if let Err(err) = &result {
+        msg!("Withdraw is not valid: {}", err);
+    }
+
+    let validation = WithdrawValidation {
+        origin: args.origin,
+        valid: result.is_ok(),
+        error: result.err().map(u64::from),
+    };
+
+    set_return_data(validation.try_to_vec()?.as_slice());
+    Ok(())
+}
+
+// Performs the same checks as withdraw handlers without creating accounts and moving tokens
+fn validate_withdraw<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    token_type: lib::TokenType,
+    args: &WithdrawArgs,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3, "ValidateWithdraw")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let withdraw_info = next_account_info(account_info_iter)?;
+
+    let bridge_admin_key = Pubkey::create_program_address(&[&args.seeds], &program_id)?;
+    if *bridge_admin_info.key != bridge_admin_key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let (withdraw_key, _) = Pubkey::find_program_address(&[args.origin.as_slice()], program_id);
+    if withdraw_key != *withdraw_info.key {
+        return Err(LibError::WrongNonce.into());
+    }
+
+    // Only partially claimed FT withdrawal of the current layout can be continued
+    let mut claimed = 0;
+    if !withdraw_info.data_is_empty() {
+        if !Withdraw::is_current_version(withdraw_info.data.borrow().as_ref()) {
+            return Err(LibError::AlreadyInUse.into());
+        }
+
+        let withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow().as_ref())?;
+        if !withdraw.is_initialized ||
+            withdraw.token_type != lib::TokenType::FT || token_type != lib::TokenType::FT ||
+            withdraw.amount != args.amount ||
+            withdraw.receiver_address != *owner_info.key ||
+            withdraw.claimed >= withdraw.amount {
+            return Err(LibError::AlreadyInUse.into());
+        }
+
+        claimed = withdraw.claimed;
+    }
+
+    let data = match token_type {
+        lib::TokenType::Native => {
+            let (net_amount, _) = withdraw_amounts(&bridge_admin, args.amount)?;
+            if bridge_admin_info.lamports() < net_amount {
+                return Err(LibError::WrongBalance.into());
+            }
+
+            TransferData::new_native_transfer(args.amount)
+        }
+        lib::TokenType::FT | lib::TokenType::NFT => {
+            let mint_info = next_account_info(account_info_iter)?;
+            let metadata_info = next_account_info(account_info_iter)?;
+            let bridge_associated_info = next_account_info(account_info_iter)?;
+            let token_config_info = next_account_info(account_info_iter)?;
+
+            let no_metadata = token_type == lib::TokenType::FT && args.no_metadata;
+            if !no_metadata && *metadata_info.key != mpl_token_metadata::pda::find_metadata_account(mint_info.key).0 {
+                return Err(LibError::WrongMetadataAccount.into());
+            }
+
+            if !get_token_config(program_id, &bridge_admin_key, token_config_info, mint_info.key, None)?.withdraw_enabled {
+                return Err(LibError::TokenDisabled.into());
+            }
+
+            if *bridge_associated_info.key != get_associated_token_address(&bridge_admin_key, mint_info.key) {
+                return Err(LibError::WrongTokenAccount.into());
+            }
+
+            let (data, mintable) = if mint_info.data.borrow().as_ref().len() == 0 {
+                // Token will be created by the withdrawal with the signed metadata
+                let token_seed = args.token_seed.ok_or(LibError::WrongTokenSeed)?;
+                let (mint_key, _) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
+                if mint_key != *mint_info.key {
+                    return Err(LibError::WrongTokenSeed.into());
+                }
+
+                let signed_meta = args.signed_meta.clone().ok_or(LibError::NoTokenMeta)?;
+                let name = normalize_metadata_field(&signed_meta.name);
+                let symbol = normalize_metadata_field(&signed_meta.symbol);
+                let uri = normalize_metadata_field(&signed_meta.uri);
+
+                let data = if token_type == lib::TokenType::FT {
+                    TransferData::new_ft_transfer(mint_info.key.to_bytes(), args.amount, name, symbol, uri, signed_meta.decimals)
+                        .with_max_supply(signed_meta.max_supply)
+                } else {
+                    TransferData::new_nft_transfer(mint_info.key.to_bytes(), None, name, symbol, uri)
+                };
+
+                (data, true)
+            } else {
+                let mint: spl_token::state::Mint = Mint::unpack_from_slice(&mut mint_info.data.borrow().as_ref())?;
+
+                let data = if token_type == lib::TokenType::FT {
+                    ft_transfer_data(mint_info, metadata_info, args.amount, mint.decimals, args.no_metadata, account_info_iter)?
+                } else {
+                    let metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut metadata_info.data.borrow().as_ref())?;
+                    verify_token_standard(&metadata, lib::TokenType::NFT)?;
+
+                    let mut name = metadata.data.name;
+                    let mut symbol = metadata.data.symbol;
+                    let mut collection: Option<[u8; 32]> = None;
+
+                    if let Some(metadata_collection) = metadata.collection {
+                        let collection_metadata_info = next_account_info(account_info_iter)?;
+                        if *collection_metadata_info.key != mpl_token_metadata::pda::find_metadata_account(&metadata_collection.key).0 {
+                            return Err(LibError::WrongMetadataAccount.into());
+                        }
+
+                        let collection_metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut collection_metadata_info.data.borrow().as_ref())?;
+                        name = collection_metadata.data.name;
+                        symbol = collection_metadata.data.symbol;
+                        collection = Some(metadata_collection.key.to_bytes());
+                    }
+
+                    TransferData::new_nft_transfer(
+                        mint_info.key.to_bytes(),
+                        collection,
+                        normalize_metadata_field(&name),
+                        normalize_metadata_field(&symbol),
+                        normalize_metadata_field(&metadata.data.uri),
+                    )
+                };
+
+                (data, mint.mint_authority == COption::Some(bridge_admin_key))
+            };
+
+            let required = if token_type == lib::TokenType::FT {
+                ft_claim_amount(args.amount, claimed, args.claim_amount)?
+            } else {
+                1
+            };
+
+            let bridge_balance = if bridge_associated_info.data.borrow().as_ref().len() == 0 {
+                0
+            } else {
+                verify_token_account_owner(bridge_associated_info)?;
+                spl_token::state::Account::unpack_from_slice(&mut bridge_associated_info.data.borrow().as_ref())?.amount
+            };
+
+            if bridge_balance < required && !mintable {
+                if token_type != lib::TokenType::FT || *mint_info.key != spl_token::native_mint::id() {
+                    return Err(LibError::WrongMintAuthority.into());
+                }
+
+                if bridge_admin_info.lamports() < required - bridge_balance {
+                    return Err(LibError::WrongBalance.into());
+                }
+            }
+
+            data
+        }
+    };
+
+    let content = Content::new(
+        args.origin,
+        owner_info.key.to_bytes(),
+        program_id.to_bytes(),
+        Box::new(data),
+    );
+
+    let leaf = content.leaf_hash_with(bridge_admin.hash_algo, args.eip712_domain_separator)?;
+    bridge_admin.verify_signature(args.path.root_from_leaf_with(bridge_admin.hash_algo, leaf)?.as_slice(), args.signature.as_slice(), args.recovery_id)?;
+
+    let amount = if token_type == lib::TokenType::NFT { 1 } else { args.amount };
+    if token_type != lib::TokenType::NFT {
+        verify_max_single_withdraw(&bridge_admin, amount)?;
+    }
+    verify_withdraw_delay(program_id, &bridge_admin_key, &bridge_admin, account_info_iter, args.origin, amount, leaf)
+}
+
+// Deposits to any network are allowed until the supported networks list is set by SetNetworks
+fn verify_network_supported<'a>(
+    program_id: &Pubkey,
+    bridge_admin_key: &Pubkey,
+    networks_info: &AccountInfo<'a>,
+    network: &lib::Network,
+    receiver: &str,
+    bump: Option<u8>,
+) -> ProgramResult {
+    verify_pda_address(program_id, &[lib::NETWORKS_PDA_SEED.as_bytes(), bridge_admin_key.as_ref()], bump, networks_info)?;
+
+    if networks_info.data.borrow().as_ref().len() == 0 {
+        return Ok(());
+    }
+
+    let supported: SupportedNetworks = BorshDeserialize::deserialize(&mut networks_info.data.borrow().as_ref())?;
+    if supported.is_initialized && !supported.networks.contains(network) {
+        msg!("Network {} is not supported", network);
+        return Err(LibError::UnsupportedNetwork.into());
+    }
+
+    // Networks without the configured format accept any receiver address
+    if let Some(format) = supported.formats.iter().find(|f| f.network == *network) {
+        format.format.validate(receiver)?;
+    }
+
+    Ok(())
+}
+
+// Tokens without the config account created by SetTokenConfig are enabled for deposits and withdrawals
+fn get_token_config<'a>(
+    program_id: &Pubkey,
+    bridge_admin_key: &Pubkey,
+    token_config_info: &AccountInfo<'a>,
+    mint: &Pubkey,
+    bump: Option<u8>,
+) -> Result<TokenConfig, ProgramError> {
+    verify_pda_address(
+        program_id,
+        &[lib::TOKEN_CONFIG_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), mint.as_ref()],
+        bump,
+        token_config_info,
+    )?;
+
+    if token_config_info.data.borrow().as_ref().len() != 0 {
+        let config = read_token_config(token_config_info)?;
+        if config.is_initialized {
+            return Ok(config);
+        }
+    }
+
+    Ok(TokenConfig {
+        mint: *mint,
+        deposit_enabled: true,
+        withdraw_enabled: true,
+        is_initialized: false,
+        target_decimals: None,
+        min_deposit: 0,
+    })
+}
+
+// Token config accounts created before target_decimals are shorter, the missing bytes are read as None
+fn read_token_config<'a>(token_config_info: &AccountInfo<'a>) -> Result<TokenConfig, ProgramError> {
+    let mut data = token_config_info.data.borrow().to_vec();
+    data.resize(max(data.len(), TOKEN_CONFIG_SIZE), 0);
+    Ok(BorshDeserialize::deserialize(&mut data.as_slice())?)
+}
+
+// Withdraw accounts are PDA(origin), the account of another type fails to deserialize or has another address
+fn is_baseline_withdraw(program_id: &Pubkey, account_info: &AccountInfo) -> bool {
+    match BaselineWithdraw::deserialize(&mut account_info.data.borrow().as_ref()) {
+        Ok(baseline) => Pubkey::find_program_address(&[baseline.origin.as_slice()], program_id).0 == *account_info.key,
+        Err(_) => false,
+    }
+}
+
+// Mints without the MintCap account created by the withdrawal have no supply cap
+fn get_mint_cap<'a>(
+    program_id: &Pubkey,
+    bridge_admin_key: &Pubkey,
+    mint_cap_info: &AccountInfo<'a>,
+    mint: &Pubkey,
+) -> Result<Option<u64>, ProgramError> {
+    verify_pda_address(
+        program_id,
+        &[lib::MINT_CAP_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), mint.as_ref()],
+        None,
+        mint_cap_info,
+    )?;
+
+    if mint_cap_info.data.borrow().as_ref().len() != 0 {
+        let mint_cap: MintCap = BorshDeserialize::deserialize(&mut mint_cap_info.data.borrow().as_ref())?;
+        if mint_cap.is_initialized {
+            return Ok(Some(mint_cap.max_supply));
+        }
+    }
+
+    Ok(None)
+}
+
+// Replayed withdrawal fails before the signature verification and saves its compute.
+// FT withdrawal is spent only when the whole amount is claimed (see partial withdrawals).
+fn verify_withdraw_not_spent<'a>(withdraw_info: &AccountInfo<'a>) -> ProgramResult {
+    if withdraw_info.data_is_empty() {
+        return Ok(());
+    }
+
+    if !Withdraw::is_current_version(withdraw_info.data.borrow().as_ref()) {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    let withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow().as_ref())?;
+    if withdraw.is_initialized && withdraw.claimed >= withdraw.amount {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    Ok(())
+}
+
+// Defense in depth against the wrongly signed amount, applies to native and FT withdrawals
+fn verify_max_single_withdraw(admin: &BridgeAdmin, amount: u64) -> ProgramResult {
+    if admin.max_single_withdraw != 0 && amount > admin.max_single_withdraw {
+        msg!("Withdrawal amount {} exceeds the limit {}", amount, admin.max_single_withdraw);
+        return Err(LibError::RateLimited.into());
+    }
+
+    Ok(())
+}
+
+// Withdrawals above the threshold require the announcement of the same signed leaf made at least `withdraw_delay` slots ago.
+// The announcement account is the next account in the list only in that case.
+fn verify_withdraw_delay<'a, 'b>(
+    program_id: &Pubkey,
+    bridge_admin_key: &Pubkey,
+    admin: &BridgeAdmin,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    origin: [u8; 32],
+    amount: u64,
+    leaf: [u8; 32],
+) -> ProgramResult {
+    if admin.withdraw_delay == 0 || amount <= admin.withdraw_delay_threshold {
+        return Ok(());
+    }
+
+    let announcement_info = next_account_info(account_info_iter)?;
+    let (announcement_key, _) = lib::instructions::bridge::find_withdraw_announcement(program_id, bridge_admin_key, origin, amount, leaf);
+    if announcement_key != *announcement_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if announcement_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let announcement: WithdrawAnnouncement = BorshDeserialize::deserialize(&mut announcement_info.data.borrow().as_ref())?;
+    if !announcement.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    if announcement.vetoed {
+        msg!("Announced withdrawal was vetoed");
+        return Err(LibError::WithdrawVetoed.into());
+    }
+
+    if Clock::get()?.slot.saturating_sub(announcement.announce_slot) < admin.withdraw_delay {
+        msg!("Withdrawal was announced at slot {}, delay is {} slots", announcement.announce_slot, admin.withdraw_delay);
+        return Err(LibError::WithdrawDelayed.into());
+    }
+
+    Ok(())
+}
+
+// Logs the remaining compute units at the withdrawal milestone, enabled by the `compute-logs` feature
+#[cfg(feature = "compute-logs")]
+fn log_compute_units(milestone: &str) {
+    msg!("Compute units at {}", milestone);
+    solana_program::log::sol_log_compute_units();
+}
+
+#[cfg(not(feature = "compute-logs"))]
+#[inline(always)]
+fn log_compute_units(_milestone: &str) {}
+
+// The bump from the instruction args is trusted only for the existing program account:
+// the program creates the config accounts with the canonical bump only, so the account at the derived address is the canonical one.
+// Otherwise (no bump or the account is not created yet) the address is checked by the canonical bump search.
+fn verify_pda_address(
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    bump: Option<u8>,
+    account_info: &AccountInfo,
+) -> ProgramResult {
+    let key = match bump {
+        Some(bump) if account_info.owner == program_id && account_info.data_len() != 0 => {
+            Pubkey::create_program_address(&[seeds, &[&[bump]]].concat(), program_id)
+                .map_err(|_| LibError::WrongSeeds)?
+        }
+        _ => Pubkey::find_program_address(seeds, program_id).0,
+    };
+
+    if key != *account_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    Ok(())
+}
+
+// Verifies the token in the collection, if the collection was registered by RegisterCollectionDelegate
+// and the delegate accounts were passed. Otherwise the verification is skipped.
+fn try_verify_delegated_collection<'a, 'b>(
+    program_id: &Pubkey,
+    bridge_admin_info: &AccountInfo<'a>,
+    seeds: [u8; 32],
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    metadata_info: &AccountInfo<'a>,
+    collection_metadata_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    collection_mint: &Pubkey,
+) -> ProgramResult {
+    let bridge_admin_key = bridge_admin_info.key;
+    let collection_delegate_info = match account_info_iter.next() {
+        Some(info) => info,
+        None => return Ok(()),
+    };
+
+    let (collection_delegate_key, _) = Pubkey::find_program_address(
+        &[lib::COLLECTION_DELEGATE_PDA_SEED.as_bytes(), bridge_admin_key.as_ref(), collection_mint.as_ref()],
+        program_id,
+    );
+    if collection_delegate_key != *collection_delegate_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if collection_delegate_info.data_is_empty() {
+        msg!("Collection is not delegated to bridge, skipping verification");
+        return Ok(());
+    }
+
+    let delegate: CollectionDelegate = BorshDeserialize::deserialize(&mut collection_delegate_info.data.borrow().as_ref())?;
+    if !delegate.is_initialized {
+        return Ok(());
+    }
+
+    let collection_mint_info = next_account_info(account_info_iter)?;
+    let collection_edition_info = next_account_info(account_info_iter)?;
+    let authority_record_info = next_account_info(account_info_iter)?;
+
+    let collection_metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut collection_metadata_info.data.borrow().as_ref())?;
+    let mut instruction = if collection_metadata.collection_details.is_some() {
+        verify_sized_collection_item(
+            mpl_token_metadata::id(),
+            *metadata_info.key,
+            *bridge_admin_key,
+            *payer_info.key,
+            *collection_mint_info.key,
+            *collection_metadata_info.key,
+            *collection_edition_info.key,
+            Some(*authority_record_info.key),
+        )
+    } else {
+        verify_collection(
+            mpl_token_metadata::id(),
+            *metadata_info.key,
+            *bridge_admin_key,
+            *payer_info.key,
+            *collection_mint_info.key,
+            *collection_metadata_info.key,
+            *collection_edition_info.key,
+            Some(*authority_record_info.key),
+        )
+    };
+
+    // Bridge admin is passed read-only to WithdrawNFT, the metadata program only requires its signature
+    instruction.accounts[1].is_writable = false;
+
+    msg!("Verifying delegated collection");
+    invoke_signed(
+        &instruction,
+        &[
+            metadata_info.clone(),
+            bridge_admin_info.clone(),
+            payer_info.clone(),
+            collection_mint_info.clone(),
+            collection_metadata_info.clone(),
+            collection_edition_info.clone(),
+            authority_record_info.clone(),
+        ],
+        &[&[seeds.as_slice()]],
+    )
+}
+
+// Metadata created by the bridge gets the standard from create_metadata_accounts_v3 by the mint decimals:
+// Fungible for FT and FungibleAsset for NFT (NonFungible requires the master edition, that would take the mint authority).
+// Metadata created before the token standard was introduced has None.
+// Programmable NFTs can not be moved by the plain token transfers, so they are rejected for both types.
+fn verify_token_standard(metadata: &mpl_token_metadata::state::Metadata, token_type: lib::TokenType) -> ProgramResult {
+    let valid = match (&metadata.token_standard, token_type) {
+        (None, _) => true,
+        (Some(TokenStandard::Fungible), lib::TokenType::FT) => true,
+        (Some(TokenStandard::FungibleAsset), lib::TokenType::FT | lib::TokenType::NFT) => true,
+        (Some(TokenStandard::NonFungible | TokenStandard::NonFungibleEdition), lib::TokenType::NFT) => true,
+        _ => false,
+    };
+
+    if !valid {
+        msg!("Token standard {:?} does not match the withdrawn token type", metadata.token_standard);
+        return Err(LibError::WrongTokenStandard.into());
+    }
+
+    Ok(())
+}
+
+// Pre-created account at the bridge associated address can be owned by another program
+// (e.g. funded system account), so it is checked before unpacking
+fn verify_token_account_owner(token_account_info: &AccountInfo) -> ProgramResult {
+    if *token_account_info.owner != spl_token::id() {
+        msg!("Token account is not owned by the token program");
+        return Err(LibError::WrongTokenAccount.into());
+    }
+
+    Ok(())
+}
+
+// Logs `WITHDRAW_PDA:<base58>` line and the same address as the binary log data,
+// so relayers do not have to recompute the Withdraw account address
+fn log_withdraw_address(withdraw_key: &Pubkey) {
+    msg!("{}:{}", lib::WITHDRAW_PDA_LOG_PREFIX, withdraw_key);
+    sol_log_data(&[lib::WITHDRAW_PDA_LOG_PREFIX.as_bytes(), withdraw_key.as_ref()]);
+}
+
+// Claims of the authorized withdrawals check the root stored by AuthorizeOrigins instead of the signature
+fn verify_withdraw_root(
+    admin: &BridgeAdmin,
+    root: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    authorized_root: Option<[u8; 32]>,
+) -> ProgramResult {
+    match authorized_root {
+        Some(authorized_root) if authorized_root == root => Ok(()),
+        Some(_) => Err(LibError::WrongMerkleRoot.into()),
+        None => admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id),
+    }
+}
+
+// Authorized roots are bound to the admin key that signed them, the claims fail after TransferOwnership or PromoteBackup.
+// The BridgeAdmin is the first account of the withdrawal.
+fn verify_root_key(accounts: &[AccountInfo], bridge_admin_key: &Pubkey, public_key: &[u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> ProgramResult {
+    let bridge_admin_info = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if *bridge_admin_key != *bridge_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow().as_ref())?;
+    if bridge_admin.public_key != *public_key {
+        return Err(LibError::RootRevoked.into());
+    }
+
+    Ok(())
+}
+
+// Part of the withdrawn amount kept by the bridge, rounded down
+fn withdraw_fee(admin: &BridgeAdmin, amount: u64) -> Result<u64, ProgramError> {
+    let fee = (amount as u128) * (admin.withdraw_fee_bps as u128) / (MAX_WITHDRAW_FEE_BPS as u128);
+    u64::try_from(fee).map_err(|_| LibError::AmountOverflow.into())
+}
+
+// Net amount transferred to the receiver and the fee
+fn withdraw_amounts(admin: &BridgeAdmin, amount: u64) -> Result<(u64, u64), ProgramError> {
+    let fee = withdraw_fee(admin, amount)?;
+    Ok((amount - fee, fee))
+}
 
-    if *owner_associated_info.key !=
-        get_associated_token_address(&owner_info.key, mint_info.key) {
-        return Err(LibError::WrongTokenAccount.into());
-    }
+fn set_withdraw_receipt(origin: [u8; 32], amount: u64, fee: u64, mint: Option<Pubkey>) -> ProgramResult {
+    let receipt = WithdrawReceipt {
+        origin,
+        amount,
+        fee,
+        mint,
+    };
 
-    if owner_associated_info.data.borrow().as_ref().len() == 0 {
-        msg!("Deposit owner associated account");
-        lib::call_create_associated_account(
-            owner_info,
-            owner_info,
-            mint_info,
-            owner_associated_info,
-            rent_info,
-            system_program,
-            token_program,
-        )?;
-    }
+    set_return_data(receipt.try_to_vec()?.as_slice());
+    Ok(())
+}
 
-    if bridge_associated.amount == 0 {
-        msg!("Minting token to bridge admin");
-        call_mint_to(
-            mint_info,
-            bridge_associated_info,
-            bridge_admin_info,
-            seeds,
-            1,
-        )?;
+// Leaf operation of the FT withdrawal of the existing mint, shared by the withdrawal and ValidateWithdraw.
+// The metadata account address is checked by the caller.
+fn ft_transfer_data<'a, 'b>(
+    mint_info: &AccountInfo<'a>,
+    metadata_info: &AccountInfo<'a>,
+    amount: u64,
+    decimals: u8,
+    no_metadata: bool,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> Result<TransferData, ProgramError> {
+    if no_metadata {
+        // Metadata account is not checked, the leaf contains only mint, amount and decimals
+        return Ok(TransferData::new_ft_transfer_without_metadata(mint_info.key.to_bytes(), amount, decimals));
     }
 
-    msg!("Transferring token");
-    call_transfer_token(
-        bridge_associated_info,
-        owner_associated_info,
-        bridge_admin_info,
-        1,
-        &[&[seeds.as_slice()]],
-    )?;
+    let metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut metadata_info.data.borrow().as_ref())?;
+    verify_token_standard(&metadata, lib::TokenType::FT)?;
 
-    let (withdraw_key, bump_seed) = Pubkey::find_program_address(&[origin.as_slice()], program_id);
-    if withdraw_key != *withdraw_info.key {
-        return Err(LibError::WrongNonce.into());
-    }
+    let (name, symbol) = ft_leaf_name_symbol(&metadata, account_info_iter)?;
 
-    msg!("Creating withdraw account");
-    lib::call_create_account(
-        owner_info,
-        withdraw_info,
-        rent_info,
-        system_program,
-        WITHDRAW_SIZE,
-        program_id,
-        &[origin.as_slice(), &[bump_seed]],
-    )?;
+    Ok(TransferData::new_ft_transfer(
+        mint_info.key.to_bytes(),
+        amount,
+        normalize_metadata_field(&name),
+        normalize_metadata_field(&symbol),
+        normalize_metadata_field(&metadata.data.uri),
+        decimals,
+    ))
+}
 
-    msg!("Initializing withdraw account");
-    let mut withdraw: Withdraw = BorshDeserialize::deserialize(&mut withdraw_info.data.borrow_mut().as_ref())?;
-    if withdraw.is_initialized {
-        return Err(LibError::AlreadyInUse.into());
+// Part of the signed FT amount transferred by the call, the rest can be claimed later
+fn ft_claim_amount(amount: u64, claimed: u64, claim_amount: Option<u64>) -> Result<u64, ProgramError> {
+    let claim_amount = claim_amount.unwrap_or(amount.saturating_sub(claimed));
+    match claimed.checked_add(claim_amount) {
+        Some(total) if claim_amount > 0 && total <= amount => Ok(claim_amount),
+        _ => Err(LibError::WrongBalance.into()),
     }
+}
 
-    withdraw.is_initialized = true;
-    withdraw.token_type = lib::TokenType::NFT;
-    withdraw.origin = origin;
-    withdraw.mint = Option::Some(mint_info.key.clone());
-    withdraw.amount = 1;
-    withdraw.receiver_address = *owner_info.key;
-    withdraw.serialize(&mut *withdraw_info.data.borrow_mut())?;
-    msg!("Withdraw account created");
-    Ok(())
+// FT leaf takes the name and symbol of the verified collection if its metadata account is passed,
+// otherwise of the token itself. The account is optional, so the next one may be the withdraw announcement.
+fn ft_leaf_name_symbol<'a, 'b>(
+    metadata: &mpl_token_metadata::state::Metadata,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> Result<(String, String), ProgramError> {
+    if let Some(collection) = &metadata.collection {
+        let collection_metadata_key = mpl_token_metadata::pda::find_metadata_account(&collection.key).0;
+        if account_info_iter.as_slice().first().map(|info| info.key) == Some(&collection_metadata_key) {
+            let collection_metadata_info = next_account_info(account_info_iter)?;
+            if !collection.verified {
+                return Err(LibError::UnverifiedCollection.into());
+            }
+
+            let collection_metadata: mpl_token_metadata::state::Metadata = BorshDeserialize::deserialize(&mut collection_metadata_info.data.borrow().as_ref())?;
+            return Ok((collection_metadata.data.name, collection_metadata.data.symbol));
+        }
+    }
+
+    Ok((metadata.data.name.clone(), metadata.data.symbol.clone()))
+}
+
+fn verify_operation_signed(
+    program_id: &Pubkey,
+    admin: &BridgeAdmin,
+    operation_type: OperationType,
+    data: Vec<u8>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: &Vec<[u8; 32]>,
+) -> ProgramResult {
+    let content = OperationContent::new(
+        admin.nonce,
+        *program_id,
+        operation_type,
+        data,
+    );
+
+    let root = get_merkle_root(content.hash(), path)?;
+    admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)
 }
 
 pub fn verify_commission_charged<'a>(
+    program_id: &Pubkey,
     bridge_admin_info: &AccountInfo<'a>,
     instruction_sysvar_info: &AccountInfo<'a>,
     admin: &BridgeAdmin,
+    owner: &Pubkey,
     token: lib::TokenType,
     amount: u64,
 ) -> ProgramResult {
-    let current_index = load_current_index_checked(instruction_sysvar_info)?;
-    let commission_instruction = load_instruction_at_checked((current_index - 1) as usize, instruction_sysvar_info)?;
+    let current_index = load_current_index_checked(instruction_sysvar_info)? as usize;
+    if current_index == 0 {
+        return Err(LibError::WrongCommissionProgram.into());
+    }
+
+    let commission_instruction = load_instruction_at_checked(current_index - 1, instruction_sysvar_info)?;
 
     if commission_instruction.program_id != admin.commission_program {
         return Err(LibError::WrongCommissionProgram.into());
     }
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &commission_instruction.program_id)?;
+    let commission_key = lib::instructions::commission::commission_admin_address(&commission_instruction.program_id, bridge_admin_info.key)?;
     if commission_key != commission_instruction.accounts[0].pubkey {
         return Err(LibError::WrongCommissionAccount.into());
     }
 
     let instruction = lib::instructions::commission::CommissionInstruction::try_from_slice(commission_instruction.data.as_slice())?;
 
-    if let lib::instructions::commission::CommissionInstruction::ChargeCommission(args) = instruction {
-        if args.deposit_token == token && args.deposit_token_amount == amount {
-            return Ok(());
-        }
+    let args = match instruction {
+        lib::instructions::commission::CommissionInstruction::ChargeCommission(args) => args,
+        _ => return Err(LibError::NotACommissionCharge.into()),
+    };
+
+    // The commission should be paid by the depositor, not by another charge of the transaction
+    let charge_owner = commission_instruction.accounts.get(2).map(|meta| meta.pubkey);
+    if charge_owner != Some(*owner) {
+        msg!("Commission is charged from another owner");
+        return Err(LibError::WrongCommissionArguments.into());
+    }
+
+    if args.deposit_token == token && args.deposit_token_amount == amount {
+        return verify_commission_pairing(program_id, admin, instruction_sysvar_info);
     }
 
     return Err(LibError::WrongCommissionArguments.into());
 }
 
+/// Checks that the commission charges and the deposits of the transaction are paired 1:1:
+/// every charge is followed by a deposit and every deposit follows a charge.
+fn verify_commission_pairing<'a>(
+    program_id: &Pubkey,
+    admin: &BridgeAdmin,
+    instruction_sysvar_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let mut previous_is_charge = false;
+    let mut index = 0;
+
+    while let Ok(instruction) = load_instruction_at_checked(index, instruction_sysvar_info) {
+        let is_charge = is_commission_charge_instruction(admin, &instruction);
+        let is_deposit = is_deposit_instruction(program_id, &instruction);
+
+        if previous_is_charge != is_deposit {
+            msg!("Commission charge and deposit are not paired at instruction {}", index);
+            return Err(LibError::WrongCommissionArguments.into());
+        }
+
+        previous_is_charge = is_charge;
+        index += 1;
+    }
+
+    if previous_is_charge {
+        msg!("Commission charge is not followed by a deposit");
+        return Err(LibError::WrongCommissionArguments.into());
+    }
+
+    Ok(())
+}
+
+fn is_deposit_instruction(program_id: &Pubkey, instruction: &solana_program::instruction::Instruction) -> bool {
+    if instruction.program_id != *program_id {
+        return false;
+    }
+
+    matches!(
+        BridgeInstruction::try_from_slice(instruction.data.as_slice()),
+        Ok(BridgeInstruction::DepositNative(_)) |
+        Ok(BridgeInstruction::DepositFT(_)) |
+        Ok(BridgeInstruction::DepositNFT(_)) |
+        Ok(BridgeInstruction::DepositNFTBatch(_))
+    )
+}
+
+fn is_commission_charge_instruction(admin: &BridgeAdmin, instruction: &solana_program::instruction::Instruction) -> bool {
+    if instruction.program_id != admin.commission_program {
+        return false;
+    }
+
+    matches!(
+        lib::instructions::commission::CommissionInstruction::try_from_slice(instruction.data.as_slice()),
+        Ok(lib::instructions::commission::CommissionInstruction::ChargeCommission(_))
+    )
+}
+
 pub fn process_create_collection<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
@@ -850,6 +3983,7 @@ pub fn process_create_collection<'a>(
     data: SignedMetadata,
     token_seed: [u8; 32],
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 10, "MintCollection")?;
     let account_info_iter = &mut accounts.iter();
     let bridge_admin_info = next_account_info(account_info_iter)?;
 
@@ -875,16 +4009,23 @@ pub fn process_create_collection<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+
     if *bridge_associated_info.key !=
         get_associated_token_address(&bridge_admin_key, mint_info.key) {
         return Err(LibError::WrongTokenAccount.into());
     }
 
-    let (mint_key, _) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
+    let (mint_key, bump_seed) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
     if mint_key != *mint_info.key {
         return Err(LibError::WrongTokenSeed.into());
     }
 
+    if !mint_info.data_is_empty() {
+        msg!("Collection mint {} already exists", mint_info.key);
+        return Err(LibError::AlreadyInUse.into());
+    }
+
     msg!("Creating mint account");
     lib::call_create_account(
         payer_info,
@@ -893,7 +4034,7 @@ pub fn process_create_collection<'a>(
         system_program,
         Mint::LEN,
         &spl_token::id(),
-        &[],
+        &[token_seed.as_slice(), &[bump_seed]],
     )?;
 
     msg!("Initializing mint account");
@@ -922,6 +4063,7 @@ pub fn process_create_collection<'a>(
         bridge_admin_info,
         seeds,
         1,
+        None,
     )?;
 
     msg!("Creating metadata account");
@@ -951,6 +4093,7 @@ fn try_mint_token_with_meta<'a>(
     rent_info: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
     seeds: [u8; 32],
+    mint_cap_info: Option<&AccountInfo<'a>>,
 ) -> ProgramResult {
     let (mint_key, bump_seed) = Pubkey::find_program_address(&[token_seed.as_slice()], program_id);
     if mint_key != *mint_info.key {
@@ -985,6 +4128,33 @@ fn try_mint_token_with_meta<'a>(
             signed_meta.decimals,
         )?;
 
+        if let (Some(max_supply), Some(mint_cap_info)) = (signed_meta.max_supply, mint_cap_info) {
+            let (mint_cap_key, mint_cap_bump) = Pubkey::find_program_address(
+                &[lib::MINT_CAP_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref(), mint_info.key.as_ref()],
+                program_id,
+            );
+            if mint_cap_key != *mint_cap_info.key {
+                return Err(LibError::WrongSeeds.into());
+            }
+
+            msg!("Creating mint cap account");
+            lib::call_create_account(
+                owner_info,
+                mint_cap_info,
+                rent_info,
+                system_program,
+                MINT_CAP_SIZE,
+                program_id,
+                &[lib::MINT_CAP_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref(), mint_info.key.as_ref(), &[mint_cap_bump]],
+            )?;
+
+            let mint_cap = MintCap {
+                max_supply,
+                is_initialized: true,
+            };
+            mint_cap.serialize(&mut *mint_cap_info.data.borrow_mut())?;
+        }
+
         msg!("Creating metadata account");
         call_create_metadata(
             metadata_info,
@@ -1003,6 +4173,31 @@ fn try_mint_token_with_meta<'a>(
 }
 
 
+fn call_close_account<'a>(
+    account_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    authority_info: &AccountInfo<'a>,
+    seeds: [u8; 32],
+) -> ProgramResult {
+    let close_account_instruction = close_account(
+        &spl_token::id(),
+        account_info.key,
+        destination_info.key,
+        authority_info.key,
+        &[],
+    )?;
+
+    invoke_signed(
+        &close_account_instruction,
+        &[
+            account_info.clone(),
+            destination_info.clone(),
+            authority_info.clone(),
+        ],
+        &[&[seeds.as_slice()]],
+    )
+}
+
 fn call_burn_token<'a>(
     associated_info: &AccountInfo<'a>,
     mint_info: &AccountInfo<'a>,
@@ -1055,13 +4250,123 @@ fn call_transfer_token<'a>(
     )
 }
 
+// Verifies the mint and its decimals, so the wrong asset can not be moved for the wrong mint account
+fn call_transfer_token_checked<'a>(
+    from: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    to: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    decimals: u8,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let transfer_tokens_instruction = transfer_checked(
+        &spl_token::id(),
+        from.key,
+        mint.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+
+    invoke_signed(
+        &transfer_tokens_instruction,
+        &[
+            from.clone(),
+            mint.clone(),
+            to.clone(),
+            authority.clone(),
+        ],
+        signers_seeds,
+    )
+}
+
+// In the sponsored mode the rent is moved from the bridge admin lamports to the account address before the creation:
+// bridge admin holds data, so it can not be the payer of the system transfer, and the associated token program
+// transfers from the payer only the lacking part of the rent.
+fn create_bridge_associated_account<'a>(
+    bridge_admin: &BridgeAdmin,
+    bridge_admin_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    bridge_associated_info: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    if bridge_admin.sponsor_token_accounts {
+        let rent = Rent::from_account_info(rent_info)?;
+        let required = rent.minimum_balance(spl_token::state::Account::LEN).saturating_sub(bridge_associated_info.lamports());
+
+        // Bridge admin should stay rent-exempt after paying
+        let available = bridge_admin_info.lamports().saturating_sub(rent.minimum_balance(bridge_admin_info.data_len()));
+        if available < required {
+            msg!("Bridge admin can not pay {} lamports of the token account rent", required);
+            return Err(LibError::WrongBalance.into());
+        }
+
+        transfer_lamports(bridge_admin_info, bridge_associated_info, required)?;
+    }
+
+    lib::call_create_associated_account(
+        payer_info,
+        bridge_admin_info,
+        mint_info,
+        bridge_associated_info,
+        rent_info,
+        system_program,
+        token_program,
+    )
+}
+
+// Moves lamports from the account owned by bridge program, fails with WrongBalance instead of overflow
+fn transfer_lamports<'a>(
+    from: &AccountInfo<'a>,
+    to: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let from_lamports = from.lamports().checked_sub(amount).ok_or(LibError::WrongBalance)?;
+    let to_lamports = to.lamports().checked_add(amount).ok_or(LibError::WrongBalance)?;
+
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+fn call_sync_native<'a>(
+    account: &AccountInfo<'a>,
+) -> ProgramResult {
+    let sync_native_instruction = sync_native(
+        &spl_token::id(),
+        account.key,
+    )?;
+
+    invoke(
+        &sync_native_instruction,
+        &[
+            account.clone(),
+        ],
+    )
+}
+
 fn call_mint_to<'a>(
     mint: &AccountInfo<'a>,
     account: &AccountInfo<'a>,
     owner: &AccountInfo<'a>,
     seeds: [u8; 32],
     amount: u64,
+    max_supply: Option<u64>,
 ) -> ProgramResult {
+    if let Some(max_supply) = max_supply {
+        let supply = Mint::unpack_from_slice(&mint.data.borrow())?.supply;
+        if supply.checked_add(amount).map_or(true, |supply| supply > max_supply) {
+            msg!("Minting {} tokens exceeds the max supply {}", amount, max_supply);
+            return Err(LibError::MaxSupplyExceeded.into());
+        }
+    }
+
     let mint_to_instruction = mint_to(
         &spl_token::id(),
         mint.key,
@@ -1082,6 +4387,31 @@ fn call_mint_to<'a>(
     )
 }
 
+fn call_thaw_account<'a>(
+    account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    freeze_authority: &AccountInfo<'a>,
+    seeds: [u8; 32],
+) -> ProgramResult {
+    let thaw_instruction = spl_token::instruction::thaw_account(
+        &spl_token::id(),
+        account.key,
+        mint.key,
+        freeze_authority.key,
+        &[],
+    )?;
+
+    invoke_signed(
+        &thaw_instruction,
+        &[
+            account.clone(),
+            mint.clone(),
+            freeze_authority.clone(),
+        ],
+        &[&[&seeds]],
+    )
+}
+
 fn call_init_mint<'a>(
     mint: &AccountInfo<'a>,
     mint_authority: &AccountInfo<'a>,
@@ -1105,6 +4435,32 @@ fn call_init_mint<'a>(
     )
 }
 
+fn call_update_metadata<'a>(
+    metadata_account: &AccountInfo<'a>,
+    update_authority: &AccountInfo<'a>,
+    data: DataV2,
+    seeds: [u8; 32],
+) -> ProgramResult {
+    let update_metadata_instruction = update_metadata_accounts_v2(
+        mpl_token_metadata::id(),
+        *metadata_account.key,
+        *update_authority.key,
+        None,
+        Some(data),
+        None,
+        None,
+    );
+
+    invoke_signed(
+        &update_metadata_instruction,
+        &[
+            metadata_account.clone(),
+            update_authority.clone(),
+        ],
+        &[&[&seeds]],
+    )
+}
+
 fn call_create_metadata<'a>(
     metadata_account: &AccountInfo<'a>,
     mint: &AccountInfo<'a>,
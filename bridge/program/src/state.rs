@@ -1,24 +1,179 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
+use solana_program::entrypoint::ProgramResult;
 use solana_program::secp256k1_recover::SECP256K1_PUBLIC_KEY_LENGTH;
-use lib::TokenType;
-use lib::instructions::bridge::{MAX_NETWORKS_SIZE, MAX_ADDRESS_SIZE};
+use lib::ecdsa::{verify_ecdsa_signer, ETH_ADDRESS_LENGTH};
+use lib::{Network, NetworkFormat, TokenType};
+use lib::merkle::HashAlgo;
+use lib::error::LibError;
+use lib::instructions::bridge::{MAX_NETWORKS_SIZE, MAX_SUPPORTED_NETWORKS};
 use std::mem::size_of;
 
-pub const BRIDGE_ADMIN_SIZE: usize = SECP256K1_PUBLIC_KEY_LENGTH + (32 as usize) + 1;
-pub const WITHDRAW_SIZE: usize = size_of::<TokenType>() + (32 as usize) + (8 as usize) + MAX_NETWORKS_SIZE + MAX_ADDRESS_SIZE + 1;
+// version | public_key | commission_program | is_initialized | appended fields
+pub const BRIDGE_ADMIN_SIZE: usize = 1 + SECP256K1_PUBLIC_KEY_LENGTH + (32 as usize) + 1 +
+    (8 as usize) + (8 as usize) + 1 + (8 as usize) + (8 as usize) + (2 as usize) + 1 + ETH_ADDRESS_LENGTH + 1 + 1 + (8 as usize) +
+    1 + SECP256K1_PUBLIC_KEY_LENGTH + (8 as usize) + 1 + (8 as usize);
+// version | token_type | mint | amount | origin | receiver_address | is_initialized | claimed.
+// Borsh writes the token type as one byte, its in-memory size is bigger.
+pub const WITHDRAW_SIZE: usize = 1 + 1 + 1 + (32 as usize) + (8 as usize) + (32 as usize) + (32 as usize) + 1 + (8 as usize);
+pub const SUPPORTED_NETWORKS_SIZE: usize = 4 + MAX_SUPPORTED_NETWORKS * (4 + MAX_NETWORKS_SIZE) + 1 + 4 + MAX_SUPPORTED_NETWORKS * (4 + MAX_NETWORKS_SIZE + 1);
+pub const WITHDRAW_ANNOUNCEMENT_SIZE: usize = (32 as usize) + (8 as usize) + (32 as usize) + (8 as usize) + 1 + 1;
+pub const TOKEN_CONFIG_SIZE: usize = (32 as usize) + 1 + 1 + 1 + 1 + 1 + (8 as usize);
+pub const COLLECTION_DELEGATE_SIZE: usize = (32 as usize) + 1;
+pub const AUTHORIZED_ORIGINS_SIZE: usize = (32 as usize) + SECP256K1_PUBLIC_KEY_LENGTH + 1 + 1;
+pub const MINT_CAP_SIZE: usize = (8 as usize) + 1;
+// BridgeAdmin of the first program version, see LegacyBridgeAdmin
+pub const LEGACY_BRIDGE_ADMIN_SIZE: usize = SECP256K1_PUBLIC_KEY_LENGTH + 1;
+// Accounts of the deployed program version without the version byte, see BaselineBridgeAdmin and BaselineWithdraw.
+// Withdraw accounts of that version reserved the network (20 bytes) and address (100 bytes) strings.
+pub const BASELINE_BRIDGE_ADMIN_SIZE: usize = SECP256K1_PUBLIC_KEY_LENGTH + (32 as usize) + 1;
+pub const BASELINE_WITHDRAW_SIZE: usize = size_of::<TokenType>() + (32 as usize) + (8 as usize) + 20 + 100 + 1;
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct BridgeAdmin {
+    // Layout version, see lib::STATE_VERSION
+    pub version: u8,
     pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     pub commission_program: Pubkey,
     pub is_initialized: bool,
+    // The fields below are appended to the deployed layout, see MigrateState
+    // Minimal amount of native deposits in lamports, see TokenConfig for the fungible tokens
+    pub min_deposit: u64,
+    // Nonce for the management operations signed by admin
+    pub nonce: u64,
+    // Deposits and withdrawals require the bridge associated accounts to be created by InitBridgeTokenAccount
+    pub strict_token_accounts: bool,
+    // Delay in slots for the announced withdrawals, zero disables announcements
+    pub withdraw_delay: u64,
+    // Withdrawals with the bigger amount should be announced before execution
+    pub withdraw_delay_threshold: u64,
+    // Part of native and fungible token withdrawals in basis points kept by the bridge
+    pub withdraw_fee_bps: u16,
+    // Signatures are verified against this Ethereum address instead of the public key if set
+    pub eth_address: Option<[u8; ETH_ADDRESS_LENGTH]>,
+    // Hash function of the withdrawal leaves and Merkle trees, management operations are always keccak
+    pub hash_algo: HashAlgo,
+    // Rent of the bridge token accounts created by deposits is paid from the bridge admin lamports
+    pub sponsor_token_accounts: bool,
+    // Max amount of a single native or FT withdrawal, zero disables the limit
+    pub max_single_withdraw: u64,
+    // Key that replaces public_key by the PromoteBackup request and confirmation
+    pub backup_key: Option<[u8; SECP256K1_PUBLIC_KEY_LENGTH]>,
+    // Min delay in slots between the promotion request and confirmation
+    pub promote_timelock: u64,
+    // Slot of the pending promotion request, the admin key can cancel it
+    pub promote_requested_slot: Option<u64>,
+}
+
+impl BridgeAdmin {
+    pub fn verify_signature(&self, hash: &[u8], signature: &[u8], recovery_id: u8) -> ProgramResult {
+        verify_ecdsa_signer(hash, signature, recovery_id, self.public_key, self.eth_address)
+    }
+
+    pub fn verify_backup_signature(&self, hash: &[u8], signature: &[u8], recovery_id: u8) -> ProgramResult {
+        match self.backup_key {
+            Some(backup_key) => verify_ecdsa_signer(hash, signature, recovery_id, backup_key, None),
+            None => Err(LibError::NotInitialized.into()),
+        }
+    }
+
+    // Current layout of the migrated admin, the settings appended to the deployed layout are reset
+    pub fn migrated(public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH], commission_program: Pubkey) -> Self {
+        BridgeAdmin {
+            version: lib::STATE_VERSION,
+            public_key,
+            commission_program,
+            is_initialized: true,
+            min_deposit: 0,
+            nonce: 0,
+            strict_token_accounts: false,
+            withdraw_delay: 0,
+            withdraw_delay_threshold: 0,
+            withdraw_fee_bps: 0,
+            eth_address: None,
+            hash_algo: HashAlgo::Keccak,
+            sponsor_token_accounts: false,
+            max_single_withdraw: 0,
+            backup_key: None,
+            promote_timelock: 0,
+            promote_requested_slot: None,
+        }
+    }
+}
+
+// Layout of the BridgeAdmin of the deployed program version (no version byte), moved to BridgeAdmin by MigrateState
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct BaselineBridgeAdmin {
+    pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub commission_program: Pubkey,
+    pub is_initialized: bool,
+}
+
+// Layout of the BridgeAdmin created by the first program version, moved to BridgeAdmin by MigrateAdmin
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct LegacyBridgeAdmin {
+    pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub is_initialized: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum OperationType {
+    SetMinDeposit,
+    SetStrictTokenAccounts,
+    SetWithdrawDelay,
+    UpdateTokenMetadata,
+    EmergencyWithdraw,
+    SetNetworks,
+    SetTokenConfig,
+    ReclaimMint,
+    SweepDust,
+    RegisterCollectionDelegate,
+    SetWithdrawFee,
+    SetEthAddress,
+    SetSponsorTokenAccounts,
+    SetMaxSingleWithdraw,
+    SetNetworkFormats,
+    SetBackupKey,
+    CancelPromotion,
+    VetoWithdraw,
+    RevokeRoot,
+}
+
+impl std::convert::Into<u8> for OperationType {
+    fn into(self) -> u8 {
+        match self {
+            OperationType::SetMinDeposit => 0,
+            OperationType::SetStrictTokenAccounts => 1,
+            OperationType::SetWithdrawDelay => 2,
+            OperationType::UpdateTokenMetadata => 3,
+            OperationType::EmergencyWithdraw => 4,
+            OperationType::SetNetworks => 5,
+            OperationType::SetTokenConfig => 6,
+            OperationType::ReclaimMint => 7,
+            OperationType::SweepDust => 8,
+            OperationType::RegisterCollectionDelegate => 9,
+            OperationType::SetWithdrawFee => 10,
+            OperationType::SetEthAddress => 11,
+            OperationType::SetSponsorTokenAccounts => 12,
+            OperationType::SetMaxSingleWithdraw => 13,
+            OperationType::SetNetworkFormats => 14,
+            OperationType::SetBackupKey => 15,
+            OperationType::CancelPromotion => 16,
+            OperationType::VetoWithdraw => 17,
+            OperationType::RevokeRoot => 18,
+        }
+    }
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct Withdraw {
+    // Layout version, see lib::STATE_VERSION
+    pub version: u8,
     pub token_type: lib::TokenType,
     pub mint: Option<Pubkey>,
     pub amount: u64,
@@ -26,4 +181,150 @@ pub struct Withdraw {
     pub origin: [u8; 32],
     pub receiver_address: Pubkey,
     pub is_initialized: bool,
-}
\ No newline at end of file
+    // Appended to the deployed layout. Already withdrawn part of the amount (partial FT withdrawals)
+    pub claimed: u64,
+}
+
+impl Withdraw {
+    // Only the accounts of the current layout are read. The accounts of the other versions (the deployed ones
+    // without the version byte are recognized by the size) belong to the completed withdrawals.
+    pub fn is_current_version(data: &[u8]) -> bool {
+        data.len() == WITHDRAW_SIZE && data[0] == lib::STATE_VERSION
+    }
+}
+
+// Layout of the Withdraw of the deployed program version (no version byte), moved to Withdraw by MigrateState
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct BaselineWithdraw {
+    pub token_type: lib::TokenType,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub origin: [u8; 32],
+    pub receiver_address: Pubkey,
+    pub is_initialized: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct WithdrawAnnouncement {
+    // Hash of deposit tx info. See spec in core for more information.
+    pub origin: [u8; 32],
+    pub amount: u64,
+    // Signed withdraw content leaf
+    pub leaf: [u8; 32],
+    pub announce_slot: u64,
+    // Vetoed announcement stays as the tombstone, the withdrawal can not be announced or made again
+    pub vetoed: bool,
+    pub is_initialized: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SupportedNetworks {
+    // Networks supported as the deposit target
+    pub networks: Vec<Network>,
+    pub is_initialized: bool,
+    // Receiver address formats of the networks, after is_initialized so the accounts created before are read without formats
+    pub formats: Vec<NetworkFormat>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct TokenConfig {
+    pub mint: Pubkey,
+    pub deposit_enabled: bool,
+    pub withdraw_enabled: bool,
+    pub is_initialized: bool,
+    // Decimals of the amount in the cross-chain message, the mint decimals if not set.
+    // Stored after is_initialized, the shorter accounts created before are read without it (see read_token_config)
+    pub target_decimals: Option<u8>,
+    // Minimal deposit amount in the mint base units, zero disables the check
+    pub min_deposit: u64,
+}
+
+// External collection that approved the bridge admin as the collection authority
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CollectionDelegate {
+    pub collection_mint: Pubkey,
+    pub is_initialized: bool,
+}
+
+// Signed Merkle root of the withdraw contents claimable without the signature (see ClaimAuthorized)
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct AuthorizedOrigins {
+    pub root: [u8; 32],
+    // Admin key that signed the root, the claims fail after the key is rotated
+    pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    // Revoked root stays as the tombstone, so the signature can not be used to authorize it again
+    pub revoked: bool,
+    pub is_initialized: bool,
+}
+
+// capacity | next | batches | public_key | roots | is_initialized
+pub fn stored_roots_size(capacity: u16) -> usize {
+    2 + 2 + (8 as usize) + SECP256K1_PUBLIC_KEY_LENGTH + 4 + (capacity as usize) * 32 + 1
+}
+
+// Ring buffer of the signed Merkle roots of the withdraw contents claimable without the signature (see ClaimStored)
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct StoredRoots {
+    pub capacity: u16,
+    // Index of the slot for the next root, the oldest root when the buffer is full
+    pub next: u16,
+    // Count of the stored batches, signed with every batch to prevent the replay
+    pub batches: u64,
+    // Admin key that signed the roots, the roots of the replaced key are dropped by the next batch and can not be claimed
+    pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub roots: Vec<[u8; 32]>,
+    pub is_initialized: bool,
+}
+
+impl StoredRoots {
+    // Adds the root, evicting the oldest one if the buffer is full
+    pub fn push(&mut self, root: [u8; 32]) {
+        if self.roots.len() < self.capacity as usize {
+            self.roots.push(root);
+        } else {
+            self.roots[self.next as usize] = root;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    // Changes the capacity, keeping the newest roots
+    pub fn resize(&mut self, capacity: u16) {
+        if capacity == self.capacity {
+            return;
+        }
+
+        // Oldest root first
+        self.roots.rotate_left(self.next as usize);
+        let evicted = self.roots.len().saturating_sub(capacity as usize);
+        self.roots.drain(..evicted);
+        self.capacity = capacity;
+        self.next = (self.roots.len() % capacity as usize) as u16;
+    }
+
+    // Removes the root, keeping the order of the rest roots
+    pub fn remove(&mut self, root: &[u8; 32]) {
+        // Oldest root first
+        self.roots.rotate_left(self.next as usize);
+        self.roots.retain(|stored| stored != root);
+        self.next = (self.roots.len() % self.capacity as usize) as u16;
+    }
+
+    pub fn contains(&self, root: &[u8; 32]) -> bool {
+        self.roots.contains(root)
+    }
+}
+
+// Supply cap of the FT mint created by the withdrawal with the signed `max_supply`
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct MintCap {
+    pub max_supply: u64,
+    pub is_initialized: bool,
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Bridge, Env, RECEIVER};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, BridgeInstruction, MintCollectionArgs, SignedMetadata, WithdrawArgs, WithdrawValidation};
+use lib::merkle::{HashAlgo, MerkleProof};
+use lib::{CommissionToken, Network, TokenType};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+const SIGNATURE: [u8; 64] = [0; 64];
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Passes one account less than the processor expects, the builder should pass at least `count` accounts
+fn assert_expects(env: &mut Env, name: &str, mut instruction: Instruction, count: usize) {
+    assert!(instruction.accounts.len() >= count, "{} builder passes {} accounts, expected {}", name, instruction.accounts.len(), count);
+    instruction.accounts.truncate(count - 1);
+    assert_eq!(env.process(&instruction), Err(LibError::NotEnoughAccounts.into()), "{}", name);
+}
+
+fn metadata(decimals: u8) -> SignedMetadata {
+    SignedMetadata { name: String::from("Rarimo"), symbol: String::from("RMO"), uri: String::from("https://rarimo.com/token.json"), decimals, max_supply: None }
+}
+
+fn withdraw_args(bridge: &Bridge) -> WithdrawArgs {
+    WithdrawArgs {
+        origin: ORIGIN,
+        amount: 1,
+        claim_amount: None,
+        signature: SIGNATURE,
+        recovery_id: 0,
+        path: MerkleProof(vec![[1; 32]]),
+        seeds: bridge.seeds,
+        token_seed: None,
+        signed_meta: None,
+        eip712_domain_separator: None,
+        strict_ata: false,
+        origin_preimage: None,
+        no_metadata: false,
+    }
+}
+
+#[test]
+fn bridge_instructions_check_account_count() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (program_id, admin, seeds) = (bridge.program_id, bridge.admin, bridge.seeds);
+    let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let receiver = || String::from(RECEIVER);
+    let withdraw_native = bridge.withdraw_native(&owner, ORIGIN, 1);
+    let withdraw = bridge.withdraw_address(&ORIGIN);
+
+    let cases = vec![
+        ("InitializeAdmin", instructions::initialize_admin(program_id, admin, owner, bridge.commission_program, [1; 64], seeds, HashAlgo::Keccak), 4),
+        ("TransferOwnership", instructions::transfer_ownership(program_id, admin, SIGNATURE, [1; 64], 0, seeds), 1),
+        ("DepositNative", instructions::deposit_native(program_id, admin, owner, seeds, Network::Ethereum, 1, receiver(), None, None), 6),
+        ("DepositNativeWithCommission", instructions::deposit_native_with_commission(
+            program_id, bridge.commission_program, admin, owner, seeds, Network::Ethereum, 1, receiver(), None, None, CommissionToken::Native, 0,
+        ), 9),
+        ("DepositFT", instructions::deposit_ft(program_id, admin, mint, owner, seeds, Network::Ethereum, receiver(), 1, None, None, None), 12),
+        ("DepositNFT", instructions::deposit_nft(program_id, admin, mint, owner, seeds, Network::Ethereum, receiver(), None, None, None), 12),
+        ("DepositNFTBatch", instructions::deposit_nft_batch(program_id, admin, owner, seeds, Network::Ethereum, receiver(), vec![mint], vec![None]), 8),
+        ("WithdrawNative", withdraw_native.clone(), 5),
+        ("WithdrawFT", instructions::withdraw_ft(
+            program_id, admin, mint, owner, withdraw, seeds, ORIGIN, 1, None, SIGNATURE, 0, vec![[1; 32]], None, None, None, false, None, false, None,
+        ), 14),
+        ("WithdrawNFT", instructions::withdraw_nft(
+            program_id, admin, mint, Pubkey::new_unique(), owner, withdraw, seeds, ORIGIN, 1, SIGNATURE, 0, vec![[1; 32]], None, None, None, false, None, None,
+        ), 13),
+        ("SetMinDeposit", instructions::set_min_deposit(program_id, admin, seeds, 1, SIGNATURE, 0, vec![]), 1),
+        ("InitBridgeTokenAccount", instructions::init_bridge_token_account(program_id, admin, mint, owner, seeds), 8),
+        ("SetStrictTokenAccounts", instructions::set_strict_token_accounts(program_id, admin, seeds, true, SIGNATURE, 0, vec![]), 1),
+        ("SetSponsorTokenAccounts", instructions::set_sponsor_token_accounts(program_id, admin, seeds, true, SIGNATURE, 0, vec![]), 1),
+        ("SetMaxSingleWithdraw", instructions::set_max_single_withdraw(program_id, admin, seeds, 1, SIGNATURE, 0, vec![]), 1),
+        ("SetBackupKey", instructions::set_backup_key(program_id, admin, seeds, None, 0, SIGNATURE, 0, vec![]), 1),
+        ("PromoteBackup", instructions::promote_backup(program_id, admin, seeds, true, SIGNATURE, 0), 1),
+        ("CancelPromotion", instructions::cancel_promotion(program_id, admin, seeds, SIGNATURE, 0, vec![]), 1),
+        ("SetWithdrawDelay", instructions::set_withdraw_delay(program_id, admin, seeds, 1, 1, SIGNATURE, 0, vec![]), 1),
+        ("SetWithdrawFee", instructions::set_withdraw_fee(program_id, admin, seeds, 1, SIGNATURE, 0, vec![]), 1),
+        ("SetEthAddress", instructions::set_eth_address(program_id, admin, seeds, None, SIGNATURE, 0, vec![]), 1),
+        ("AuthorizeOrigins", instructions::authorize_origins(program_id, admin, seeds, owner, [1; 32], SIGNATURE, 0), 5),
+        ("ClaimAuthorized", instructions::claim_authorized(withdraw_native.clone(), admin, [1; 32]), 1),
+        ("RevokeRoot", instructions::revoke_root(program_id, admin, seeds, owner, [1; 32], SIGNATURE, 0, vec![]), 6),
+        ("StoreRoots", instructions::store_roots(program_id, admin, seeds, owner, vec![[1; 32]], 1, SIGNATURE, 0), 5),
+        ("ClaimStored", instructions::claim_stored(withdraw_native.clone(), admin, [1; 32]), 1),
+        ("AnnounceWithdraw", instructions::announce_withdraw(program_id, admin, seeds, owner, ORIGIN, 1, [1; 32], SIGNATURE, 0, MerkleProof(vec![[1; 32]])), 5),
+        ("VetoWithdraw", instructions::veto_withdraw(program_id, admin, seeds, Pubkey::new_unique(), SIGNATURE, 0, vec![]), 2),
+        ("UpdateTokenMetadata", instructions::update_token_metadata(program_id, admin, seeds, [1; 32], metadata(6), SIGNATURE, 0, vec![]), 4),
+        ("EmergencyWithdraw", instructions::emergency_withdraw(program_id, admin, seeds, owner, vec![], SIGNATURE, 0, vec![]), 3),
+        ("SetNetworks", instructions::set_networks(program_id, admin, seeds, owner, vec![Network::Ethereum], SIGNATURE, 0, vec![]), 5),
+        ("SetNetworkFormats", instructions::set_network_formats(program_id, admin, seeds, owner, vec![], SIGNATURE, 0, vec![]), 4),
+        ("SetTokenConfig", instructions::set_token_config(program_id, admin, seeds, owner, mint, true, true, None, 0, SIGNATURE, 0, vec![]), 5),
+        ("ReclaimMint", instructions::reclaim_mint(program_id, admin, seeds, [1; 32], owner, SIGNATURE, 0, vec![]), 5),
+        ("SweepDust", instructions::sweep_dust(program_id, admin, seeds, owner, vec![mint], SIGNATURE, 0, vec![]), 4),
+        ("RegisterCollectionDelegate", instructions::register_collection_delegate(program_id, admin, seeds, owner, mint, SIGNATURE, 0, vec![]), 6),
+        ("MigrateAdmin", instructions::migrate_admin(program_id, admin, seeds, owner, bridge.commission_program, SIGNATURE, 0), 3),
+        ("MigrateState", instructions::migrate_state(program_id, admin, owner), 3),
+        ("MintCollection", Instruction {
+            program_id,
+            accounts: (0..10).map(|_| AccountMeta::new(Pubkey::new_unique(), false)).collect(),
+            data: BridgeInstruction::MintCollection(MintCollectionArgs { data: metadata(0), seeds, token_seed: [1; 32] }).try_to_vec().unwrap(),
+        }, 10),
+    ];
+
+    for (name, instruction, count) in cases {
+        assert_expects(&mut env, name, instruction, count);
+    }
+}
+
+#[test]
+fn validate_withdraw_reports_missing_accounts() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let mut instruction = instructions::validate_withdraw(
+        bridge.program_id, bridge.admin, Pubkey::new_unique(), bridge.withdraw_address(&ORIGIN), None, TokenType::Native, withdraw_args(&bridge),
+    );
+    assert!(instruction.accounts.len() >= 3);
+    instruction.accounts.truncate(2);
+
+    // Validation result is returned instead of failing the instruction
+    env.process(&instruction).unwrap();
+    let (_, data) = env.return_data().unwrap();
+    let validation = WithdrawValidation::deserialize(&mut data.as_slice()).unwrap();
+    assert!(!validation.valid);
+    assert_eq!(validation.error, Some(u64::from(ProgramError::from(LibError::NotEnoughAccounts))));
+}
+
+#[test]
+fn withdraw_nft_appends_collection_metadata() {
+    let (program_id, admin, mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let collection_metadata = Pubkey::new_unique();
+    let build = |collection_metadata| instructions::withdraw_nft(
+        program_id, admin, mint, Pubkey::new_unique(), owner, Pubkey::new_unique(), [1; 32], ORIGIN, 1, SIGNATURE, 0, vec![[1; 32]], None, None, None, false, None, collection_metadata,
+    );
+
+    assert_eq!(build(None).accounts.len(), 13);
+    let instruction = build(Some(collection_metadata));
+    assert_eq!(instruction.accounts.len(), 14);
+    assert_eq!(instruction.accounts[13], AccountMeta::new(collection_metadata, false));
+}
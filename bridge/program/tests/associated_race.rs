@@ -0,0 +1,81 @@
+mod common;
+
+use common::Env;
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+// Checks that the associated account is empty as the bridge does, then creates it with `call_create_associated_account`.
+// With the first input byte set the account is created by another instruction between the check and the call.
+// Accounts: payer, wallet, mint, associated account, rent, system program, token program.
+fn racing_processor<'a>(_program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    let [payer, wallet, mint, account, rent_info, system_program, spl_token] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !account.data_is_empty() {
+        return Ok(());
+    }
+
+    if input.first() == Some(&1) {
+        invoke(
+            &create_associated_token_account(payer.key, wallet.key, mint.key, spl_token.key),
+            &[payer.clone(), account.clone(), wallet.clone(), mint.clone(), system_program.clone(), spl_token.clone()],
+        )?;
+    }
+
+    lib::call_create_associated_account(payer, wallet, mint, account, rent_info, system_program, spl_token)
+}
+
+fn create(program_id: Pubkey, payer: &Pubkey, wallet: &Pubkey, mint: &Pubkey, race: bool) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(wallet, mint), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: vec![race as u8],
+    }
+}
+
+fn setup() -> (Env, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let program_id = Pubkey::new_unique();
+    env.add_program(program_id, racing_processor);
+    let (payer, wallet) = (env.new_funded(), Pubkey::new_unique());
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    (env, program_id, payer, wallet, mint)
+}
+
+#[test]
+fn missing_associated_account_is_created() {
+    let (mut env, program_id, payer, wallet, mint) = setup();
+    env.process(&create(program_id, &payer, &wallet, &mint, false)).unwrap();
+
+    let account = env.token_account(&get_associated_token_address(&wallet, &mint));
+    assert_eq!(account.owner, wallet);
+    assert_eq!(account.mint, mint);
+}
+
+#[test]
+fn associated_account_created_after_check_is_accepted() {
+    let (mut env, program_id, payer, wallet, mint) = setup();
+    env.process(&create(program_id, &payer, &wallet, &mint, true)).unwrap();
+
+    let account = env.token_account(&get_associated_token_address(&wallet, &mint));
+    assert_eq!(account.owner, wallet);
+    assert_eq!(account.mint, mint);
+    assert_eq!(account.amount, 0);
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use bridge::state::{AuthorizedOrigins, OperationType};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env, Signer};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, get_authorized_origins_address};
+use lib::merkle::{get_merkle_root_with, Content, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+// Native withdrawals of the authorized tree
+struct Claim {
+    owner: Pubkey,
+    origin: [u8; 32],
+    amount: u64,
+}
+
+impl Claim {
+    fn leaf(&self, bridge: &Bridge) -> [u8; 32] {
+        let content = Content::new(self.origin, self.owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(self.amount)));
+        content.hash_with(bridge.hash_algo)
+    }
+
+    // Claim of the withdrawal with the proof to the authorized root, the signature is not set
+    fn instruction(&self, bridge: &Bridge, path: Vec<[u8; 32]>, root: [u8; 32]) -> Instruction {
+        let withdraw = instructions::withdraw_native(
+            bridge.program_id, bridge.admin, self.owner, bridge.withdraw_address(&self.origin), bridge.seeds, self.origin, self.amount,
+            [0; 64], 0, path, None, None, None, false, None,
+        );
+        instructions::claim_authorized(withdraw, bridge.admin, root)
+    }
+}
+
+fn revoke(env: &mut Env, bridge: &Bridge, root: [u8; 32]) -> Result<(), ProgramError> {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::RevokeRoot, root.to_vec());
+    let payer = env.new_funded();
+    env.process(&instructions::revoke_root(bridge.program_id, bridge.admin, bridge.seeds, payer, root, signature, recovery_id, vec![]))
+}
+
+fn authorize(env: &mut Env, bridge: &Bridge, signer: &Signer, root: [u8; 32]) -> Result<(), ProgramError> {
+    let hash = solana_program::keccak::hashv(&[lib::BRIDGE_DOMAIN, lib::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge.admin.as_ref(), root.as_slice()]);
+    let (signature, recovery_id) = signer.sign(&hash.to_bytes());
+    let payer = env.new_funded();
+    env.process(&instructions::authorize_origins(bridge.program_id, bridge.admin, bridge.seeds, payer, root, signature, recovery_id))
+}
+
+// Two withdrawals authorized by one root
+fn setup() -> (Env, Bridge, [Claim; 2], [u8; 32]) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 10_000);
+
+    let claims = [
+        Claim { owner: env.new_funded(), origin: [7; 32], amount: 1000 },
+        Claim { owner: env.new_funded(), origin: [8; 32], amount: 2000 },
+    ];
+    let root = get_merkle_root_with(bridge.hash_algo, claims[0].leaf(&bridge), &vec![claims[1].leaf(&bridge)]).unwrap();
+    authorize(&mut env, &bridge, &bridge.signer, root).unwrap();
+    (env, bridge, claims, root)
+}
+
+#[test]
+fn authorized_origins_are_claimed() {
+    let (mut env, bridge, claims, root) = setup();
+    let stored = AuthorizedOrigins::deserialize(&mut env.data(&get_authorized_origins_address(&bridge.program_id, &bridge.admin, &root)).as_slice()).unwrap();
+    assert_eq!(stored, AuthorizedOrigins { root, public_key: bridge.signer.public_key(), revoked: false, is_initialized: true });
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&claims[0].instruction(&bridge, vec![claims[1].leaf(&bridge)], root)).unwrap();
+    env.process(&claims[1].instruction(&bridge, vec![claims[0].leaf(&bridge)], root)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 3000);
+    assert!(env.exists(&bridge.withdraw_address(&claims[0].origin)));
+
+    // Claimed origin is spent
+    assert_eq!(
+        env.process(&claims[0].instruction(&bridge, vec![claims[1].leaf(&bridge)], root)),
+        Err(LibError::AlreadyInUse.into()),
+    );
+}
+
+#[test]
+fn unauthorized_origin_is_rejected() {
+    let (mut env, bridge, claims, root) = setup();
+
+    let other = Claim { owner: claims[0].owner, origin: [9; 32], amount: 1000 };
+    assert_eq!(
+        env.process(&other.instruction(&bridge, vec![claims[1].leaf(&bridge)], root)),
+        Err(LibError::WrongMerkleRoot.into()),
+    );
+
+    // The authorized amount can not be changed
+    let changed = Claim { owner: claims[0].owner, origin: claims[0].origin, amount: 5000 };
+    assert_eq!(
+        env.process(&changed.instruction(&bridge, vec![claims[1].leaf(&bridge)], root)),
+        Err(LibError::WrongMerkleRoot.into()),
+    );
+    assert!(!env.exists(&bridge.withdraw_address(&other.origin)));
+}
+
+#[test]
+fn root_signed_by_other_key_is_not_stored() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let root = [5; 32];
+
+    assert_eq!(authorize(&mut env, &bridge, &Signer::new(2), root), Err(LibError::WrongSignature.into()));
+    assert!(!env.exists(&get_authorized_origins_address(&bridge.program_id, &bridge.admin, &root)));
+}
+
+#[test]
+fn revoked_root_is_not_claimed() {
+    let (mut env, bridge, claims, root) = setup();
+    env.process(&claims[0].instruction(&bridge, vec![claims[1].leaf(&bridge)], root)).unwrap();
+
+    revoke(&mut env, &bridge, root).unwrap();
+    assert_eq!(bridge.state(&env).nonce, 1);
+    assert_eq!(
+        env.process(&claims[1].instruction(&bridge, vec![claims[0].leaf(&bridge)], root)),
+        Err(LibError::RootRevoked.into()),
+    );
+    assert!(!env.exists(&bridge.withdraw_address(&claims[1].origin)));
+
+    // The tombstone can not be authorized again with the same signature
+    assert_eq!(authorize(&mut env, &bridge, &bridge.signer, root), Err(LibError::AlreadyInUse.into()));
+}
+
+#[test]
+fn root_is_revoked_before_authorization() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let root = [5; 32];
+
+    revoke(&mut env, &bridge, root).unwrap();
+    let stored = AuthorizedOrigins::deserialize(&mut env.data(&get_authorized_origins_address(&bridge.program_id, &bridge.admin, &root)).as_slice()).unwrap();
+    assert!(stored.revoked);
+    assert_eq!(authorize(&mut env, &bridge, &bridge.signer, root), Err(LibError::AlreadyInUse.into()));
+}
+
+#[test]
+fn unsigned_revocation_is_rejected() {
+    let (mut env, bridge, claims, root) = setup();
+
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::RevokeRoot, [9; 32].to_vec());
+    let payer = env.new_funded();
+    assert_eq!(
+        env.process(&instructions::revoke_root(bridge.program_id, bridge.admin, bridge.seeds, payer, root, signature, recovery_id, vec![])),
+        Err(LibError::WrongSignature.into()),
+    );
+    env.process(&claims[0].instruction(&bridge, vec![claims[1].leaf(&bridge)], root)).unwrap();
+}
+
+#[test]
+fn root_of_rotated_key_is_not_claimed() {
+    let (mut env, bridge, claims, root) = setup();
+
+    let new_key = Signer::new(2).public_key();
+    let (signature, recovery_id) = bridge.signer.sign(&solana_program::keccak::hashv(&[lib::BRIDGE_DOMAIN, new_key.as_slice()]).to_bytes());
+    env.process(&instructions::transfer_ownership(bridge.program_id, bridge.admin, signature, new_key, recovery_id, bridge.seeds)).unwrap();
+
+    assert_eq!(
+        env.process(&claims[0].instruction(&bridge, vec![claims[1].leaf(&bridge)], root)),
+        Err(LibError::RootRevoked.into()),
+    );
+}
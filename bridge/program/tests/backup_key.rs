@@ -0,0 +1,152 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env, Signer};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::amount_bytes;
+use solana_program::program_error::ProgramError;
+
+const TIMELOCK: u64 = 100;
+
+fn set_backup_key(env: &mut Env, bridge: &Bridge, backup: Option<&Signer>) -> Result<(), ProgramError> {
+    let backup_key = backup.map(Signer::public_key);
+    let mut data = backup_key.map(Vec::from).unwrap_or_default();
+    data.extend(amount_bytes(TIMELOCK));
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetBackupKey, data);
+    env.process(&instructions::set_backup_key(bridge.program_id, bridge.admin, bridge.seeds, backup_key, TIMELOCK, signature, recovery_id, vec![]))
+}
+
+// Promotion step signed by `signer` for the current nonce
+fn promote(env: &mut Env, bridge: &Bridge, signer: &Signer, confirm: bool) -> Result<(), ProgramError> {
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        lib::PROMOTE_BACKUP_TAG.as_bytes(),
+        bridge.admin.as_ref(),
+        bridge.state(env).nonce.to_be_bytes().as_slice(),
+        &[confirm as u8],
+    ]);
+    let (signature, recovery_id) = signer.sign(&hash.to_bytes());
+    env.process(&instructions::promote_backup(bridge.program_id, bridge.admin, bridge.seeds, confirm, signature, recovery_id))
+}
+
+fn cancel(env: &mut Env, bridge: &Bridge, requested_slot: u64) -> Result<(), ProgramError> {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::CancelPromotion, amount_bytes(requested_slot));
+    env.process(&instructions::cancel_promotion(bridge.program_id, bridge.admin, bridge.seeds, signature, recovery_id, vec![]))
+}
+
+// Bridge with the backup key of the signer 2 at slot 10
+fn setup() -> (Env, Bridge, Signer) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let backup = Signer::new(2);
+    set_backup_key(&mut env, &bridge, Some(&backup)).unwrap();
+    env.set_slot(10);
+    (env, bridge, backup)
+}
+
+#[test]
+fn backup_key_is_set() {
+    let (mut env, bridge, backup) = setup();
+    let state = bridge.state(&env);
+    assert_eq!(state.backup_key, Some(backup.public_key()));
+    assert_eq!(state.promote_timelock, TIMELOCK);
+    assert_eq!(state.promote_requested_slot, None);
+    assert_eq!(state.nonce, 1);
+
+    set_backup_key(&mut env, &bridge, None).unwrap();
+    assert_eq!(bridge.state(&env).backup_key, None);
+    assert_eq!(promote(&mut env, &bridge, &backup, false), Err(LibError::NotInitialized.into()));
+}
+
+#[test]
+fn promotion_is_requested() {
+    let (mut env, bridge, backup) = setup();
+    promote(&mut env, &bridge, &backup, false).unwrap();
+    assert_eq!(bridge.state(&env).promote_requested_slot, Some(10));
+    assert_eq!(bridge.state(&env).nonce, 2);
+
+    // The pending request is not restarted
+    env.set_slot(50);
+    assert_eq!(promote(&mut env, &bridge, &backup, false), Err(LibError::AlreadyInUse.into()));
+    assert_eq!(bridge.state(&env).promote_requested_slot, Some(10));
+}
+
+#[test]
+fn promotion_requires_backup_signature() {
+    let (mut env, bridge, _) = setup();
+    assert_eq!(promote(&mut env, &bridge, &bridge.signer, false), Err(LibError::WrongSignature.into()));
+    assert_eq!(promote(&mut env, &bridge, &Signer::new(3), false), Err(LibError::WrongSignature.into()));
+    assert_eq!(bridge.state(&env).promote_requested_slot, None);
+}
+
+#[test]
+fn confirmation_requires_request() {
+    let (mut env, bridge, backup) = setup();
+    env.set_slot(10 + TIMELOCK);
+    assert_eq!(promote(&mut env, &bridge, &backup, true), Err(LibError::NoPendingPromotion.into()));
+}
+
+#[test]
+fn promotion_is_confirmed_after_timelock() {
+    let (mut env, bridge, backup) = setup();
+    promote(&mut env, &bridge, &backup, false).unwrap();
+
+    env.set_slot(10 + TIMELOCK - 1);
+    assert_eq!(promote(&mut env, &bridge, &backup, true), Err(LibError::PromotionTimelocked.into()));
+    assert_eq!(bridge.state(&env).public_key, bridge.signer.public_key());
+
+    env.set_slot(10 + TIMELOCK);
+    promote(&mut env, &bridge, &backup, true).unwrap();
+    let state = bridge.state(&env);
+    assert_eq!(state.public_key, backup.public_key());
+    assert_eq!((state.backup_key, state.promote_requested_slot, state.eth_address), (None, None, None));
+
+    // The lost key can not sign the operations anymore
+    assert_eq!(set_backup_key(&mut env, &bridge, Some(&Signer::new(3))), Err(LibError::WrongSignature.into()));
+    let promoted = Bridge { signer: backup, ..bridge };
+    set_backup_key(&mut env, &promoted, Some(&Signer::new(3))).unwrap();
+}
+
+#[test]
+fn promotion_is_cancelled_by_admin_key() {
+    let (mut env, bridge, backup) = setup();
+    assert_eq!(cancel(&mut env, &bridge, 10), Err(LibError::NoPendingPromotion.into()));
+
+    promote(&mut env, &bridge, &backup, false).unwrap();
+    // Signed for another request
+    assert_eq!(cancel(&mut env, &bridge, 11), Err(LibError::WrongSignature.into()));
+    cancel(&mut env, &bridge, 10).unwrap();
+    assert_eq!(bridge.state(&env).promote_requested_slot, None);
+
+    env.set_slot(10 + TIMELOCK);
+    assert_eq!(promote(&mut env, &bridge, &backup, true), Err(LibError::NoPendingPromotion.into()));
+    assert_eq!(bridge.state(&env).public_key, bridge.signer.public_key());
+}
+
+#[test]
+fn cancelled_request_can_not_be_replayed() {
+    let (mut env, bridge, backup) = setup();
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        lib::PROMOTE_BACKUP_TAG.as_bytes(),
+        bridge.admin.as_ref(),
+        bridge.state(&env).nonce.to_be_bytes().as_slice(),
+        &[0],
+    ]);
+    let (signature, recovery_id) = backup.sign(&hash.to_bytes());
+    let request = instructions::promote_backup(bridge.program_id, bridge.admin, bridge.seeds, false, signature, recovery_id);
+    env.process(&request).unwrap();
+    cancel(&mut env, &bridge, 10).unwrap();
+
+    assert_eq!(env.process(&request), Err(LibError::WrongSignature.into()));
+    assert_eq!(bridge.state(&env).promote_requested_slot, None);
+}
+
+#[test]
+fn backup_key_change_cancels_promotion() {
+    let (mut env, bridge, backup) = setup();
+    promote(&mut env, &bridge, &backup, false).unwrap();
+    set_backup_key(&mut env, &bridge, Some(&backup)).unwrap();
+    assert_eq!(bridge.state(&env).promote_requested_slot, None);
+}
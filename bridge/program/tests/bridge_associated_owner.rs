@@ -0,0 +1,70 @@
+mod common;
+
+use common::{ft_metadata, nft_metadata, Account, Bridge, Env};
+use lib::error::LibError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Pre-created system account at the bridge associated address with the valid token account data
+fn set_system_owned_bridge_associated(env: &mut Env, bridge: &Bridge, mint: &Pubkey, amount: u64) -> Pubkey {
+    let key = get_associated_token_address(&bridge.admin, mint);
+    let state = spl_token::state::Account {
+        mint: *mint,
+        owner: bridge.admin,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    };
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    state.pack_into_slice(&mut data);
+    env.set_account(key, Account { lamports: Rent::default().minimum_balance(data.len()), data, owner: system_program::id(), executable: false });
+    key
+}
+
+fn setup_ft() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    env.create_associated_token_account(&mint, &owner, 1000);
+    (env, bridge, owner, mint)
+}
+
+#[test]
+fn ft_deposit_rejects_system_owned_bridge_account() {
+    let (mut env, bridge, owner, mint) = setup_ft();
+    set_system_owned_bridge_associated(&mut env, &bridge, &mint, 0);
+
+    assert_eq!(env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)), Err((1, LibError::WrongTokenAccount.into())));
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+}
+
+#[test]
+fn ft_withdraw_rejects_system_owned_bridge_account() {
+    let (mut env, bridge, owner, mint) = setup_ft();
+    set_system_owned_bridge_associated(&mut env, &bridge, &mint, 1000);
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 500, None)), Err(LibError::WrongTokenAccount.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn nft_withdraw_rejects_system_owned_bridge_account() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 0, None);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+    set_system_owned_bridge_associated(&mut env, &bridge, &mint, 1);
+
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)), Err(LibError::WrongTokenAccount.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
@@ -0,0 +1,61 @@
+//! Full bridge flow on the host, the processor is called as a library (see the `no-entrypoint` feature).
+
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::HashAlgo;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+#[test]
+fn initialize_deposit_and_withdraw() {
+    let mut env = Env::new();
+    let bridge = Bridge::uninitialized(&mut env);
+
+    // Initialization
+    let payer = env.new_funded();
+    env.process(&instructions::initialize_admin(
+        bridge.program_id, bridge.admin, payer, bridge.commission_program, bridge.signer.public_key(), bridge.seeds, HashAlgo::Keccak,
+    )).unwrap();
+    let state = bridge.state(&env);
+    assert!(state.is_initialized);
+    assert_eq!(state.public_key, bridge.signer.public_key());
+    assert_eq!(state.commission_program, bridge.commission_program);
+    assert_eq!(env.account(&bridge.admin).unwrap().owner, bridge.program_id);
+
+    // Native and FT deposits
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 1000);
+    let bridge_associated = get_associated_token_address(&bridge.admin, &mint);
+
+    let (owner_lamports, admin_lamports) = (env.lamports(&owner), env.lamports(&bridge.admin));
+    env.process_transaction(&bridge.deposit_native(&owner, 5000)).unwrap();
+    assert_eq!(env.lamports(&owner), owner_lamports - 5000);
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + 5000);
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    assert_eq!(env.token_balance(&owner_associated), 0);
+    assert_eq!(env.token_balance(&bridge_associated), 1000);
+
+    // Withdrawals back to the owner
+    let receiver = env.new_funded();
+    // The receiver pays the rent of the withdraw account
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&bridge.withdraw_native(&receiver, [1; 32], 3000)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 3000);
+    assert!(env.exists(&bridge.withdraw_address(&[1; 32])));
+
+    env.process(&bridge.withdraw_ft(&env, &receiver, &mint, [2; 32], 400, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&receiver, &mint)), 400);
+    assert_eq!(env.token_balance(&bridge_associated), 600);
+
+    // Every withdrawal is executed once
+    assert_eq!(env.process(&bridge.withdraw_native(&receiver, [1; 32], 3000)), Err(LibError::AlreadyInUse.into()));
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &receiver, &mint, [2; 32], 400, None)), Err(LibError::AlreadyInUse.into()));
+    assert_eq!(env.token_balance(&bridge_associated), 600);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{init_bridge_token_account, set_strict_token_accounts};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+fn set_strict(env: &mut Env, bridge: &Bridge) {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetStrictTokenAccounts, vec![1]);
+    env.process(&set_strict_token_accounts(bridge.program_id, bridge.admin, bridge.seeds, true, signature, recovery_id, vec![])).unwrap();
+}
+
+#[test]
+fn init_creates_bridge_associated_account() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    let payer = env.new_funded();
+
+    env.process(&init_bridge_token_account(bridge.program_id, bridge.admin, mint, payer, bridge.seeds)).unwrap();
+
+    let account = env.token_account(&get_associated_token_address(&bridge.admin, &mint));
+    assert_eq!(account.owner, bridge.admin);
+    assert_eq!(account.mint, mint);
+
+    assert_eq!(
+        env.process(&init_bridge_token_account(bridge.program_id, bridge.admin, mint, payer, bridge.seeds)),
+        Err(LibError::AlreadyInUse.into()),
+    );
+}
+
+#[test]
+fn strict_deposit_requires_initialized_account() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_strict(&mut env, &bridge);
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    let owner = env.new_funded();
+    env.create_associated_token_account(&mint, &owner, 1000);
+
+    assert_eq!(env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)), Err((1, LibError::WrongTokenAccount.into())));
+
+    let payer = env.new_funded();
+    env.process(&init_bridge_token_account(bridge.program_id, bridge.admin, mint, payer, bridge.seeds)).unwrap();
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use bridge::state::{CollectionDelegate, OperationType};
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{nft_metadata, Account, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, get_collection_delegate_address};
+use lib::merkle::{normalize_metadata_field, Content, TransferData};
+use mpl_token_metadata::instruction::approve_collection_authority;
+use mpl_token_metadata::pda::{find_collection_authority_account, find_master_edition_account, find_metadata_account};
+use mpl_token_metadata::state::{Collection, Key, MasterEditionV2, MAX_MASTER_EDITION_LEN};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+struct Setup {
+    env: Env,
+    bridge: Bridge,
+    owner: Pubkey,
+    nft: Pubkey,
+    collection: Pubkey,
+    update_authority: Pubkey,
+}
+
+// External collection master edition with the bridge holding its unverified member
+fn setup() -> Setup {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let update_authority = env.new_funded();
+
+    let collection = Pubkey::new_unique();
+    env.create_mint(&collection, &Pubkey::new_unique(), 0, None);
+    env.set_metadata(&mpl_token_metadata::state::Metadata {
+        update_authority,
+        ..nft_metadata(&collection, "Collection", "COL", "https://rarimo.com/collection.json")
+    });
+    let edition = MasterEditionV2 { key: Key::MasterEditionV2, supply: 0, max_supply: Some(0) };
+    let mut data = edition.try_to_vec().unwrap();
+    data.resize(MAX_MASTER_EDITION_LEN, 0);
+    env.set_account(find_master_edition_account(&collection).0, Account {
+        lamports: Rent::default().minimum_balance(data.len()), data, owner: mpl_token_metadata::id(), executable: false,
+    });
+
+    let nft = Pubkey::new_unique();
+    env.create_mint(&nft, &Pubkey::new_unique(), 0, None);
+    env.set_metadata(&mpl_token_metadata::state::Metadata {
+        collection: Some(Collection { verified: false, key: collection }),
+        ..nft_metadata(&nft, "Item", "ITM", "https://rarimo.com/item.json")
+    });
+    env.create_associated_token_account(&nft, &bridge.admin, 1);
+
+    let owner = env.new_funded();
+    Setup { env, bridge, owner, nft, collection, update_authority }
+}
+
+impl Setup {
+    // Collection authority record of the bridge admin approved by the collection update authority
+    fn approve(&mut self) {
+        let payer = self.env.new_funded();
+        self.env.process(&approve_collection_authority(
+            mpl_token_metadata::id(),
+            find_collection_authority_account(&self.collection, &self.bridge.admin).0,
+            self.bridge.admin,
+            self.update_authority,
+            payer,
+            find_metadata_account(&self.collection).0,
+            self.collection,
+        )).unwrap();
+    }
+
+    fn register(&mut self) -> Result<(), solana_program::program_error::ProgramError> {
+        let (signature, recovery_id) = self.bridge.sign_operation(&self.env, OperationType::RegisterCollectionDelegate, self.collection.to_bytes().to_vec());
+        let payer = self.env.new_funded();
+        self.env.process(&instructions::register_collection_delegate(
+            self.bridge.program_id, self.bridge.admin, self.bridge.seeds, payer, self.collection, signature, recovery_id, vec![],
+        ))
+    }
+
+    // NFT withdrawal with the collection metadata and the delegated verification accounts
+    fn withdraw(&self) -> Instruction {
+        let transfer = TransferData::new_nft_transfer(
+            self.nft.to_bytes(),
+            Some(self.collection.to_bytes()),
+            normalize_metadata_field("Collection"),
+            normalize_metadata_field("COL"),
+            normalize_metadata_field("https://rarimo.com/item.json"),
+        );
+        let content = Content::new(ORIGIN, self.owner.to_bytes(), self.bridge.program_id.to_bytes(), Box::new(transfer));
+        let (signature, recovery_id, path) = self.bridge.sign_withdraw(content);
+        let mut instruction = instructions::withdraw_nft(
+            self.bridge.program_id, self.bridge.admin, self.nft, find_metadata_account(&self.nft).0, self.owner, self.bridge.withdraw_address(&ORIGIN),
+            self.bridge.seeds, ORIGIN, 1, signature, recovery_id, path, None, None, None, false, None, Some(find_metadata_account(&self.collection).0),
+        );
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(get_collection_delegate_address(&self.bridge.program_id, &self.bridge.admin, &self.collection), false),
+            AccountMeta::new_readonly(self.collection, false),
+            AccountMeta::new_readonly(find_master_edition_account(&self.collection).0, false),
+            AccountMeta::new_readonly(find_collection_authority_account(&self.collection, &self.bridge.admin).0, false),
+        ]);
+        instruction
+    }
+
+    fn verified(&self) -> bool {
+        self.env.metadata(&self.nft).collection.unwrap().verified
+    }
+}
+
+#[test]
+fn delegated_collection_is_verified() {
+    let mut setup = setup();
+    setup.approve();
+    setup.register().unwrap();
+
+    let delegate_address = get_collection_delegate_address(&setup.bridge.program_id, &setup.bridge.admin, &setup.collection);
+    let delegate = CollectionDelegate::deserialize(&mut setup.env.data(&delegate_address).as_slice()).unwrap();
+    assert!(delegate.is_initialized);
+    assert_eq!(delegate.collection_mint, setup.collection);
+
+    let instruction = setup.withdraw();
+    setup.env.process(&instruction).unwrap();
+    assert!(setup.verified());
+    assert_eq!(setup.env.token_balance(&spl_associated_token_account::get_associated_token_address(&setup.owner, &setup.nft)), 1);
+}
+
+#[test]
+fn undelegated_collection_is_not_verified() {
+    let mut setup = setup();
+    let instruction = setup.withdraw();
+    setup.env.process(&instruction).unwrap();
+    assert!(!setup.verified());
+    assert!(setup.env.logs().iter().any(|log| log.contains("Collection is not delegated to bridge, skipping verification")));
+}
+
+#[test]
+fn registration_requires_authority_record() {
+    let mut setup = setup();
+    assert_eq!(setup.register(), Err(LibError::NotCollectionAuthority.into()));
+    assert!(!setup.env.exists(&get_collection_delegate_address(&setup.bridge.program_id, &setup.bridge.admin, &setup.collection)));
+}
+
+#[test]
+fn collection_withdraw_passes_collection_metadata() {
+    let mut setup = setup();
+    let instruction = setup.withdraw();
+    assert_eq!(instruction.accounts[13], AccountMeta::new(find_metadata_account(&setup.collection).0, false));
+
+    // Account list of the builder without the collection metadata
+    let mut without_collection = instruction.clone();
+    without_collection.accounts.truncate(13);
+    assert_eq!(setup.env.process(&without_collection), Err(ProgramError::NotEnoughAccountKeys));
+
+    setup.env.process(&instruction).unwrap();
+    assert_eq!(setup.env.token_balance(&spl_associated_token_account::get_associated_token_address(&setup.owner, &setup.nft)), 1);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::TokenType;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn setup() -> (Env, Bridge, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    (env, bridge, owner)
+}
+
+#[test]
+fn two_charged_deposits_are_accepted() {
+    let (mut env, bridge, owner) = setup();
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    let transaction: Vec<Instruction> = [bridge.deposit_native(&owner, 1000), bridge.deposit_native(&owner, 2000)].concat();
+    env.process_transaction(&transaction).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + 3000);
+}
+
+#[test]
+fn second_deposit_of_one_charge_is_rejected() {
+    let (mut env, bridge, owner) = setup();
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    let mut transaction = bridge.deposit_native(&owner, 1000);
+    transaction.push(transaction[1].clone());
+    assert_eq!(env.process_transaction(&transaction), Err((1, LibError::WrongCommissionArguments.into())));
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+}
+
+#[test]
+fn charge_without_deposit_is_rejected() {
+    let (mut env, bridge, owner) = setup();
+
+    let mut transaction = bridge.deposit_native(&owner, 1000);
+    transaction.push(bridge.charge(&owner, TokenType::Native, 1000));
+    assert_eq!(env.process_transaction(&transaction), Err((1, LibError::WrongCommissionArguments.into())));
+}
+
+#[test]
+fn deposit_charged_from_other_owner_is_rejected() {
+    let (mut env, bridge, owner) = setup();
+    let payer = env.new_funded();
+    let (admin_lamports, owner_lamports) = (env.lamports(&bridge.admin), env.lamports(&owner));
+
+    // The payer charge is followed by the owner deposit
+    let mut transaction = bridge.deposit_native(&owner, 1000);
+    transaction[0] = bridge.charge(&payer, TokenType::Native, 1000);
+    assert_eq!(env.process_transaction(&transaction), Err((1, LibError::WrongCommissionArguments.into())));
+    assert!(env.logs().iter().any(|log| log == "Commission is charged from another owner"));
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert_eq!(env.lamports(&owner), owner_lamports);
+}
+
+#[test]
+fn ft_deposit_charged_from_other_owner_is_rejected() {
+    let (mut env, bridge, owner) = setup();
+    let payer = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 1000);
+
+    let mut transaction = bridge.deposit_ft(&owner, &mint, 1000);
+    transaction[0] = bridge.charge(&payer, TokenType::FT, 1000);
+    assert_eq!(env.process_transaction(&transaction), Err((1, LibError::WrongCommissionArguments.into())));
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+}
@@ -0,0 +1,829 @@
+//! In-process runtime for the program tests.
+//!
+//! Accounts are serialized in the loader input format, so `realloc` and `assign` behave as on chain.
+//! Cross-program invocations are processed by the host builds of the system (emulated), token,
+//! associated token and metadata programs. Sysvars, return data and logs are kept per test thread.
+
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+use borsh::BorshSerialize;
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::{deserialize, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_program::program_utils::limited_deserialize;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_instruction::SystemInstruction;
+use solana_program::system_program;
+use solana_program::sysvar;
+use solana_program::sysvar::instructions::{construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction};
+use borsh::BorshDeserialize;
+use bridge::merkle::OperationContent;
+use bridge::state::{BridgeAdmin, OperationType};
+use lib::instructions::bridge as instructions;
+use lib::merkle::{amount_bytes, get_merkle_root_with, normalize_metadata_field, Content, HashAlgo, TransferData};
+use mpl_token_metadata::pda::find_metadata_account;
+use mpl_token_metadata::state::{Data, Key, Metadata, TokenStandard, MAX_METADATA_LEN};
+use lib::{CommissionToken, Network, TokenType};
+
+pub type Processor = for<'a> fn(&'a Pubkey, &'a [AccountInfo<'a>], &[u8]) -> ProgramResult;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+// Accounts state at the start of the invocation (updated after the nested invocations),
+// used to check the account changes made by the program as the runtime does
+struct Frame {
+    program: Pubkey,
+    accounts: HashMap<Pubkey, (u64, Vec<u8>, Pubkey, bool)>,
+}
+
+#[derive(Default)]
+struct Context {
+    processors: HashMap<Pubkey, Processor>,
+    // Current invocation stack
+    frames: Vec<Frame>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    logs: Vec<String>,
+    data_logs: Vec<Vec<Vec<u8>>>,
+    slot: u64,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        CONTEXT.with(|c| c.borrow_mut().logs.push(message.to_string()));
+    }
+
+    fn sol_log_compute_units(&self) {
+        self.sol_log("Program consumption: compute units");
+    }
+
+    fn sol_invoke_signed(&self, instruction: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Clock) = clock() };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        CONTEXT.with(|c| c.borrow().return_data.clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        CONTEXT.with(|c| {
+            let mut c = c.borrow_mut();
+            let program = c.frames.last().unwrap().program;
+            c.return_data = if data.is_empty() { None } else { Some((program, data.to_vec())) };
+        });
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        CONTEXT.with(|c| c.borrow_mut().data_logs.push(fields.iter().map(|f| f.to_vec()).collect()));
+    }
+}
+
+fn clock() -> Clock {
+    let slot = CONTEXT.with(|c| c.borrow().slot);
+    Clock {
+        slot,
+        unix_timestamp: slot as i64 / 2,
+        ..Clock::default()
+    }
+}
+
+fn invoke_signed(instruction: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+    let caller = CONTEXT.with(|c| c.borrow().frames.last().unwrap().program);
+    let signers = signers_seeds.iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+
+    let mut infos = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos.iter().find(|info| *info.key == meta.pubkey).ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !info.is_signer && !signers.contains(&meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut info = info.clone();
+        info.is_signer = meta.is_signer;
+        info.is_writable = meta.is_writable;
+        infos.push(info);
+    }
+
+    // AccountInfo is invariant over its lifetime, the program id and the infos should outlive the caller's account infos
+    let program_id: &'static Pubkey = Box::leak(Box::new(instruction.program_id));
+    let infos: &[AccountInfo] = Box::leak(infos.into_boxed_slice());
+    run(program_id, infos, &instruction.data)
+}
+
+fn snapshot(accounts: &[AccountInfo]) -> HashMap<Pubkey, (u64, Vec<u8>, Pubkey, bool)> {
+    let mut state = HashMap::new();
+    for info in accounts {
+        let is_writable = info.is_writable || state.get(info.key).map_or(false, |s: &(u64, Vec<u8>, Pubkey, bool)| s.3);
+        state.insert(*info.key, (info.lamports(), info.data.borrow().to_vec(), *info.owner, is_writable));
+    }
+    state
+}
+
+fn run<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
+    let processor = CONTEXT.with(|c| {
+        let mut c = c.borrow_mut();
+        c.frames.push(Frame { program: *program_id, accounts: snapshot(accounts) });
+        c.return_data = None;
+        c.processors.get(program_id).copied()
+    });
+
+    let mut result = match processor {
+        Some(processor) => processor(program_id, accounts, data),
+        None => Err(ProgramError::IncorrectProgramId),
+    };
+
+    let frame = CONTEXT.with(|c| c.borrow_mut().frames.pop().unwrap());
+    if result.is_ok() {
+        result = verify_changes(&frame, accounts);
+    }
+
+    // Changes of the callee are not the changes of the caller
+    let current = snapshot(accounts);
+    CONTEXT.with(|c| {
+        if let Some(parent) = c.borrow_mut().frames.last_mut() {
+            for (key, (lamports, data, owner, _)) in current {
+                if let Some(state) = parent.accounts.get_mut(&key) {
+                    (state.0, state.1, state.2) = (lamports, data, owner);
+                }
+            }
+        }
+    });
+
+    result
+}
+
+// The runtime rules: read-only accounts are not changed, only the owner can spend lamports, change data or assign
+fn verify_changes(frame: &Frame, accounts: &[AccountInfo]) -> ProgramResult {
+    for (key, (lamports, data, owner, _)) in snapshot(accounts) {
+        let (pre_lamports, pre_data, pre_owner, is_writable) = &frame.accounts[&key];
+        let changed = lamports != *pre_lamports || data != *pre_data || owner != *pre_owner;
+        if changed && !is_writable {
+            log(format!("Read-only account {} was modified by {}", key, frame.program));
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let owned = *pre_owner == frame.program;
+        if !owned && (lamports < *pre_lamports || data != *pre_data || owner != *pre_owner) {
+            log(format!("Account {} of {} was modified by {}", key, pre_owner, frame.program));
+            return Err(ProgramError::IllegalOwner);
+        }
+    }
+
+    Ok(())
+}
+
+fn log(message: String) {
+    CONTEXT.with(|c| c.borrow_mut().logs.push(message));
+}
+
+fn system_processor<'a>(_program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction = limited_deserialize(input, 1232).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            if !from.is_signer || !to.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            if to.lamports() > 0 || to.data_len() > 0 || *to.owner != system_program::id() {
+                // SystemError::AccountAlreadyInUse
+                return Err(ProgramError::Custom(0));
+            }
+
+            transfer(from, to, lamports)?;
+            to.realloc(space as usize, true)?;
+            to.assign(&owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            if !from.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            if from.data_len() > 0 {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            transfer(from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let account = &accounts[0];
+            if !account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            if account.data_len() > 0 || *account.owner != system_program::id() {
+                return Err(ProgramError::Custom(0));
+            }
+
+            account.realloc(space as usize, true)?;
+        }
+        SystemInstruction::Assign { owner } => {
+            let account = &accounts[0];
+            if *account.owner != owner {
+                if !account.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+
+                account.assign(&owner);
+            }
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+
+    Ok(())
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    let from_lamports = from.lamports().checked_sub(lamports).ok_or(ProgramError::Custom(1))?;
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn token_processor<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    spl_token::processor::Processor::process(program_id, accounts, input)
+}
+
+fn associated_token_processor<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    spl_associated_token_account::processor::process_instruction(program_id, accounts, input)
+}
+
+fn metadata_processor<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    mpl_token_metadata::processor::process_instruction(program_id, accounts, input)
+}
+
+// Offsets of the serialized account fields that can be changed by the program
+struct Serialized {
+    key: Pubkey,
+    owner: usize,
+    lamports: usize,
+    data_len: usize,
+    data: usize,
+}
+
+pub struct Env {
+    pub accounts: HashMap<Pubkey, Account>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Stubs));
+        });
+
+        CONTEXT.with(|c| *c.borrow_mut() = Context::default());
+
+        let mut env = Env { accounts: HashMap::new() };
+        env.add_program(system_program::id(), system_processor);
+        env.add_program(spl_token::id(), token_processor);
+        env.add_program(spl_associated_token_account::id(), associated_token_processor);
+        env.add_program(mpl_token_metadata::id(), metadata_processor);
+
+        let rent = Rent::default();
+        let mut rent_data = Vec::new();
+        rent_data.extend_from_slice(&rent.lamports_per_byte_year.to_le_bytes());
+        rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        rent_data.push(rent.burn_percent);
+        env.set_account(sysvar::rent::id(), Account { lamports: 1, data: rent_data, owner: sysvar::id(), executable: false });
+        env
+    }
+
+    pub fn add_program(&mut self, program_id: Pubkey, processor: Processor) {
+        CONTEXT.with(|c| c.borrow_mut().processors.insert(program_id, processor));
+        self.set_account(program_id, Account { lamports: 1, data: Vec::new(), owner: solana_program::bpf_loader::id(), executable: true });
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&Account> {
+        self.accounts.get(key)
+    }
+
+    pub fn data(&self, key: &Pubkey) -> Vec<u8> {
+        self.accounts.get(key).map(|a| a.data.clone()).unwrap_or_default()
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map(|a| a.lamports).unwrap_or_default()
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts.contains_key(key)
+    }
+
+    // System account with lamports, used as the payer or owner
+    pub fn fund(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_insert_with(|| Account { owner: system_program::id(), ..Account::default() });
+        account.lamports += lamports;
+    }
+
+    pub fn new_funded(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.fund(&key, 1_000_000_000_000);
+        key
+    }
+
+    pub fn set_slot(&mut self, slot: u64) {
+        CONTEXT.with(|c| c.borrow_mut().slot = slot);
+    }
+
+    pub fn slot(&self) -> u64 {
+        CONTEXT.with(|c| c.borrow().slot)
+    }
+
+    pub fn logs(&self) -> Vec<String> {
+        CONTEXT.with(|c| c.borrow().logs.clone())
+    }
+
+    pub fn data_logs(&self) -> Vec<Vec<Vec<u8>>> {
+        CONTEXT.with(|c| c.borrow().data_logs.clone())
+    }
+
+    pub fn return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        CONTEXT.with(|c| c.borrow().return_data.clone())
+    }
+
+    pub fn create_mint(&mut self, mint: &Pubkey, authority: &Pubkey, decimals: u8, freeze_authority: Option<&Pubkey>) {
+        let state = spl_token::state::Mint {
+            mint_authority: Some(*authority).into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: freeze_authority.copied().into(),
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        state.pack_into_slice(&mut data);
+        self.set_account(*mint, Account { lamports: Rent::default().minimum_balance(data.len()), data, owner: spl_token::id(), executable: false });
+    }
+
+    pub fn create_token_account(&mut self, key: &Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let state = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        state.pack_into_slice(&mut data);
+        self.set_account(*key, Account { lamports: Rent::default().minimum_balance(data.len()), data, owner: spl_token::id(), executable: false });
+
+        if let Ok(mut mint_state) = spl_token::state::Mint::unpack(&self.data(mint)) {
+            mint_state.supply += amount;
+            spl_token::state::Mint::pack(mint_state, &mut self.accounts.get_mut(mint).unwrap().data).unwrap();
+        }
+    }
+
+    pub fn create_associated_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let key = spl_associated_token_account::get_associated_token_address(owner, mint);
+        self.create_token_account(&key, mint, owner, amount);
+        key
+    }
+
+    pub fn token_account(&self, key: &Pubkey) -> spl_token::state::Account {
+        spl_token::state::Account::unpack(&self.data(key)).unwrap()
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        self.token_account(key).amount
+    }
+
+    pub fn mint(&self, key: &Pubkey) -> spl_token::state::Mint {
+        spl_token::state::Mint::unpack(&self.data(key)).unwrap()
+    }
+
+    /// Metadata account of the mint as written by the metadata program, the fields are not padded
+    pub fn set_metadata(&mut self, metadata: &Metadata) -> Pubkey {
+        let key = find_metadata_account(&metadata.mint).0;
+        let mut data = vec![0; MAX_METADATA_LEN];
+        metadata.save(&mut data).unwrap();
+        self.set_account(key, Account { lamports: Rent::default().minimum_balance(data.len()), data, owner: mpl_token_metadata::id(), executable: false });
+        key
+    }
+
+    pub fn metadata(&self, mint: &Pubkey) -> Metadata {
+        Metadata::deserialize(&mut self.data(&find_metadata_account(mint).0).as_slice()).unwrap()
+    }
+
+    // Program-owned account with the Borsh-encoded state
+    pub fn set_state<T: BorshSerialize>(&mut self, key: &Pubkey, owner: &Pubkey, state: &T, size: usize) {
+        let mut data = state.try_to_vec().unwrap();
+        data.resize(size, 0);
+        self.set_account(*key, Account { lamports: Rent::default().minimum_balance(size), data, owner: *owner, executable: false });
+    }
+
+    pub fn process(&mut self, instruction: &Instruction) -> ProgramResult {
+        self.process_transaction(&[instruction.clone()]).map_err(|(_, err)| err)
+    }
+
+    /// Processes the instructions atomically: all account changes are discarded if any instruction fails.
+    /// Returns the index of the failed instruction with its error.
+    pub fn process_transaction(&mut self, instructions: &[Instruction]) -> Result<(), (usize, ProgramError)> {
+        let snapshot = self.accounts.clone();
+        CONTEXT.with(|c| {
+            let mut c = c.borrow_mut();
+            c.logs.clear();
+            c.data_logs.clear();
+        });
+
+        let mut instructions_data = construct_instructions_data(
+            &instructions.iter().map(|ix| BorrowedInstruction {
+                program_id: &ix.program_id,
+                accounts: ix.accounts.iter().map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                }).collect(),
+                data: &ix.data,
+            }).collect::<Vec<_>>(),
+        );
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            store_current_index(&mut instructions_data, index as u16);
+            self.set_account(sysvar::instructions::id(), Account { lamports: 1, data: instructions_data.clone(), owner: sysvar::id(), executable: false });
+
+            if let Err(err) = self.process_instruction(instruction) {
+                self.accounts = snapshot;
+                return Err((index, err));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_instruction(&mut self, instruction: &Instruction) -> ProgramResult {
+        let (mut input, serialized) = self.serialize(instruction);
+
+        let result = {
+            let (program_id, accounts, data) = unsafe { deserialize(input.as_mut_ptr() as *mut u8) };
+            run(program_id, &accounts, data)
+        };
+
+        if result.is_ok() {
+            let bytes = unsafe { std::slice::from_raw_parts(input.as_ptr() as *const u8, input.len() * 8) };
+            for s in &serialized {
+                let data_len = u64::from_le_bytes(bytes[s.data_len..s.data_len + 8].try_into().unwrap()) as usize;
+                let lamports = u64::from_le_bytes(bytes[s.lamports..s.lamports + 8].try_into().unwrap());
+                let account = self.accounts.entry(s.key).or_default();
+                account.owner = Pubkey::new_from_array(bytes[s.owner..s.owner + 32].try_into().unwrap());
+                account.lamports = lamports;
+                account.data = bytes[s.data..s.data + data_len].to_vec();
+
+                // Accounts without lamports are removed by the runtime after the transaction
+                if lamports == 0 {
+                    self.accounts.remove(&s.key);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn serialize(&self, instruction: &Instruction) -> (Vec<u64>, Vec<Serialized>) {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut serialized = Vec::new();
+        bytes.extend_from_slice(&(instruction.accounts.len() as u64).to_le_bytes());
+
+        for (index, meta) in instruction.accounts.iter().enumerate() {
+            if let Some(first) = instruction.accounts[..index].iter().position(|m| m.pubkey == meta.pubkey) {
+                bytes.push(first as u8);
+                bytes.extend_from_slice(&[0; 7]);
+                continue;
+            }
+
+            // Duplicates share the most permissive flags
+            let same: Vec<&AccountMeta> = instruction.accounts.iter().filter(|m| m.pubkey == meta.pubkey).collect();
+            let is_signer = same.iter().any(|m| m.is_signer);
+            let is_writable = same.iter().any(|m| m.is_writable);
+
+            let account = self.accounts.get(&meta.pubkey).cloned().unwrap_or(Account { owner: system_program::id(), ..Account::default() });
+            bytes.push(NON_DUP_MARKER);
+            bytes.push(is_signer as u8);
+            bytes.push(is_writable as u8);
+            bytes.push(account.executable as u8);
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(meta.pubkey.as_ref());
+            let owner = bytes.len();
+            bytes.extend_from_slice(account.owner.as_ref());
+            let lamports = bytes.len();
+            bytes.extend_from_slice(&account.lamports.to_le_bytes());
+            let data_len = bytes.len();
+            bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            let data = bytes.len();
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize((bytes.len() + 7) / 8 * 8, 0);
+            bytes.extend_from_slice(&0u64.to_le_bytes());
+
+            serialized.push(Serialized { key: meta.pubkey, owner, lamports, data_len, data });
+        }
+
+        bytes.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&instruction.data);
+        bytes.extend_from_slice(instruction.program_id.as_ref());
+
+        let mut input = vec![0u64; (bytes.len() + 7) / 8];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len()) };
+        (input, serialized)
+    }
+}
+
+/// Signer of the bridge admin operations with the secp256k1 key.
+pub struct Signer {
+    secret: libsecp256k1::SecretKey,
+}
+
+impl Signer {
+    pub fn new(seed: u8) -> Self {
+        Signer { secret: libsecp256k1::SecretKey::parse(&[seed; 32]).unwrap() }
+    }
+
+    /// Uncompressed public key without the prefix byte, the format of BridgeAdmin.public_key
+    pub fn public_key(&self) -> [u8; 64] {
+        let key = libsecp256k1::PublicKey::from_secret_key(&self.secret).serialize();
+        key[1..].try_into().unwrap()
+    }
+
+    pub fn eth_address(&self) -> [u8; 20] {
+        let hash = solana_program::keccak::hash(&self.public_key());
+        hash.0[12..].try_into().unwrap()
+    }
+
+    /// Signature and recovery id of the 32-byte hash
+    pub fn sign(&self, hash: &[u8; 32]) -> ([u8; 64], u8) {
+        let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(hash), &self.secret);
+        (signature.serialize(), recovery_id.serialize())
+    }
+}
+
+fn stub_processor<'a>(_program_id: &'a Pubkey, _accounts: &'a [AccountInfo<'a>], _input: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+/// Fungible token metadata without a collection
+pub fn ft_metadata(mint: &Pubkey, name: &str, symbol: &str, uri: &str) -> Metadata {
+    Metadata {
+        key: Key::MetadataV1,
+        update_authority: Pubkey::new_unique(),
+        mint: *mint,
+        data: Data {
+            name: String::from(name),
+            symbol: String::from(symbol),
+            uri: String::from(uri),
+            seller_fee_basis_points: 0,
+            creators: None,
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::Fungible),
+        collection: None,
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    }
+}
+
+/// Non-fungible token metadata without a collection
+pub fn nft_metadata(mint: &Pubkey, name: &str, symbol: &str, uri: &str) -> Metadata {
+    Metadata { token_standard: Some(TokenStandard::NonFungible), ..ft_metadata(mint, name, symbol, uri) }
+}
+
+/// Initialized bridge admin with the secp256k1 signer. The commission program is a stub accepting every charge,
+/// the bridge only checks the charge instruction placed before the deposit.
+pub struct Bridge {
+    pub program_id: Pubkey,
+    pub seeds: [u8; 32],
+    pub admin: Pubkey,
+    pub commission_program: Pubkey,
+    pub signer: Signer,
+    pub hash_algo: HashAlgo,
+}
+
+impl Bridge {
+    pub fn new(env: &mut Env) -> Self {
+        Self::with_hash_algo(env, HashAlgo::Keccak)
+    }
+
+    pub fn with_hash_algo(env: &mut Env, hash_algo: HashAlgo) -> Self {
+        let bridge = Bridge { hash_algo, ..Self::uninitialized(env) };
+        let payer = env.new_funded();
+        env.process(&instructions::initialize_admin(
+            bridge.program_id,
+            bridge.admin,
+            payer,
+            bridge.commission_program,
+            bridge.signer.public_key(),
+            bridge.seeds,
+            hash_algo,
+        )).unwrap();
+        bridge
+    }
+
+    /// Another initialized admin of the same bridge and commission programs with the same signer (e.g. the redeployed bridge admin)
+    pub fn redeployed(&self, env: &mut Env) -> Self {
+        let (seeds, admin) = (self.seeds[0] + 1..=u8::MAX)
+            .filter_map(|i| Pubkey::create_program_address(&[&[i; 32]], &self.program_id).ok().map(|key| ([i; 32], key)))
+            .find(|(_, admin)| lib::instructions::commission::commission_admin_address(&self.commission_program, admin).is_ok())
+            .unwrap();
+
+        let bridge = Bridge { seeds, admin, signer: Signer::new(1), ..*self };
+        let payer = env.new_funded();
+        env.process(&instructions::initialize_admin(
+            bridge.program_id,
+            bridge.admin,
+            payer,
+            bridge.commission_program,
+            bridge.signer.public_key(),
+            bridge.seeds,
+            bridge.hash_algo,
+        )).unwrap();
+        bridge
+    }
+
+    pub fn uninitialized(env: &mut Env) -> Self {
+        let program_id = Pubkey::new_unique();
+        env.add_program(program_id, bridge::processor::process_instruction);
+
+        let (seeds, admin) = (0..=u8::MAX)
+            .find_map(|i| Pubkey::create_program_address(&[&[i; 32]], &program_id).ok().map(|key| ([i; 32], key)))
+            .unwrap();
+
+        // Commission admin address is derived without the bump, so not every program id has it
+        let commission_program = std::iter::repeat_with(Pubkey::new_unique)
+            .find(|id| lib::instructions::commission::commission_admin_address(id, &admin).is_ok())
+            .unwrap();
+        env.add_program(commission_program, stub_processor);
+
+        Bridge { program_id, seeds, admin, commission_program, signer: Signer::new(1), hash_algo: HashAlgo::Keccak }
+    }
+
+    pub fn state(&self, env: &Env) -> BridgeAdmin {
+        BridgeAdmin::deserialize(&mut env.data(&self.admin).as_slice()).unwrap()
+    }
+
+    pub fn set_state(&self, env: &mut Env, state: &BridgeAdmin) {
+        let account = env.accounts.get_mut(&self.admin).unwrap();
+        state.serialize(&mut account.data.as_mut_slice()).unwrap();
+    }
+
+    /// Signs the management operation with the current admin nonce, the path is empty (the content hash is the root)
+    pub fn sign_operation(&self, env: &Env, operation_type: OperationType, data: Vec<u8>) -> ([u8; 64], u8) {
+        let content = OperationContent::new(self.state(env).nonce, self.program_id, operation_type, data);
+        self.signer.sign(&content.hash().to_bytes())
+    }
+
+    /// Signs the two-leaf tree of the withdrawal and the `SIBLING` leaf, returns the signature with the proof.
+    /// Withdrawal proofs can not be empty.
+    pub fn sign_withdraw(&self, content: Content) -> ([u8; 64], u8, Vec<[u8; 32]>) {
+        let path = vec![SIBLING];
+        let root = get_merkle_root_with(self.hash_algo, content.hash_with(self.hash_algo), &path).unwrap();
+        let (signature, recovery_id) = self.signer.sign(&root);
+        (signature, recovery_id, path)
+    }
+
+    pub fn commission_admin(&self) -> Pubkey {
+        lib::instructions::commission::commission_admin_address(&self.commission_program, &self.admin).unwrap()
+    }
+
+    /// Stub charge instruction that should precede every deposit
+    pub fn charge(&self, owner: &Pubkey, deposit_token: TokenType, amount: u64) -> Instruction {
+        lib::instructions::commission::charge_commission_native(
+            self.commission_program,
+            self.commission_admin(),
+            self.admin,
+            *owner,
+            CommissionToken::Native,
+            deposit_token,
+            amount,
+            0,
+        )
+    }
+
+    pub fn deposit_native(&self, owner: &Pubkey, amount: u64) -> Vec<Instruction> {
+        vec![
+            self.charge(owner, TokenType::Native, amount),
+            instructions::deposit_native(self.program_id, self.admin, *owner, self.seeds, Network::Ethereum, amount, String::from(RECEIVER), None, None),
+        ]
+    }
+
+    pub fn deposit_ft(&self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Vec<Instruction> {
+        vec![
+            self.charge(owner, TokenType::FT, amount),
+            instructions::deposit_ft(self.program_id, self.admin, *mint, *owner, self.seeds, Network::Ethereum, String::from(RECEIVER), amount, None, None, None),
+        ]
+    }
+
+    pub fn set_token_config(&self, env: &mut Env, mint: &Pubkey, deposit_enabled: bool, withdraw_enabled: bool, target_decimals: Option<u8>, min_deposit: u64) -> ProgramResult {
+        let mut data = [mint.as_ref(), &[deposit_enabled as u8, withdraw_enabled as u8]].concat();
+        data.extend(target_decimals);
+        if min_deposit != 0 {
+            data.extend(amount_bytes(min_deposit));
+        }
+
+        let (signature, recovery_id) = self.sign_operation(env, OperationType::SetTokenConfig, data);
+        let payer = env.new_funded();
+        env.process(&instructions::set_token_config(
+            self.program_id, self.admin, self.seeds, payer, *mint, deposit_enabled, withdraw_enabled, target_decimals, min_deposit, signature, recovery_id, vec![],
+        ))
+    }
+
+    pub fn withdraw_address(&self, origin: &[u8; 32]) -> Pubkey {
+        Pubkey::find_program_address(&[origin.as_slice()], &self.program_id).0
+    }
+
+    /// Signed native withdrawal to the owner
+    pub fn withdraw_native(&self, owner: &Pubkey, origin: [u8; 32], amount: u64) -> Instruction {
+        let content = Content::new(origin, owner.to_bytes(), self.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(amount)));
+        let (signature, recovery_id, path) = self.sign_withdraw(content);
+        instructions::withdraw_native(
+            self.program_id, self.admin, *owner, self.withdraw_address(&origin), self.seeds, origin, amount, signature, recovery_id, path, None, None, None, false, None,
+        )
+    }
+
+    /// FT transfer leaf of the existing mint, built from its metadata account and decimals as the bridge does
+    pub fn ft_content(&self, env: &Env, owner: &Pubkey, mint: &Pubkey, origin: [u8; 32], amount: u64) -> Content {
+        let metadata = env.metadata(mint);
+        let transfer = TransferData::new_ft_transfer(
+            mint.to_bytes(),
+            amount,
+            normalize_metadata_field(&metadata.data.name),
+            normalize_metadata_field(&metadata.data.symbol),
+            normalize_metadata_field(&metadata.data.uri),
+            env.mint(mint).decimals,
+        );
+        Content::new(origin, owner.to_bytes(), self.program_id.to_bytes(), Box::new(transfer))
+    }
+
+    /// Signed NFT withdrawal of the existing mint without a collection to the owner associated account
+    pub fn withdraw_nft(&self, env: &Env, owner: &Pubkey, mint: &Pubkey, origin: [u8; 32]) -> Instruction {
+        let metadata = env.metadata(mint);
+        let transfer = TransferData::new_nft_transfer(
+            mint.to_bytes(),
+            None,
+            normalize_metadata_field(&metadata.data.name),
+            normalize_metadata_field(&metadata.data.symbol),
+            normalize_metadata_field(&metadata.data.uri),
+        );
+        let content = Content::new(origin, owner.to_bytes(), self.program_id.to_bytes(), Box::new(transfer));
+        let (signature, recovery_id, path) = self.sign_withdraw(content);
+        instructions::withdraw_nft(
+            self.program_id, self.admin, *mint, find_metadata_account(mint).0, *owner, self.withdraw_address(&origin), self.seeds, origin, 1, signature, recovery_id, path, None, None, None, false, None, None,
+        )
+    }
+
+    /// Signed FT withdrawal of the existing mint to the owner associated account
+    pub fn withdraw_ft(&self, env: &Env, owner: &Pubkey, mint: &Pubkey, origin: [u8; 32], amount: u64, claim_amount: Option<u64>) -> Instruction {
+        let (signature, recovery_id, path) = self.sign_withdraw(self.ft_content(env, owner, mint, origin, amount));
+        instructions::withdraw_ft(
+            self.program_id, self.admin, *mint, *owner, self.withdraw_address(&origin), self.seeds, origin, amount, claim_amount, signature, recovery_id, path, None, None, None, false, None, false, None,
+        )
+    }
+}
+
+// Second leaf of the signed withdrawal trees
+pub const SIBLING: [u8; 32] = [0xAB; 32];
+
+// EVM receiver of the test deposits
+pub const RECEIVER: &str = "0x0000000000000000000000000000000000000001";
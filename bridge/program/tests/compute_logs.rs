@@ -0,0 +1,47 @@
+//! Run with and without `--features compute-logs`.
+
+mod common;
+
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use solana_program::pubkey::Pubkey;
+
+const MILESTONES: [&str; 6] = ["start", "token created", "signature verified", "token accounts ready", "token transferred", "withdraw account saved"];
+
+fn milestones(env: &Env) -> Vec<String> {
+    env.logs().iter().filter_map(|log| log.split("Compute units at ").nth(1).map(String::from)).collect()
+}
+
+fn expected() -> Vec<String> {
+    if cfg!(feature = "compute-logs") {
+        MILESTONES.iter().map(|m| m.to_string()).collect()
+    } else {
+        vec![]
+    }
+}
+
+#[test]
+fn ft_withdraw_logs_milestones_in_order() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)).unwrap();
+    assert_eq!(milestones(&env), expected());
+}
+
+#[test]
+fn nft_withdraw_logs_milestones_in_order() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 0, None);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+    env.create_associated_token_account(&mint, &bridge.admin, 1);
+
+    env.process(&bridge.withdraw_nft(&env, &owner, &mint, [7; 32])).unwrap();
+    assert_eq!(milestones(&env), expected());
+}
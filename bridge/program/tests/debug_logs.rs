@@ -0,0 +1,24 @@
+//! Run with and without `--features debug-logs`.
+
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn recovered_key_is_logged_only_with_debug_logs() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)).unwrap();
+
+    let logs = env.logs();
+    let logged = |prefix: &str| logs.iter().any(|log| log.starts_with(prefix));
+    assert_eq!(logged("Recovered public key from signature"), cfg!(feature = "debug-logs"));
+    assert_eq!(logged("Required public key"), cfg!(feature = "debug-logs"));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::{Bridge, Env};
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn program_accounts(env: &Env, bridge: &Bridge) -> usize {
+    env.accounts.values().filter(|account| account.owner == bridge.program_id).count()
+}
+
+// Deposits are tracked by the transaction data, there is no deposit account to close
+#[test]
+fn deposits_create_no_program_accounts() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&mint, &owner, 1000);
+
+    let accounts = program_accounts(&env, &bridge);
+    env.process_transaction(&bridge.deposit_native(&owner, 1000)).unwrap();
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    assert_eq!(program_accounts(&env, &bridge), accounts);
+}
+
+// Withdraw account is created in the failed instruction and reverted with it
+#[test]
+fn failed_withdraw_leaves_no_withdraw_account() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let owner_lamports = env.lamports(&owner);
+
+    let accounts = program_accounts(&env, &bridge);
+    let amount = env.lamports(&bridge.admin) + 1;
+    assert!(env.process(&bridge.withdraw_native(&owner, ORIGIN, amount)).is_err());
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+    assert_eq!(program_accounts(&env, &bridge), accounts);
+    assert_eq!(env.lamports(&owner), owner_lamports);
+}
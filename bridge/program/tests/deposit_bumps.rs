@@ -0,0 +1,84 @@
+mod common;
+
+use bridge::state::OperationType;
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, get_deposit_bumps, BridgeInstruction, DepositBumps};
+use lib::Network;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn set_networks(env: &mut Env, bridge: &Bridge) {
+    let data = solana_program::keccak::hash(Network::Ethereum.as_bytes()).to_bytes().to_vec();
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetNetworks, data);
+    let payer = env.new_funded();
+    env.process(&instructions::set_networks(bridge.program_id, bridge.admin, bridge.seeds, payer, vec![Network::Ethereum], signature, recovery_id, vec![])).unwrap();
+}
+
+// Replaces the bumps of the deposit built with the canonical ones
+fn with_bumps(mut instructions: Vec<Instruction>, bumps: impl Fn(DepositBumps) -> DepositBumps) -> Vec<Instruction> {
+    let deposit = &mut instructions[1];
+    let instruction = match BridgeInstruction::try_from_slice(&deposit.data).unwrap() {
+        BridgeInstruction::DepositNative(mut args) => {
+            args.bumps = args.bumps.map(&bumps);
+            BridgeInstruction::DepositNative(args)
+        }
+        BridgeInstruction::DepositFT(mut args) => {
+            args.bumps = args.bumps.map(&bumps);
+            BridgeInstruction::DepositFT(args)
+        }
+        _ => unreachable!(),
+    };
+    deposit.data = instruction.try_to_vec().unwrap();
+    instructions
+}
+
+#[test]
+fn canonical_bumps_are_accepted() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_networks(&mut env, &bridge);
+    let owner = env.new_funded();
+
+    let bumps = get_deposit_bumps(&bridge.program_id, &bridge.admin, None);
+    let networks = instructions::get_networks_address(&bridge.program_id, &bridge.admin);
+    assert_eq!(Pubkey::create_program_address(&[lib::NETWORKS_PDA_SEED.as_bytes(), bridge.admin.as_ref(), &[bumps.networks]], &bridge.program_id), Ok(networks));
+    env.process_transaction(&bridge.deposit_native(&owner, 1000)).unwrap();
+}
+
+#[test]
+fn wrong_networks_bump_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_networks(&mut env, &bridge);
+    let owner = env.new_funded();
+
+    let deposit = with_bumps(bridge.deposit_native(&owner, 1000), |bumps| DepositBumps { networks: bumps.networks.wrapping_sub(1), ..bumps });
+    assert_eq!(env.process_transaction(&deposit), Err((1, LibError::WrongSeeds.into())));
+}
+
+#[test]
+fn wrong_token_config_bump_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.create_associated_token_account(&mint, &owner, 1000);
+    bridge.set_token_config(&mut env, &mint, true, true, None, 0).unwrap();
+
+    let deposit = with_bumps(bridge.deposit_ft(&owner, &mint, 1000), |bumps| DepositBumps { token_config: bumps.token_config.wrapping_sub(1), ..bumps });
+    assert_eq!(env.process_transaction(&deposit), Err((1, LibError::WrongSeeds.into())));
+}
+
+#[test]
+fn bump_of_missing_account_is_not_trusted() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    // No SupportedNetworks account, the address is checked by the bump search
+    let deposit = with_bumps(bridge.deposit_native(&owner, 1000), |bumps| DepositBumps { networks: bumps.networks.wrapping_sub(1), ..bumps });
+    env.process_transaction(&deposit).unwrap();
+}
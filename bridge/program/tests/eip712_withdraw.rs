@@ -0,0 +1,45 @@
+mod common;
+
+use common::{Bridge, Env, SIBLING};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::{get_merkle_root_with, Content, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+const DOMAIN_SEPARATOR: [u8; 32] = [9; 32];
+
+// Native withdrawal of 1000 lamports signed over the EIP-712 digest of the leaf
+fn withdraw(bridge: &Bridge, owner: &Pubkey, eip712_domain_separator: Option<[u8; 32]>) -> Instruction {
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(1000)));
+    let path = vec![SIBLING];
+    let root = get_merkle_root_with(bridge.hash_algo, content.hash_eip712(DOMAIN_SEPARATOR).to_bytes(), &path).unwrap();
+    let (signature, recovery_id) = bridge.signer.sign(&root);
+    instructions::withdraw_native(
+        bridge.program_id, bridge.admin, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000,
+        signature, recovery_id, path, None, None, eip712_domain_separator, false, None,
+    )
+}
+
+#[test]
+fn eip712_signed_withdraw_is_accepted() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    env.process(&withdraw(&bridge, &owner, Some(DOMAIN_SEPARATOR))).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 1000);
+}
+
+#[test]
+fn eip712_signature_does_not_match_raw_leaf() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    assert_eq!(env.process(&withdraw(&bridge, &owner, None)), Err(LibError::WrongSignature.into()));
+    assert_eq!(env.process(&withdraw(&bridge, &owner, Some([8; 32]))), Err(LibError::WrongSignature.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
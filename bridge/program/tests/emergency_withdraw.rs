@@ -0,0 +1,77 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::emergency_withdraw;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use spl_associated_token_account::get_associated_token_address;
+
+// Bridge treasury with 50000 lamports above rent, 1000 and 2000 tokens of two mints
+fn setup() -> (Env, Bridge, Pubkey, [Pubkey; 2]) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 50_000);
+
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let destination = Pubkey::new_unique();
+    for (mint, amount) in mints.iter().zip([1000, 2000]) {
+        env.create_mint(mint, &Pubkey::new_unique(), 6, None);
+        env.create_associated_token_account(mint, &bridge.admin, amount);
+        env.create_associated_token_account(mint, &destination, 0);
+    }
+    (env, bridge, destination, mints)
+}
+
+// EmergencyWithdraw of `mints` signed for the `signed_destination`
+fn sweep(env: &Env, bridge: &Bridge, destination: &Pubkey, signed_destination: &Pubkey, mints: &[Pubkey]) -> Instruction {
+    let mut data = Vec::from(signed_destination.as_ref());
+    for mint in mints {
+        data.extend_from_slice(mint.as_ref());
+    }
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::EmergencyWithdraw, data);
+    emergency_withdraw(bridge.program_id, bridge.admin, bridge.seeds, *destination, mints.to_vec(), signature, recovery_id, vec![])
+}
+
+#[test]
+fn native_and_tokens_are_swept() {
+    let (mut env, bridge, destination, mints) = setup();
+    let lamports = env.lamports(&bridge.admin);
+    let rent_exempt = Rent::default().minimum_balance(env.data(&bridge.admin).len());
+
+    env.process(&sweep(&env, &bridge, &destination, &destination, &mints)).unwrap();
+
+    assert_eq!(env.lamports(&bridge.admin), rent_exempt);
+    assert_eq!(env.lamports(&destination), lamports - rent_exempt);
+    for (mint, amount) in mints.iter().zip([1000, 2000]) {
+        assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, mint)), 0);
+        assert_eq!(env.token_balance(&get_associated_token_address(&destination, mint)), amount);
+    }
+    assert_eq!(bridge.state(&env).nonce, 1);
+}
+
+#[test]
+fn replayed_sweep_is_rejected() {
+    let (mut env, bridge, destination, mints) = setup();
+    let instruction = sweep(&env, &bridge, &destination, &destination, &mints);
+    env.process(&instruction).unwrap();
+
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+}
+
+#[test]
+fn sweep_to_unsigned_destination_is_rejected() {
+    let (mut env, bridge, destination, mints) = setup();
+    let attacker = Pubkey::new_unique();
+    for mint in &mints {
+        env.create_associated_token_account(mint, &attacker, 0);
+    }
+
+    assert_eq!(
+        env.process(&sweep(&env, &bridge, &attacker, &destination, &mints)),
+        Err(LibError::WrongSignature.into()),
+    );
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mints[0])), 1000);
+}
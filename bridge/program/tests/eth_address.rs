@@ -0,0 +1,58 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env, Signer};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Switches the admin signed by the public key of the bridge signer to the Ethereum address of the `address_signer`
+fn setup() -> (Env, Bridge, Bridge) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 10_000);
+
+    let address_signer = Signer::new(2);
+    let eth_address = address_signer.eth_address();
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::SetEthAddress, eth_address.to_vec());
+    env.process(&instructions::set_eth_address(bridge.program_id, bridge.admin, bridge.seeds, Some(eth_address), signature, recovery_id, vec![])).unwrap();
+
+    let address_bridge = Bridge { signer: address_signer, ..bridge };
+    (env, bridge, address_bridge)
+}
+
+#[test]
+fn eth_address_is_stored() {
+    let (env, bridge, _) = setup();
+    let state = bridge.state(&env);
+    assert_eq!(state.eth_address, Some(Signer::new(2).eth_address()));
+    assert_eq!(state.public_key, bridge.signer.public_key());
+    assert_eq!(state.nonce, 1);
+}
+
+#[test]
+fn withdraw_is_verified_against_eth_address() {
+    let (mut env, bridge, address_bridge) = setup();
+    let owner = env.new_funded();
+
+    // Public key signer is not the admin in the address mode
+    assert_eq!(env.process(&bridge.withdraw_native(&owner, ORIGIN, 1000)), Err(LibError::WrongSignature.into()));
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&address_bridge.withdraw_native(&owner, ORIGIN, 1000)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 1000);
+}
+
+#[test]
+fn public_key_mode_is_restored() {
+    let (mut env, bridge, address_bridge) = setup();
+
+    let (signature, recovery_id) = address_bridge.sign_operation(&env, OperationType::SetEthAddress, vec![]);
+    env.process(&instructions::set_eth_address(bridge.program_id, bridge.admin, bridge.seeds, None, signature, recovery_id, vec![])).unwrap();
+    assert_eq!(bridge.state(&env).eth_address, None);
+
+    let owner = env.new_funded();
+    assert_eq!(env.process(&address_bridge.withdraw_native(&owner, ORIGIN, 1000)), Err(LibError::WrongSignature.into()));
+    env.process(&bridge.withdraw_native(&owner, ORIGIN, 1000)).unwrap();
+}
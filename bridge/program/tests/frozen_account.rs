@@ -0,0 +1,86 @@
+mod common;
+
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use lib::error::LibError;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::instruction::{freeze_account, set_authority, AuthorityType};
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Mint of the bridge with the frozen owner associated account
+fn setup(decimals: u8) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let (mint, freeze_authority) = (Pubkey::new_unique(), env.new_funded());
+    env.create_mint(&mint, &bridge.admin, decimals, Some(&freeze_authority));
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 0);
+    env.process(&freeze_account(&spl_token::id(), &owner_associated, &mint, &freeze_authority, &[]).unwrap()).unwrap();
+    (env, bridge, owner, mint)
+}
+
+// Frozen account of the mint whose freeze authority is passed to the bridge afterwards
+fn setup_bridge_freeze_authority(decimals: u8) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let (mint, freeze_authority) = (Pubkey::new_unique(), env.new_funded());
+    env.create_mint(&mint, &bridge.admin, decimals, Some(&freeze_authority));
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 0);
+    env.process(&freeze_account(&spl_token::id(), &owner_associated, &mint, &freeze_authority, &[]).unwrap()).unwrap();
+    env.process(&set_authority(
+        &spl_token::id(), &mint, Some(&bridge.admin), AuthorityType::FreezeAccount, &freeze_authority, &[],
+    ).unwrap()).unwrap();
+    (env, bridge, owner, mint)
+}
+
+#[test]
+fn ft_withdraw_to_frozen_account_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(6);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)), Err(LibError::AccountFrozen.into()));
+    assert!(env.logs().iter().all(|log| !log.contains("MintTo")));
+    assert_eq!(env.mint(&mint).supply, 0);
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn nft_withdraw_to_frozen_account_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(0);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)), Err(LibError::AccountFrozen.into()));
+    assert!(env.logs().iter().all(|log| !log.contains("MintTo")));
+    assert_eq!(env.mint(&mint).supply, 0);
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn ft_withdraw_thaws_account_frozen_by_bridge() {
+    let (mut env, bridge, owner, mint) = setup_bridge_freeze_authority(6);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    let owner_associated = get_associated_token_address(&owner, &mint);
+    assert!(env.token_account(&owner_associated).is_frozen());
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert!(env.logs().iter().any(|log| log == "Thawing owner associated account"));
+    assert!(!env.token_account(&owner_associated).is_frozen());
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+    assert_eq!(env.mint(&mint).supply, 1000);
+    assert!(env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+// Only the FT withdrawal thaws the account
+#[test]
+fn nft_withdraw_does_not_thaw_account_frozen_by_bridge() {
+    let (mut env, bridge, owner, mint) = setup_bridge_freeze_authority(0);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)), Err(LibError::AccountFrozen.into()));
+    assert!(env.token_account(&get_associated_token_address(&owner, &mint)).is_frozen());
+    assert_eq!(env.mint(&mint).supply, 0);
+}
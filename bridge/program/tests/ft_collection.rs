@@ -0,0 +1,78 @@
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::withdraw_ft;
+use lib::merkle::{Content, TransferData};
+use mpl_token_metadata::pda::find_metadata_account;
+use mpl_token_metadata::state::Collection;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Token of the wrapped asset family, the collection metadata is named after the family
+fn setup(verified: bool) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let collection = Pubkey::new_unique();
+    env.set_metadata(&ft_metadata(&collection, "Wrapped family", "WFAM", "https://example.com/family.json"));
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    let mut metadata = ft_metadata(&mint, "Wrapped token", "WTKN", "https://example.com/token.json");
+    metadata.collection = Some(Collection { verified, key: collection });
+    env.set_metadata(&metadata);
+
+    (env, bridge, owner, mint)
+}
+
+fn withdraw(bridge: &Bridge, owner: &Pubkey, mint: &Pubkey, name: &str, symbol: &str, collection_metadata: Option<Pubkey>) -> Instruction {
+    let transfer = TransferData::new_ft_transfer(mint.to_bytes(), 1000, String::from(name), String::from(symbol), String::from("https://example.com/token.json"), 6);
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer));
+    let (signature, recovery_id, path) = bridge.sign_withdraw(content);
+    withdraw_ft(
+        bridge.program_id, bridge.admin, *mint, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000, None, signature, recovery_id, path, None, None, None, false, None, false, collection_metadata,
+    )
+}
+
+fn collection_metadata(env: &Env, mint: &Pubkey) -> Pubkey {
+    find_metadata_account(&env.metadata(mint).collection.unwrap().key).0
+}
+
+#[test]
+fn withdraw_with_collection_uses_collection_name() {
+    let (mut env, bridge, owner, mint) = setup(true);
+    let collection_metadata = collection_metadata(&env, &mint);
+
+    // Leaf of the token name is not accepted when the collection metadata is passed
+    assert_eq!(
+        env.process(&withdraw(&bridge, &owner, &mint, "Wrapped token", "WTKN", Some(collection_metadata))),
+        Err(LibError::WrongSignature.into()),
+    );
+
+    env.process(&withdraw(&bridge, &owner, &mint, "Wrapped family", "WFAM", Some(collection_metadata))).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+}
+
+#[test]
+fn withdraw_without_collection_account_uses_token_name() {
+    let (mut env, bridge, owner, mint) = setup(true);
+
+    env.process(&withdraw(&bridge, &owner, &mint, "Wrapped token", "WTKN", None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+}
+
+#[test]
+fn withdraw_with_unverified_collection_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(false);
+    let collection_metadata = collection_metadata(&env, &mint);
+
+    assert_eq!(
+        env.process(&withdraw(&bridge, &owner, &mint, "Wrapped family", "WFAM", Some(collection_metadata))),
+        Err(LibError::UnverifiedCollection.into()),
+    );
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::{Bridge, Env, SIBLING};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::{get_merkle_root_with, Content, HashAlgo, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn content(bridge: &Bridge, owner: &Pubkey) -> Content {
+    Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(1000)))
+}
+
+// Native withdrawal of the tree hashed by `algo`
+fn withdraw(bridge: &Bridge, owner: &Pubkey, algo: HashAlgo) -> Instruction {
+    let path = vec![SIBLING];
+    let root = get_merkle_root_with(algo, content(bridge, owner).hash_with(algo), &path).unwrap();
+    let (signature, recovery_id) = bridge.signer.sign(&root);
+    instructions::withdraw_native(
+        bridge.program_id, bridge.admin, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000, signature, recovery_id, path, None, None, None, false, None,
+    )
+}
+
+#[test]
+fn same_tree_differs_between_algorithms() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = Pubkey::new_unique();
+
+    let keccak_leaf = content(&bridge, &owner).hash_with(HashAlgo::Keccak);
+    let sha256_leaf = content(&bridge, &owner).hash_with(HashAlgo::Sha256);
+    assert_eq!(keccak_leaf, content(&bridge, &owner).hash().to_bytes());
+    assert_ne!(keccak_leaf, sha256_leaf);
+
+    let path = vec![SIBLING];
+    assert_ne!(get_merkle_root_with(HashAlgo::Keccak, keccak_leaf, &path), get_merkle_root_with(HashAlgo::Sha256, sha256_leaf, &path));
+}
+
+#[test]
+fn proof_is_verified_with_admin_algorithm() {
+    for (algo, other) in [(HashAlgo::Keccak, HashAlgo::Sha256), (HashAlgo::Sha256, HashAlgo::Keccak)] {
+        let mut env = Env::new();
+        let bridge = Bridge::with_hash_algo(&mut env, algo);
+        assert_eq!(bridge.state(&env).hash_algo, algo);
+        env.fund(&bridge.admin, 1000);
+        let owner = env.new_funded();
+
+        assert_eq!(env.process(&withdraw(&bridge, &owner, other)), Err(LibError::WrongSignature.into()), "{:?}", algo);
+
+        let admin_lamports = env.lamports(&bridge.admin);
+        env.process(&withdraw(&bridge, &owner, algo)).unwrap();
+        assert_eq!(env.lamports(&bridge.admin), admin_lamports - 1000, "{:?}", algo);
+    }
+}
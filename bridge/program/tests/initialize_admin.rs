@@ -0,0 +1,39 @@
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::HashAlgo;
+use solana_program::instruction::Instruction;
+
+fn initialize_admin(env: &mut Env, bridge: &Bridge) -> Instruction {
+    let payer = env.new_funded();
+    instructions::initialize_admin(
+        bridge.program_id, bridge.admin, payer, bridge.commission_program, bridge.signer.public_key(), bridge.seeds, HashAlgo::Keccak,
+    )
+}
+
+#[test]
+fn pre_funded_admin_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::uninitialized(&mut env);
+    env.fund(&bridge.admin, 1000);
+
+    let instruction = initialize_admin(&mut env, &bridge);
+    assert_eq!(env.process(&instruction), Err(LibError::AlreadyInUse.into()));
+    assert!(env.logs().iter().any(|log| log.contains("is pre-funded with 1000 lamports")));
+    assert_eq!(env.lamports(&bridge.admin), 1000);
+    assert!(env.data(&bridge.admin).is_empty());
+}
+
+#[test]
+fn initialized_admin_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let state = bridge.state(&env);
+
+    let instruction = initialize_admin(&mut env, &bridge);
+    assert_eq!(env.process(&instruction), Err(LibError::AlreadyInUse.into()));
+    assert!(env.logs().iter().any(|log| log.contains("is already initialized")));
+    assert_eq!(bridge.state(&env), state);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{Bridge, Env, Signer};
+use lib::error::LibError;
+use lib::instructions::bridge::transfer_ownership;
+use lib::{KeyRotated, BRIDGE_DOMAIN, KEY_ROTATED_LOG_PREFIX};
+use solana_program::keccak;
+
+// KeyRotated events of the last processed instruction
+fn key_rotated(env: &Env) -> Vec<KeyRotated> {
+    env.data_logs().iter()
+        .filter(|log| log[0] == KEY_ROTATED_LOG_PREFIX.as_bytes())
+        .map(|log| KeyRotated::try_from_slice(&log[1]).unwrap())
+        .collect()
+}
+
+#[test]
+fn ownership_transfer_emits_key_rotated() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (old_key, new_key) = (bridge.signer.public_key(), Signer::new(2).public_key());
+
+    let (signature, recovery_id) = bridge.signer.sign(&keccak::hashv(&[BRIDGE_DOMAIN, new_key.as_slice()]).to_bytes());
+    env.process(&transfer_ownership(bridge.program_id, bridge.admin, signature, new_key, recovery_id, bridge.seeds)).unwrap();
+    assert_eq!(key_rotated(&env), vec![KeyRotated { admin: bridge.admin, old_key, new_key }]);
+
+    // Rejected rotation is not emitted
+    assert_eq!(
+        env.process(&transfer_ownership(bridge.program_id, bridge.admin, signature, new_key, recovery_id, bridge.seeds)),
+        Err(LibError::WrongSignature.into()),
+    );
+    assert!(key_rotated(&env).is_empty());
+}
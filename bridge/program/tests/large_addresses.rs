@@ -0,0 +1,48 @@
+//! Run with and without `--features large-addresses`.
+
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::deposit_native;
+use lib::{Network, TokenType};
+use solana_program::program_error::ProgramError;
+
+// Longer than the default limits (20 and 100 bytes), shorter than the large ones (64 and 256 bytes)
+const LONG_NETWORK: &str = "cosmoshub-4-long-network-name";
+const LONG_RECEIVER_SIZE: usize = 150;
+
+fn deposit(network: &str, receiver_size: usize) -> Result<(), (usize, ProgramError)> {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    env.process_transaction(&[
+        bridge.charge(&owner, TokenType::Native, 1000),
+        deposit_native(bridge.program_id, bridge.admin, owner, bridge.seeds, Network::Custom(String::from(network)), 1000, "a".repeat(receiver_size), None, None),
+    ])
+}
+
+fn expected() -> Result<(), (usize, ProgramError)> {
+    if cfg!(feature = "large-addresses") {
+        Ok(())
+    } else {
+        Err((1, LibError::WrongArgsSize.into()))
+    }
+}
+
+#[test]
+fn long_network_accepted_only_with_large_addresses() {
+    assert_eq!(deposit(LONG_NETWORK, 42), expected());
+}
+
+#[test]
+fn long_receiver_accepted_only_with_large_addresses() {
+    assert_eq!(deposit("Cosmos", LONG_RECEIVER_SIZE), expected());
+}
+
+#[test]
+fn oversized_inputs_are_always_rejected() {
+    assert_eq!(deposit(&"n".repeat(65), 42), Err((1, LibError::WrongArgsSize.into())));
+    assert_eq!(deposit("Cosmos", 257), Err((1, LibError::WrongArgsSize.into())));
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::set_max_single_withdraw;
+use lib::merkle::amount_bytes;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const LIMIT: u64 = 1000;
+
+fn set_limit(env: &mut Env, bridge: &Bridge, limit: u64) {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetMaxSingleWithdraw, amount_bytes(limit));
+    env.process(&set_max_single_withdraw(bridge.program_id, bridge.admin, bridge.seeds, limit, signature, recovery_id, vec![])).unwrap();
+}
+
+fn setup() -> (Env, Bridge, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_limit(&mut env, &bridge, LIMIT);
+    env.fund(&bridge.admin, 10_000);
+    let owner = env.new_funded();
+    (env, bridge, owner)
+}
+
+#[test]
+fn limit_is_stored() {
+    let (env, bridge, _) = setup();
+    assert_eq!(bridge.state(&env).max_single_withdraw, LIMIT);
+    assert_eq!(bridge.state(&env).nonce, 1);
+}
+
+#[test]
+fn native_withdraw_is_limited() {
+    let (mut env, bridge, owner) = setup();
+    assert_eq!(env.process(&bridge.withdraw_native(&owner, [7; 32], LIMIT + 1)), Err(LibError::RateLimited.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&[7; 32])));
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&bridge.withdraw_native(&owner, [8; 32], LIMIT)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - LIMIT);
+}
+
+#[test]
+fn ft_withdraw_is_limited() {
+    let (mut env, bridge, owner) = setup();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], LIMIT + 1, None)), Err(LibError::RateLimited.into()));
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [8; 32], LIMIT, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), LIMIT);
+}
+
+#[test]
+fn zero_limit_disables_check() {
+    let (mut env, bridge, owner) = setup();
+    set_limit(&mut env, &bridge, 0);
+    env.process(&bridge.withdraw_native(&owner, [7; 32], LIMIT + 1)).unwrap();
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use bridge::state::MintCap;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, get_mint_cap_address, SignedMetadata};
+use lib::merkle::{Content, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const TOKEN_SEED: [u8; 32] = [3; 32];
+const MAX_SUPPLY: u64 = 1500;
+
+fn signed_meta(max_supply: Option<u64>) -> SignedMetadata {
+    SignedMetadata {
+        name: String::from("Rarimo"),
+        symbol: String::from("RMO"),
+        uri: String::from("https://rarimo.com/token.json"),
+        decimals: 6,
+        max_supply,
+    }
+}
+
+fn token_mint(bridge: &Bridge) -> Pubkey {
+    Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id).0
+}
+
+// FT withdrawal creating the mint of the token seed, the cap is signed in the leaf
+fn create(bridge: &Bridge, owner: &Pubkey, origin: [u8; 32], amount: u64, max_supply: Option<u64>) -> Instruction {
+    let (mint, meta) = (token_mint(bridge), signed_meta(max_supply));
+    let transfer = TransferData::new_ft_transfer(mint.to_bytes(), amount, meta.name.clone(), meta.symbol.clone(), meta.uri.clone(), meta.decimals)
+        .with_max_supply(max_supply);
+    let (signature, recovery_id, path) = bridge.sign_withdraw(Content::new(origin, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer)));
+    instructions::withdraw_ft(
+        bridge.program_id, bridge.admin, mint, *owner, bridge.withdraw_address(&origin), bridge.seeds, origin, amount, None, signature, recovery_id, path,
+        Some(TOKEN_SEED), Some(meta), None, false, None, false, None,
+    )
+}
+
+#[test]
+fn mint_is_capped_by_signed_max_supply() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = token_mint(&bridge);
+
+    env.process(&create(&bridge, &owner, [7; 32], 1000, Some(MAX_SUPPLY))).unwrap();
+    let mint_cap = MintCap::deserialize(&mut env.data(&get_mint_cap_address(&bridge.program_id, &bridge.admin, &mint)).as_slice()).unwrap();
+    assert_eq!(mint_cap, MintCap { max_supply: MAX_SUPPLY, is_initialized: true });
+
+    // Minting up to the cap
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [8; 32], MAX_SUPPLY - 1000, None)).unwrap();
+    assert_eq!(env.mint(&mint).supply, MAX_SUPPLY);
+
+    // One over the cap
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, [9; 32], 1, None)), Err(LibError::MaxSupplyExceeded.into()));
+    assert_eq!(env.mint(&mint).supply, MAX_SUPPLY);
+}
+
+#[test]
+fn creation_over_max_supply_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    assert_eq!(env.process(&create(&bridge, &owner, [7; 32], MAX_SUPPLY + 1, Some(MAX_SUPPLY))), Err(LibError::MaxSupplyExceeded.into()));
+    assert!(!env.exists(&token_mint(&bridge)));
+}
+
+#[test]
+fn mint_without_cap_is_unlimited() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = token_mint(&bridge);
+
+    env.process(&create(&bridge, &owner, [7; 32], 1000, None)).unwrap();
+    assert!(!env.exists(&get_mint_cap_address(&bridge.program_id, &bridge.admin, &mint)));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [8; 32], u64::MAX - 1000, None)).unwrap();
+    assert_eq!(env.mint(&mint).supply, u64::MAX);
+}
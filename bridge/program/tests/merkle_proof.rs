@@ -0,0 +1,34 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{BridgeInstruction, WithdrawArgs};
+use lib::merkle::{MerkleProof, MAX_PROOF_LENGTH};
+use solana_program::instruction::Instruction;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Native withdrawal with the replaced proof
+fn with_path(instruction: Instruction, path: Vec<[u8; 32]>) -> Instruction {
+    let args = match BridgeInstruction::try_from_slice(&instruction.data).unwrap() {
+        BridgeInstruction::WithdrawNative(args) => WithdrawArgs { path: MerkleProof(path), ..args },
+        _ => unreachable!(),
+    };
+    Instruction { data: BridgeInstruction::WithdrawNative(args).try_to_vec().unwrap(), ..instruction }
+}
+
+#[test]
+fn withdraw_proof_length_is_checked() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 10_000);
+    let owner = env.new_funded();
+
+    let instruction = bridge.withdraw_native(&owner, ORIGIN, 1000);
+    assert_eq!(env.process(&with_path(instruction.clone(), vec![])), Err(LibError::WrongMerkleProof.into()));
+    // Over-long proof is rejected by the instruction validation
+    assert_eq!(env.process(&with_path(instruction.clone(), vec![[1; 32]; MAX_PROOF_LENGTH + 1])), Err(LibError::WrongMerklePath.into()));
+
+    env.process(&instruction).unwrap();
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::{Content, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Token held by the bridge with the metadata name as stored by Metaplex
+fn setup(name: &str) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&mint, &bridge.admin, 1000);
+    env.set_metadata(&ft_metadata(&mint, name, "RMO\0\0", "https://rarimo.com/token.json\0"));
+    let owner = env.new_funded();
+    (env, bridge, mint, owner)
+}
+
+// Withdrawal with the leaf signed off-chain for the name `signed_name`
+fn withdraw(bridge: &Bridge, owner: &Pubkey, mint: &Pubkey, signed_name: &str) -> Instruction {
+    let transfer = TransferData::new_ft_transfer(
+        mint.to_bytes(), 1000, String::from(signed_name), String::from("RMO"), String::from("https://rarimo.com/token.json"), 6,
+    );
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer));
+    let (signature, recovery_id, path) = bridge.sign_withdraw(content);
+    instructions::withdraw_ft(
+        bridge.program_id, bridge.admin, *mint, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000, None, signature, recovery_id, path,
+        None, None, None, false, None, false, None,
+    )
+}
+
+#[test]
+fn trailing_nulls_are_not_signed() {
+    let (mut env, bridge, mint, owner) = setup("Rarimo\0\0\0");
+    env.process(&withdraw(&bridge, &owner, &mint, "Rarimo")).unwrap();
+}
+
+#[test]
+fn leading_nulls_are_signed() {
+    let (mut env, bridge, mint, owner) = setup("\0Rarimo\0");
+    assert_eq!(env.process(&withdraw(&bridge, &owner, &mint, "Rarimo")), Err(LibError::WrongSignature.into()));
+    env.process(&withdraw(&bridge, &owner, &mint, "\0Rarimo")).unwrap();
+}
+
+#[test]
+fn embedded_nulls_are_signed() {
+    let (mut env, bridge, mint, owner) = setup("Rari\0mo\0\0");
+    assert_eq!(env.process(&withdraw(&bridge, &owner, &mint, "Rarimo")), Err(LibError::WrongSignature.into()));
+    env.process(&withdraw(&bridge, &owner, &mint, "Rari\0mo")).unwrap();
+}
+
+#[test]
+fn whitespace_is_signed() {
+    let (mut env, bridge, mint, owner) = setup(" Rarimo \0");
+    assert_eq!(env.process(&withdraw(&bridge, &owner, &mint, "Rarimo")), Err(LibError::WrongSignature.into()));
+    env.process(&withdraw(&bridge, &owner, &mint, " Rarimo ")).unwrap();
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use bridge::state::{BaselineBridgeAdmin, BridgeAdmin, LegacyBridgeAdmin, BASELINE_BRIDGE_ADMIN_SIZE, BRIDGE_ADMIN_SIZE, LEGACY_BRIDGE_ADMIN_SIZE};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::migrate_admin;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn migrate(env: &mut Env, bridge: &Bridge, commission_program: &Pubkey) -> Instruction {
+    let hash = solana_program::keccak::hashv(&[lib::BRIDGE_DOMAIN, bridge.admin.as_ref(), commission_program.as_ref()]);
+    let (signature, recovery_id) = bridge.signer.sign(&hash.to_bytes());
+    let payer = env.new_funded();
+    migrate_admin(bridge.program_id, bridge.admin, bridge.seeds, payer, *commission_program, signature, recovery_id)
+}
+
+#[test]
+fn legacy_admin_is_migrated() {
+    let mut env = Env::new();
+    let bridge = Bridge::uninitialized(&mut env);
+    let legacy = LegacyBridgeAdmin { public_key: bridge.signer.public_key(), is_initialized: true };
+    env.set_state(&bridge.admin, &bridge.program_id, &legacy, LEGACY_BRIDGE_ADMIN_SIZE);
+
+    let instruction = migrate(&mut env, &bridge, &bridge.commission_program);
+    env.process(&instruction).unwrap();
+    assert_eq!(env.data(&bridge.admin).len(), BRIDGE_ADMIN_SIZE);
+    let state = BridgeAdmin::deserialize(&mut env.data(&bridge.admin).as_slice()).unwrap();
+    assert_eq!(state, BridgeAdmin::migrated(bridge.signer.public_key(), bridge.commission_program));
+    assert_eq!(state.version, lib::STATE_VERSION);
+
+    // The account is not in the legacy layout anymore
+    assert_eq!(env.process(&instruction), Err(LibError::UnsupportedVersion.into()));
+}
+
+#[test]
+fn deployed_admin_commission_program_is_replaced() {
+    let mut env = Env::new();
+    let bridge = Bridge::uninitialized(&mut env);
+    let baseline = BaselineBridgeAdmin { public_key: bridge.signer.public_key(), commission_program: Pubkey::new_unique(), is_initialized: true };
+    env.set_state(&bridge.admin, &bridge.program_id, &baseline, BASELINE_BRIDGE_ADMIN_SIZE);
+
+    let instruction = migrate(&mut env, &bridge, &bridge.commission_program);
+    env.process(&instruction).unwrap();
+    assert_eq!(bridge.state(&env), BridgeAdmin::migrated(bridge.signer.public_key(), bridge.commission_program));
+}
+
+#[test]
+fn migration_requires_stored_key_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::uninitialized(&mut env);
+    let legacy = LegacyBridgeAdmin { public_key: common::Signer::new(2).public_key(), is_initialized: true };
+    env.set_state(&bridge.admin, &bridge.program_id, &legacy, LEGACY_BRIDGE_ADMIN_SIZE);
+
+    let instruction = migrate(&mut env, &bridge, &bridge.commission_program);
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+    assert_eq!(env.data(&bridge.admin).len(), LEGACY_BRIDGE_ADMIN_SIZE);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::amount_bytes;
+use solana_program::pubkey::Pubkey;
+
+fn set_min_deposit(env: &mut Env, bridge: &Bridge, min_deposit: u64) {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetMinDeposit, amount_bytes(min_deposit));
+    env.process(&instructions::set_min_deposit(bridge.program_id, bridge.admin, bridge.seeds, min_deposit, signature, recovery_id, vec![])).unwrap();
+}
+
+#[test]
+fn native_deposit_at_minimum() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_min_deposit(&mut env, &bridge, 1000);
+    assert_eq!(bridge.state(&env).min_deposit, 1000);
+    assert_eq!(bridge.state(&env).nonce, 1);
+
+    let owner = env.new_funded();
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process_transaction(&bridge.deposit_native(&owner, 1000)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + 1000);
+}
+
+#[test]
+fn native_deposit_below_minimum() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_min_deposit(&mut env, &bridge, 1000);
+
+    let owner = env.new_funded();
+    let result = env.process_transaction(&bridge.deposit_native(&owner, 999));
+    assert_eq!(result, Err((1, LibError::BelowMinimum.into())));
+}
+
+#[test]
+fn set_min_deposit_requires_admin_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+
+    // Signed for another value
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::SetMinDeposit, amount_bytes(1));
+    let result = env.process(&instructions::set_min_deposit(bridge.program_id, bridge.admin, bridge.seeds, 1000, signature, recovery_id, vec![]));
+    assert_eq!(result, Err(LibError::WrongSignature.into()));
+    assert_eq!(bridge.state(&env).min_deposit, 0);
+}
+
+#[test]
+fn ft_deposit_minimum_per_mint() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let (mint, other_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for mint in [&mint, &other_mint] {
+        env.create_mint(mint, &Pubkey::new_unique(), 6, None);
+        env.create_associated_token_account(mint, &owner, 10_000);
+    }
+
+    bridge.set_token_config(&mut env, &mint, true, true, None, 500).unwrap();
+
+    let result = env.process_transaction(&bridge.deposit_ft(&owner, &mint, 499));
+    assert_eq!(result, Err((1, LibError::BelowMinimum.into())));
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 500)).unwrap();
+    let bridge_associated = spl_associated_token_account::get_associated_token_address(&bridge.admin, &mint);
+    assert_eq!(env.token_balance(&bridge_associated), 500);
+
+    // The minimum of another mint is not affected
+    env.process_transaction(&bridge.deposit_ft(&owner, &other_mint, 1)).unwrap();
+}
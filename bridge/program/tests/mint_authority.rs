@@ -0,0 +1,57 @@
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn withdraw_mints_shortfall_of_bridge_owned_mint() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)).unwrap();
+
+    let owner_associated = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+    assert_eq!(env.mint(&mint).supply, 1000);
+}
+
+#[test]
+fn withdraw_rejects_minting_of_foreign_mint() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Wrapped", "WRP", "https://example.com/token.json"));
+
+    assert_eq!(
+        env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)),
+        Err(LibError::WrongMintAuthority.into()),
+    );
+}
+
+#[test]
+fn withdraw_of_foreign_mint_uses_bridge_balance() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Wrapped", "WRP", "https://example.com/token.json"));
+    let bridge_associated = env.create_associated_token_account(&mint, &bridge.admin, 1500);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)).unwrap();
+
+    let owner_associated = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+    assert_eq!(env.token_balance(&bridge_associated), 500);
+    assert_eq!(env.mint(&mint).supply, 1500);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{BridgeInstruction, MintCollectionArgs, SignedMetadata};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+use spl_associated_token_account::get_associated_token_address;
+
+const TOKEN_SEED: [u8; 32] = [5; 32];
+
+fn mint_collection(bridge: &Bridge, payer: &Pubkey) -> Instruction {
+    let mint = Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id).0;
+    let data = SignedMetadata {
+        name: String::from("Rarimo Collection"),
+        symbol: String::from("RMC"),
+        uri: String::from("https://rarimo.com/collection.json"),
+        decimals: 0,
+        max_supply: None,
+    };
+    Instruction {
+        program_id: bridge.program_id,
+        accounts: vec![
+            AccountMeta::new(bridge.admin, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(get_associated_token_address(&bridge.admin, &mint), false),
+            AccountMeta::new(mpl_token_metadata::pda::find_metadata_account(&mint).0, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: BridgeInstruction::MintCollection(MintCollectionArgs { data, seeds: bridge.seeds, token_seed: TOKEN_SEED }).try_to_vec().unwrap(),
+    }
+}
+
+#[test]
+fn collection_is_minted_once_per_seed() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let payer = env.new_funded();
+    let mint = Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id).0;
+
+    env.process(&mint_collection(&bridge, &payer)).unwrap();
+    assert_eq!(env.mint(&mint).supply, 1);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1);
+
+    let payer_lamports = env.lamports(&payer);
+    assert_eq!(env.process(&mint_collection(&bridge, &payer)), Err(LibError::AlreadyInUse.into()));
+    assert!(env.logs().iter().any(|log| log == &format!("Collection mint {} already exists", mint)));
+    assert_eq!(env.lamports(&payer), payer_lamports);
+    assert_eq!(env.mint(&mint).supply, 1);
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+
+const ORIGIN: [u8; 32] = [7; 32];
+const AMOUNT: u64 = 100_000_000;
+
+#[test]
+fn withdraw_to_overflowing_recipient_fails_gracefully() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, AMOUNT);
+    let owner = env.new_funded();
+    env.fund(&owner, u64::MAX - env.lamports(&owner) - 1000);
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    assert_eq!(env.process(&bridge.withdraw_native(&owner, ORIGIN, AMOUNT)), Err(LibError::WrongBalance.into()));
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert_eq!(env.lamports(&owner), u64::MAX - 1000);
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn withdraw_above_admin_balance_fails_gracefully() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let amount = env.lamports(&bridge.admin) + 1;
+    assert!(env.process(&bridge.withdraw_native(&owner, ORIGIN, amount)).is_err());
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
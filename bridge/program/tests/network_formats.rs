@@ -0,0 +1,100 @@
+mod common;
+
+use bridge::state::{OperationType, SupportedNetworks};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::{AddressFormat, Network, NetworkFormat, TokenType};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn cosmos() -> Network {
+    Network::Custom(String::from("Cosmos"))
+}
+
+fn formats() -> Vec<NetworkFormat> {
+    vec![
+        NetworkFormat { network: Network::Ethereum, format: AddressFormat::Hex },
+        NetworkFormat { network: Network::Solana, format: AddressFormat::Base58 },
+        NetworkFormat { network: cosmos(), format: AddressFormat::Bech32 },
+        NetworkFormat { network: Network::Polygon, format: AddressFormat::Any },
+    ]
+}
+
+fn setup() -> (Env, Bridge) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let networks = vec![Network::Ethereum, Network::Solana, cosmos(), Network::Polygon, Network::BSC];
+
+    let data = networks.iter().flat_map(|network| solana_program::keccak::hash(network.as_bytes()).to_bytes()).collect();
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::SetNetworks, data);
+    let payer = env.new_funded();
+    env.process(&instructions::set_networks(bridge.program_id, bridge.admin, bridge.seeds, payer, networks, signature, recovery_id, vec![])).unwrap();
+
+    let mut data = Vec::new();
+    for format in formats() {
+        data.extend(solana_program::keccak::hash(format.network.as_bytes()).to_bytes());
+        data.push(format.format as u8);
+    }
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::SetNetworkFormats, data);
+    env.process(&instructions::set_network_formats(bridge.program_id, bridge.admin, bridge.seeds, payer, formats(), signature, recovery_id, vec![])).unwrap();
+    (env, bridge)
+}
+
+fn deposit_native(bridge: &Bridge, owner: &Pubkey, network: Network, receiver: &str) -> Vec<Instruction> {
+    vec![
+        bridge.charge(owner, TokenType::Native, 1000),
+        instructions::deposit_native(bridge.program_id, bridge.admin, *owner, bridge.seeds, network, 1000, String::from(receiver), None, None),
+    ]
+}
+
+#[test]
+fn formats_are_stored() {
+    let (env, bridge) = setup();
+    let networks = instructions::get_networks_address(&bridge.program_id, &bridge.admin);
+    let state = SupportedNetworks::deserialize(&mut env.data(&networks).as_slice()).unwrap();
+    assert_eq!(state.formats, formats());
+    assert_eq!(bridge.state(&env).nonce, 2);
+}
+
+#[test]
+fn receiver_of_network_format_is_accepted() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let cases = [
+        (Network::Ethereum, "0x52908400098527886E0F7030069857D2E4169EE7"),
+        (Network::Solana, "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        (cosmos(), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+        (Network::Polygon, "any receiver"),
+        // Network without format
+        (Network::BSC, "any receiver"),
+    ];
+
+    for (network, receiver) in cases {
+        let admin_lamports = env.lamports(&bridge.admin);
+        env.process_transaction(&deposit_native(&bridge, &owner, network, receiver)).unwrap();
+        assert_eq!(env.lamports(&bridge.admin), admin_lamports + 1000, "{}", receiver);
+    }
+}
+
+#[test]
+fn receiver_of_other_format_is_rejected() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let cases = [
+        (Network::Ethereum, "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        (Network::Solana, "0x52908400098527886E0F7030069857D2E4169EE7"),
+        (cosmos(), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5"),
+    ];
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    for (network, receiver) in cases {
+        assert_eq!(
+            env.process_transaction(&deposit_native(&bridge, &owner, network, receiver)),
+            Err((1, LibError::WrongAddressFormat.into())),
+            "{}", receiver,
+        );
+    }
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+}
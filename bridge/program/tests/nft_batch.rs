@@ -0,0 +1,68 @@
+mod common;
+
+use common::{Bridge, Env, RECEIVER};
+use lib::error::LibError;
+use lib::instructions::bridge::{deposit_nft_batch, MAX_NFT_BATCH_SIZE};
+use lib::{Network, TokenType};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+fn nft(env: &mut Env, owner: &Pubkey, amount: u64) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 0, None);
+    env.create_associated_token_account(&mint, owner, amount);
+    mint
+}
+
+fn batch(bridge: &Bridge, owner: &Pubkey, mints: &[Pubkey]) -> Vec<Instruction> {
+    vec![
+        bridge.charge(owner, TokenType::NFT, mints.len() as u64),
+        deposit_nft_batch(
+            bridge.program_id, bridge.admin, *owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), mints.to_vec(), vec![None; mints.len()],
+        ),
+    ]
+}
+
+#[test]
+fn batch_of_three_is_deposited() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mints: Vec<Pubkey> = (0..3).map(|_| nft(&mut env, &owner, 1)).collect();
+
+    env.process_transaction(&batch(&bridge, &owner, &mints)).unwrap();
+
+    for mint in &mints {
+        assert_eq!(env.token_balance(&get_associated_token_address(&owner, mint)), 0);
+        assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, mint)), 1);
+    }
+    assert!(env.logs().iter().any(|log| log.starts_with("Deposited 3 NFTs")));
+}
+
+#[test]
+fn batch_with_empty_account_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mints = [nft(&mut env, &owner, 1), nft(&mut env, &owner, 0), nft(&mut env, &owner, 1)];
+
+    assert_eq!(env.process_transaction(&batch(&bridge, &owner, &mints)), Err((1, LibError::WrongBalance.into())));
+
+    // Nothing is deposited
+    for (mint, amount) in mints.iter().zip([1, 0, 1]) {
+        assert_eq!(env.token_balance(&get_associated_token_address(&owner, mint)), amount);
+        assert!(!env.exists(&get_associated_token_address(&bridge.admin, mint)));
+    }
+}
+
+#[test]
+fn oversized_batch_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mints: Vec<Pubkey> = (0..MAX_NFT_BATCH_SIZE + 1).map(|_| nft(&mut env, &owner, 1)).collect();
+
+    assert_eq!(env.process_transaction(&batch(&bridge, &owner, &mints)), Err((1, LibError::WrongArgsSize.into())));
+    env.process_transaction(&batch(&bridge, &owner, &mints[..MAX_NFT_BATCH_SIZE])).unwrap();
+}
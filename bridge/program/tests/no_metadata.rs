@@ -0,0 +1,57 @@
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::{Content, TransferData};
+use mpl_token_metadata::pda::find_metadata_account;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Wrapped token without metadata account, held by the bridge
+fn setup() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 8, None);
+    env.create_associated_token_account(&mint, &bridge.admin, 1000);
+    let owner = env.new_funded();
+    (env, bridge, mint, owner)
+}
+
+fn withdraw(bridge: &Bridge, owner: &Pubkey, mint: &Pubkey, no_metadata: bool, token_seed: Option<[u8; 32]>) -> Instruction {
+    let transfer = TransferData::new_ft_transfer_without_metadata(mint.to_bytes(), 1000, 8);
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer));
+    let (signature, recovery_id, path) = bridge.sign_withdraw(content);
+    instructions::withdraw_ft(
+        bridge.program_id, bridge.admin, *mint, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000, None, signature, recovery_id, path,
+        token_seed, None, None, false, None, no_metadata, None,
+    )
+}
+
+#[test]
+fn token_without_metadata_is_withdrawn() {
+    let (mut env, bridge, mint, owner) = setup();
+    assert!(!env.exists(&find_metadata_account(&mint).0));
+
+    env.process(&withdraw(&bridge, &owner, &mint, true, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 0);
+    assert!(env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn metadata_is_required_by_default() {
+    let (mut env, bridge, mint, owner) = setup();
+    assert!(env.process(&withdraw(&bridge, &owner, &mint, false, None)).is_err());
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn created_token_can_not_skip_metadata() {
+    let (mut env, bridge, mint, owner) = setup();
+    assert_eq!(env.process(&withdraw(&bridge, &owner, &mint, true, Some([5; 32]))), Err(LibError::WrongArgsSize.into()));
+}
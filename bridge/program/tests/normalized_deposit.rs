@@ -0,0 +1,79 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::{Deposited, DEPOSITED_LOG_PREFIX};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// Deposited events of the last processed transaction
+fn deposited(env: &Env) -> Vec<Deposited> {
+    env.data_logs().iter()
+        .filter(|log| log[0] == DEPOSITED_LOG_PREFIX.as_bytes())
+        .map(|log| Deposited::try_from_slice(&log[1]).unwrap())
+        .collect()
+}
+
+// Mint of `decimals` with the target decimals configured, the owner holds `balance`
+fn setup(decimals: u8, target_decimals: Option<u8>, balance: u64) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), decimals, None);
+    env.create_associated_token_account(&mint, &owner, balance);
+    bridge.set_token_config(&mut env, &mint, true, true, target_decimals, 0).unwrap();
+    (env, bridge, owner, mint)
+}
+
+#[test]
+fn amount_without_target_decimals_is_not_normalized() {
+    let (mut env, bridge, owner, mint) = setup(6, None, 1000);
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+
+    let event = &deposited(&env)[0];
+    assert_eq!(event.mint, mint);
+    assert_eq!((event.amount, event.normalized_amount), (1000, 1000));
+}
+
+#[test]
+fn amount_is_scaled_up_to_target_decimals() {
+    let (mut env, bridge, owner, mint) = setup(6, Some(18), 1000);
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+
+    let event = &deposited(&env)[0];
+    assert_eq!((event.amount, event.normalized_amount), (1000, 1000 * 10u64.pow(12)));
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+}
+
+#[test]
+fn amount_is_scaled_down_to_target_decimals() {
+    let (mut env, bridge, owner, mint) = setup(9, Some(6), 5_000_000);
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 5_000_000)).unwrap();
+
+    let event = &deposited(&env)[0];
+    assert_eq!((event.amount, event.normalized_amount), (5_000_000, 5000));
+}
+
+#[test]
+fn overflowing_amount_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(0, Some(18), u64::MAX);
+    let owner_associated = get_associated_token_address(&owner, &mint);
+
+    let result = env.process_transaction(&bridge.deposit_ft(&owner, &mint, 100));
+    assert_eq!(result, Err((1, LibError::WrongAmountPrecision.into())));
+    assert_eq!(env.token_balance(&owner_associated), u64::MAX);
+
+    // 18 * 10^18 still fits in u64
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 18)).unwrap();
+    assert_eq!(deposited(&env)[0].normalized_amount, 18 * 10u64.pow(18));
+}
+
+#[test]
+fn amount_with_lost_digits_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(9, Some(6), 5_000_000);
+    let result = env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1_000_001));
+    assert_eq!(result, Err((1, LibError::WrongAmountPrecision.into())));
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 5_000_000);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, OriginPreimage};
+use lib::merkle::{Content, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn setup() -> (Env, Bridge, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 10_000);
+    let owner = env.new_funded();
+    (env, bridge, owner)
+}
+
+fn preimage(event_id: &str) -> OriginPreimage {
+    OriginPreimage { tx_hash: vec![0xEE; 32], event_id: String::from(event_id), network_from: String::from("Ethereum") }
+}
+
+// Native withdrawal of the signed `origin` with the preimage provided
+fn withdraw(bridge: &Bridge, owner: &Pubkey, origin: [u8; 32], origin_preimage: Option<OriginPreimage>) -> Instruction {
+    let content = Content::new(origin, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(1000)));
+    let (signature, recovery_id, path) = bridge.sign_withdraw(content);
+    instructions::withdraw_native(
+        bridge.program_id, bridge.admin, *owner, bridge.withdraw_address(&origin), bridge.seeds, origin, 1000, signature, recovery_id, path, None, None, None, false, origin_preimage,
+    )
+}
+
+#[test]
+fn matching_preimage_is_accepted() {
+    let (mut env, bridge, owner) = setup();
+    let origin = preimage("1").hash();
+    assert_eq!(origin, solana_program::keccak::hashv(&[&[0xEE; 32], b"1", b"Ethereum"]).to_bytes());
+
+    env.process(&withdraw(&bridge, &owner, origin, Some(preimage("1")))).unwrap();
+    assert!(env.exists(&bridge.withdraw_address(&origin)));
+}
+
+#[test]
+fn mismatching_preimage_is_rejected() {
+    let (mut env, bridge, owner) = setup();
+    let origin = preimage("1").hash();
+
+    assert_eq!(env.process(&withdraw(&bridge, &owner, origin, Some(preimage("2")))), Err(LibError::WrongContentHash.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&origin)));
+}
+
+#[test]
+fn raw_origin_is_accepted_without_preimage() {
+    let (mut env, bridge, owner) = setup();
+    env.process(&withdraw(&bridge, &owner, [7; 32], None)).unwrap();
+    assert!(env.exists(&bridge.withdraw_address(&[7; 32])));
+}
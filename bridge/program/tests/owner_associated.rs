@@ -0,0 +1,68 @@
+mod common;
+
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use lib::error::LibError;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// Token account at the owner associated address with the wrong owner or mint field,
+// it can not be created by the associated token program, so it is written directly
+fn set_owner_associated(env: &mut Env, owner: &Pubkey, mint: &Pubkey, account_owner: &Pubkey, account_mint: &Pubkey) {
+    let key = get_associated_token_address(owner, mint);
+    env.create_token_account(&key, account_mint, account_owner, 0);
+}
+
+fn setup_ft() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    (env, bridge, owner, mint)
+}
+
+fn setup_nft() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 0, None);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+    env.create_associated_token_account(&mint, &bridge.admin, 1);
+    (env, bridge, owner, mint)
+}
+
+#[test]
+fn ft_withdraw_rejects_foreign_owner_account() {
+    let (mut env, bridge, owner, mint) = setup_ft();
+    set_owner_associated(&mut env, &owner, &mint, &Pubkey::new_unique(), &mint);
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)), Err(LibError::WrongTokenAccount.into()));
+}
+
+#[test]
+fn ft_withdraw_rejects_other_mint_account() {
+    let (mut env, bridge, owner, mint) = setup_ft();
+    let other_mint = Pubkey::new_unique();
+    env.create_mint(&other_mint, &bridge.admin, 6, None);
+    set_owner_associated(&mut env, &owner, &mint, &owner, &other_mint);
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, None)), Err(LibError::WrongTokenAccount.into()));
+}
+
+#[test]
+fn nft_withdraw_rejects_foreign_owner_account() {
+    let (mut env, bridge, owner, mint) = setup_nft();
+    set_owner_associated(&mut env, &owner, &mint, &Pubkey::new_unique(), &mint);
+
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, [7; 32])), Err(LibError::WrongTokenAccount.into()));
+}
+
+#[test]
+fn nft_withdraw_to_owner_account() {
+    let (mut env, bridge, owner, mint) = setup_nft();
+
+    env.process(&bridge.withdraw_nft(&env, &owner, &mint, [7; 32])).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use bridge::state::Withdraw;
+use borsh::BorshDeserialize;
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn setup() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    (env, bridge, owner, mint)
+}
+
+fn withdraw(env: &Env, bridge: &Bridge) -> Withdraw {
+    Withdraw::deserialize(&mut env.data(&bridge.withdraw_address(&ORIGIN)).as_slice()).unwrap()
+}
+
+#[test]
+fn two_partial_claims_sum_to_total() {
+    let (mut env, bridge, owner, mint) = setup();
+    let owner_associated = get_associated_token_address(&owner, &mint);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, Some(400))).unwrap();
+    assert_eq!(env.token_balance(&owner_associated), 400);
+    assert_eq!(withdraw(&env, &bridge).claimed, 400);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, Some(600))).unwrap();
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+    assert_eq!(withdraw(&env, &bridge).claimed, 1000);
+
+    // Fully claimed withdrawal is spent
+    assert_eq!(
+        env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)),
+        Err(LibError::AlreadyInUse.into()),
+    );
+}
+
+#[test]
+fn over_claim_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup();
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, Some(700))).unwrap();
+    assert_eq!(
+        env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, Some(301))),
+        Err(LibError::WrongBalance.into()),
+    );
+
+    // Remaining amount is claimed by default
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+}
+
+#[test]
+fn claim_of_other_total_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup();
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, Some(400))).unwrap();
+    assert_eq!(
+        env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 2000, Some(400))),
+        Err(LibError::AlreadyInUse.into()),
+    );
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use bridge::state::Withdraw;
+use borsh::BorshDeserialize;
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use lib::error::LibError;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn setup(decimals: u8) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, decimals, None);
+    (env, bridge, owner, mint)
+}
+
+fn receiver(env: &Env, bridge: &Bridge) -> Pubkey {
+    Withdraw::deserialize(&mut env.data(&bridge.withdraw_address(&ORIGIN)).as_slice()).unwrap().receiver_address
+}
+
+#[test]
+fn ft_is_delivered_to_content_receiver() {
+    let (mut env, bridge, owner, mint) = setup(6);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+    assert_eq!(receiver(&env, &bridge), owner);
+}
+
+#[test]
+fn ft_account_of_other_owner_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(6);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    // Token account at the receiver associated address owned by someone else
+    env.create_token_account(&get_associated_token_address(&owner, &mint), &mint, &Pubkey::new_unique(), 0);
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)), Err(LibError::WrongTokenAccount.into()));
+    assert_eq!(env.mint(&mint).supply, 0);
+}
+
+#[test]
+fn nft_is_delivered_to_content_receiver() {
+    let (mut env, bridge, owner, mint) = setup(0);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+
+    env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1);
+    assert_eq!(receiver(&env, &bridge), owner);
+}
+
+#[test]
+fn nft_account_of_other_owner_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup(0);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+    env.create_token_account(&get_associated_token_address(&owner, &mint), &mint, &Pubkey::new_unique(), 0);
+
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)), Err(LibError::WrongTokenAccount.into()));
+    assert_eq!(env.mint(&mint).supply, 0);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const TOKEN_SEED: [u8; 32] = [5; 32];
+
+// Bridged mint with the empty bridge associated account
+fn setup() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (mint, _) = Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id);
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    let bridge_associated = env.create_associated_token_account(&mint, &bridge.admin, 0);
+    (env, bridge, mint, bridge_associated)
+}
+
+fn reclaim_mint(env: &Env, bridge: &Bridge, receiver: &Pubkey) -> Instruction {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::ReclaimMint, [TOKEN_SEED.as_slice(), receiver.as_ref()].concat());
+    instructions::reclaim_mint(bridge.program_id, bridge.admin, bridge.seeds, TOKEN_SEED, *receiver, signature, recovery_id, vec![])
+}
+
+#[test]
+fn zero_supply_mint_is_reclaimed() {
+    let (mut env, bridge, mint, bridge_associated) = setup();
+    let receiver = Pubkey::new_unique();
+    let rent = env.lamports(&bridge_associated);
+
+    env.process(&reclaim_mint(&env, &bridge, &receiver)).unwrap();
+    assert!(!env.exists(&bridge_associated));
+    assert_eq!(env.lamports(&receiver), rent);
+    assert_eq!(bridge.state(&env).nonce, 1);
+    // Mint accounts can not be closed
+    assert!(env.exists(&mint));
+}
+
+#[test]
+fn nonzero_supply_mint_is_rejected() {
+    let (mut env, bridge, mint, bridge_associated) = setup();
+    // Tokens still held on Solana
+    env.create_associated_token_account(&mint, &Pubkey::new_unique(), 10);
+    let receiver = Pubkey::new_unique();
+
+    assert_eq!(env.process(&reclaim_mint(&env, &bridge, &receiver)), Err(LibError::NonZeroSupply.into()));
+    assert!(env.exists(&bridge_associated));
+    assert!(!env.exists(&receiver));
+    assert_eq!(bridge.state(&env).nonce, 0);
+}
+
+#[test]
+fn reclaim_for_other_receiver_is_rejected() {
+    let (mut env, bridge, mint, _) = setup();
+    let mut instruction = reclaim_mint(&env, &bridge, &Pubkey::new_unique());
+    instruction.accounts[3].pubkey = Pubkey::new_unique();
+
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+    assert!(env.exists(&get_associated_token_address(&bridge.admin, &mint)));
+}
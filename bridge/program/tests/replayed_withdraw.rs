@@ -0,0 +1,74 @@
+//! Run with and without `--features compute-logs,debug-logs`.
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::BridgeInstruction;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// The same withdrawal with the zeroed signature, fails with InvalidSignature if the signature is verified
+fn unsigned(mut instruction: Instruction) -> Instruction {
+    let mut data = BridgeInstruction::try_from_slice(&instruction.data).unwrap();
+    match &mut data {
+        BridgeInstruction::WithdrawNative(args) | BridgeInstruction::WithdrawFT(args) | BridgeInstruction::WithdrawNFT(args) => args.signature = [0; 64],
+        _ => unreachable!(),
+    }
+    instruction.data = data.try_to_vec().unwrap();
+    instruction
+}
+
+// Neither the signature is verified nor the compute milestone after it is logged
+fn assert_rejected_early(env: &mut Env, instruction: Instruction) {
+    assert_eq!(env.process(&unsigned(instruction)), Err(LibError::AlreadyInUse.into()));
+    assert!(env.logs().iter().all(|log| !log.contains("signature verified") && !log.starts_with("Recovered public key")));
+}
+
+#[test]
+fn replayed_native_withdraw_fails_before_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 10_000);
+    let owner = env.new_funded();
+
+    let instruction = bridge.withdraw_native(&owner, ORIGIN, 1000);
+    assert_eq!(env.process(&unsigned(instruction.clone())), Err(LibError::InvalidSignature.into()));
+    env.process(&instruction).unwrap();
+    assert_rejected_early(&mut env, instruction);
+}
+
+#[test]
+fn replayed_ft_withdraw_fails_before_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    // Partially claimed withdrawal is not spent, the next claim is verified
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, Some(400))).unwrap();
+    assert_eq!(env.process(&unsigned(bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None))), Err(LibError::InvalidSignature.into()));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    let instruction = bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None);
+    assert_rejected_early(&mut env, instruction);
+}
+
+#[test]
+fn replayed_nft_withdraw_fails_before_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 0, None);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+
+    let instruction = bridge.withdraw_nft(&env, &owner, &mint, ORIGIN);
+    env.process(&instruction).unwrap();
+    assert_rejected_early(&mut env, instruction);
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::{Bridge, Env, Signer};
+use lib::error::LibError;
+use lib::instructions::bridge::transfer_ownership;
+use lib::{BRIDGE_DOMAIN, COMMISSION_DOMAIN, UPGRADE_DOMAIN};
+use solana_program::keccak;
+
+fn transfer_ownership_signed(bridge: &Bridge, domain: &[u8], new_public_key: [u8; 64]) -> solana_program::instruction::Instruction {
+    let (signature, recovery_id) = bridge.signer.sign(&keccak::hashv(&[domain, new_public_key.as_slice()]).to_bytes());
+    transfer_ownership(bridge.program_id, bridge.admin, signature, new_public_key, recovery_id, bridge.seeds)
+}
+
+#[test]
+fn signature_of_other_domain_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let new_public_key = Signer::new(2).public_key();
+
+    // The upgrade program signs the ownership transfer of the same key format
+    for domain in [UPGRADE_DOMAIN, COMMISSION_DOMAIN, b"".as_slice()] {
+        assert_eq!(
+            env.process(&transfer_ownership_signed(&bridge, domain, new_public_key)),
+            Err(LibError::WrongSignature.into()),
+        );
+    }
+
+    env.process(&transfer_ownership_signed(&bridge, BRIDGE_DOMAIN, new_public_key)).unwrap();
+    assert_eq!(bridge.state(&env).public_key, new_public_key);
+}
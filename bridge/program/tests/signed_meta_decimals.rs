@@ -0,0 +1,90 @@
+mod common;
+
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, SignedMetadata, MAX_FT_DECIMALS};
+use lib::merkle::{Content, TransferData};
+use mpl_token_metadata::pda::find_metadata_account;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+const TOKEN_SEED: [u8; 32] = [3; 32];
+
+fn signed_meta(decimals: u8) -> SignedMetadata {
+    SignedMetadata {
+        name: String::from("Rarimo"),
+        symbol: String::from("RMO"),
+        uri: String::from("https://rarimo.com/token.json"),
+        decimals,
+        max_supply: None,
+    }
+}
+
+fn token_mint(bridge: &Bridge) -> Pubkey {
+    Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id).0
+}
+
+// FT withdrawal creating the mint of the token seed with the signed metadata
+fn withdraw_ft(bridge: &Bridge, owner: &Pubkey, meta: SignedMetadata) -> Instruction {
+    let mint = token_mint(bridge);
+    let transfer = TransferData::new_ft_transfer(mint.to_bytes(), 1000, meta.name.clone(), meta.symbol.clone(), meta.uri.clone(), meta.decimals);
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer));
+    let (signature, recovery_id, path) = bridge.sign_withdraw(content);
+    instructions::withdraw_ft(
+        bridge.program_id, bridge.admin, mint, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000, None, signature, recovery_id, path,
+        Some(TOKEN_SEED), Some(meta), None, false, None, false, None,
+    )
+}
+
+// NFT withdrawal creating the mint of the token seed with the signed metadata
+fn withdraw_nft(bridge: &Bridge, owner: &Pubkey, meta: SignedMetadata) -> Instruction {
+    let mint = token_mint(bridge);
+    let transfer = TransferData::new_nft_transfer(mint.to_bytes(), None, meta.name.clone(), meta.symbol.clone(), meta.uri.clone());
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer));
+    let (signature, recovery_id, path) = bridge.sign_withdraw(content);
+    instructions::withdraw_nft(
+        bridge.program_id, bridge.admin, mint, find_metadata_account(&mint).0, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1,
+        signature, recovery_id, path, Some(TOKEN_SEED), Some(meta), None, false, None, None,
+    )
+}
+
+#[test]
+fn ft_mint_is_created_with_signed_decimals() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    env.process(&withdraw_ft(&bridge, &owner, signed_meta(6))).unwrap();
+    let mint = env.mint(&token_mint(&bridge));
+    assert_eq!(mint.decimals, 6);
+    assert_eq!(mint.supply, 1000);
+}
+
+#[test]
+fn ft_decimals_above_max_are_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    assert_eq!(env.process(&withdraw_ft(&bridge, &owner, signed_meta(MAX_FT_DECIMALS + 1))), Err(LibError::WrongArgsSize.into()));
+    assert!(!env.exists(&token_mint(&bridge)));
+
+    env.process(&withdraw_ft(&bridge, &owner, signed_meta(MAX_FT_DECIMALS))).unwrap();
+    assert_eq!(env.mint(&token_mint(&bridge)).decimals, MAX_FT_DECIMALS);
+}
+
+#[test]
+fn nft_with_nonzero_decimals_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    assert_eq!(env.process(&withdraw_nft(&bridge, &owner, signed_meta(1))), Err(LibError::WrongArgsSize.into()));
+    assert!(!env.exists(&token_mint(&bridge)));
+
+    env.process(&withdraw_nft(&bridge, &owner, signed_meta(0))).unwrap();
+    let mint = env.mint(&token_mint(&bridge));
+    assert_eq!(mint.decimals, 0);
+    assert_eq!(mint.supply, 1);
+}
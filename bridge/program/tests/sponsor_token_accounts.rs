@@ -0,0 +1,76 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::set_sponsor_token_accounts;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use spl_associated_token_account::get_associated_token_address;
+
+fn set_sponsor(env: &mut Env, bridge: &Bridge, sponsor: bool) {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetSponsorTokenAccounts, vec![sponsor as u8]);
+    env.process(&set_sponsor_token_accounts(bridge.program_id, bridge.admin, bridge.seeds, sponsor, signature, recovery_id, vec![])).unwrap();
+}
+
+// Depositor holding 1000 tokens of a new mint, the bridge associated account does not exist
+fn setup() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&mint, &owner, 1000);
+    (env, bridge, owner, mint)
+}
+
+fn token_account_rent() -> u64 {
+    Rent::default().minimum_balance(spl_token::state::Account::LEN)
+}
+
+#[test]
+fn depositor_pays_by_default() {
+    let (mut env, bridge, owner, mint) = setup();
+    let (owner_lamports, admin_lamports) = (env.lamports(&owner), env.lamports(&bridge.admin));
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+    assert_eq!(env.lamports(&owner), owner_lamports - token_account_rent());
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+}
+
+#[test]
+fn bridge_admin_pays_in_sponsored_mode() {
+    let (mut env, bridge, owner, mint) = setup();
+    set_sponsor(&mut env, &bridge, true);
+    assert!(bridge.state(&env).sponsor_token_accounts);
+    env.fund(&bridge.admin, token_account_rent());
+    let (owner_lamports, admin_lamports) = (env.lamports(&owner), env.lamports(&bridge.admin));
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+    assert_eq!(env.lamports(&owner), owner_lamports);
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - token_account_rent());
+
+    // Disabled again, the depositor pays
+    set_sponsor(&mut env, &bridge, false);
+    let other_mint = Pubkey::new_unique();
+    env.create_mint(&other_mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&other_mint, &owner, 1000);
+    env.process_transaction(&bridge.deposit_ft(&owner, &other_mint, 1000)).unwrap();
+    assert_eq!(env.lamports(&owner), owner_lamports - token_account_rent());
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - token_account_rent());
+}
+
+#[test]
+fn sponsored_deposit_keeps_admin_rent_exempt() {
+    let (mut env, bridge, owner, mint) = setup();
+    set_sponsor(&mut env, &bridge, true);
+    env.fund(&bridge.admin, token_account_rent() - 1);
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    assert_eq!(env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)), Err((1, LibError::WrongBalance.into())));
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert!(!env.exists(&get_associated_token_address(&bridge.admin, &mint)));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use bridge::state::{BaselineBridgeAdmin, BaselineWithdraw, BridgeAdmin, Withdraw, BASELINE_BRIDGE_ADMIN_SIZE, BASELINE_WITHDRAW_SIZE, BRIDGE_ADMIN_SIZE, WITHDRAW_SIZE};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::migrate_state;
+use lib::TokenType;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn migrate(env: &mut Env, bridge: &Bridge, account: &Pubkey) -> Result<(), solana_program::program_error::ProgramError> {
+    let payer = env.new_funded();
+    env.process(&migrate_state(bridge.program_id, *account, payer))
+}
+
+#[test]
+fn baseline_admin_is_migrated() {
+    let mut env = Env::new();
+    let bridge = Bridge::uninitialized(&mut env);
+    let baseline = BaselineBridgeAdmin { public_key: bridge.signer.public_key(), commission_program: bridge.commission_program, is_initialized: true };
+    env.set_state(&bridge.admin, &bridge.program_id, &baseline, BASELINE_BRIDGE_ADMIN_SIZE);
+
+    // Version 0 account is not read by the other instructions
+    let owner = env.new_funded();
+    assert!(env.process(&bridge.withdraw_native(&owner, ORIGIN, 1000)).is_err());
+
+    migrate(&mut env, &bridge, &bridge.admin).unwrap();
+    assert_eq!(env.data(&bridge.admin).len(), BRIDGE_ADMIN_SIZE);
+    assert!(env.lamports(&bridge.admin) >= Rent::default().minimum_balance(BRIDGE_ADMIN_SIZE));
+    let state = bridge.state(&env);
+    assert_eq!(state, BridgeAdmin::migrated(bridge.signer.public_key(), bridge.commission_program));
+    assert_eq!(state.version, lib::STATE_VERSION);
+
+    env.fund(&bridge.admin, 1000);
+    env.process(&bridge.withdraw_native(&owner, ORIGIN, 1000)).unwrap();
+}
+
+#[test]
+fn baseline_withdraw_is_migrated_as_claimed() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let withdraw = bridge.withdraw_address(&ORIGIN);
+    let baseline = BaselineWithdraw { token_type: TokenType::Native, mint: None, amount: 1000, origin: ORIGIN, receiver_address: owner, is_initialized: true };
+    env.set_state(&withdraw, &bridge.program_id, &baseline, BASELINE_WITHDRAW_SIZE);
+
+    migrate(&mut env, &bridge, &withdraw).unwrap();
+    assert_eq!(env.data(&withdraw).len(), WITHDRAW_SIZE);
+    let state = Withdraw::deserialize(&mut env.data(&withdraw).as_slice()).unwrap();
+    assert_eq!(state, Withdraw {
+        version: lib::STATE_VERSION,
+        token_type: TokenType::Native,
+        mint: None,
+        amount: 1000,
+        origin: ORIGIN,
+        receiver_address: owner,
+        is_initialized: true,
+        claimed: 1000,
+    });
+
+    assert_eq!(env.process(&bridge.withdraw_native(&owner, ORIGIN, 1000)), Err(LibError::AlreadyInUse.into()));
+}
+
+#[test]
+fn current_version_is_left_as_is() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let data = env.data(&bridge.admin);
+
+    migrate(&mut env, &bridge, &bridge.admin).unwrap();
+    assert_eq!(env.data(&bridge.admin), data);
+    assert_eq!(bridge.state(&env).version, lib::STATE_VERSION);
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let state = BridgeAdmin { version: lib::STATE_VERSION + 1, ..bridge.state(&env) };
+    bridge.set_state(&mut env, &state);
+
+    assert_eq!(migrate(&mut env, &bridge, &bridge.admin), Err(LibError::UnsupportedVersion.into()));
+    let owner = env.new_funded();
+    assert_eq!(env.process(&bridge.withdraw_native(&owner, ORIGIN, 1000)), Err(LibError::UnsupportedVersion.into()));
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use bridge::state::{OperationType, StoredRoots};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env, Signer, SIBLING};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, get_stored_roots_address};
+use lib::merkle::{get_merkle_root_with, Content, TransferData};
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn store(env: &mut Env, bridge: &Bridge, signer: &Signer, roots: Vec<[u8; 32]>, capacity: u16, batches: u64) -> Result<(), ProgramError> {
+    let hash = solana_program::keccak::hashv(&[
+        lib::BRIDGE_DOMAIN,
+        lib::STORED_ROOTS_PDA_SEED.as_bytes(),
+        bridge.admin.as_ref(),
+        capacity.to_be_bytes().as_slice(),
+        batches.to_be_bytes().as_slice(),
+        solana_program::keccak::hash(roots.concat().as_slice()).as_ref(),
+    ]);
+    let (signature, recovery_id) = signer.sign(&hash.to_bytes());
+    let payer = env.new_funded();
+    env.process(&instructions::store_roots(bridge.program_id, bridge.admin, bridge.seeds, payer, roots, capacity, signature, recovery_id))
+}
+
+fn stored(env: &Env, bridge: &Bridge) -> StoredRoots {
+    StoredRoots::deserialize(&mut env.data(&get_stored_roots_address(&bridge.program_id, &bridge.admin)).as_slice()).unwrap()
+}
+
+fn leaf(bridge: &Bridge, owner: &Pubkey) -> [u8; 32] {
+    let content = Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(1000)));
+    content.hash_with(bridge.hash_algo)
+}
+
+// Native withdrawal of 1000 lamports claimed against the root, the signature is not set
+fn claim(bridge: &Bridge, owner: &Pubkey, root: [u8; 32]) -> Instruction {
+    let withdraw = instructions::withdraw_native(
+        bridge.program_id, bridge.admin, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000,
+        [0; 64], 0, vec![SIBLING], None, None, None, false, None,
+    );
+    instructions::claim_stored(withdraw, bridge.admin, root)
+}
+
+fn setup() -> (Env, Bridge) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.fund(&bridge.admin, 10_000);
+    (env, bridge)
+}
+
+#[test]
+fn roots_are_stored() {
+    let (mut env, bridge) = setup();
+    let public_key = bridge.signer.public_key();
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32], [2; 32]], 3, 0).unwrap();
+    assert_eq!(stored(&env, &bridge), StoredRoots { capacity: 3, next: 2, batches: 1, public_key, roots: vec![[1; 32], [2; 32]], is_initialized: true });
+
+    store(&mut env, &bridge, &bridge.signer, vec![[3; 32]], 3, 1).unwrap();
+    assert_eq!(stored(&env, &bridge), StoredRoots { capacity: 3, next: 0, batches: 2, public_key, roots: vec![[1; 32], [2; 32], [3; 32]], is_initialized: true });
+}
+
+#[test]
+fn oldest_roots_are_evicted() {
+    let (mut env, bridge) = setup();
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32], [2; 32], [3; 32]], 3, 0).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![[4; 32], [5; 32]], 3, 1).unwrap();
+
+    let state = stored(&env, &bridge);
+    assert_eq!(state.roots, vec![[4; 32], [5; 32], [3; 32]]);
+    assert_eq!(state.next, 2);
+    assert!(!state.contains(&[1; 32]));
+    assert!(state.contains(&[3; 32]));
+}
+
+#[test]
+fn capacity_change_keeps_newest_roots() {
+    let (mut env, bridge) = setup();
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32], [2; 32], [3; 32]], 3, 0).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![[4; 32]], 2, 1).unwrap();
+
+    let state = stored(&env, &bridge);
+    assert_eq!((state.capacity, state.next), (2, 1));
+    assert_eq!(state.roots, vec![[4; 32], [3; 32]]);
+    assert_eq!(env.data(&get_stored_roots_address(&bridge.program_id, &bridge.admin)).len(), bridge::state::stored_roots_size(2));
+
+    store(&mut env, &bridge, &bridge.signer, vec![[5; 32]], 4, 2).unwrap();
+    assert_eq!(stored(&env, &bridge).roots, vec![[3; 32], [4; 32], [5; 32]]);
+}
+
+#[test]
+fn replayed_or_foreign_batch_is_rejected() {
+    let (mut env, bridge) = setup();
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32]], 2, 0).unwrap();
+
+    // Signed for the previous batch count
+    assert_eq!(store(&mut env, &bridge, &bridge.signer, vec![[1; 32]], 2, 0), Err(LibError::WrongSignature.into()));
+    assert_eq!(store(&mut env, &bridge, &Signer::new(2), vec![[2; 32]], 2, 1), Err(LibError::WrongSignature.into()));
+    assert_eq!(stored(&env, &bridge).roots, vec![[1; 32]]);
+}
+
+#[test]
+fn oversized_batch_is_rejected() {
+    let (mut env, bridge) = setup();
+    assert_eq!(store(&mut env, &bridge, &bridge.signer, vec![[1; 32], [2; 32], [3; 32]], 2, 0), Err(LibError::WrongArgsSize.into()));
+    assert_eq!(store(&mut env, &bridge, &bridge.signer, vec![], 2, 0), Err(LibError::WrongArgsSize.into()));
+    assert!(!env.exists(&get_stored_roots_address(&bridge.program_id, &bridge.admin)));
+}
+
+#[test]
+fn withdrawal_is_claimed_against_stored_root() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let root = get_merkle_root_with(bridge.hash_algo, leaf(&bridge, &owner), &vec![SIBLING]).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32], root], 4, 0).unwrap();
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&claim(&bridge, &owner, root)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 1000);
+    assert!(env.exists(&bridge.withdraw_address(&ORIGIN)));
+
+    assert_eq!(env.process(&claim(&bridge, &owner, root)), Err(LibError::AlreadyInUse.into()));
+}
+
+#[test]
+fn claim_against_unknown_or_evicted_root_is_rejected() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let root = get_merkle_root_with(bridge.hash_algo, leaf(&bridge, &owner), &vec![SIBLING]).unwrap();
+
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32]], 1, 0).unwrap();
+    assert_eq!(env.process(&claim(&bridge, &owner, root)), Err(LibError::WrongMerkleRoot.into()));
+
+    store(&mut env, &bridge, &bridge.signer, vec![root], 1, 1).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![[2; 32]], 1, 2).unwrap();
+    assert_eq!(env.process(&claim(&bridge, &owner, root)), Err(LibError::WrongMerkleRoot.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn claim_with_proof_to_other_root_is_rejected() {
+    let (mut env, bridge) = setup();
+    let (owner, other) = (env.new_funded(), env.new_funded());
+    let root = get_merkle_root_with(bridge.hash_algo, leaf(&bridge, &owner), &vec![SIBLING]).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![root], 1, 0).unwrap();
+
+    // The other owner leaf does not lead to the stored root
+    assert!(env.process(&claim(&bridge, &other, root)).is_err());
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn revoked_root_is_removed() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let root = get_merkle_root_with(bridge.hash_algo, leaf(&bridge, &owner), &vec![SIBLING]).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![[1; 32], root, [2; 32]], 3, 0).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![[3; 32]], 3, 1).unwrap();
+
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::RevokeRoot, root.to_vec());
+    let payer = env.new_funded();
+    env.process(&instructions::revoke_root(bridge.program_id, bridge.admin, bridge.seeds, payer, root, signature, recovery_id, vec![])).unwrap();
+
+    // The rest roots keep the order, the oldest one is evicted next
+    let state = stored(&env, &bridge);
+    assert_eq!((state.roots, state.next), (vec![[2; 32], [3; 32]], 2));
+    assert_eq!(env.process(&claim(&bridge, &owner, root)), Err(LibError::WrongMerkleRoot.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn roots_of_rotated_key_are_not_claimed() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let root = get_merkle_root_with(bridge.hash_algo, leaf(&bridge, &owner), &vec![SIBLING]).unwrap();
+    store(&mut env, &bridge, &bridge.signer, vec![root], 2, 0).unwrap();
+
+    let new_signer = Signer::new(2);
+    let new_key = new_signer.public_key();
+    let (signature, recovery_id) = bridge.signer.sign(&solana_program::keccak::hashv(&[lib::BRIDGE_DOMAIN, new_key.as_slice()]).to_bytes());
+    env.process(&instructions::transfer_ownership(bridge.program_id, bridge.admin, signature, new_key, recovery_id, bridge.seeds)).unwrap();
+    assert_eq!(env.process(&claim(&bridge, &owner, root)), Err(LibError::RootRevoked.into()));
+
+    // The next batch of the new key drops the roots of the old one
+    store(&mut env, &bridge, &new_signer, vec![[1; 32]], 2, 1).unwrap();
+    let state = stored(&env, &bridge);
+    assert_eq!((state.public_key, state.roots), (new_key, vec![[1; 32]]));
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::BridgeInstruction;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// The flag is not signed, so it is set on the signed withdrawal
+fn strict(mut instruction: Instruction) -> Instruction {
+    let data = match BridgeInstruction::try_from_slice(&instruction.data).unwrap() {
+        BridgeInstruction::WithdrawFT(mut args) => {
+            args.strict_ata = true;
+            BridgeInstruction::WithdrawFT(args)
+        }
+        BridgeInstruction::WithdrawNFT(mut args) => {
+            args.strict_ata = true;
+            BridgeInstruction::WithdrawNFT(args)
+        }
+        _ => panic!("not a token withdrawal"),
+    };
+    instruction.data = data.try_to_vec().unwrap();
+    instruction
+}
+
+fn setup_ft() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    (env, bridge, owner, mint)
+}
+
+#[test]
+fn strict_ft_withdraw_requires_owner_account() {
+    let (mut env, bridge, owner, mint) = setup_ft();
+
+    let instruction = strict(bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None));
+    assert_eq!(env.process(&instruction), Err(LibError::WrongTokenAccount.into()));
+    assert!(!env.exists(&get_associated_token_address(&owner, &mint)));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 0);
+    env.process(&instruction).unwrap();
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+}
+
+#[test]
+fn non_strict_ft_withdraw_creates_owner_account() {
+    let (mut env, bridge, owner, mint) = setup_ft();
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+}
+
+#[test]
+fn strict_nft_withdraw_requires_owner_account() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 0, None);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+    env.create_associated_token_account(&mint, &bridge.admin, 1);
+
+    let instruction = strict(bridge.withdraw_nft(&env, &owner, &mint, ORIGIN));
+    assert_eq!(env.process(&instruction), Err(LibError::WrongTokenAccount.into()));
+
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 0);
+    env.process(&instruction).unwrap();
+    assert_eq!(env.token_balance(&owner_associated), 1);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use bridge::state::{OperationType, SupportedNetworks};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env, RECEIVER};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::{Network, TokenType};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn set_networks(env: &mut Env, bridge: &Bridge, networks: Vec<Network>) -> Pubkey {
+    let data = networks.iter().flat_map(|network| solana_program::keccak::hash(network.as_bytes()).to_bytes()).collect();
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetNetworks, data);
+    let payer = env.new_funded();
+    env.process(&instructions::set_networks(bridge.program_id, bridge.admin, bridge.seeds, payer, networks, signature, recovery_id, vec![])).unwrap();
+    instructions::get_networks_address(&bridge.program_id, &bridge.admin)
+}
+
+fn deposit_native(bridge: &Bridge, owner: &Pubkey, network: Network) -> Vec<Instruction> {
+    vec![
+        bridge.charge(owner, TokenType::Native, 1000),
+        instructions::deposit_native(bridge.program_id, bridge.admin, *owner, bridge.seeds, network, 1000, String::from(RECEIVER), None, None),
+    ]
+}
+
+#[test]
+fn any_network_is_allowed_without_list() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    env.process_transaction(&deposit_native(&bridge, &owner, Network::Custom(String::from("Unknown")))).unwrap();
+}
+
+#[test]
+fn deposit_to_listed_network_is_allowed() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let networks = set_networks(&mut env, &bridge, vec![Network::Ethereum, Network::Polygon]);
+
+    let state = SupportedNetworks::deserialize(&mut env.data(&networks).as_slice()).unwrap();
+    assert!(state.is_initialized);
+    assert_eq!(state.networks, vec![Network::Ethereum, Network::Polygon]);
+    assert_eq!(bridge.state(&env).nonce, 1);
+
+    let owner = env.new_funded();
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process_transaction(&deposit_native(&bridge, &owner, Network::Polygon)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + 1000);
+}
+
+#[test]
+fn deposit_to_unlisted_network_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_networks(&mut env, &bridge, vec![Network::Ethereum]);
+
+    let owner = env.new_funded();
+    let admin_lamports = env.lamports(&bridge.admin);
+    assert_eq!(
+        env.process_transaction(&deposit_native(&bridge, &owner, Network::BSC)),
+        Err((1, LibError::UnsupportedNetwork.into())),
+    );
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+}
+
+#[test]
+fn set_networks_requires_admin_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::SetNetworks, solana_program::keccak::hash(b"BSC").to_bytes().to_vec());
+    let payer = env.new_funded();
+    assert!(env.process(&instructions::set_networks(
+        bridge.program_id, bridge.admin, bridge.seeds, payer, vec![Network::Ethereum], signature, recovery_id, vec![],
+    )).is_err());
+    assert!(!env.exists(&instructions::get_networks_address(&bridge.program_id, &bridge.admin)));
+}
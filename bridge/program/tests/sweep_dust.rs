@@ -0,0 +1,50 @@
+mod common;
+
+use bridge::state::OperationType;
+use common::{Bridge, Env};
+use lib::instructions::bridge as instructions;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+fn sweep_dust(env: &Env, bridge: &Bridge, receiver: &Pubkey, mints: Vec<Pubkey>) -> Instruction {
+    let data = [receiver.as_ref().to_vec(), mints.iter().flat_map(|mint| mint.to_bytes()).collect()].concat();
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SweepDust, data);
+    instructions::sweep_dust(bridge.program_id, bridge.admin, bridge.seeds, *receiver, mints, signature, recovery_id, vec![])
+}
+
+fn bridge_account(env: &mut Env, bridge: &Bridge, amount: u64) -> (Pubkey, Pubkey) {
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    (mint, env.create_associated_token_account(&mint, &bridge.admin, amount))
+}
+
+#[test]
+fn empty_account_is_swept() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (mint, bridge_associated) = bridge_account(&mut env, &bridge, 0);
+    let rent = env.lamports(&bridge_associated);
+    let receiver = Pubkey::new_unique();
+
+    env.process(&sweep_dust(&env, &bridge, &receiver, vec![mint])).unwrap();
+    assert!(!env.exists(&bridge_associated));
+    assert_eq!(env.lamports(&receiver), rent);
+    assert_eq!(bridge.state(&env).nonce, 1);
+}
+
+#[test]
+fn non_empty_and_missing_accounts_are_skipped() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (empty_mint, empty) = bridge_account(&mut env, &bridge, 0);
+    let (held_mint, held) = bridge_account(&mut env, &bridge, 10);
+    let (rent, held_lamports) = (env.lamports(&empty), env.lamports(&held));
+    let missing_mint = Pubkey::new_unique();
+    let receiver = Pubkey::new_unique();
+
+    env.process(&sweep_dust(&env, &bridge, &receiver, vec![held_mint, missing_mint, empty_mint])).unwrap();
+    assert!(!env.exists(&empty));
+    assert_eq!(env.token_balance(&held), 10);
+    assert_eq!(env.lamports(&held), held_lamports);
+    assert_eq!(env.lamports(&receiver), rent);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use bridge::state::TokenConfig;
+use borsh::BorshDeserialize;
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::get_token_config_address;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Mint of the bridge with 1000 tokens of the owner
+fn setup() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    env.create_associated_token_account(&mint, &owner, 1000);
+    (env, bridge, owner, mint)
+}
+
+#[test]
+fn token_is_enabled_without_config() {
+    let (mut env, bridge, owner, mint) = setup();
+    assert!(!env.exists(&get_token_config_address(&bridge.program_id, &bridge.admin, &mint)));
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+}
+
+#[test]
+fn disabled_deposit_keeps_withdraw_enabled() {
+    let (mut env, bridge, owner, mint) = setup();
+    bridge.set_token_config(&mut env, &mint, false, true, None, 0).unwrap();
+
+    let config = TokenConfig::deserialize(&mut env.data(&get_token_config_address(&bridge.program_id, &bridge.admin, &mint)).as_slice()).unwrap();
+    assert_eq!(config.mint, mint);
+    assert!(!config.deposit_enabled);
+    assert!(config.withdraw_enabled);
+
+    assert_eq!(env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)), Err((1, LibError::TokenDisabled.into())));
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 500, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1500);
+}
+
+#[test]
+fn disabled_withdraw_keeps_deposit_enabled() {
+    let (mut env, bridge, owner, mint) = setup();
+    bridge.set_token_config(&mut env, &mint, true, false, None, 0).unwrap();
+
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 500, None)), Err(LibError::TokenDisabled.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+
+    // Enabled again by the next config
+    bridge.set_token_config(&mut env, &mint, true, true, None, 0).unwrap();
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 500, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 500);
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, SignedMetadata};
+use lib::merkle::{Content, TransferData};
+use mpl_token_metadata::pda::find_metadata_account;
+use mpl_token_metadata::state::{Metadata, TokenStandard};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+const TOKEN_SEED: [u8; 32] = [3; 32];
+
+fn signed_meta(decimals: u8) -> SignedMetadata {
+    SignedMetadata {
+        name: String::from("Rarimo"),
+        symbol: String::from("RMO"),
+        uri: String::from("https://rarimo.com/token.json"),
+        decimals,
+        max_supply: None,
+    }
+}
+
+fn token_mint(bridge: &Bridge) -> Pubkey {
+    Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id).0
+}
+
+// FT withdrawal creating the mint of the token seed
+fn create_ft(bridge: &Bridge, owner: &Pubkey) -> Instruction {
+    let (mint, meta) = (token_mint(bridge), signed_meta(6));
+    let transfer = TransferData::new_ft_transfer(mint.to_bytes(), 1000, meta.name.clone(), meta.symbol.clone(), meta.uri.clone(), meta.decimals);
+    let (signature, recovery_id, path) = bridge.sign_withdraw(Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer)));
+    instructions::withdraw_ft(
+        bridge.program_id, bridge.admin, mint, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1000, None, signature, recovery_id, path,
+        Some(TOKEN_SEED), Some(meta), None, false, None, false, None,
+    )
+}
+
+// NFT withdrawal creating the mint of the token seed
+fn create_nft(bridge: &Bridge, owner: &Pubkey) -> Instruction {
+    let (mint, meta) = (token_mint(bridge), signed_meta(0));
+    let transfer = TransferData::new_nft_transfer(mint.to_bytes(), None, meta.name.clone(), meta.symbol.clone(), meta.uri.clone());
+    let (signature, recovery_id, path) = bridge.sign_withdraw(Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(transfer)));
+    instructions::withdraw_nft(
+        bridge.program_id, bridge.admin, mint, find_metadata_account(&mint).0, *owner, bridge.withdraw_address(&ORIGIN), bridge.seeds, ORIGIN, 1,
+        signature, recovery_id, path, Some(TOKEN_SEED), Some(meta), None, false, None, None,
+    )
+}
+
+// Existing mint of the bridge with the metadata of the token standard
+fn existing_mint(env: &mut Env, bridge: &Bridge, decimals: u8, token_standard: Option<TokenStandard>) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, decimals, None);
+    let metadata = if decimals == 0 {
+        nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json")
+    } else {
+        ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json")
+    };
+    env.set_metadata(&Metadata { token_standard, ..metadata });
+    mint
+}
+
+#[test]
+fn created_ft_is_fungible() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    env.process(&create_ft(&bridge, &owner)).unwrap();
+    assert_eq!(env.metadata(&token_mint(&bridge)).token_standard, Some(TokenStandard::Fungible));
+}
+
+#[test]
+fn created_nft_is_fungible_asset() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    env.process(&create_nft(&bridge, &owner)).unwrap();
+    assert_eq!(env.metadata(&token_mint(&bridge)).token_standard, Some(TokenStandard::FungibleAsset));
+}
+
+#[test]
+fn ft_withdraw_of_non_fungible_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = existing_mint(&mut env, &bridge, 6, Some(TokenStandard::NonFungible));
+    assert_eq!(env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)), Err(LibError::WrongTokenStandard.into()));
+
+    let mint = existing_mint(&mut env, &bridge, 6, Some(TokenStandard::FungibleAsset));
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+}
+
+#[test]
+fn nft_withdraw_of_programmable_nft_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mint = existing_mint(&mut env, &bridge, 0, Some(TokenStandard::ProgrammableNonFungible));
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)), Err(LibError::WrongTokenStandard.into()));
+
+    let mint = existing_mint(&mut env, &bridge, 0, Some(TokenStandard::Fungible));
+    assert_eq!(env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)), Err(LibError::WrongTokenStandard.into()));
+
+    // Metadata created before the token standard was introduced
+    let mint = existing_mint(&mut env, &bridge, 0, None);
+    env.process(&bridge.withdraw_nft(&env, &owner, &mint, ORIGIN)).unwrap();
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::error::TokenError;
+use spl_token::instruction::transfer_checked;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Mint of 6 decimals not controlled by the bridge, the owner holds 1000 tokens
+fn setup() -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    env.create_associated_token_account(&mint, &owner, 1000);
+    (env, bridge, owner, mint)
+}
+
+fn checked_transfers(env: &Env) -> (usize, usize) {
+    let logs = env.logs();
+    let count = |name: &str| logs.iter().filter(|log| log.ends_with(name)).count();
+    (count("Instruction: TransferChecked"), count("Instruction: Transfer"))
+}
+
+#[test]
+fn mismatched_decimals_are_rejected_by_token_program() {
+    let (mut env, _, owner, mint) = setup();
+    let from = get_associated_token_address(&owner, &mint);
+    let to = env.create_associated_token_account(&mint, &Pubkey::new_unique(), 0);
+
+    let instruction = transfer_checked(&spl_token::id(), &from, &mint, &to, &owner, &[], 100, 9).unwrap();
+    assert_eq!(env.process(&instruction), Err(TokenError::MintDecimalsMismatch.into()));
+    assert_eq!(env.token_balance(&from), 1000);
+
+    let instruction = transfer_checked(&spl_token::id(), &from, &mint, &to, &owner, &[], 100, 6).unwrap();
+    env.process(&instruction).unwrap();
+    assert_eq!(env.token_balance(&to), 100);
+}
+
+#[test]
+fn deposit_and_withdraw_use_checked_transfer() {
+    let (mut env, bridge, owner, mint) = setup();
+
+    env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)).unwrap();
+    assert_eq!(checked_transfers(&env), (1, 0));
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 400, None)).unwrap();
+    assert_eq!(checked_transfers(&env), (1, 0));
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 400);
+}
+
+#[test]
+fn deposit_from_account_of_other_mint_is_rejected() {
+    let (mut env, bridge, owner, mint) = setup();
+    let other_mint = Pubkey::new_unique();
+    env.create_mint(&other_mint, &Pubkey::new_unique(), 6, None);
+    // Owner associated address of the mint holds the tokens of the other mint
+    let owner_associated = get_associated_token_address(&owner, &mint);
+    env.create_token_account(&owner_associated, &other_mint, &owner, 1000);
+
+    assert_eq!(env.process_transaction(&bridge.deposit_ft(&owner, &mint, 1000)), Err((1, TokenError::MintMismatch.into())));
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+}
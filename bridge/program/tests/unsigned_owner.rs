@@ -0,0 +1,83 @@
+mod common;
+
+use common::{Bridge, Env, RECEIVER};
+use lib::error::LibError;
+use lib::instructions::bridge::{deposit_nft, deposit_nft_batch};
+use lib::{Network, TokenType};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// The owner is passed as a non-signer account in every instruction of the transaction
+fn unsigned(mut instructions: Vec<Instruction>, owner: &Pubkey) -> Vec<Instruction> {
+    for instruction in &mut instructions {
+        for meta in instruction.accounts.iter_mut().filter(|meta| meta.pubkey == *owner) {
+            meta.is_signer = false;
+        }
+    }
+    instructions
+}
+
+fn nft(env: &mut Env, owner: &Pubkey) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 0, None);
+    env.create_associated_token_account(&mint, owner, 1);
+    mint
+}
+
+#[test]
+fn unsigned_native_deposit_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let (owner_lamports, admin_lamports) = (env.lamports(&owner), env.lamports(&bridge.admin));
+
+    assert_eq!(env.process_transaction(&unsigned(bridge.deposit_native(&owner, 1000), &owner)), Err((1, LibError::MissingSigner.into())));
+    assert_eq!(env.lamports(&owner), owner_lamports);
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+
+    env.process_transaction(&bridge.deposit_native(&owner, 1000)).unwrap();
+}
+
+#[test]
+fn unsigned_ft_deposit_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    let owner_associated = env.create_associated_token_account(&mint, &owner, 1000);
+
+    assert_eq!(env.process_transaction(&unsigned(bridge.deposit_ft(&owner, &mint, 1000), &owner)), Err((1, LibError::MissingSigner.into())));
+    assert_eq!(env.token_balance(&owner_associated), 1000);
+}
+
+#[test]
+fn unsigned_nft_deposit_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = nft(&mut env, &owner);
+
+    let deposit = vec![
+        bridge.charge(&owner, TokenType::NFT, 1),
+        deposit_nft(bridge.program_id, bridge.admin, mint, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), None, None, None),
+    ];
+    assert_eq!(env.process_transaction(&unsigned(deposit, &owner)), Err((1, LibError::MissingSigner.into())));
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1);
+}
+
+#[test]
+fn unsigned_nft_batch_deposit_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mints = vec![nft(&mut env, &owner), nft(&mut env, &owner)];
+
+    let deposit = vec![
+        bridge.charge(&owner, TokenType::NFT, 2),
+        deposit_nft_batch(bridge.program_id, bridge.admin, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), mints.clone(), vec![None; 2]),
+    ];
+    assert_eq!(env.process_transaction(&unsigned(deposit, &owner)), Err((1, LibError::MissingSigner.into())));
+    assert!(mints.iter().all(|mint| env.token_balance(&get_associated_token_address(&owner, mint)) == 1));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use bridge::merkle::metadata_data;
+use bridge::state::OperationType;
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{update_token_metadata, SignedMetadata};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const TOKEN_SEED: [u8; 32] = [3; 32];
+
+// Bridged mint with the stale metadata, the bridge admin is the update authority
+fn setup() -> (Env, Bridge, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (mint, _) = Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id);
+    env.create_mint(&mint, &bridge.admin, 6, None);
+
+    let mut metadata = ft_metadata(&mint, "Rarimo", "RM", "https://rarimo.com/stale.json");
+    metadata.update_authority = bridge.admin;
+    env.set_metadata(&metadata);
+    (env, bridge, mint)
+}
+
+fn signed_metadata(uri: &str) -> SignedMetadata {
+    SignedMetadata {
+        name: String::from("Rarimo"),
+        symbol: String::from("RMO"),
+        uri: String::from(uri),
+        decimals: 6,
+        max_supply: None,
+    }
+}
+
+// UpdateTokenMetadata of `data` signed for the `signed` metadata
+fn update(env: &Env, bridge: &Bridge, token_seed: [u8; 32], data: SignedMetadata, signed: &SignedMetadata) -> Instruction {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::UpdateTokenMetadata, metadata_data(&token_seed, signed));
+    update_token_metadata(bridge.program_id, bridge.admin, bridge.seeds, token_seed, data, signature, recovery_id, vec![])
+}
+
+#[test]
+fn uri_and_symbol_are_updated() {
+    let (mut env, bridge, mint) = setup();
+    let data = signed_metadata("https://rarimo.com/token.json");
+
+    env.process(&update(&env, &bridge, TOKEN_SEED, data.clone(), &data)).unwrap();
+
+    let metadata = env.metadata(&mint);
+    assert_eq!(metadata.data.uri.trim_end_matches('\0'), "https://rarimo.com/token.json");
+    assert_eq!(metadata.data.symbol.trim_end_matches('\0'), "RMO");
+    assert_eq!(metadata.data.name.trim_end_matches('\0'), "Rarimo");
+    assert_eq!(metadata.update_authority, bridge.admin);
+    assert_eq!(bridge.state(&env).nonce, 1);
+}
+
+#[test]
+fn unsigned_metadata_is_rejected() {
+    let (mut env, bridge, mint) = setup();
+    let signed = signed_metadata("https://rarimo.com/token.json");
+
+    assert_eq!(
+        env.process(&update(&env, &bridge, TOKEN_SEED, signed_metadata("https://attacker.com/token.json"), &signed)),
+        Err(LibError::WrongSignature.into()),
+    );
+    assert_eq!(env.metadata(&mint).data.uri.trim_end_matches('\0'), "https://rarimo.com/stale.json");
+}
+
+#[test]
+fn moved_field_boundary_is_rejected() {
+    let (mut env, bridge, mint) = setup();
+    let signed = signed_metadata("https://rarimo.com/token.json");
+
+    // The same concatenated bytes with the other name and symbol
+    let mut data = signed.clone();
+    data.name = String::from("RarimoR");
+    data.symbol = String::from("MO");
+    assert_eq!(
+        env.process(&update(&env, &bridge, TOKEN_SEED, data, &signed)),
+        Err(LibError::WrongSignature.into()),
+    );
+    assert_eq!(env.metadata(&mint).data.name.trim_end_matches('\0'), "Rarimo");
+}
+
+#[test]
+fn mint_of_other_token_seed_is_rejected() {
+    let (mut env, bridge, _) = setup();
+    let data = signed_metadata("https://rarimo.com/token.json");
+
+    let mut instruction = update(&env, &bridge, [4; 32], data.clone(), &data);
+    let (mint, _) = Pubkey::find_program_address(&[TOKEN_SEED.as_slice()], &bridge.program_id);
+    instruction.accounts[1].pubkey = mint;
+    assert_eq!(env.process(&instruction), Err(LibError::WrongTokenSeed.into()));
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, BridgeInstruction, WithdrawArgs, WithdrawValidation};
+use lib::TokenType;
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn withdraw_args(instruction: &Instruction) -> WithdrawArgs {
+    match BridgeInstruction::try_from_slice(&instruction.data).unwrap() {
+        BridgeInstruction::WithdrawNative(args) | BridgeInstruction::WithdrawFT(args) | BridgeInstruction::WithdrawNFT(args) => args,
+        _ => panic!("not a withdraw instruction"),
+    }
+}
+
+fn validate(env: &mut Env, bridge: &Bridge, owner: &Pubkey, mint: Option<Pubkey>, token_type: TokenType, withdraw: &Instruction) -> WithdrawValidation {
+    let instruction = instructions::validate_withdraw(
+        bridge.program_id, bridge.admin, *owner, bridge.withdraw_address(&ORIGIN), mint, token_type, withdraw_args(withdraw),
+    );
+    env.process(&instruction).unwrap();
+    let (program_id, data) = env.return_data().unwrap();
+    assert_eq!(program_id, bridge.program_id);
+    WithdrawValidation::deserialize(&mut data.as_slice()).unwrap()
+}
+
+fn error(err: LibError) -> Option<u64> {
+    Some(u64::from(ProgramError::from(err)))
+}
+
+#[test]
+fn valid_native_withdraw_passes_without_execution() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let (admin_lamports, owner_lamports) = (env.lamports(&bridge.admin), env.lamports(&owner));
+
+    let withdraw = bridge.withdraw_native(&owner, ORIGIN, 1000);
+    assert_eq!(validate(&mut env, &bridge, &owner, None, TokenType::Native, &withdraw), WithdrawValidation { origin: ORIGIN, valid: true, error: None });
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert_eq!(env.lamports(&owner), owner_lamports);
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn spent_origin_fails() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let withdraw = bridge.withdraw_native(&owner, ORIGIN, 1000);
+    env.process(&withdraw).unwrap();
+    let validation = validate(&mut env, &bridge, &owner, None, TokenType::Native, &withdraw);
+    assert!(!validation.valid);
+    assert_eq!(validation.error, error(LibError::AlreadyInUse));
+}
+
+#[test]
+fn wrong_signature_and_balance_fail() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    // Signed for other receiver
+    let withdraw = bridge.withdraw_native(&Pubkey::new_unique(), ORIGIN, 1000);
+    assert!(!validate(&mut env, &bridge, &owner, None, TokenType::Native, &withdraw).valid);
+
+    let withdraw = bridge.withdraw_native(&owner, ORIGIN, env.lamports(&bridge.admin) + 1);
+    assert_eq!(validate(&mut env, &bridge, &owner, None, TokenType::Native, &withdraw).error, error(LibError::WrongBalance));
+}
+
+#[test]
+fn valid_ft_withdraw_passes_without_execution() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    let withdraw = bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None);
+    assert!(validate(&mut env, &bridge, &owner, Some(mint), TokenType::FT, &withdraw).valid);
+    assert!(!env.exists(&get_associated_token_address(&owner, &mint)));
+    assert_eq!(env.mint(&mint).supply, 0);
+
+    env.process(&withdraw).unwrap();
+    assert_eq!(validate(&mut env, &bridge, &owner, Some(mint), TokenType::FT, &withdraw).error, error(LibError::AlreadyInUse));
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use bridge::state::{OperationType, WithdrawAnnouncement};
+use borsh::BorshDeserialize;
+use common::{Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge as instructions;
+use lib::merkle::{amount_bytes, Content, MerkleProof, TransferData};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+const DELAY: u64 = 100;
+const THRESHOLD: u64 = 1000;
+
+// Withdrawals above 1000 lamports are delayed by 100 slots
+fn setup() -> (Env, Bridge, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::SetWithdrawDelay, [amount_bytes(DELAY), amount_bytes(THRESHOLD)].concat());
+    env.process(&instructions::set_withdraw_delay(bridge.program_id, bridge.admin, bridge.seeds, DELAY, THRESHOLD, signature, recovery_id, vec![])).unwrap();
+    env.fund(&bridge.admin, 10_000);
+    env.set_slot(10);
+
+    let owner = env.new_funded();
+    (env, bridge, owner)
+}
+
+fn native_content(bridge: &Bridge, owner: &Pubkey, amount: u64) -> Content {
+    Content::new(ORIGIN, owner.to_bytes(), bridge.program_id.to_bytes(), Box::new(TransferData::new_native_transfer(amount)))
+}
+
+fn native_leaf(bridge: &Bridge, owner: &Pubkey, amount: u64) -> [u8; 32] {
+    native_content(bridge, owner, amount).hash_with(bridge.hash_algo)
+}
+
+fn announcement_address(bridge: &Bridge, owner: &Pubkey, amount: u64) -> Pubkey {
+    instructions::find_withdraw_announcement(&bridge.program_id, &bridge.admin, ORIGIN, amount, native_leaf(bridge, owner, amount)).0
+}
+
+fn announce(env: &mut Env, bridge: &Bridge, owner: &Pubkey, amount: u64) -> Pubkey {
+    let leaf = native_leaf(bridge, owner, amount);
+    let (signature, recovery_id, path) = bridge.sign_withdraw(native_content(bridge, owner, amount));
+    let payer = env.new_funded();
+    env.process(&instructions::announce_withdraw(
+        bridge.program_id, bridge.admin, bridge.seeds, payer, ORIGIN, amount, leaf, signature, recovery_id, MerkleProof(path),
+    )).unwrap();
+    announcement_address(bridge, owner, amount)
+}
+
+// Native withdrawal with the announcement as the trailing account
+fn delayed_withdraw(bridge: &Bridge, owner: &Pubkey, amount: u64) -> Instruction {
+    let mut instruction = bridge.withdraw_native(owner, ORIGIN, amount);
+    instruction.accounts.push(AccountMeta::new_readonly(announcement_address(bridge, owner, amount), false));
+    instruction
+}
+
+#[test]
+fn set_withdraw_delay_is_stored() {
+    let (env, bridge, _) = setup();
+    let state = bridge.state(&env);
+    assert_eq!(state.withdraw_delay, DELAY);
+    assert_eq!(state.withdraw_delay_threshold, THRESHOLD);
+    assert_eq!(state.nonce, 1);
+}
+
+#[test]
+fn small_withdraw_is_not_delayed() {
+    let (mut env, bridge, owner) = setup();
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&bridge.withdraw_native(&owner, ORIGIN, THRESHOLD)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - THRESHOLD);
+}
+
+#[test]
+fn large_withdraw_requires_announcement() {
+    let (mut env, bridge, owner) = setup();
+    assert!(env.process(&bridge.withdraw_native(&owner, ORIGIN, 5000)).is_err());
+    assert!(env.process(&delayed_withdraw(&bridge, &owner, 5000)).is_err());
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn announced_withdraw_executes_after_delay() {
+    let (mut env, bridge, owner) = setup();
+    let announcement = announce(&mut env, &bridge, &owner, 5000);
+
+    let state = WithdrawAnnouncement::deserialize(&mut env.data(&announcement).as_slice()).unwrap();
+    assert!(state.is_initialized);
+    assert_eq!(state.announce_slot, 10);
+    assert_eq!(state.amount, 5000);
+
+    // Premature withdrawal
+    env.set_slot(10 + DELAY - 1);
+    assert_eq!(env.process(&delayed_withdraw(&bridge, &owner, 5000)), Err(LibError::WithdrawDelayed.into()));
+
+    env.set_slot(10 + DELAY);
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&delayed_withdraw(&bridge, &owner, 5000)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 5000);
+    assert!(env.exists(&bridge.withdraw_address(&ORIGIN)));
+}
+
+#[test]
+fn announcement_of_other_amount_does_not_unlock() {
+    let (mut env, bridge, owner) = setup();
+    announce(&mut env, &bridge, &owner, 5000);
+    env.set_slot(10 + DELAY);
+
+    let mut instruction = bridge.withdraw_native(&owner, ORIGIN, 6000);
+    instruction.accounts.push(AccountMeta::new_readonly(announcement_address(&bridge, &owner, 5000), false));
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSeeds.into()));
+}
+
+#[test]
+fn vetoed_withdraw_is_blocked() {
+    let (mut env, bridge, owner) = setup();
+    let announcement = announce(&mut env, &bridge, &owner, 5000);
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::VetoWithdraw, announcement.to_bytes().to_vec());
+    let veto = instructions::veto_withdraw(bridge.program_id, bridge.admin, bridge.seeds, announcement, signature, recovery_id, vec![]);
+    env.process(&veto).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert_eq!(bridge.state(&env).nonce, 2);
+    assert!(WithdrawAnnouncement::deserialize(&mut env.data(&announcement).as_slice()).unwrap().vetoed);
+
+    env.set_slot(10 + DELAY);
+    assert_eq!(env.process(&delayed_withdraw(&bridge, &owner, 5000)), Err(LibError::WithdrawVetoed.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+
+    // The same signed leaf can not be announced again to wait out one more delay
+    let (signature, recovery_id, path) = bridge.sign_withdraw(native_content(&bridge, &owner, 5000));
+    let payer = env.new_funded();
+    assert_eq!(
+        env.process(&instructions::announce_withdraw(
+            bridge.program_id, bridge.admin, bridge.seeds, payer, ORIGIN, 5000, native_leaf(&bridge, &owner, 5000), signature, recovery_id, MerkleProof(path),
+        )),
+        Err(LibError::WithdrawVetoed.into()),
+    );
+
+    env.set_slot(10 + 2 * DELAY);
+    assert_eq!(env.process(&delayed_withdraw(&bridge, &owner, 5000)), Err(LibError::WithdrawVetoed.into()));
+    assert!(!env.exists(&bridge.withdraw_address(&ORIGIN)));
+
+    // Vetoed once
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::VetoWithdraw, announcement.to_bytes().to_vec());
+    assert_eq!(
+        env.process(&instructions::veto_withdraw(bridge.program_id, bridge.admin, bridge.seeds, announcement, signature, recovery_id, vec![])),
+        Err(LibError::WithdrawVetoed.into()),
+    );
+}
+
+#[test]
+fn veto_requires_bridge_signature() {
+    let (mut env, bridge, owner) = setup();
+    let announcement = announce(&mut env, &bridge, &owner, 5000);
+
+    let (signature, recovery_id) = bridge.sign_operation(&env, OperationType::VetoWithdraw, Pubkey::new_unique().to_bytes().to_vec());
+    assert!(env.process(&instructions::veto_withdraw(bridge.program_id, bridge.admin, bridge.seeds, announcement, signature, recovery_id, vec![])).is_err());
+    assert!(WithdrawAnnouncement::deserialize(&mut env.data(&announcement).as_slice()).unwrap().is_initialized);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use bridge::state::OperationType;
+use borsh::BorshDeserialize;
+use common::{ft_metadata, Bridge, Env};
+use lib::error::LibError;
+use lib::instructions::bridge::{self as instructions, WithdrawReceipt, MAX_WITHDRAW_FEE_BPS};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn set_withdraw_fee(env: &mut Env, bridge: &Bridge, bps: u16) -> Result<(), solana_program::program_error::ProgramError> {
+    let (signature, recovery_id) = bridge.sign_operation(env, OperationType::SetWithdrawFee, bps.to_be_bytes().to_vec());
+    env.process(&instructions::set_withdraw_fee(bridge.program_id, bridge.admin, bridge.seeds, bps, signature, recovery_id, vec![]))
+}
+
+fn receipt(env: &Env) -> WithdrawReceipt {
+    let (_, data) = env.return_data().unwrap();
+    WithdrawReceipt::deserialize(&mut data.as_slice()).unwrap()
+}
+
+// 1% withdrawal fee
+fn setup() -> (Env, Bridge, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    set_withdraw_fee(&mut env, &bridge, 100).unwrap();
+    assert_eq!(bridge.state(&env).withdraw_fee_bps, 100);
+    let owner = env.new_funded();
+    (env, bridge, owner)
+}
+
+#[test]
+fn native_withdraw_keeps_fee() {
+    let (mut env, bridge, owner) = setup();
+    env.fund(&bridge.admin, 10_000);
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    env.process(&bridge.withdraw_native(&owner, ORIGIN, 10_000)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 9900);
+    let receipt = receipt(&env);
+    assert_eq!((receipt.amount, receipt.fee), (9900, 100));
+}
+
+#[test]
+fn ft_withdraw_keeps_fee() {
+    let (mut env, bridge, owner) = setup();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    let bridge_associated = env.create_associated_token_account(&mint, &bridge.admin, 1000);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 990);
+    assert_eq!(env.token_balance(&bridge_associated), 10);
+    let receipt = receipt(&env);
+    assert_eq!((receipt.amount, receipt.fee, receipt.mint), (990, 10, Some(mint)));
+}
+
+#[test]
+fn fee_is_rounded_down() {
+    let (mut env, bridge, owner) = setup();
+    env.fund(&bridge.admin, 10_000);
+
+    env.process(&bridge.withdraw_native(&owner, ORIGIN, 199)).unwrap();
+    let receipt = receipt(&env);
+    assert_eq!((receipt.amount, receipt.fee), (198, 1));
+}
+
+#[test]
+fn fee_above_maximum_is_rejected() {
+    let (mut env, bridge, _) = setup();
+    assert_eq!(set_withdraw_fee(&mut env, &bridge, MAX_WITHDRAW_FEE_BPS + 1), Err(LibError::WrongArgsSize.into()));
+    assert_eq!(bridge.state(&env).withdraw_fee_bps, 100);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{ft_metadata, nft_metadata, Bridge, Env};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Processes the withdrawal and checks both the text and the data log of its Withdraw account
+fn assert_logged(env: &mut Env, bridge: &Bridge, instruction: &Instruction) {
+    env.process(instruction).unwrap();
+    let withdraw = bridge.withdraw_address(&ORIGIN);
+    assert!(env.exists(&withdraw));
+
+    let line = format!("{}:{}", lib::WITHDRAW_PDA_LOG_PREFIX, withdraw);
+    assert_eq!(env.logs().iter().filter(|log| log.ends_with(&line)).count(), 1);
+    let data = vec![lib::WITHDRAW_PDA_LOG_PREFIX.as_bytes().to_vec(), withdraw.to_bytes().to_vec()];
+    assert_eq!(env.data_logs().iter().filter(|log| **log == data).count(), 1);
+}
+
+#[test]
+fn native_withdraw_logs_address() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let instruction = bridge.withdraw_native(&owner, ORIGIN, 1000);
+    assert_logged(&mut env, &bridge, &instruction);
+}
+
+#[test]
+fn ft_withdraw_logs_address() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    let instruction = bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None);
+    assert_logged(&mut env, &bridge, &instruction);
+}
+
+#[test]
+fn nft_withdraw_logs_address() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 0, None);
+    env.set_metadata(&nft_metadata(&mint, "Rarimo NFT", "RNFT", "https://rarimo.com/nft.json"));
+    env.create_associated_token_account(&mint, &bridge.admin, 1);
+
+    let instruction = bridge.withdraw_nft(&env, &owner, &mint, ORIGIN);
+    assert_logged(&mut env, &bridge, &instruction);
+}
+
+#[test]
+fn failed_withdraw_logs_nothing() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+
+    let mut instruction = bridge.withdraw_native(&owner, ORIGIN, 1000);
+    instruction.accounts[1].pubkey = Pubkey::new_unique();
+    assert!(env.process(&instruction).is_err());
+    assert!(!env.logs().iter().any(|log| log.contains(lib::WITHDRAW_PDA_LOG_PREFIX)));
+}
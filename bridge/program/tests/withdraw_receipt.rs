@@ -0,0 +1,76 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{ft_metadata, Bridge, Env};
+use lib::instructions::bridge::WithdrawReceipt;
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::{get_return_data, invoke, set_return_data};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+// Withdraws on behalf of the owner and returns the bridge receipt as its own return data.
+// Accounts: the bridge program, then the accounts of the bridge instruction.
+fn caller_processor<'a>(_program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    let (bridge_program, accounts) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let instruction = Instruction {
+        program_id: *bridge_program.key,
+        accounts: accounts.iter().map(|info| AccountMeta { pubkey: *info.key, is_signer: info.is_signer, is_writable: info.is_writable }).collect(),
+        data: input.to_vec(),
+    };
+    invoke(&instruction, accounts)?;
+
+    let (program, data) = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    if program != *bridge_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let receipt = WithdrawReceipt::try_from_slice(data.as_slice())?;
+    set_return_data(receipt.try_to_vec()?.as_slice());
+    Ok(())
+}
+
+fn call(env: &mut Env, caller: Pubkey, instruction: Instruction) -> WithdrawReceipt {
+    let mut accounts = vec![AccountMeta::new_readonly(instruction.program_id, false)];
+    accounts.extend(instruction.accounts);
+    env.process(&Instruction { program_id: caller, accounts, data: instruction.data }).unwrap();
+
+    let (program, data) = env.return_data().unwrap();
+    assert_eq!(program, caller);
+    WithdrawReceipt::try_from_slice(data.as_slice()).unwrap()
+}
+
+#[test]
+fn caller_reads_ft_withdraw_receipt() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let caller = Pubkey::new_unique();
+    env.add_program(caller, caller_processor);
+    let owner = env.new_funded();
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    let withdraw = bridge.withdraw_ft(&env, &owner, &mint, [7; 32], 1000, Some(400));
+    let receipt = call(&mut env, caller, withdraw);
+    assert_eq!(receipt, WithdrawReceipt { origin: [7; 32], amount: 400, fee: 0, mint: Some(mint) });
+}
+
+#[test]
+fn caller_reads_native_withdraw_receipt() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let caller = Pubkey::new_unique();
+    env.add_program(caller, caller_processor);
+    env.fund(&bridge.admin, 5000);
+    let owner = env.new_funded();
+
+    let balance = env.lamports(&owner);
+    let receipt = call(&mut env, caller, bridge.withdraw_native(&owner, [7; 32], 5000));
+    assert_eq!(receipt, WithdrawReceipt { origin: [7; 32], amount: 5000, fee: 0, mint: None });
+
+    let withdraw_rent = env.lamports(&bridge.withdraw_address(&[7; 32]));
+    assert_eq!(env.lamports(&owner), balance + 5000 - withdraw_rent);
+}
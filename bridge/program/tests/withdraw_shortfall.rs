@@ -0,0 +1,52 @@
+mod common;
+
+use common::{ft_metadata, Bridge, Env};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+// Mint of the bridge or of the other authority with the bridge holding `bridge_amount` tokens
+fn setup(bridge_authority: bool, bridge_amount: u64) -> (Env, Bridge, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let authority = if bridge_authority { bridge.admin } else { Pubkey::new_unique() };
+
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &authority, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+    env.create_associated_token_account(&mint, &bridge.admin, bridge_amount);
+    let owner = env.new_funded();
+    (env, bridge, mint, owner)
+}
+
+#[test]
+fn withdraw_below_bridge_balance_does_not_mint() {
+    // The bridge is not the mint authority, so any mint would fail
+    let (mut env, bridge, mint, owner) = setup(false, 1500);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 500);
+    assert_eq!(env.mint(&mint).supply, 1500);
+}
+
+#[test]
+fn withdraw_of_bridge_balance_does_not_mint() {
+    let (mut env, bridge, mint, owner) = setup(false, 1000);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 0);
+    assert_eq!(env.mint(&mint).supply, 1000);
+}
+
+#[test]
+fn withdraw_above_bridge_balance_mints_shortfall() {
+    let (mut env, bridge, mint, owner) = setup(true, 300);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &mint)), 1000);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 0);
+    assert_eq!(env.mint(&mint).supply, 1000);
+}
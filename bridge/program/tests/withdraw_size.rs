@@ -0,0 +1,42 @@
+mod common;
+
+use borsh::BorshSerialize;
+use bridge::state::{Withdraw, WITHDRAW_SIZE};
+use common::{ft_metadata, Bridge, Env};
+use lib::TokenType;
+use solana_program::pubkey::Pubkey;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+#[test]
+fn withdraw_size_matches_serialized_withdraw() {
+    // The largest layout: the mint is set
+    let withdraw = Withdraw {
+        version: lib::STATE_VERSION,
+        token_type: TokenType::FT,
+        mint: Some(Pubkey::new_unique()),
+        amount: u64::MAX,
+        origin: ORIGIN,
+        receiver_address: Pubkey::new_unique(),
+        is_initialized: true,
+        claimed: u64::MAX,
+    };
+    assert_eq!(withdraw.try_to_vec().unwrap().len(), WITHDRAW_SIZE);
+
+    // Native withdrawals keep the trailing byte of the missing mint unused
+    let native = Withdraw { token_type: TokenType::Native, mint: None, ..withdraw };
+    assert_eq!(native.try_to_vec().unwrap().len(), WITHDRAW_SIZE - 32);
+}
+
+#[test]
+fn created_withdraw_has_withdraw_size() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &bridge.admin, 6, None);
+    env.set_metadata(&ft_metadata(&mint, "Rarimo", "RMO", "https://rarimo.com/token.json"));
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &mint, ORIGIN, 1000, None)).unwrap();
+    assert_eq!(env.data(&bridge.withdraw_address(&ORIGIN)).len(), WITHDRAW_SIZE);
+}
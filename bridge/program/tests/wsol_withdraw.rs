@@ -0,0 +1,65 @@
+mod common;
+
+use common::{ft_metadata, Account, Bridge, Env};
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::native_mint;
+use spl_token::state::AccountState;
+
+const ORIGIN: [u8; 32] = [7; 32];
+
+fn setup() -> (Env, Bridge) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.create_mint(&native_mint::id(), &Pubkey::new_unique(), native_mint::DECIMALS, None);
+    env.set_metadata(&ft_metadata(&native_mint::id(), "Wrapped SOL", "SOL", ""));
+    env.fund(&bridge.admin, 1_000_000);
+    (env, bridge)
+}
+
+#[test]
+fn wsol_is_wrapped_to_fresh_owner() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    env.process(&bridge.withdraw_ft(&env, &owner, &native_mint::id(), ORIGIN, 5000, None)).unwrap();
+
+    let owner_associated = env.token_account(&get_associated_token_address(&owner, &native_mint::id()));
+    assert!(owner_associated.is_native());
+    assert_eq!(owner_associated.amount, 5000);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &native_mint::id())), 0);
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 5000);
+}
+
+#[test]
+fn held_wsol_is_wrapped_only_for_shortfall() {
+    let (mut env, bridge) = setup();
+    let owner = env.new_funded();
+
+    // Bridge holds 2000 wSOL deposited earlier
+    let bridge_associated = get_associated_token_address(&bridge.admin, &native_mint::id());
+    let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let state = spl_token::state::Account {
+        mint: native_mint::id(),
+        owner: bridge.admin,
+        amount: 2000,
+        state: AccountState::Initialized,
+        is_native: COption::Some(rent),
+        ..spl_token::state::Account::default()
+    };
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    state.pack_into_slice(&mut data);
+    env.set_account(bridge_associated, Account { lamports: rent + 2000, data, owner: spl_token::id(), executable: false });
+
+    let admin_lamports = env.lamports(&bridge.admin);
+    env.process(&bridge.withdraw_ft(&env, &owner, &native_mint::id(), ORIGIN, 3000, None)).unwrap();
+
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports - 1000);
+    assert_eq!(env.token_balance(&bridge_associated), 0);
+    assert_eq!(env.lamports(&bridge_associated), rent);
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner, &native_mint::id())), 3000);
+}
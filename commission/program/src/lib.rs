@@ -3,4 +3,4 @@
 pub mod entrypoint;
 pub mod processor;
 pub mod state;
-mod merkle;
\ No newline at end of file
+pub mod merkle;
\ No newline at end of file
@@ -6,9 +6,27 @@ use solana_program::{
 };
 use lib::merkle::amount_bytes;
 use crate::state::{CommissionToken, OperationType};
-use lib::SOLANA_NETWORK;
+use lib::{COMMISSION_DOMAIN, SOLANA_NETWORK};
 
-const SOLANA_NATIVE_DECIMALS: u8 = 9u8;
+/// Signed data of the whole acceptable tokens list (SetFeeTokens): the tokens count (32 bytes), then every token
+/// prefixed by its encoding length: `length (1 byte) | class | mint (FT and NFT only) | amount (32 bytes)`.
+/// Different lists never produce the same data.
+pub fn token_list_data(tokens: &Vec<CommissionToken>) -> Vec<u8> {
+    let mut data = amount_bytes(tokens.len() as u64);
+
+    for token in tokens {
+        let mut entry = vec![token.token.class()];
+        if let lib::CommissionToken::FT(mint) | lib::CommissionToken::NFT(mint) = token.token {
+            entry.extend_from_slice(mint.as_ref());
+        }
+        entry.append(&mut amount_bytes(token.amount));
+
+        data.push(entry.len() as u8);
+        data.append(&mut entry);
+    }
+
+    data
+}
 
 pub struct Content {
     pub nonce: u64,
@@ -16,23 +34,39 @@ pub struct Content {
     pub contract: Pubkey,
     pub network: String,
     pub operation_type: OperationType,
-    pub token: CommissionToken,
+    pub tokens: Vec<CommissionToken>,
+    // Withdrawal split receivers with their shares in basis points
+    pub split: Vec<(Pubkey, u16)>,
+    // Operation specific data
+    pub data: Vec<u8>,
 }
 
 impl Content {
-    pub fn new(nonce: u64, receiver: Option<Pubkey>, contract: Pubkey, operation_type: OperationType, token: CommissionToken) -> Self {
+    pub fn new(nonce: u64, receiver: Option<Pubkey>, contract: Pubkey, operation_type: OperationType, tokens: Vec<CommissionToken>) -> Self {
         Content {
             nonce,
             receiver,
             contract,
             network: String::from(SOLANA_NETWORK),
             operation_type,
-            token
+            tokens,
+            split: Vec::new(),
+            data: Vec::new(),
         }
     }
 
+    pub fn with_split(mut self, split: Vec<(Pubkey, u16)>) -> Self {
+        self.split = split;
+        self
+    }
+
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
     pub fn hash(self) -> solana_program::keccak::Hash {
-        let mut data = Vec::new();
+        let mut data = Vec::from(COMMISSION_DOMAIN);
         data.append(&mut Vec::from(amount_bytes(self.nonce)));
 
         if let Some(receiver) = self.receiver {
@@ -45,19 +79,25 @@ impl Content {
 
         data.push(self.operation_type.clone().into());
 
-        match self.token.token {
-            lib::CommissionToken::Native => {
-                // Nothing to add
-            }
-            lib::CommissionToken::FT(mint) => {
-                data.append(&mut Vec::from(mint.to_bytes()))
-            }
-            lib::CommissionToken::NFT(mint) => {
+        // Native and Any are hashed by the class only
+        for token in self.tokens {
+            data.push(token.token.class());
+
+            if let lib::CommissionToken::FT(mint) | lib::CommissionToken::NFT(mint) = token.token {
                 data.append(&mut Vec::from(mint.to_bytes()))
             }
+
+            data.append(&mut Vec::from(amount_bytes(token.amount)));
+        }
+
+        // Empty split is not hashed, so the content without split is the same as before
+        for (receiver, bps) in self.split {
+            data.append(&mut Vec::from(receiver.as_ref()));
+            data.append(&mut Vec::from(bps.to_be_bytes()));
         }
 
-        data.append(&mut Vec::from(amount_bytes(self.token.amount)));
+        // Empty for the operations without specific data
+        data.append(&mut self.data.clone());
 
         solana_program::keccak::hash(data.as_slice())
     }
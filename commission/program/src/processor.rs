@@ -1,21 +1,22 @@
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult, msg,
-    program::{invoke, invoke_signed}, pubkey::Pubkey, system_instruction,
+    program::{invoke, invoke_signed, set_return_data}, program_error::ProgramError, pubkey::Pubkey, system_instruction,
     sysvar::{rent::Rent, Sysvar},
 };
-use crate::state::{CommissionToken, CommissionAdmin, MAX_ADMIN_SIZE, OperationType};
+use crate::state::{CommissionToken, CommissionAdmin, BaselineCommissionAdmin, Charge, MAX_ADMIN_SIZE, BASELINE_ADMIN_SIZE, CHARGE_SIZE, OperationType};
 use borsh::{
     BorshDeserialize, BorshSerialize,
 };
 use spl_token::instruction::transfer;
+use spl_token::solana_program::program_pack::Pack;
 use spl_associated_token_account::get_associated_token_address;
 use spl_associated_token_account::instruction::create_associated_token_account;
 use solana_program::secp256k1_recover::SECP256K1_PUBLIC_KEY_LENGTH;
-use lib::merkle::get_merkle_root;
-use crate::merkle::Content;
-use lib::ecdsa::verify_ecdsa_signature;
-use lib::instructions::commission::{CommissionInstruction, CommissionTokenArg};
+use lib::merkle::{amount_bytes, get_merkle_root, MerkleProof};
+use crate::merkle::{token_list_data, Content};
+use lib::instructions::commission::{CommissionInstruction, CommissionTokenArg, CollectedCommission, SPLIT_TOTAL_BPS, commission_admin_address, commission_admin_seeds};
+use lib::instructions::InstructionValidation;
 use lib::error::LibError;
 use bridge::state::BridgeAdmin;
 
@@ -28,27 +29,59 @@ pub fn process_instruction<'a>(
     match instruction {
         CommissionInstruction::InitializeAdmin(args) => {
             msg!("Instruction: Create Comission Admin");
+            args.validate()?;
             process_init_admin(program_id, accounts, args.acceptable_tokens)
         }
         CommissionInstruction::ChargeCommission(args) => {
             msg!("Instruction: Charge commission");
-            process_charge_commission(program_id, accounts, args.token)
+            process_charge_commission(program_id, accounts, args.token, args.deposit_token, args.deposit_token_amount, args.deposit_nonce)
         }
         CommissionInstruction::AddFeeToken(args) => {
             msg!("Instruction: Add fee token");
+            args.validate()?;
             process_add_token(program_id, accounts, args.signature, args.recovery_id, args.path, args.token)
         }
         CommissionInstruction::RemoveFeeToken(args) => {
             msg!("Instruction: Remove fee token");
+            args.validate()?;
             process_remove_token(program_id, accounts, args.signature, args.recovery_id, args.path, args.token)
         }
         CommissionInstruction::UpdateFeeToken(args) => {
             msg!("Instruction: Update fee token");
+            args.validate()?;
             process_update_token(program_id, accounts, args.signature, args.recovery_id, args.path, args.token)
         }
         CommissionInstruction::Withdraw(args) => {
             msg!("Instruction: Withdraw collected tokens");
-            process_withdraw(program_id, accounts,  args.signature, args.recovery_id, args.path, args.token, args.withdraw_amount)
+            args.validate()?;
+            process_withdraw(program_id, accounts,  args.signature, args.recovery_id, args.path, args.token, args.withdraw_amount, args.split)
+        }
+        CommissionInstruction::SetFeeTokens(args) => {
+            msg!("Instruction: Set fee tokens");
+            args.validate()?;
+            process_set_tokens(program_id, accounts, args.signature, args.recovery_id, args.path, args.tokens)
+        }
+        CommissionInstruction::MigrateBridgeAdmin(args) => {
+            msg!("Instruction: Migrate bridge admin");
+            process_migrate_bridge_admin(program_id, accounts, args.signature, args.recovery_id, args.path)
+        }
+        CommissionInstruction::GetCollected(args) => {
+            msg!("Instruction: Get collected");
+            process_get_collected(program_id, accounts, args.token)
+        }
+        CommissionInstruction::MigrateState => {
+            msg!("Instruction: Migrate state");
+            process_migrate_state(program_id, accounts)
+        }
+        CommissionInstruction::CloseAdmin(args) => {
+            msg!("Instruction: Close admin");
+            args.validate()?;
+            process_close_admin(program_id, accounts, args.signature, args.recovery_id, args.path)
+        }
+        CommissionInstruction::ResetNonces(args) => {
+            msg!("Instruction: Reset nonces");
+            args.validate()?;
+            process_reset_nonces(program_id, accounts, args.add_token_nonce, args.remove_token_nonce, args.update_token_nonce, args.withdraw_token_nonce, args.set_tokens_nonce, args.signature, args.recovery_id, args.path)
         }
     }
 }
@@ -59,6 +92,7 @@ pub fn process_init_admin<'a>(
     accounts: &'a [AccountInfo<'a>],
     acceptable_tokens: Vec<CommissionTokenArg>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "InitializeAdmin")?;
     let account_info_iter = &mut accounts.iter();
 
     let commission_admin_info = next_account_info(account_info_iter)?;
@@ -68,11 +102,13 @@ pub fn process_init_admin<'a>(
     let system_program = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &program_id)?;
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
     if commission_key != *commission_admin_info.key {
         return Err(LibError::WrongAdmin.into());
     }
 
+    lib::expect_empty_admin(commission_admin_info)?;
+
     lib::call_create_account(
         fee_payer_info,
         commission_admin_info,
@@ -80,7 +116,7 @@ pub fn process_init_admin<'a>(
         system_program,
         MAX_ADMIN_SIZE,
         program_id,
-        &[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()],
+        &commission_admin_seeds(bridge_admin_info.key),
     )?;
 
     let mut commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
@@ -90,11 +126,12 @@ pub fn process_init_admin<'a>(
 
     commission_admin.acceptable_tokens = Vec::new();
     for t in acceptable_tokens {
-        commission_admin.acceptable_tokens.push(CommissionToken::from(&t))
+        insert_token(&mut commission_admin.acceptable_tokens, CommissionToken::from(&t))?;
     }
 
+    commission_admin.version = lib::STATE_VERSION;
     commission_admin.is_initialized = true;
-    commission_admin.serialize(&mut *commission_admin_info.data.borrow_mut())?;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
     Ok(())
 }
 
@@ -103,34 +140,83 @@ pub fn process_charge_commission<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     token: lib::CommissionToken,
+    deposit_token: lib::TokenType,
+    deposit_token_amount: u64,
+    deposit_nonce: u64,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 6, "ChargeCommission")?;
     let account_info_iter = &mut accounts.iter();
 
     let commission_admin_info = next_account_info(account_info_iter)?;
     let bridge_admin_info = next_account_info(account_info_iter)?;
     let owner_info = next_account_info(account_info_iter)?;
+    let charge_info = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &program_id)?;
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
     if commission_key != *commission_admin_info.key {
         return Err(LibError::WrongAdmin.into());
     }
 
-    let commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
+    let mut commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
     if !commission_admin.is_initialized {
         return Err(LibError::NotInitialized.into());
     }
 
-    let commission_token = check_token_is_acceptable(commission_admin.acceptable_tokens, token)?;
+    lib::check_version(commission_admin.version)?;
+
+    let commission_token = check_token_is_acceptable(commission_admin.acceptable_tokens.clone(), token)?;
 
-    match commission_token.token.into() {
-        lib::CommissionToken::Native => {
+    let (charge_key, bump_seed) = Pubkey::find_program_address(
+        &[lib::CHARGE_PDA_SEED.as_bytes(), owner_info.key.as_ref(), deposit_nonce.to_be_bytes().as_slice()],
+        program_id,
+    );
+    if charge_key != *charge_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    // Charge account is created once per deposit nonce, so the retried transaction can not charge twice
+    if charge_info.data.borrow().as_ref().len() != 0 {
+        return Err(LibError::AlreadyInUse.into());
+    }
+
+    msg!("Creating charge account");
+    lib::call_create_account(
+        owner_info,
+        charge_info,
+        rent_info,
+        system_program,
+        CHARGE_SIZE,
+        program_id,
+        &[lib::CHARGE_PDA_SEED.as_bytes(), owner_info.key.as_ref(), deposit_nonce.to_be_bytes().as_slice(), &[bump_seed]],
+    )?;
+
+    let mut charge: Charge = BorshDeserialize::deserialize(&mut charge_info.data.borrow_mut().as_ref())?;
+    charge.owner = *owner_info.key;
+    charge.deposit_nonce = deposit_nonce;
+    charge.is_initialized = true;
+    charge.serialize(&mut *charge_info.data.borrow_mut())?;
+
+    // Zero amount acceptable token makes NFT bridging free, other deposits should be charged
+    let commission_amount = checked_commission(deposit_token_amount, &commission_token)?;
+
+    if commission_amount == 0 {
+        if deposit_token != lib::TokenType::NFT {
+            return Err(LibError::NotAcceptable.into());
+        }
+
+        msg!("Free NFT deposit, nothing to charge");
+        return Ok(());
+    }
+
+    match commission_token.token.clone().into() {
+        lib::CommissionToken::Native | lib::CommissionToken::Any => {
             call_transfer_native(
                 owner_info,
                 commission_admin_info,
-                commission_token.amount,
-                &[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()],
+                commission_amount,
+                &commission_admin_seeds(bridge_admin_info.key),
             )?;
         }
         lib::CommissionToken::FT(mint) => {
@@ -161,8 +247,8 @@ pub fn process_charge_commission<'a>(
                 owner_associated_info,
                 commission_associated_info,
                 owner_info,
-                commission_token.amount,
-                &[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()],
+                commission_amount,
+                &commission_admin_seeds(bridge_admin_info.key),
             )?;
         }
         lib::CommissionToken::NFT(mint) => {
@@ -170,6 +256,12 @@ pub fn process_charge_commission<'a>(
         }
     }
 
+    if let Ok(i) = find_token(&commission_admin.acceptable_tokens, &commission_token.token) {
+        let t = &mut commission_admin.acceptable_tokens[i];
+        t.collected = t.collected.checked_add(commission_amount).ok_or(LibError::AmountOverflow)?;
+    }
+
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
     Ok(())
 }
 
@@ -182,12 +274,13 @@ pub fn process_add_token<'a>(
     path: Vec<[u8; 32]>,
     token: CommissionTokenArg,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "AddFeeToken")?;
     let account_info_iter = &mut accounts.iter();
 
     let commission_admin_info = next_account_info(account_info_iter)?;
     let bridge_admin_info = next_account_info(account_info_iter)?;
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &program_id)?;
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
     if commission_key != *commission_admin_info.key {
         return Err(LibError::WrongAdmin.into());
     }
@@ -197,25 +290,29 @@ pub fn process_add_token<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(commission_admin.version)?;
+
     let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
     if !bridge_admin.is_initialized {
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+
     let content = Content::new(
         commission_admin.add_token_nonce,
         None,
         *program_id,
         OperationType::AddToken,
-        CommissionToken::from(&token),
+        vec![CommissionToken::from(&token)],
     );
 
     let root = get_merkle_root(content.hash(), &path)?;
-    verify_ecdsa_signature(root.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
 
     commission_admin.add_token_nonce += 1;
-    commission_admin.acceptable_tokens.push(CommissionToken::from(&token));
-    commission_admin.serialize(&mut *commission_admin_info.data.borrow_mut())?;
+    insert_token(&mut commission_admin.acceptable_tokens, CommissionToken::from(&token))?;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
 
     Ok(())
 }
@@ -228,12 +325,13 @@ pub fn process_remove_token<'a>(
     path: Vec<[u8; 32]>,
     token: CommissionTokenArg,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "RemoveFeeToken")?;
     let account_info_iter = &mut accounts.iter();
 
     let commission_admin_info = next_account_info(account_info_iter)?;
     let bridge_admin_info = next_account_info(account_info_iter)?;
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &program_id)?;
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
     if commission_key != *commission_admin_info.key {
         return Err(LibError::WrongAdmin.into());
     }
@@ -243,31 +341,34 @@ pub fn process_remove_token<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(commission_admin.version)?;
+
     let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
     if !bridge_admin.is_initialized {
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+
     let content = Content::new(
         commission_admin.remove_token_nonce,
         None,
         *program_id,
         OperationType::RemoveToken,
-        CommissionToken::from(&token),
+        vec![CommissionToken::from(&token)],
     );
     let root = get_merkle_root(content.hash(), &path)?;
-    verify_ecdsa_signature(root.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
 
     let token_to_remove = CommissionToken::from(&token);
-    for i in 0..commission_admin.acceptable_tokens.len() {
-        if commission_admin.acceptable_tokens[i].eq(&token_to_remove) {
+    if let Ok(i) = find_token(&commission_admin.acceptable_tokens, &token_to_remove.token) {
+        if commission_admin.acceptable_tokens[i].amount == token_to_remove.amount {
             commission_admin.acceptable_tokens.remove(i);
-            break;
         }
     }
 
     commission_admin.remove_token_nonce += 1;
-    commission_admin.serialize(&mut *commission_admin_info.data.borrow_mut())?;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
 
     Ok(())
 }
@@ -280,12 +381,13 @@ pub fn process_update_token<'a>(
     path: Vec<[u8; 32]>,
     token: CommissionTokenArg,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "UpdateFeeToken")?;
     let account_info_iter = &mut accounts.iter();
 
     let commission_admin_info = next_account_info(account_info_iter)?;
     let bridge_admin_info = next_account_info(account_info_iter)?;
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &program_id)?;
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
     if commission_key != *commission_admin_info.key {
         return Err(LibError::WrongAdmin.into());
     }
@@ -295,45 +397,111 @@ pub fn process_update_token<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(commission_admin.version)?;
+
     let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
     if !bridge_admin.is_initialized {
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+
     let content = Content::new(
         commission_admin.update_token_nonce,
         None,
         *program_id,
         OperationType::UpdateToken,
-        CommissionToken::from(&token),
+        vec![CommissionToken::from(&token)],
     );
     let root = get_merkle_root(content.hash(), &path)?;
-    verify_ecdsa_signature(root.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
 
     let token_to_update = CommissionToken::from(&token);
-    for i in 0..commission_admin.acceptable_tokens.len() {
-        if commission_admin.acceptable_tokens[i].token.eq(&token_to_update.token) {
-            commission_admin.acceptable_tokens[i].amount = token_to_update.amount;
-            break;
-        }
+    if let Ok(i) = find_token(&commission_admin.acceptable_tokens, &token_to_update.token) {
+        commission_admin.acceptable_tokens[i].amount = token_to_update.amount;
     }
 
     commission_admin.update_token_nonce += 1;
-    commission_admin.serialize(&mut *commission_admin_info.data.borrow_mut())?;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
 
     Ok(())
 }
 
+pub fn process_set_tokens<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+    tokens: Vec<CommissionTokenArg>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "SetFeeTokens")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let commission_admin_info = next_account_info(account_info_iter)?;
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
+    if commission_key != *commission_admin_info.key {
+        return Err(LibError::WrongAdmin.into());
+    }
+
+    let mut commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
+    if !commission_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(commission_admin.version)?;
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let new_tokens: Vec<CommissionToken> = tokens.iter().map(CommissionToken::from).collect();
+
+    // The list is signed as the operation data with the length prefixes, not as the plain content tokens
+    let content = Content::new(
+        commission_admin.set_tokens_nonce,
+        None,
+        *program_id,
+        OperationType::SetTokens,
+        Vec::new(),
+    ).with_data(token_list_data(&new_tokens));
+    let root = get_merkle_root(content.hash(), &path)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
+
+    // Keep the counters of the tokens that stay acceptable
+    let mut new_tokens = new_tokens;
+    for t in new_tokens.iter_mut() {
+        if let Ok(old) = check_token_is_acceptable(commission_admin.acceptable_tokens.clone(), t.token.clone()) {
+            t.collected = old.collected;
+            t.withdrawn = old.withdrawn;
+        }
+    }
+
+    new_tokens.sort_by_key(|t| t.token.sort_key());
+
+    commission_admin.acceptable_tokens = new_tokens;
+    commission_admin.set_tokens_nonce += 1;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
+
+    Ok(())
+}
 
 pub fn process_withdraw<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     recovery_id: u8,
-    path: Vec<[u8; 32]>,
+    path: MerkleProof,
     token: CommissionTokenArg,
     withdraw_amount: u64,
+    split: Vec<(Pubkey, u16)>,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "Withdraw")?;
     let account_info_iter = &mut accounts.iter();
 
     let commission_admin_info = next_account_info(account_info_iter)?;
@@ -342,7 +510,7 @@ pub fn process_withdraw<'a>(
     let system_program = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
 
-    let commission_key = Pubkey::create_program_address(&[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()], &program_id)?;
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
     if commission_key != *commission_admin_info.key {
         return Err(LibError::WrongAdmin.into());
     }
@@ -352,78 +520,378 @@ pub fn process_withdraw<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(commission_admin.version)?;
+
+    // Transfer to the commission admin itself changes nothing but spends the signed nonce
+    if *receiver_info.key == commission_key || split.iter().any(|(split_receiver, _)| *split_receiver == commission_key) {
+        msg!("Commission can not be withdrawn to the commission admin");
+        return Err(LibError::InvalidKey.into());
+    }
+
     let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
     if !bridge_admin.is_initialized {
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(bridge_admin.version)?;
+
     let content = Content::new(
         commission_admin.withdraw_token_nonce,
         Some(*receiver_info.key),
         *program_id,
         OperationType::WithdrawToken,
-        CommissionToken::from(&token),
-    );
-    let root = get_merkle_root(content.hash(), &path)?;
-    verify_ecdsa_signature(root.as_slice(), signature.as_slice(), recovery_id, bridge_admin.public_key)?;
+        vec![CommissionToken::from(&token)],
+    ).with_split(split.clone()).with_data(amount_bytes(withdraw_amount));
+    let root = path.root_from_leaf(content.hash())?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
+
+    let shares = split_shares(withdraw_amount, &split)?;
+
+    match token.token.clone().into() {
+        lib::CommissionToken::Native | lib::CommissionToken::Any => {
+            // Commission admin holds data, so the lamports are moved directly instead of the system transfer
+            if split.is_empty() {
+                transfer_lamports(commission_admin_info, receiver_info, withdraw_amount)?;
+            }
 
-    match token.token.into() {
-        lib::CommissionToken::Native => {
-            call_transfer_native(
-                commission_admin_info,
-                receiver_info,
-                withdraw_amount,
-                &[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()],
-            )?;
+            for ((split_receiver, _), share) in split.iter().zip(shares) {
+                let split_receiver_info = next_account_info(account_info_iter)?;
+                if *split_receiver_info.key != *split_receiver {
+                    return Err(LibError::InvalidKey.into());
+                }
+
+                transfer_lamports(commission_admin_info, split_receiver_info, share)?;
+            }
         }
         lib::CommissionToken::FT(mint) => {
             let token_program = next_account_info(account_info_iter)?;
             let receiver_associated_info = next_account_info(account_info_iter)?;
             let commission_associated_info = next_account_info(account_info_iter)?;
+            let mint_info = next_account_info(account_info_iter)?;
+
+            if *mint_info.key != mint {
+                return Err(LibError::WrongMint.into());
+            }
 
             if *commission_associated_info.key !=
                 get_associated_token_address(&commission_key, &mint) {
                 return Err(LibError::WrongTokenAccount.into());
             }
 
-            if *receiver_associated_info.key !=
-                get_associated_token_address(receiver_info.key, &mint) {
+            if split.is_empty() {
+                if *receiver_associated_info.key !=
+                    get_associated_token_address(receiver_info.key, &mint) {
+                    return Err(LibError::WrongTokenAccount.into());
+                }
+
+                if receiver_associated_info.data.borrow().as_ref().len() == 0 {
+                    msg!("Creating receiver associated account");
+                    lib::call_create_associated_account(
+                        receiver_info,
+                        receiver_info,
+                        mint_info,
+                        receiver_associated_info,
+                        rent_info,
+                        system_program,
+                        token_program,
+                    )?;
+                }
+
+                call_transfer_ft(
+                    commission_associated_info,
+                    receiver_associated_info,
+                    commission_admin_info,
+                    withdraw_amount,
+                    &commission_admin_seeds(bridge_admin_info.key),
+                )?;
+            }
+
+            for ((split_receiver, _), share) in split.iter().zip(shares) {
+                let split_receiver_info = next_account_info(account_info_iter)?;
+                let split_associated_info = next_account_info(account_info_iter)?;
+                if *split_receiver_info.key != *split_receiver {
+                    return Err(LibError::InvalidKey.into());
+                }
+
+                if *split_associated_info.key !=
+                    get_associated_token_address(split_receiver, &mint) {
+                    return Err(LibError::WrongTokenAccount.into());
+                }
+
+                if split_associated_info.data.borrow().as_ref().len() == 0 {
+                    msg!("Creating split receiver associated account");
+                    lib::call_create_associated_account(
+                        receiver_info,
+                        split_receiver_info,
+                        mint_info,
+                        split_associated_info,
+                        rent_info,
+                        system_program,
+                        token_program,
+                    )?;
+                }
+
+                call_transfer_ft(
+                    commission_associated_info,
+                    split_associated_info,
+                    commission_admin_info,
+                    share,
+                    &commission_admin_seeds(bridge_admin_info.key),
+                )?;
+            }
+        }
+        lib::CommissionToken::NFT(mint) => {
+            return Err(LibError::NotSupported.into());
+        }
+    }
+
+    if let Ok(i) = find_token(&commission_admin.acceptable_tokens, &token.token) {
+        let t = &mut commission_admin.acceptable_tokens[i];
+        t.withdrawn = t.withdrawn.saturating_add(withdraw_amount);
+    }
+
+    commission_admin.withdraw_token_nonce += 1;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
+
+    Ok(())
+}
+
+pub fn process_migrate_bridge_admin<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 8, "MigrateBridgeAdmin")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let commission_admin_info = next_account_info(account_info_iter)?;
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let new_commission_admin_info = next_account_info(account_info_iter)?;
+    let new_bridge_admin_info = next_account_info(account_info_iter)?;
+    let fee_payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
+    if commission_key != *commission_admin_info.key {
+        return Err(LibError::WrongAdmin.into());
+    }
+
+    let new_commission_key = commission_admin_address(program_id, new_bridge_admin_info.key)?;
+    if new_commission_key != *new_commission_admin_info.key {
+        return Err(LibError::WrongAdmin.into());
+    }
+
+    let mut commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
+    if !commission_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(commission_admin.version)?;
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let new_bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut new_bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !new_bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(new_bridge_admin.version)?;
+
+    if new_bridge_admin.commission_program != *program_id {
+        return Err(LibError::WrongCommissionProgram.into());
+    }
+
+    let content = Content::new(
+        commission_admin.withdraw_token_nonce,
+        Some(*new_bridge_admin_info.key),
+        *program_id,
+        OperationType::MigrateBridgeAdmin,
+        Vec::new(),
+    );
+    let root = get_merkle_root(content.hash(), &path)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
+
+    msg!("Creating new commission admin account");
+    lib::call_create_account(
+        fee_payer_info,
+        new_commission_admin_info,
+        rent_info,
+        system_program,
+        MAX_ADMIN_SIZE,
+        program_id,
+        &commission_admin_seeds(new_bridge_admin_info.key),
+    )?;
+
+    // NFT commission accounts are moved as well, the tokens could be sent to them directly
+    for token in &commission_admin.acceptable_tokens {
+        if let lib::CommissionToken::FT(mint) | lib::CommissionToken::NFT(mint) = token.token {
+            let associated_info = next_account_info(account_info_iter)?;
+            let new_associated_info = next_account_info(account_info_iter)?;
+            let mint_info = next_account_info(account_info_iter)?;
+
+            if *associated_info.key != get_associated_token_address(&commission_key, &mint) ||
+                *new_associated_info.key != get_associated_token_address(&new_commission_key, &mint) {
                 return Err(LibError::WrongTokenAccount.into());
             }
 
-            if receiver_associated_info.data.borrow().as_ref().len() == 0 {
-                msg!("Creating receiver associated account");
-                let mint_info = next_account_info(account_info_iter)?;
+            if associated_info.data.borrow().as_ref().len() == 0 {
+                continue;
+            }
+
+            if new_associated_info.data.borrow().as_ref().len() == 0 {
+                msg!("Creating new commission admin associated account");
                 lib::call_create_associated_account(
-                    receiver_info,
-                    receiver_info,
+                    fee_payer_info,
+                    new_commission_admin_info,
                     mint_info,
-                    receiver_associated_info,
+                    new_associated_info,
                     rent_info,
                     system_program,
                     token_program,
                 )?;
             }
 
-            call_transfer_ft(
-                commission_associated_info,
-                receiver_associated_info,
-                commission_admin_info,
-                withdraw_amount,
-                &[lib::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin_info.key.as_ref()],
-            )?;
-        }
-        lib::CommissionToken::NFT(mint) => {
-            return Err(LibError::NotSupported.into());
+            let associated = spl_token::state::Account::unpack_from_slice(&mut associated_info.data.borrow().as_ref())?;
+            if associated.amount > 0 {
+                msg!("Transferring collected token");
+                call_transfer_ft(
+                    associated_info,
+                    new_associated_info,
+                    commission_admin_info,
+                    associated.amount,
+                    &commission_admin_seeds(bridge_admin_info.key),
+                )?;
+            }
         }
     }
 
+    // Keep the old account rent-exempt, everything above belongs to the collected commission
+    let rent = Rent::from_account_info(rent_info)?;
+    let collected = commission_admin_info.lamports().saturating_sub(rent.minimum_balance(commission_admin_info.data_len()));
+
+    msg!("Transferring collected native token");
+    **commission_admin_info.try_borrow_mut_lamports()? -= collected;
+    **new_commission_admin_info.try_borrow_mut_lamports()? += collected;
+
+    let new_commission_admin = CommissionAdmin {
+        version: lib::STATE_VERSION,
+        acceptable_tokens: commission_admin.acceptable_tokens.clone(),
+        add_token_nonce: commission_admin.add_token_nonce,
+        update_token_nonce: commission_admin.update_token_nonce,
+        remove_token_nonce: commission_admin.remove_token_nonce,
+        withdraw_token_nonce: commission_admin.withdraw_token_nonce + 1,
+        set_tokens_nonce: commission_admin.set_tokens_nonce,
+        is_initialized: true,
+    };
+    lib::serialize_checked(&new_commission_admin, new_commission_admin_info)?;
+
     commission_admin.withdraw_token_nonce += 1;
-    commission_admin.serialize(&mut *commission_admin_info.data.borrow_mut())?;
+    commission_admin.is_initialized = false;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
 
     Ok(())
 }
 
+pub fn process_close_admin<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 4, "CloseAdmin")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let commission_admin_info = next_account_info(account_info_iter)?;
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
+    if commission_key != *commission_admin_info.key {
+        return Err(LibError::WrongAdmin.into());
+    }
+
+    let commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
+    if !commission_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(commission_admin.version)?;
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    let content = Content::new(
+        commission_admin.withdraw_token_nonce,
+        Some(*receiver_info.key),
+        *program_id,
+        OperationType::CloseAdmin,
+        Vec::new(),
+    );
+    let root = get_merkle_root(content.hash(), &path)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
+
+    // The actual balances are checked: the counters are not tracked for the commission collected before MigrateState
+    let rent = Rent::get()?.minimum_balance(commission_admin_info.data_len());
+    if commission_admin_info.lamports() > rent {
+        msg!("Collected native commission is not withdrawn: {} lamports above rent", commission_admin_info.lamports() - rent);
+        return Err(LibError::WrongBalance.into());
+    }
+
+    for token in &commission_admin.acceptable_tokens {
+        if let lib::CommissionToken::FT(mint) = token.token {
+            let associated_info = next_account_info(account_info_iter)?;
+            if *associated_info.key != get_associated_token_address(&commission_key, &mint) {
+                return Err(LibError::WrongTokenAccount.into());
+            }
+
+            if associated_info.data.borrow().as_ref().len() == 0 {
+                continue;
+            }
+
+            // Tokens transferred directly to the associated account are not tracked as collected too
+            let associated = spl_token::state::Account::unpack_from_slice(&mut associated_info.data.borrow().as_ref())?;
+            if associated.amount > 0 {
+                return Err(LibError::WrongBalance.into());
+            }
+
+            msg!("Closing commission admin associated account");
+            invoke_signed(
+                &spl_token::instruction::close_account(
+                    token_program.key,
+                    associated_info.key,
+                    receiver_info.key,
+                    commission_admin_info.key,
+                    &[],
+                )?,
+                &[associated_info.clone(), receiver_info.clone(), commission_admin_info.clone()],
+                &[&commission_admin_seeds(bridge_admin_info.key)],
+            )?;
+        }
+    }
+
+    msg!("Closing commission admin account");
+    let lamports = commission_admin_info.lamports();
+    **commission_admin_info.try_borrow_mut_lamports()? -= lamports;
+    **receiver_info.try_borrow_mut_lamports()? += lamports;
+    commission_admin_info.data.borrow_mut().fill(0);
+    Ok(())
+}
+
 fn call_transfer_native<'a>(
     from: &AccountInfo<'a>,
     to: &AccountInfo<'a>,
@@ -448,6 +916,74 @@ fn call_transfer_native<'a>(
     invoke(&transfer_tokens_instruction, &accounts)
 }
 
+fn transfer_lamports<'a>(
+    from: &AccountInfo<'a>,
+    to: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let from_lamports = from.lamports().checked_sub(amount).ok_or(LibError::WrongBalance)?;
+    let to_lamports = to.lamports().checked_add(amount).ok_or(LibError::WrongBalance)?;
+
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+pub fn process_migrate_state<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3, "MigrateState")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let account_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if account_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    match lib::state_version(account_info, BASELINE_ADMIN_SIZE)? {
+        lib::BASELINE_STATE_VERSION => {
+            let baseline: BaselineCommissionAdmin = BorshDeserialize::deserialize(&mut account_info.data.borrow().as_ref())?;
+            if !baseline.is_initialized {
+                return Err(LibError::NotInitialized.into());
+            }
+
+            // Baseline lists were kept in the insertion order, the counters were not tracked
+            let mut acceptable_tokens: Vec<CommissionToken> = baseline.acceptable_tokens.into_iter().map(|t| CommissionToken {
+                token: t.token,
+                amount: t.amount,
+                collected: 0,
+                withdrawn: 0,
+            }).collect();
+            acceptable_tokens.sort_by_key(|t| t.token.sort_key());
+
+            let commission_admin = CommissionAdmin {
+                version: lib::STATE_VERSION,
+                acceptable_tokens,
+                add_token_nonce: baseline.add_token_nonce,
+                update_token_nonce: baseline.update_token_nonce,
+                remove_token_nonce: baseline.remove_token_nonce,
+                withdraw_token_nonce: baseline.withdraw_token_nonce,
+                set_tokens_nonce: 0,
+                is_initialized: true,
+            };
+
+            msg!("Migrating commission admin to the version {}", lib::STATE_VERSION);
+            lib::realloc_with_rent(account_info, payer_info, system_program, MAX_ADMIN_SIZE)?;
+            lib::serialize_checked(&commission_admin, account_info)?;
+            Ok(())
+        }
+        lib::STATE_VERSION => {
+            msg!("Account {} is already of the current version", account_info.key);
+            Ok(())
+        }
+        version => lib::check_version(version),
+    }
+}
+
 fn call_transfer_ft<'a>(
     from: &AccountInfo<'a>,
     to: &AccountInfo<'a>,
@@ -477,12 +1013,168 @@ fn call_transfer_ft<'a>(
     invoke(&transfer_tokens_instruction, &accounts)
 }
 
+pub fn process_get_collected<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    token: lib::CommissionToken,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "GetCollected")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let commission_admin_info = next_account_info(account_info_iter)?;
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
+    if commission_key != *commission_admin_info.key {
+        return Err(LibError::WrongAdmin.into());
+    }
+
+    let commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow().as_ref())?;
+    if !commission_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(commission_admin.version)?;
+
+    let commission_token = check_token_is_acceptable(commission_admin.acceptable_tokens, token)?;
+
+    let collected = CollectedCommission {
+        token: commission_token.token,
+        collected: commission_token.collected,
+        withdrawn: commission_token.withdrawn,
+    };
+
+    set_return_data(collected.try_to_vec()?.as_slice());
+    Ok(())
+}
+
+/// Calculates the commission charged for the deposit of `_deposit_amount` tokens.
+/// Acceptable tokens define a fixed amount for now; the calculation is done in `u128`
+/// and narrowed with a checked cast, so amount based fees can not silently overflow.
+fn checked_commission(_deposit_amount: u64, token: &CommissionToken) -> Result<u64, ProgramError> {
+    let commission = u128::from(token.amount);
+    u64::try_from(commission).map_err(|_| LibError::AmountOverflow.into())
+}
+
+// Splits the amount by the shares in basis points, the last receiver takes the rounding remainder
+fn split_shares(amount: u64, split: &Vec<(Pubkey, u16)>) -> Result<Vec<u64>, ProgramError> {
+    let mut shares = Vec::with_capacity(split.len());
+    let mut distributed: u64 = 0;
+
+    for (i, (_, bps)) in split.iter().enumerate() {
+        let share = if i == split.len() - 1 {
+            amount - distributed
+        } else {
+            let share = u128::from(amount) * u128::from(*bps) / u128::from(SPLIT_TOTAL_BPS);
+            u64::try_from(share).map_err(|_| LibError::AmountOverflow)?
+        };
+
+        distributed = distributed.checked_add(share).ok_or(LibError::AmountOverflow)?;
+        shares.push(share);
+    }
+
+    Ok(shares)
+}
+
+// Token missing from the list is charged by the `Any` entry (flat native fee) if it is acceptable
 fn check_token_is_acceptable(list: Vec<CommissionToken>, token: lib::CommissionToken) -> Result<CommissionToken, LibError> {
-    for l in list {
-        if l.token == token {
-            return Ok(l);
+    match find_token(&list, &token).or_else(|_| find_token(&list, &lib::CommissionToken::Any)) {
+        Ok(i) => Ok(list[i].clone()),
+        Err(_) => Err(LibError::NotAcceptable.into()),
+    }
+}
+
+// Acceptable tokens list is sorted by `lib::CommissionToken::sort_key`.
+// Returns the token index or the index where it should be inserted.
+fn find_token(list: &[CommissionToken], token: &lib::CommissionToken) -> Result<usize, usize> {
+    let key = token.sort_key();
+    list.binary_search_by_key(&key, |t| t.token.sort_key())
+}
+
+fn insert_token(list: &mut Vec<CommissionToken>, token: CommissionToken) -> ProgramResult {
+    match find_token(list, &token.token) {
+        Ok(_) => Err(LibError::AlreadyInUse.into()),
+        Err(i) => {
+            list.insert(i, token);
+            Ok(())
         }
     }
+}
 
-    return Err(LibError::NotAcceptable.into());
-}
\ No newline at end of file
+pub fn process_reset_nonces<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    add_token_nonce: u64,
+    remove_token_nonce: u64,
+    update_token_nonce: u64,
+    withdraw_token_nonce: u64,
+    set_tokens_nonce: u64,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 2, "ResetNonces")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let commission_admin_info = next_account_info(account_info_iter)?;
+    let bridge_admin_info = next_account_info(account_info_iter)?;
+
+    let commission_key = commission_admin_address(program_id, bridge_admin_info.key)?;
+    if commission_key != *commission_admin_info.key {
+        return Err(LibError::WrongAdmin.into());
+    }
+
+    let mut commission_admin: CommissionAdmin = BorshDeserialize::deserialize(&mut commission_admin_info.data.borrow_mut().as_ref())?;
+    if !commission_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(commission_admin.version)?;
+
+    let bridge_admin: BridgeAdmin = BorshDeserialize::deserialize(&mut bridge_admin_info.data.borrow_mut().as_ref())?;
+    if !bridge_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(bridge_admin.version)?;
+
+    // New nonces in the stored order: add | remove | update | withdraw | set tokens
+    let mut data = Vec::new();
+    for nonce in [add_token_nonce, remove_token_nonce, update_token_nonce, withdraw_token_nonce, set_tokens_nonce] {
+        data.append(&mut amount_bytes(nonce));
+    }
+
+    // Signed for the current withdraw nonce, so the reset can not be replayed after the next withdrawal
+    let content = Content::new(
+        commission_admin.withdraw_token_nonce,
+        None,
+        *program_id,
+        OperationType::ResetNonces,
+        Vec::new(),
+    ).with_data(data);
+    let root = get_merkle_root(content.hash(), &path)?;
+    bridge_admin.verify_signature(root.as_slice(), signature.as_slice(), recovery_id)?;
+
+    // Moving a nonce back would make the spent signatures of that operation valid again
+    let current = [
+        commission_admin.add_token_nonce,
+        commission_admin.remove_token_nonce,
+        commission_admin.update_token_nonce,
+        commission_admin.withdraw_token_nonce,
+        commission_admin.set_tokens_nonce,
+    ];
+    let new = [add_token_nonce, remove_token_nonce, update_token_nonce, withdraw_token_nonce, set_tokens_nonce];
+    if new.iter().zip(current.iter()).any(|(new, current)| new < current) {
+        msg!("Nonces can not be moved back");
+        return Err(LibError::WrongNonce.into());
+    }
+
+    commission_admin.add_token_nonce = add_token_nonce;
+    commission_admin.remove_token_nonce = remove_token_nonce;
+    commission_admin.update_token_nonce = update_token_nonce;
+    commission_admin.withdraw_token_nonce = withdraw_token_nonce;
+    commission_admin.set_tokens_nonce = set_tokens_nonce;
+    lib::serialize_checked(&commission_admin, commission_admin_info)?;
+
+    Ok(())
+}
@@ -3,7 +3,10 @@ use solana_program::pubkey::Pubkey;
 use lib::instructions::commission::{MAX_TOKENS_COUNT, MAX_TOKEN_SIZE};
 use std::mem::size_of;
 
-pub const MAX_ADMIN_SIZE: usize = MAX_TOKENS_COUNT * (MAX_TOKEN_SIZE + 8) + (32 as usize) + (8 as usize);
+pub const MAX_ADMIN_SIZE: usize = 1 + MAX_TOKENS_COUNT * (MAX_TOKEN_SIZE + 8 + 8 + 8) + (32 as usize) + (8 as usize) + (8 as usize);
+pub const CHARGE_SIZE: usize = (32 as usize) + (8 as usize) + 1;
+// CommissionAdmin of the deployed version without the version byte, see BaselineCommissionAdmin
+pub const BASELINE_ADMIN_SIZE: usize = MAX_TOKENS_COUNT * (MAX_TOKEN_SIZE + 8) + (32 as usize) + (8 as usize);
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -12,6 +15,10 @@ pub enum OperationType {
     RemoveToken,
     UpdateToken,
     WithdrawToken,
+    MigrateBridgeAdmin,
+    SetTokens,
+    CloseAdmin,
+    ResetNonces,
 }
 
 impl std::convert::Into<u8> for OperationType {
@@ -21,6 +28,10 @@ impl std::convert::Into<u8> for OperationType {
             OperationType::RemoveToken => 1,
             OperationType::UpdateToken => 2,
             OperationType::WithdrawToken => 3,
+            OperationType::MigrateBridgeAdmin => 4,
+            OperationType::SetTokens => 5,
+            OperationType::CloseAdmin => 6,
+            OperationType::ResetNonces => 7,
         }
     }
 }
@@ -30,6 +41,10 @@ impl std::convert::Into<u8> for OperationType {
 pub struct CommissionToken {
     pub token: lib::CommissionToken,
     pub amount: u64,
+    // Total charged amount
+    pub collected: u64,
+    // Total withdrawn amount
+    pub withdrawn: u64,
 }
 
 impl CommissionToken {
@@ -37,6 +52,8 @@ impl CommissionToken {
         CommissionToken {
             token: value.token.clone(),
             amount: value.amount,
+            collected: 0,
+            withdrawn: 0,
         }
     }
 }
@@ -44,10 +61,42 @@ impl CommissionToken {
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct CommissionAdmin {
+    // Layout version, see lib::STATE_VERSION
+    pub version: u8,
+    // Sorted by lib::CommissionToken::sort_key
     pub acceptable_tokens: Vec<CommissionToken>,
     pub add_token_nonce: u64,
     pub update_token_nonce: u64,
     pub remove_token_nonce: u64,
     pub withdraw_token_nonce: u64,
     pub is_initialized: bool,
-}
\ No newline at end of file
+    // Appended to the deployed layout, see MigrateState
+    pub set_tokens_nonce: u64,
+}
+
+// Layout of the CommissionAdmin of the deployed program version (no version byte), moved to CommissionAdmin by MigrateState
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct BaselineCommissionAdmin {
+    pub acceptable_tokens: Vec<BaselineCommissionToken>,
+    pub add_token_nonce: u64,
+    pub update_token_nonce: u64,
+    pub remove_token_nonce: u64,
+    pub withdraw_token_nonce: u64,
+    pub is_initialized: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct BaselineCommissionToken {
+    pub token: lib::CommissionToken,
+    pub amount: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Charge {
+    pub owner: Pubkey,
+    pub deposit_nonce: u64,
+    pub is_initialized: bool,
+}
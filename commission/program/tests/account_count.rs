@@ -0,0 +1,62 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use borsh::BorshSerialize;
+use common::{Bridge, Env};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{self as instructions, CommissionInstruction, FeeTokenArgs};
+use lib::{CommissionToken, TokenType};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+const SIGNATURE: [u8; 64] = [0; 64];
+
+// Passes one account less than the processor expects, the builder should pass at least `count` accounts
+fn assert_expects(env: &mut Env, name: &str, mut instruction: Instruction, count: usize) {
+    assert!(instruction.accounts.len() >= count, "{} builder passes {} accounts, expected {}", name, instruction.accounts.len(), count);
+    instruction.accounts.truncate(count - 1);
+    assert_eq!(env.process(&instruction), Err(LibError::NotEnoughAccounts.into()), "{}", name);
+}
+
+fn fee_token(commission: &Commission, instruction: fn(FeeTokenArgs) -> CommissionInstruction) -> Instruction {
+    Instruction {
+        program_id: commission.program_id,
+        accounts: vec![
+            AccountMeta::new(commission.admin, false),
+            AccountMeta::new_readonly(commission.bridge_admin, false),
+        ],
+        data: instruction(FeeTokenArgs { signature: SIGNATURE, recovery_id: 0, path: vec![], token: token(CommissionToken::Native, 1) }).try_to_vec().unwrap(),
+    }
+}
+
+#[test]
+fn commission_instructions_check_account_count() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1)]);
+    let (program_id, admin, bridge_admin) = (commission.program_id, commission.admin, commission.bridge_admin);
+    let (owner, mint) = (env.new_funded(), Pubkey::new_unique());
+
+    let cases = vec![
+        ("InitializeAdmin", commission.initialize_admin(&owner, vec![token(CommissionToken::Native, 1)]), 5),
+        ("ChargeCommission", instructions::charge_commission_native(program_id, admin, bridge_admin, owner, CommissionToken::Native, TokenType::Native, 1, 0), 6),
+        ("AddFeeToken", fee_token(&commission, CommissionInstruction::AddFeeToken), 2),
+        ("RemoveFeeToken", fee_token(&commission, CommissionInstruction::RemoveFeeToken), 2),
+        ("UpdateFeeToken", fee_token(&commission, CommissionInstruction::UpdateFeeToken), 2),
+        ("Withdraw", fixture::withdraw_native(&env, &bridge, &commission, &owner, token(CommissionToken::Native, 1), 1), 5),
+        ("SetFeeTokens", fixture::set_fee_tokens(&env, &bridge, &commission, vec![token(CommissionToken::Native, 1)]), 2),
+        ("MigrateBridgeAdmin", instructions::migrate_bridge_admin(
+            program_id, admin, bridge_admin, Pubkey::new_unique(), Pubkey::new_unique(), owner, vec![], SIGNATURE, 0, vec![],
+        ), 8),
+        ("GetCollected", instructions::get_collected(program_id, admin, bridge_admin, CommissionToken::Native), 2),
+        ("MigrateState", instructions::migrate_state(program_id, admin, owner), 3),
+        ("CloseAdmin", instructions::close_admin(program_id, admin, bridge_admin, owner, vec![mint], SIGNATURE, 0, vec![]), 4),
+        ("ResetNonces", instructions::reset_nonces(program_id, admin, bridge_admin, 0, 0, 0, 0, 0, SIGNATURE, 0, vec![]), 2),
+    ];
+
+    for (name, instruction, count) in cases {
+        assert_expects(&mut env, name, instruction, count);
+    }
+}
@@ -0,0 +1,101 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::merkle::Content;
+use commission::state::{CommissionToken, OperationType};
+use common::{Bridge, Env, RECEIVER};
+use fixture::{add_fee_token, token, Commission};
+use lib::error::LibError;
+use lib::instructions::bridge::deposit_ft;
+use lib::instructions::commission::charge_commission_native;
+use lib::{Network, TokenType};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+const FLAT_FEE: u64 = 500;
+
+// FT fee token and the flat native fee for any other token
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(lib::CommissionToken::FT(fee_mint), 50),
+        token(lib::CommissionToken::Any, FLAT_FEE),
+    ]);
+    (env, bridge, commission, fee_mint)
+}
+
+fn entry(token: lib::CommissionToken) -> CommissionToken {
+    CommissionToken { token, amount: FLAT_FEE, collected: 0, withdrawn: 0 }
+}
+
+#[test]
+fn arbitrary_ft_deposit_is_charged_flat_native_fee() {
+    let (mut env, bridge, commission, _) = setup();
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 9, None);
+    env.create_associated_token_account(&mint, &owner, 1_000_000);
+
+    let collected = env.lamports(&commission.admin);
+    env.process_transaction(&[
+        charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, lib::CommissionToken::FT(mint), TokenType::FT, 1_000_000, 0),
+        deposit_ft(bridge.program_id, bridge.admin, mint, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), 1_000_000, None, None, None),
+    ]).unwrap();
+
+    assert_eq!(env.lamports(&commission.admin), collected + FLAT_FEE);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1_000_000);
+
+    let state = commission.state(&env);
+    let any = state.acceptable_tokens.iter().find(|t| t.token == lib::CommissionToken::Any).unwrap();
+    assert_eq!(any.collected, FLAT_FEE);
+}
+
+#[test]
+fn flat_fee_does_not_depend_on_deposit_amount() {
+    let (mut env, bridge, commission, _) = setup();
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&mint, &owner, 10);
+
+    let collected = env.lamports(&commission.admin);
+    env.process_transaction(&[
+        charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, lib::CommissionToken::Any, TokenType::FT, 10, 0),
+        deposit_ft(bridge.program_id, bridge.admin, mint, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), 10, None, None, None),
+    ]).unwrap();
+    assert_eq!(env.lamports(&commission.admin), collected + FLAT_FEE);
+}
+
+#[test]
+fn unlisted_token_without_any_entry_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(lib::CommissionToken::Native, FLAT_FEE)]);
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+
+    assert_eq!(
+        env.process(&charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, lib::CommissionToken::FT(mint), TokenType::FT, 1000, 0)),
+        Err(LibError::NotAcceptable.into()),
+    );
+}
+
+#[test]
+fn any_entry_is_hashed_deterministically() {
+    let contract = Pubkey::new_unique();
+    let content = |token| Content::new(0, None, contract, OperationType::AddToken, vec![entry(token)]).hash();
+
+    assert_eq!(content(lib::CommissionToken::Any), content(lib::CommissionToken::Any));
+    assert_ne!(content(lib::CommissionToken::Any), content(lib::CommissionToken::Native));
+
+    // Signed Any entry is added by the program
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(lib::CommissionToken::Native, 1000)]);
+    env.process(&add_fee_token(&env, &bridge, &commission, token(lib::CommissionToken::Any, FLAT_FEE))).unwrap();
+    assert!(commission.state(&env).acceptable_tokens.iter().any(|t| t.token == lib::CommissionToken::Any && t.amount == FLAT_FEE));
+}
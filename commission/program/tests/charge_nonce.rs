@@ -0,0 +1,66 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use borsh::BorshDeserialize;
+use commission::state::Charge;
+use common::{Bridge, Env};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{charge_commission_native, get_charge_address};
+use lib::{CommissionToken, TokenType};
+
+fn setup() -> (Env, Bridge, Commission) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+    (env, bridge, commission)
+}
+
+#[test]
+fn first_charge_is_recorded() {
+    let (mut env, bridge, commission) = setup();
+    let owner = env.new_funded();
+    let collected = env.lamports(&commission.admin);
+
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 5,
+    )).unwrap();
+    assert_eq!(env.lamports(&commission.admin), collected + 1000);
+
+    let charge = Charge::deserialize(&mut env.data(&get_charge_address(&commission.program_id, &owner, 5)).as_slice()).unwrap();
+    assert!(charge.is_initialized);
+    assert_eq!(charge.owner, owner);
+    assert_eq!(charge.deposit_nonce, 5);
+}
+
+#[test]
+fn replayed_charge_is_rejected() {
+    let (mut env, bridge, commission) = setup();
+    let owner = env.new_funded();
+    let charge = charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 5,
+    );
+    env.process(&charge).unwrap();
+    let collected = env.lamports(&commission.admin);
+
+    assert_eq!(env.process(&charge), Err(LibError::AlreadyInUse.into()));
+    assert_eq!(env.lamports(&commission.admin), collected);
+
+    // Next deposit nonce is charged
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 6,
+    )).unwrap();
+    assert_eq!(env.lamports(&commission.admin), collected + 1000);
+}
+
+#[test]
+fn same_nonce_of_other_owner_is_charged() {
+    let (mut env, bridge, commission) = setup();
+    for _ in 0..2 {
+        let owner = env.new_funded();
+        env.process(&charge_commission_native(
+            commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 5,
+        )).unwrap();
+    }
+}
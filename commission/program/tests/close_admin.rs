@@ -0,0 +1,116 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::state::{BaselineCommissionAdmin, BaselineCommissionToken, OperationType, BASELINE_ADMIN_SIZE};
+use common::{Bridge, Env};
+use fixture::{token, withdraw_native, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{charge_commission_native, close_admin, migrate_state};
+use lib::{CommissionToken, TokenType};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(fee_mint), 50),
+    ]);
+    (env, bridge, commission, fee_mint)
+}
+
+// CloseAdmin to the receiver signed for the current withdraw nonce
+fn close(env: &Env, bridge: &Bridge, commission: &Commission, receiver: &Pubkey, fee_mint: &Pubkey) -> Instruction {
+    let content = commission.content(commission.state(env).withdraw_token_nonce, Some(*receiver), OperationType::CloseAdmin);
+    let (signature, recovery_id) = commission.sign(bridge, content);
+    close_admin(commission.program_id, commission.admin, bridge.admin, *receiver, vec![*fee_mint], signature, recovery_id, vec![])
+}
+
+#[test]
+fn clean_admin_is_closed() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let commission_associated = env.create_associated_token_account(&fee_mint, &commission.admin, 0);
+    let receiver = env.new_funded();
+    let rent = env.lamports(&commission.admin) + env.lamports(&commission_associated);
+    let lamports = env.lamports(&receiver);
+
+    env.process(&close(&env, &bridge, &commission, &receiver, &fee_mint)).unwrap();
+    assert_eq!(env.lamports(&receiver), lamports + rent);
+    assert_eq!(env.lamports(&commission.admin), 0);
+    assert!(env.data(&commission.admin).iter().all(|byte| *byte == 0));
+    assert_eq!(env.lamports(&commission_associated), 0);
+}
+
+#[test]
+fn close_with_collected_commission_is_rejected() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let owner = env.new_funded();
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 0,
+    )).unwrap();
+
+    let receiver = env.new_funded();
+    assert_eq!(env.process(&close(&env, &bridge, &commission, &receiver, &fee_mint)), Err(LibError::WrongBalance.into()));
+    assert!(commission.state(&env).is_initialized);
+
+    // Withdrawn commission allows the close
+    env.process(&withdraw_native(&env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1000)).unwrap();
+    env.process(&close(&env, &bridge, &commission, &receiver, &fee_mint)).unwrap();
+    assert_eq!(env.lamports(&commission.admin), 0);
+}
+
+#[test]
+fn close_with_untracked_tokens_is_rejected() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    env.create_associated_token_account(&fee_mint, &commission.admin, 10);
+
+    let receiver = env.new_funded();
+    assert_eq!(env.process(&close(&env, &bridge, &commission, &receiver, &fee_mint)), Err(LibError::WrongBalance.into()));
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 10);
+}
+
+#[test]
+fn close_to_unsigned_receiver_is_rejected() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let receiver = env.new_funded();
+    let mut instruction = close(&env, &bridge, &commission, &receiver, &fee_mint);
+    instruction.accounts[2].pubkey = env.new_funded();
+
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+    assert!(commission.state(&env).is_initialized);
+}
+
+#[test]
+fn migrated_admin_is_closed_by_balance() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.add_program(bridge.commission_program, commission::processor::process_instruction);
+    let commission = Commission::of(&bridge);
+    let baseline = BaselineCommissionAdmin {
+        acceptable_tokens: vec![BaselineCommissionToken { token: CommissionToken::Native, amount: 1000 }],
+        add_token_nonce: 0,
+        update_token_nonce: 0,
+        remove_token_nonce: 0,
+        withdraw_token_nonce: 0,
+        is_initialized: true,
+    };
+    env.set_state(&commission.admin, &commission.program_id, &baseline, BASELINE_ADMIN_SIZE);
+    let payer = env.new_funded();
+    env.process(&migrate_state(commission.program_id, commission.admin, payer)).unwrap();
+    // Commission collected before the migration is above the rent, but not counted
+    env.fund(&commission.admin, 1000);
+
+    let (receiver, fee_mint) = (env.new_funded(), Pubkey::new_unique());
+    assert_eq!(env.process(&close(&env, &bridge, &commission, &receiver, &fee_mint)), Err(LibError::WrongBalance.into()));
+
+    // Withdrawn amount above the zero collected counter does not lock the close
+    env.process(&withdraw_native(&env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1000)).unwrap();
+    assert!(commission.state(&env).acceptable_tokens[0].withdrawn > commission.state(&env).acceptable_tokens[0].collected);
+    env.process(&close(&env, &bridge, &commission, &receiver, &fee_mint)).unwrap();
+    assert_eq!(env.lamports(&commission.admin), 0);
+}
@@ -0,0 +1,59 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use borsh::BorshDeserialize;
+use common::{Bridge, Env};
+use fixture::{token, withdraw_native, Commission};
+use lib::instructions::commission::{charge_commission_ft, charge_commission_native, get_collected, CollectedCommission};
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+
+fn collected(env: &mut Env, commission: &Commission, commission_token: CommissionToken) -> CollectedCommission {
+    env.process(&get_collected(commission.program_id, commission.admin, commission.bridge_admin, commission_token)).unwrap();
+    let (program_id, data) = env.return_data().unwrap();
+    assert_eq!(program_id, commission.program_id);
+    CollectedCommission::deserialize(&mut data.as_slice()).unwrap()
+}
+
+#[test]
+fn collected_tracks_charges_and_withdrawals() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(fee_mint), 50),
+    ]);
+
+    let owner = env.new_funded();
+    env.create_associated_token_account(&fee_mint, &owner, 500);
+    for nonce in 0..2 {
+        env.process(&charge_commission_native(
+            commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, nonce,
+        )).unwrap();
+    }
+    env.process(&charge_commission_ft(
+        commission.program_id, commission.admin, bridge.admin, owner, fee_mint, CommissionToken::FT(fee_mint), TokenType::FT, 1, 2,
+    )).unwrap();
+
+    let native = collected(&mut env, &commission, CommissionToken::Native);
+    assert_eq!((native.collected, native.withdrawn), (2000, 0));
+    let ft = collected(&mut env, &commission, CommissionToken::FT(fee_mint));
+    assert_eq!((ft.collected, ft.withdrawn), (50, 0));
+
+    let receiver = env.new_funded();
+    let lamports = env.lamports(&receiver);
+    env.process(&withdraw_native(&env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1500)).unwrap();
+    assert_eq!(env.lamports(&receiver), lamports + 1500);
+
+    let native = collected(&mut env, &commission, CommissionToken::Native);
+    assert_eq!(native.token, CommissionToken::Native);
+    assert_eq!((native.collected, native.withdrawn), (2000, 1500));
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 1);
+
+    // Other tokens are not affected
+    let ft = collected(&mut env, &commission, CommissionToken::FT(fee_mint));
+    assert_eq!((ft.collected, ft.withdrawn), (50, 0));
+}
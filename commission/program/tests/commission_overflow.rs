@@ -0,0 +1,62 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{charge_commission_ft, charge_commission_native};
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(fee_mint), 50),
+    ]);
+    (env, bridge, commission, fee_mint)
+}
+
+#[test]
+fn max_deposit_is_charged_the_fixed_amount() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let owner = env.new_funded();
+    let owner_associated = env.create_associated_token_account(&fee_mint, &owner, 100);
+
+    let collected = env.lamports(&commission.admin);
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, u64::MAX, 0,
+    )).unwrap();
+    assert_eq!(env.lamports(&commission.admin), collected + 1000);
+
+    env.process(&charge_commission_ft(
+        commission.program_id, commission.admin, bridge.admin, owner, fee_mint, CommissionToken::FT(fee_mint), TokenType::FT, u64::MAX, 1,
+    )).unwrap();
+    assert_eq!(env.token_balance(&owner_associated), 50);
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 50);
+}
+
+#[test]
+fn collected_overflow_is_rejected() {
+    let (mut env, bridge, commission, _) = setup();
+    let mut state = commission.state(&env);
+    let native = state.acceptable_tokens.iter_mut().find(|t| t.token == CommissionToken::Native).unwrap();
+    native.collected = u64::MAX - 999;
+    let size = env.data(&commission.admin).len();
+    env.set_state(&commission.admin, &commission.program_id, &state, size);
+
+    let owner = env.new_funded();
+    let collected = env.lamports(&commission.admin);
+    assert_eq!(
+        env.process(&charge_commission_native(
+            commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 0,
+        )),
+        Err(LibError::AmountOverflow.into()),
+    );
+    assert_eq!(env.lamports(&commission.admin), collected);
+}
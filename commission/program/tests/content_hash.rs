@@ -0,0 +1,40 @@
+use commission::merkle::Content;
+use commission::state::{CommissionToken, OperationType};
+use lib::merkle::amount_bytes;
+use lib::{COMMISSION_DOMAIN, SOLANA_NETWORK};
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+
+fn token(token: lib::CommissionToken, amount: u64) -> CommissionToken {
+    CommissionToken { token, amount, collected: 0, withdrawn: 0 }
+}
+
+// Content of AddToken signed off-chain: the token is hashed by its class byte, then the mint and amount
+fn expected_hash(contract: &Pubkey, class: u8, mint: Option<&Pubkey>, amount: u64) -> [u8; 32] {
+    let mut data = Vec::from(COMMISSION_DOMAIN);
+    data.extend(amount_bytes(1));
+    data.extend_from_slice(contract.as_ref());
+    data.extend_from_slice(SOLANA_NETWORK.as_bytes());
+    data.push(0);
+    data.push(class);
+    if let Some(mint) = mint {
+        data.extend_from_slice(mint.as_ref());
+    }
+    data.extend(amount_bytes(amount));
+    keccak::hash(&data).to_bytes()
+}
+
+#[test]
+fn token_is_hashed_by_class() {
+    let contract = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+
+    let hash = |commission_token: lib::CommissionToken| {
+        Content::new(1, None, contract, OperationType::AddToken, vec![token(commission_token, 100)]).hash().to_bytes()
+    };
+
+    assert_eq!(hash(lib::CommissionToken::Native), expected_hash(&contract, 0, None, 100));
+    assert_eq!(hash(lib::CommissionToken::FT(mint)), expected_hash(&contract, 1, Some(&mint), 100));
+    assert_eq!(hash(lib::CommissionToken::NFT(mint)), expected_hash(&contract, 2, Some(&mint), 100));
+    assert_eq!(hash(lib::CommissionToken::Any), expected_hash(&contract, 3, None, 100));
+}
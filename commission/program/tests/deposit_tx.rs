@@ -0,0 +1,69 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env, RECEIVER};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::bridge::{build_deposit_ft_tx, build_deposit_native_tx};
+use lib::{CommissionToken, Network};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// Commission of 1000 lamports or 50 fee tokens
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(fee_mint), 50),
+    ]);
+    (env, bridge, commission, fee_mint)
+}
+
+#[test]
+fn native_deposit_tx_is_accepted() {
+    let (mut env, bridge, commission, _) = setup();
+    let owner = env.new_funded();
+    let (admin_lamports, collected) = (env.lamports(&bridge.admin), env.lamports(&commission.admin));
+
+    let tx = build_deposit_native_tx(
+        bridge.program_id, commission.program_id, bridge.admin, owner, bridge.seeds, Network::Ethereum, 5000, String::from(RECEIVER), None, None,
+        CommissionToken::Native, 0,
+    );
+    assert_eq!(tx[0].program_id, commission.program_id);
+    assert_eq!(tx[1].program_id, bridge.program_id);
+
+    env.process_transaction(&tx).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + 5000);
+    assert_eq!(env.lamports(&commission.admin), collected + 1000);
+
+    // The reversed deposit is not preceded by the charge
+    let owner = env.new_funded();
+    let mut tx = build_deposit_native_tx(
+        bridge.program_id, commission.program_id, bridge.admin, owner, bridge.seeds, Network::Ethereum, 5000, String::from(RECEIVER), None, None,
+        CommissionToken::Native, 0,
+    );
+    tx.reverse();
+    assert_eq!(env.process_transaction(&tx), Err((0, LibError::WrongCommissionProgram.into())));
+}
+
+#[test]
+fn ft_deposit_tx_with_ft_commission_is_accepted() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&mint, &owner, 1000);
+    let owner_fee_associated = env.create_associated_token_account(&fee_mint, &owner, 100);
+
+    env.process_transaction(&build_deposit_ft_tx(
+        bridge.program_id, commission.program_id, bridge.admin, mint, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), 1000, None, None, None,
+        CommissionToken::FT(fee_mint), 0,
+    )).unwrap();
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+    assert_eq!(env.token_balance(&owner_fee_associated), 50);
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 50);
+}
@@ -0,0 +1,85 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::state::CHARGE_SIZE;
+use common::{Bridge, Env, RECEIVER};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::bridge::deposit_native_with_commission;
+use lib::instructions::commission::get_charge_address;
+use lib::{CommissionToken, Network};
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+
+const FEE: u64 = 1000;
+const AMOUNT: u64 = 5000;
+
+fn setup() -> (Env, Bridge, Commission) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, FEE)]);
+    (env, bridge, commission)
+}
+
+fn deposit(bridge: &Bridge, owner: &Pubkey, commission_token: CommissionToken) -> Instruction {
+    deposit_native_with_commission(
+        bridge.program_id, bridge.commission_program, bridge.admin, *owner, bridge.seeds, Network::Ethereum, AMOUNT, String::from(RECEIVER), None, None, commission_token, 0,
+    )
+}
+
+fn charge_rent() -> u64 {
+    Rent::default().minimum_balance(CHARGE_SIZE)
+}
+
+#[test]
+fn deposit_charges_commission_in_one_instruction() {
+    let (mut env, bridge, commission) = setup();
+    let owner = env.new_funded();
+    let (owner_lamports, admin_lamports, collected) = (env.lamports(&owner), env.lamports(&bridge.admin), env.lamports(&commission.admin));
+
+    // No preceding ChargeCommission instruction
+    env.process(&deposit(&bridge, &owner, CommissionToken::Native)).unwrap();
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + AMOUNT);
+    assert_eq!(env.lamports(&commission.admin), collected + FEE);
+    assert_eq!(env.lamports(&owner), owner_lamports - AMOUNT - FEE - charge_rent());
+    assert!(env.exists(&get_charge_address(&commission.program_id, &owner, 0)));
+
+    // The charge account of the deposit nonce is spent
+    assert_eq!(env.process(&deposit(&bridge, &owner, CommissionToken::Native)), Err(LibError::AlreadyInUse.into()));
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports + AMOUNT);
+}
+
+#[test]
+fn failed_charge_fails_deposit() {
+    let (mut env, bridge, commission) = setup();
+    let owner = env.new_funded();
+    let (owner_lamports, admin_lamports) = (env.lamports(&owner), env.lamports(&bridge.admin));
+
+    assert_eq!(
+        env.process(&deposit(&bridge, &owner, CommissionToken::NFT(Pubkey::new_unique()))),
+        Err(LibError::NotAcceptable.into()),
+    );
+    assert_eq!(env.lamports(&owner), owner_lamports);
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert!(!env.exists(&get_charge_address(&commission.program_id, &owner, 0)));
+}
+
+#[test]
+fn failed_transfer_reverts_charge() {
+    let (mut env, bridge, commission) = setup();
+    // Enough for the commission, one lamport short of the deposit
+    let owner = Pubkey::new_unique();
+    env.fund(&owner, FEE + charge_rent() + AMOUNT - 1);
+    let (admin_lamports, collected) = (env.lamports(&bridge.admin), env.lamports(&commission.admin));
+
+    // SystemError::ResultWithNegativeLamports
+    assert_eq!(env.process(&deposit(&bridge, &owner, CommissionToken::Native)), Err(ProgramError::Custom(1)));
+    assert_eq!(env.lamports(&owner), FEE + charge_rent() + AMOUNT - 1);
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert_eq!(env.lamports(&commission.admin), collected);
+    assert_eq!(commission.state(&env).acceptable_tokens[0].collected, 0);
+    assert!(!env.exists(&get_charge_address(&commission.program_id, &owner, 0)));
+}
@@ -0,0 +1,166 @@
+//! Commission admin of the test bridge, processed by the commission program instead of the bridge test stub.
+
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use commission::merkle::{token_list_data, Content};
+use commission::state::{CommissionAdmin, CommissionToken, OperationType};
+use lib::instructions::commission::{CommissionInstruction, CommissionTokenArg, FeeTokenArgs, InitializeAdminArgs, SetFeeTokensArgs, WithdrawArgs};
+use lib::merkle::{amount_bytes, MerkleProof};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::{Bridge, Env, SIBLING};
+
+pub struct Commission {
+    pub program_id: Pubkey,
+    pub admin: Pubkey,
+    pub bridge_admin: Pubkey,
+}
+
+impl Commission {
+    /// Replaces the commission stub of the bridge by the commission program and initializes the admin
+    pub fn new(env: &mut Env, bridge: &Bridge, acceptable_tokens: Vec<CommissionTokenArg>) -> Self {
+        env.add_program(bridge.commission_program, commission::processor::process_instruction);
+        let commission = Self::of(bridge);
+
+        let payer = env.new_funded();
+        env.process(&commission.initialize_admin(&payer, acceptable_tokens)).unwrap();
+        commission
+    }
+
+    /// Commission admin of the bridge, not initialized by the call
+    pub fn of(bridge: &Bridge) -> Self {
+        Commission {
+            program_id: bridge.commission_program,
+            admin: bridge.commission_admin(),
+            bridge_admin: bridge.admin,
+        }
+    }
+
+    pub fn initialize_admin(&self, payer: &Pubkey, acceptable_tokens: Vec<CommissionTokenArg>) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.admin, false),
+                AccountMeta::new_readonly(self.bridge_admin, false),
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: CommissionInstruction::InitializeAdmin(InitializeAdminArgs { acceptable_tokens }).try_to_vec().unwrap(),
+        }
+    }
+
+    pub fn state(&self, env: &Env) -> CommissionAdmin {
+        CommissionAdmin::deserialize(&mut env.data(&self.admin).as_slice()).unwrap()
+    }
+
+    /// Signs the operation content by the bridge signer, the path is empty (the content hash is the root)
+    pub fn sign(&self, bridge: &Bridge, content: Content) -> ([u8; 64], u8) {
+        bridge.signer.sign(&content.hash().to_bytes())
+    }
+
+    /// Content of the operation with the nonce `nonce`, the commission program is the signed contract
+    pub fn content(&self, nonce: u64, receiver: Option<Pubkey>, operation_type: OperationType) -> Content {
+        Content::new(nonce, receiver, self.program_id, operation_type, Vec::new())
+    }
+}
+
+/// SetFeeTokens signed for the current nonce
+pub fn set_fee_tokens(env: &Env, bridge: &Bridge, commission: &Commission, tokens: Vec<CommissionTokenArg>) -> Instruction {
+    let list: Vec<CommissionToken> = tokens.iter().map(CommissionToken::from).collect();
+    let content = commission.content(commission.state(env).set_tokens_nonce, None, OperationType::SetTokens).with_data(token_list_data(&list));
+    let (signature, recovery_id) = commission.sign(bridge, content);
+    Instruction {
+        program_id: commission.program_id,
+        accounts: vec![
+            AccountMeta::new(commission.admin, false),
+            AccountMeta::new_readonly(commission.bridge_admin, false),
+        ],
+        data: CommissionInstruction::SetFeeTokens(SetFeeTokensArgs { signature, recovery_id, path: vec![], tokens }).try_to_vec().unwrap(),
+    }
+}
+
+/// AddFeeToken signed for the current nonce
+pub fn add_fee_token(env: &Env, bridge: &Bridge, commission: &Commission, token: CommissionTokenArg) -> Instruction {
+    let content = Content::new(
+        commission.state(env).add_token_nonce, None, commission.program_id, OperationType::AddToken, vec![CommissionToken::from(&token)],
+    );
+    let (signature, recovery_id) = commission.sign(bridge, content);
+    Instruction {
+        program_id: commission.program_id,
+        accounts: vec![
+            AccountMeta::new(commission.admin, false),
+            AccountMeta::new_readonly(commission.bridge_admin, false),
+        ],
+        data: CommissionInstruction::AddFeeToken(FeeTokenArgs { signature, recovery_id, path: vec![], token }).try_to_vec().unwrap(),
+    }
+}
+
+/// Native Withdraw of `amount` to the receiver signed for the current nonce
+pub fn withdraw_native(env: &Env, bridge: &Bridge, commission: &Commission, receiver: &Pubkey, token: CommissionTokenArg, amount: u64) -> Instruction {
+    withdraw(env, bridge, commission, receiver, token, amount, vec![], vec![])
+}
+
+/// FT Withdraw of `amount` to the receiver associated account, the same accounts whether it exists or not
+pub fn withdraw_ft(env: &Env, bridge: &Bridge, commission: &Commission, receiver: &Pubkey, mint: &Pubkey, token: CommissionTokenArg, amount: u64) -> Instruction {
+    withdraw(env, bridge, commission, receiver, token, amount, vec![], ft_accounts(commission, receiver, mint))
+}
+
+/// Native Withdraw of `amount` split between the receivers by basis points
+pub fn withdraw_split_native(
+    env: &Env, bridge: &Bridge, commission: &Commission, receiver: &Pubkey, token: CommissionTokenArg, amount: u64, split: Vec<(Pubkey, u16)>,
+) -> Instruction {
+    let split_accounts = split.iter().map(|(split_receiver, _)| AccountMeta::new(*split_receiver, false)).collect();
+    withdraw(env, bridge, commission, receiver, token, amount, split, split_accounts)
+}
+
+/// FT Withdraw of `amount` split between the associated accounts of the receivers by basis points
+pub fn withdraw_split_ft(
+    env: &Env, bridge: &Bridge, commission: &Commission, receiver: &Pubkey, mint: &Pubkey, token: CommissionTokenArg, amount: u64, split: Vec<(Pubkey, u16)>,
+) -> Instruction {
+    let mut accounts = ft_accounts(commission, receiver, mint);
+    for (split_receiver, _) in &split {
+        accounts.push(AccountMeta::new_readonly(*split_receiver, false));
+        accounts.push(AccountMeta::new(get_associated_token_address(split_receiver, mint), false));
+    }
+    withdraw(env, bridge, commission, receiver, token, amount, split, accounts)
+}
+
+fn ft_accounts(commission: &Commission, receiver: &Pubkey, mint: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(get_associated_token_address(receiver, mint), false),
+        AccountMeta::new(get_associated_token_address(&commission.admin, mint), false),
+        AccountMeta::new_readonly(*mint, false),
+    ]
+}
+
+fn withdraw(
+    env: &Env, bridge: &Bridge, commission: &Commission, receiver: &Pubkey, token: CommissionTokenArg, amount: u64, split: Vec<(Pubkey, u16)>, accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let content = Content::new(
+        commission.state(env).withdraw_token_nonce, Some(*receiver), commission.program_id, OperationType::WithdrawToken, vec![CommissionToken::from(&token)],
+    ).with_split(split.clone()).with_data(amount_bytes(amount));
+    // Withdrawal proof can not be empty
+    let path = MerkleProof(vec![SIBLING]);
+    let (signature, recovery_id) = bridge.signer.sign(&path.root_from_leaf(content.hash()).unwrap());
+    Instruction {
+        program_id: commission.program_id,
+        accounts: vec![
+            AccountMeta::new(commission.admin, false),
+            AccountMeta::new_readonly(commission.bridge_admin, false),
+            AccountMeta::new(*receiver, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ].into_iter().chain(accounts).collect(),
+        data: CommissionInstruction::Withdraw(WithdrawArgs { signature, recovery_id, path, token, withdraw_amount: amount, split }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn token(token: lib::CommissionToken, amount: u64) -> CommissionTokenArg {
+    CommissionTokenArg { token, amount }
+}
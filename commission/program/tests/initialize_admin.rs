@@ -0,0 +1,68 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::CommissionToken;
+
+#[test]
+fn pre_funded_admin_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.add_program(bridge.commission_program, commission::processor::process_instruction);
+    let commission = Commission::of(&bridge);
+    env.fund(&commission.admin, 1000);
+
+    let payer = env.new_funded();
+    assert_eq!(
+        env.process(&commission.initialize_admin(&payer, vec![token(CommissionToken::Native, 1000)])),
+        Err(LibError::AlreadyInUse.into()),
+    );
+    assert_eq!(env.lamports(&commission.admin), 1000);
+    assert!(env.data(&commission.admin).is_empty());
+}
+
+#[test]
+fn initialized_admin_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+
+    let payer = env.new_funded();
+    assert_eq!(
+        env.process(&commission.initialize_admin(&payer, vec![token(CommissionToken::Native, 1)])),
+        Err(LibError::AlreadyInUse.into()),
+    );
+    assert_eq!(commission.state(&env).acceptable_tokens[0].amount, 1000);
+}
+
+#[test]
+fn admin_is_created_at_derived_address() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+
+    let key = lib::instructions::commission::commission_admin_address(&bridge.commission_program, &bridge.admin).unwrap();
+    assert_eq!(commission.admin, key);
+    assert_eq!(env.account(&key).unwrap().owner, bridge.commission_program);
+    assert!(commission.state(&env).is_initialized);
+}
+
+#[test]
+fn empty_acceptable_tokens_are_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.add_program(bridge.commission_program, commission::processor::process_instruction);
+    let commission = Commission::of(&bridge);
+
+    let payer = env.new_funded();
+    assert_eq!(env.process(&commission.initialize_admin(&payer, vec![])), Err(LibError::WrongArgsSize.into()));
+    assert!(!env.exists(&commission.admin));
+
+    env.process(&commission.initialize_admin(&payer, vec![token(CommissionToken::Native, 1000)])).unwrap();
+    let state = commission.state(&env);
+    assert_eq!(state.acceptable_tokens.len(), 1);
+    assert_eq!(state.acceptable_tokens[0].token, CommissionToken::Native);
+}
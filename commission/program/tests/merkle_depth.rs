@@ -0,0 +1,60 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use borsh::BorshSerialize;
+use commission::merkle::Content;
+use commission::state::{CommissionToken, OperationType};
+use common::{Bridge, Env};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{CommissionInstruction, CommissionTokenArg, FeeTokenArgs};
+use lib::merkle::{get_merkle_root, MAX_MERKLE_DEPTH};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+// AddFeeToken signed over the root of the `depth` long path
+fn add_fee_token(env: &Env, bridge: &Bridge, commission: &Commission, token: CommissionTokenArg, depth: usize) -> Instruction {
+    let content = Content::new(
+        commission.state(env).add_token_nonce, None, commission.program_id, OperationType::AddToken, vec![CommissionToken::from(&token)],
+    );
+    let path = vec![[1; 32]; depth];
+    // Over-long path has no root to sign
+    let (signature, recovery_id) = match get_merkle_root(content.hash(), &path) {
+        Ok(root) => bridge.signer.sign(&root),
+        Err(_) => ([0; 64], 0),
+    };
+    Instruction {
+        program_id: commission.program_id,
+        accounts: vec![
+            AccountMeta::new(commission.admin, false),
+            AccountMeta::new_readonly(commission.bridge_admin, false),
+        ],
+        data: CommissionInstruction::AddFeeToken(FeeTokenArgs { signature, recovery_id, path, token }).try_to_vec().unwrap(),
+    }
+}
+
+#[test]
+fn path_of_max_depth_is_accepted() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(lib::CommissionToken::Native, 1000)]);
+
+    let mint = Pubkey::new_unique();
+    env.process(&add_fee_token(&env, &bridge, &commission, token(lib::CommissionToken::FT(mint), 10), MAX_MERKLE_DEPTH)).unwrap();
+    assert_eq!(commission.state(&env).acceptable_tokens.len(), 2);
+}
+
+#[test]
+fn path_over_max_depth_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(lib::CommissionToken::Native, 1000)]);
+
+    let mint = Pubkey::new_unique();
+    assert_eq!(
+        env.process(&add_fee_token(&env, &bridge, &commission, token(lib::CommissionToken::FT(mint), 10), MAX_MERKLE_DEPTH + 1)),
+        Err(LibError::WrongMerklePath.into()),
+    );
+    assert_eq!(commission.state(&env).acceptable_tokens.len(), 1);
+}
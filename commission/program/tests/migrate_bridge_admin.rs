@@ -0,0 +1,87 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::state::OperationType;
+use common::{Bridge, Env, Signer};
+use fixture::{token, Commission};
+use lib::instructions::commission::{charge_commission_ft, charge_commission_native, migrate_bridge_admin};
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use spl_associated_token_account::get_associated_token_address;
+
+// Collects 1000 lamports and 50 tokens of the fee mint
+fn charged_commission(env: &mut Env, bridge: &Bridge, fee_mint: &Pubkey) -> Commission {
+    env.create_mint(fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(env, bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(*fee_mint), 50),
+    ]);
+
+    let owner = env.new_funded();
+    env.create_associated_token_account(fee_mint, &owner, 500);
+
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 0,
+    )).unwrap();
+    env.process(&charge_commission_ft(
+        commission.program_id, commission.admin, bridge.admin, owner, *fee_mint, CommissionToken::FT(*fee_mint), TokenType::FT, 1, 1,
+    )).unwrap();
+
+    commission
+}
+
+#[test]
+fn migrate_moves_native_and_ft_balances() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    let commission = charged_commission(&mut env, &bridge, &fee_mint);
+
+    let new_bridge = bridge.redeployed(&mut env);
+    let new_commission = Commission::of(&new_bridge);
+
+    let nonce = commission.state(&env).withdraw_token_nonce;
+    let (signature, recovery_id) = commission.sign(&bridge, commission.content(nonce, Some(new_bridge.admin), OperationType::MigrateBridgeAdmin));
+    let payer = env.new_funded();
+    env.process(&migrate_bridge_admin(
+        commission.program_id, commission.admin, bridge.admin, new_commission.admin, new_bridge.admin, payer, vec![fee_mint], signature, recovery_id, vec![],
+    )).unwrap();
+
+    let rent = Rent::default();
+    assert_eq!(env.lamports(&commission.admin), rent.minimum_balance(env.data(&commission.admin).len()));
+    assert_eq!(env.lamports(&new_commission.admin), rent.minimum_balance(env.data(&new_commission.admin).len()) + 1000);
+
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 0);
+    assert_eq!(env.token_balance(&get_associated_token_address(&new_commission.admin, &fee_mint)), 50);
+
+    let old_state = commission.state(&env);
+    let new_state = new_commission.state(&env);
+    assert!(!old_state.is_initialized);
+    assert!(new_state.is_initialized);
+    assert_eq!(new_state.acceptable_tokens, old_state.acceptable_tokens);
+    assert_eq!(new_state.withdraw_token_nonce, nonce + 1);
+}
+
+#[test]
+fn migrate_requires_bridge_admin_signature() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    let commission = charged_commission(&mut env, &bridge, &fee_mint);
+
+    let new_bridge = bridge.redeployed(&mut env);
+    let new_commission = Commission::of(&new_bridge);
+
+    let nonce = commission.state(&env).withdraw_token_nonce;
+    let content = commission.content(nonce, Some(new_bridge.admin), OperationType::MigrateBridgeAdmin);
+    let (signature, recovery_id) = Signer::new(2).sign(&content.hash().to_bytes());
+    let payer = env.new_funded();
+    assert!(env.process(&migrate_bridge_admin(
+        commission.program_id, commission.admin, bridge.admin, new_commission.admin, new_bridge.admin, payer, vec![fee_mint], signature, recovery_id, vec![],
+    )).is_err());
+
+    assert!(commission.state(&env).is_initialized);
+    assert!(!env.exists(&new_commission.admin));
+}
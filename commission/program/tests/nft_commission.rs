@@ -0,0 +1,99 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env, RECEIVER};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::bridge::{deposit_ft, deposit_nft};
+use lib::instructions::commission::{charge_commission_ft, charge_commission_native};
+use lib::{CommissionToken, Network, TokenType};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// Free NFT bridging: zero native commission; FT deposits are charged in the fee token only
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 0),
+        token(CommissionToken::FT(fee_mint), 50),
+    ]);
+    (env, bridge, commission, fee_mint)
+}
+
+#[test]
+fn nft_deposit_is_free() {
+    let (mut env, bridge, commission, _) = setup();
+    let owner = env.new_funded();
+    let nft = Pubkey::new_unique();
+    env.create_mint(&nft, &Pubkey::new_unique(), 0, None);
+    env.create_associated_token_account(&nft, &owner, 1);
+
+    let collected = env.lamports(&commission.admin);
+    env.process_transaction(&[
+        charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::NFT, 1, 0),
+        deposit_nft(bridge.program_id, bridge.admin, nft, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), None, None, None),
+    ]).unwrap();
+
+    assert_eq!(env.lamports(&commission.admin), collected);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &nft)), 1);
+}
+
+#[test]
+fn nft_deposit_reported_as_ft_is_rejected() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let owner = env.new_funded();
+    let nft = Pubkey::new_unique();
+    env.create_mint(&nft, &Pubkey::new_unique(), 0, None);
+    env.create_associated_token_account(&nft, &owner, 1);
+    env.create_associated_token_account(&fee_mint, &owner, 100);
+
+    // Zero commission is not acceptable for FT
+    assert_eq!(
+        env.process_transaction(&[
+            charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::FT, 1, 0),
+            deposit_nft(bridge.program_id, bridge.admin, nft, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), None, None, None),
+        ]),
+        Err((0, LibError::NotAcceptable.into())),
+    );
+
+    // Charged FT commission does not match the NFT deposit
+    assert_eq!(
+        env.process_transaction(&[
+            charge_commission_ft(commission.program_id, commission.admin, bridge.admin, owner, fee_mint, CommissionToken::FT(fee_mint), TokenType::FT, 1, 0),
+            deposit_nft(bridge.program_id, bridge.admin, nft, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), None, None, None),
+        ]),
+        Err((1, LibError::WrongCommissionArguments.into())),
+    );
+}
+
+#[test]
+fn ft_deposit_is_charged() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let owner = env.new_funded();
+    let mint = Pubkey::new_unique();
+    env.create_mint(&mint, &Pubkey::new_unique(), 6, None);
+    env.create_associated_token_account(&mint, &owner, 1000);
+    let owner_fee_associated = env.create_associated_token_account(&fee_mint, &owner, 100);
+
+    // Free native commission is not applied to FT deposits
+    assert_eq!(
+        env.process_transaction(&[
+            charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::FT, 1000, 0),
+            deposit_ft(bridge.program_id, bridge.admin, mint, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), 1000, None, None, None),
+        ]),
+        Err((0, LibError::NotAcceptable.into())),
+    );
+
+    env.process_transaction(&[
+        charge_commission_ft(commission.program_id, commission.admin, bridge.admin, owner, fee_mint, CommissionToken::FT(fee_mint), TokenType::FT, 1000, 0),
+        deposit_ft(bridge.program_id, bridge.admin, mint, owner, bridge.seeds, Network::Ethereum, String::from(RECEIVER), 1000, None, None, None),
+    ]).unwrap();
+
+    assert_eq!(env.token_balance(&owner_fee_associated), 50);
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 50);
+    assert_eq!(env.token_balance(&get_associated_token_address(&bridge.admin, &mint)), 1000);
+}
@@ -0,0 +1,30 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env, RECEIVER};
+use fixture::{add_fee_token, token, Commission};
+use lib::error::LibError;
+use lib::instructions::bridge::deposit_native;
+use lib::{CommissionToken, Network};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn other_commission_instruction_before_deposit_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+    let owner = env.new_funded();
+    let admin_lamports = env.lamports(&bridge.admin);
+
+    let add_token = add_fee_token(&env, &bridge, &commission, token(CommissionToken::FT(Pubkey::new_unique()), 10));
+    assert_eq!(
+        env.process_transaction(&[
+            add_token,
+            deposit_native(bridge.program_id, bridge.admin, owner, bridge.seeds, Network::Ethereum, 5000, String::from(RECEIVER), None, None),
+        ]),
+        Err((1, LibError::NotACommissionCharge.into())),
+    );
+    assert_eq!(env.lamports(&bridge.admin), admin_lamports);
+    assert_eq!(commission.state(&env).acceptable_tokens.len(), 1);
+}
@@ -0,0 +1,90 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::state::OperationType;
+use common::{Bridge, Env, Signer};
+use fixture::{token, withdraw_native, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{charge_commission_native, reset_nonces};
+use lib::merkle::amount_bytes;
+use lib::{CommissionToken, TokenType};
+use solana_program::instruction::Instruction;
+
+// add | remove | update | withdraw | set tokens
+const NONCES: [u64; 5] = [3, 5, 7, 9, 11];
+
+// ResetNonces to `nonces` with the signature of `signed` by `bridge` for the current withdraw nonce
+fn reset(env: &Env, bridge: &Bridge, commission: &Commission, signed: [u64; 5], nonces: [u64; 5]) -> Instruction {
+    let data = signed.iter().flat_map(|nonce| amount_bytes(*nonce)).collect();
+    let content = commission.content(commission.state(env).withdraw_token_nonce, None, OperationType::ResetNonces).with_data(data);
+    let (signature, recovery_id) = commission.sign(bridge, content);
+    let [add, remove, update, withdraw, set_tokens] = nonces;
+    reset_nonces(commission.program_id, commission.admin, commission.bridge_admin, add, remove, update, withdraw, set_tokens, signature, recovery_id, vec![])
+}
+
+fn nonces(env: &Env, commission: &Commission) -> [u64; 5] {
+    let state = commission.state(env);
+    [state.add_token_nonce, state.remove_token_nonce, state.update_token_nonce, state.withdraw_token_nonce, state.set_tokens_nonce]
+}
+
+#[test]
+fn nonces_are_reset() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+
+    let instruction = reset(&env, &bridge, &commission, NONCES, NONCES);
+    env.process(&instruction).unwrap();
+    assert_eq!(nonces(&env, &commission), NONCES);
+
+    // Signed for the previous withdraw nonce
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+
+    // Withdrawals are signed for the reset nonce
+    let owner = env.new_funded();
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 0,
+    )).unwrap();
+    let receiver = env.new_funded();
+    env.process(&withdraw_native(&env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1000)).unwrap();
+    assert_eq!(commission.state(&env).withdraw_token_nonce, NONCES[3] + 1);
+}
+
+#[test]
+fn reset_with_wrong_signature_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+    let before = nonces(&env, &commission);
+
+    let other = Bridge { signer: Signer::new(2), ..bridge };
+    assert_eq!(env.process(&reset(&env, &other, &commission, NONCES, NONCES)), Err(LibError::WrongSignature.into()));
+
+    // Signature over the other nonces
+    let mut tampered = NONCES;
+    tampered[3] = 0;
+    assert_eq!(env.process(&reset(&env, &bridge, &commission, NONCES, tampered)), Err(LibError::WrongSignature.into()));
+    assert_eq!(nonces(&env, &commission), before);
+}
+
+#[test]
+fn nonces_can_not_be_moved_back() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+    env.process(&reset(&env, &bridge, &commission, NONCES, NONCES)).unwrap();
+
+    for i in 0..NONCES.len() {
+        let mut rewound = NONCES;
+        rewound[i] -= 1;
+        assert_eq!(env.process(&reset(&env, &bridge, &commission, rewound, rewound)), Err(LibError::WrongNonce.into()));
+    }
+    assert_eq!(nonces(&env, &commission), NONCES);
+
+    // Equal nonces are kept, the greater ones are set
+    let mut forward = NONCES;
+    forward[0] += 10;
+    env.process(&reset(&env, &bridge, &commission, forward, forward)).unwrap();
+    assert_eq!(nonces(&env, &commission), forward);
+}
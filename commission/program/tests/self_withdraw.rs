@@ -0,0 +1,56 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env};
+use fixture::{token, withdraw_native, withdraw_split_native, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::charge_commission_native;
+use lib::{CommissionToken, TokenType};
+
+// Commission collected 1000 lamports
+fn setup() -> (Env, Bridge, Commission) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+
+    let owner = env.new_funded();
+    env.process(&charge_commission_native(
+        commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, 0,
+    )).unwrap();
+    (env, bridge, commission)
+}
+
+#[test]
+fn withdraw_to_commission_admin_is_rejected() {
+    let (mut env, bridge, commission) = setup();
+    let admin_lamports = env.lamports(&commission.admin);
+
+    assert_eq!(
+        env.process(&withdraw_native(&env, &bridge, &commission, &commission.admin, token(CommissionToken::Native, 1000), 1000)),
+        Err(LibError::InvalidKey.into()),
+    );
+    assert_eq!(env.lamports(&commission.admin), admin_lamports);
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 0);
+    assert_eq!(commission.state(&env).acceptable_tokens[0].withdrawn, 0);
+
+    // The signed nonce is still available for the other receiver
+    let receiver = env.new_funded();
+    env.process(&withdraw_native(&env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1000)).unwrap();
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 1);
+}
+
+#[test]
+fn split_to_commission_admin_is_rejected() {
+    let (mut env, bridge, commission) = setup();
+    let receiver = env.new_funded();
+    let treasury = env.new_funded();
+
+    assert_eq!(
+        env.process(&withdraw_split_native(
+            &env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1000, vec![(treasury, 5000), (commission.admin, 5000)],
+        )),
+        Err(LibError::InvalidKey.into()),
+    );
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 0);
+}
@@ -0,0 +1,57 @@
+use borsh::BorshSerialize;
+use commission::state::{CommissionAdmin, CommissionToken, MAX_ADMIN_SIZE};
+use lib::error::LibError;
+use lib::instructions::commission::MAX_TOKENS_COUNT;
+use solana_program::account_info::AccountInfo;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+fn admin(tokens_count: usize) -> CommissionAdmin {
+    CommissionAdmin {
+        version: lib::STATE_VERSION,
+        acceptable_tokens: (0..tokens_count).map(|_| CommissionToken {
+            token: lib::CommissionToken::FT(Pubkey::new_unique()),
+            amount: 100,
+            collected: 0,
+            withdrawn: 0,
+        }).collect(),
+        add_token_nonce: 1,
+        update_token_nonce: 2,
+        remove_token_nonce: 3,
+        withdraw_token_nonce: 4,
+        is_initialized: true,
+        set_tokens_nonce: 5,
+    }
+}
+
+// Writes the admin into the admin account of MAX_ADMIN_SIZE bytes filled with `fill`
+fn serialize(admin: &CommissionAdmin, fill: u8) -> (Result<(), ProgramError>, Vec<u8>) {
+    let (key, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lamports = 0;
+    let mut data = vec![fill; MAX_ADMIN_SIZE];
+    let result = {
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+        lib::serialize_checked(admin, &info)
+    };
+    (result, data)
+}
+
+#[test]
+fn admin_with_max_tokens_fits() {
+    let admin = admin(MAX_TOKENS_COUNT);
+    let (result, data) = serialize(&admin, 0);
+    assert_eq!(result, Ok(()));
+
+    let serialized = admin.try_to_vec().unwrap();
+    assert_eq!(&data[..serialized.len()], serialized.as_slice());
+}
+
+#[test]
+fn over_capacity_admin_is_not_written() {
+    let admin = admin(MAX_TOKENS_COUNT * 2);
+    assert!(admin.try_to_vec().unwrap().len() > MAX_ADMIN_SIZE);
+
+    let (result, data) = serialize(&admin, 0xAA);
+    assert_eq!(result, Err(LibError::WrongDataLen.into()));
+    assert!(data.iter().all(|byte| *byte == 0xAA));
+}
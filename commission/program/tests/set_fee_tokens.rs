@@ -0,0 +1,53 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env};
+use fixture::{set_fee_tokens, token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::MAX_TOKENS_COUNT;
+use lib::CommissionToken;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn set_fee_tokens_replaces_whole_set() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(Pubkey::new_unique()), 10),
+    ]);
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let tokens = vec![
+        token(CommissionToken::FT(first), 20),
+        token(CommissionToken::Native, 500),
+        token(CommissionToken::FT(second), 30),
+    ];
+    let instruction = set_fee_tokens(&env, &bridge, &commission, tokens.clone());
+    env.process(&instruction).unwrap();
+
+    let state = commission.state(&env);
+    let mut expected: Vec<(CommissionToken, u64)> = tokens.iter().map(|t| (t.token.clone(), t.amount)).collect();
+    expected.sort_by_key(|(token, _)| token.sort_key());
+    assert_eq!(state.acceptable_tokens.iter().map(|t| (t.token.clone(), t.amount)).collect::<Vec<_>>(), expected);
+    assert_eq!(state.set_tokens_nonce, 1);
+
+    // Signed for the previous nonce
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+}
+
+#[test]
+fn oversized_or_duplicated_set_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+
+    let oversized = (0..=MAX_TOKENS_COUNT).map(|_| token(CommissionToken::FT(Pubkey::new_unique()), 10)).collect();
+    assert_eq!(env.process(&set_fee_tokens(&env, &bridge, &commission, oversized)), Err(LibError::WrongArgsSize.into()));
+
+    let duplicated = vec![token(CommissionToken::Native, 10), token(CommissionToken::Native, 20)];
+    assert_eq!(env.process(&set_fee_tokens(&env, &bridge, &commission, duplicated)), Err(LibError::WrongArgsSize.into()));
+
+    assert_eq!(commission.state(&env).acceptable_tokens.len(), 1);
+}
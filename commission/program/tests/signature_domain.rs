@@ -0,0 +1,48 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::state::OperationType;
+use common::{Bridge, Env};
+use fixture::{token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::migrate_bridge_admin;
+use lib::merkle::amount_bytes;
+use lib::{CommissionToken, BRIDGE_DOMAIN, SOLANA_NETWORK};
+use solana_program::keccak;
+
+#[test]
+fn bridge_domain_signature_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::Native, 1000)]);
+    let new_bridge = bridge.redeployed(&mut env);
+    let new_commission = Commission::of(&new_bridge);
+
+    let nonce = commission.state(&env).withdraw_token_nonce;
+    let content = commission.content(nonce, Some(new_bridge.admin), OperationType::MigrateBridgeAdmin);
+
+    // Same layout as the commission content, the bridge operations are hashed the same way
+    let bridge_hash = keccak::hashv(&[
+        BRIDGE_DOMAIN,
+        amount_bytes(nonce).as_slice(),
+        new_bridge.admin.as_ref(),
+        commission.program_id.as_ref(),
+        SOLANA_NETWORK.as_bytes(),
+        &[OperationType::MigrateBridgeAdmin.into()],
+    ]);
+    let (signature, recovery_id) = bridge.signer.sign(&bridge_hash.to_bytes());
+    let payer = env.new_funded();
+    assert_eq!(
+        env.process(&migrate_bridge_admin(
+            commission.program_id, commission.admin, bridge.admin, new_commission.admin, new_bridge.admin, payer, vec![], signature, recovery_id, vec![],
+        )),
+        Err(LibError::WrongSignature.into()),
+    );
+
+    let (signature, recovery_id) = commission.sign(&bridge, content);
+    env.process(&migrate_bridge_admin(
+        commission.program_id, commission.admin, bridge.admin, new_commission.admin, new_bridge.admin, payer, vec![], signature, recovery_id, vec![],
+    )).unwrap();
+    assert!(new_commission.state(&env).is_initialized);
+}
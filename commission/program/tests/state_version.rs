@@ -0,0 +1,75 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use commission::state::{BaselineCommissionAdmin, BaselineCommissionToken, CommissionAdmin, CommissionToken, BASELINE_ADMIN_SIZE, MAX_ADMIN_SIZE};
+use common::{Bridge, Env};
+use fixture::Commission;
+use lib::error::LibError;
+use lib::instructions::commission::{charge_commission_native, migrate_state};
+use lib::TokenType;
+use solana_program::pubkey::Pubkey;
+
+fn setup_baseline(env: &mut Env, bridge: &Bridge, fee_mint: &Pubkey) -> Commission {
+    env.add_program(bridge.commission_program, commission::processor::process_instruction);
+    let commission = Commission::of(bridge);
+    // Baseline lists were kept in the insertion order
+    let baseline = BaselineCommissionAdmin {
+        acceptable_tokens: vec![
+            BaselineCommissionToken { token: lib::CommissionToken::FT(*fee_mint), amount: 50 },
+            BaselineCommissionToken { token: lib::CommissionToken::Native, amount: 1000 },
+        ],
+        add_token_nonce: 1,
+        update_token_nonce: 2,
+        remove_token_nonce: 3,
+        withdraw_token_nonce: 4,
+        is_initialized: true,
+    };
+    env.set_state(&commission.admin, &commission.program_id, &baseline, BASELINE_ADMIN_SIZE);
+    commission
+}
+
+#[test]
+fn baseline_admin_is_migrated() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    let commission = setup_baseline(&mut env, &bridge, &fee_mint);
+
+    let owner = env.new_funded();
+    let charge = charge_commission_native(commission.program_id, commission.admin, bridge.admin, owner, lib::CommissionToken::Native, TokenType::Native, 1, 0);
+    assert!(env.process(&charge).is_err());
+
+    let payer = env.new_funded();
+    env.process(&migrate_state(commission.program_id, commission.admin, payer)).unwrap();
+    assert_eq!(env.data(&commission.admin).len(), MAX_ADMIN_SIZE);
+    assert_eq!(commission.state(&env), CommissionAdmin {
+        version: lib::STATE_VERSION,
+        acceptable_tokens: vec![
+            CommissionToken { token: lib::CommissionToken::Native, amount: 1000, collected: 0, withdrawn: 0 },
+            CommissionToken { token: lib::CommissionToken::FT(fee_mint), amount: 50, collected: 0, withdrawn: 0 },
+        ],
+        add_token_nonce: 1,
+        update_token_nonce: 2,
+        remove_token_nonce: 3,
+        withdraw_token_nonce: 4,
+        is_initialized: true,
+        set_tokens_nonce: 0,
+    });
+
+    let collected = env.lamports(&commission.admin);
+    env.process(&charge).unwrap();
+    assert_eq!(env.lamports(&commission.admin), collected + 1000);
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let commission = Commission::new(&mut env, &bridge, vec![fixture::token(lib::CommissionToken::Native, 1000)]);
+    let state = CommissionAdmin { version: lib::STATE_VERSION + 1, ..commission.state(&env) };
+    env.set_state(&commission.admin, &commission.program_id, &state, MAX_ADMIN_SIZE);
+
+    let payer = env.new_funded();
+    assert_eq!(env.process(&migrate_state(commission.program_id, commission.admin, payer)), Err(LibError::UnsupportedVersion.into()));
+}
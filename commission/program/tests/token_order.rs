@@ -0,0 +1,110 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env};
+use fixture::{add_fee_token, token, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::charge_commission_ft;
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+fn tokens(commission: &Commission, env: &Env) -> Vec<CommissionToken> {
+    commission.state(env).acceptable_tokens.into_iter().map(|t| t.token).collect()
+}
+
+fn is_sorted(list: &[CommissionToken]) -> bool {
+    list.windows(2).all(|w| w[0].sort_key() < w[1].sort_key())
+}
+
+#[test]
+fn initial_tokens_are_sorted() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (first, second) = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]));
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::NFT(first), 1),
+        token(CommissionToken::FT(second), 20),
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(first), 10),
+    ]);
+
+    let list = tokens(&commission, &env);
+    assert!(is_sorted(&list));
+    assert_eq!(list[0], CommissionToken::Native);
+    assert_eq!(&list[1..3], &[CommissionToken::FT(first), CommissionToken::FT(second)]);
+    assert_eq!(list[3], CommissionToken::NFT(first));
+}
+
+#[test]
+fn duplicated_initial_token_is_rejected() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    env.add_program(bridge.commission_program, commission::processor::process_instruction);
+    let commission = Commission::of(&bridge);
+    let mint = Pubkey::new_unique();
+
+    let payer = env.new_funded();
+    assert_eq!(
+        env.process(&commission.initialize_admin(&payer, vec![token(CommissionToken::FT(mint), 10), token(CommissionToken::FT(mint), 20)])),
+        Err(LibError::AlreadyInUse.into()),
+    );
+    assert!(!env.exists(&commission.admin));
+}
+
+#[test]
+fn added_token_keeps_order() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let (first, second, third) = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]), Pubkey::new_from_array([3; 32]));
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(first), 10),
+        token(CommissionToken::FT(third), 30),
+    ]);
+
+    env.process(&add_fee_token(&env, &bridge, &commission, token(CommissionToken::FT(second), 20))).unwrap();
+    let list = tokens(&commission, &env);
+    assert!(is_sorted(&list));
+    assert_eq!(list, vec![CommissionToken::Native, CommissionToken::FT(first), CommissionToken::FT(second), CommissionToken::FT(third)]);
+
+    assert_eq!(
+        env.process(&add_fee_token(&env, &bridge, &commission, token(CommissionToken::FT(second), 40))),
+        Err(LibError::AlreadyInUse.into()),
+    );
+    assert_eq!(commission.state(&env).acceptable_tokens[2].amount, 20);
+}
+
+#[test]
+fn lookup_finds_listed_tokens_only() {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let mints: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    for mint in &mints {
+        env.create_mint(mint, &Pubkey::new_unique(), 6, None);
+    }
+    // Every second mint is acceptable, with the amount matching its index
+    let commission = Commission::new(
+        &mut env, &bridge, mints.iter().enumerate().step_by(2).map(|(i, mint)| token(CommissionToken::FT(*mint), i as u64 + 1)).collect(),
+    );
+
+    let owner = env.new_funded();
+    for (i, mint) in mints.iter().enumerate() {
+        let owner_associated = env.create_associated_token_account(mint, &owner, 100);
+        let result = env.process(&charge_commission_ft(
+            commission.program_id, commission.admin, bridge.admin, owner, *mint, CommissionToken::FT(*mint), TokenType::FT, 1, i as u64,
+        ));
+
+        if i % 2 == 0 {
+            result.unwrap();
+            assert_eq!(env.token_balance(&owner_associated), 100 - (i as u64 + 1));
+            assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, mint)), i as u64 + 1);
+        } else {
+            assert_eq!(result, Err(LibError::NotAcceptable.into()));
+        }
+    }
+
+    let state = commission.state(&env);
+    assert!(state.acceptable_tokens.iter().all(|t| t.collected == t.amount));
+}
@@ -0,0 +1,60 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use common::{Bridge, Env};
+use fixture::{token, withdraw_ft, Commission};
+use lib::instructions::commission::charge_commission_ft;
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// Commission collected 100 fee tokens in two charges
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![token(CommissionToken::FT(fee_mint), 50)]);
+
+    let owner = env.new_funded();
+    env.create_associated_token_account(&fee_mint, &owner, 100);
+    for nonce in 0..2 {
+        env.process(&charge_commission_ft(
+            commission.program_id, commission.admin, bridge.admin, owner, fee_mint, CommissionToken::FT(fee_mint), TokenType::FT, 1, nonce,
+        )).unwrap();
+    }
+    (env, bridge, commission, fee_mint)
+}
+
+#[test]
+fn withdraw_creates_missing_receiver_associated_account() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let receiver = env.new_funded();
+    let receiver_associated = get_associated_token_address(&receiver, &fee_mint);
+
+    let instruction = withdraw_ft(&env, &bridge, &commission, &receiver, &fee_mint, token(CommissionToken::FT(fee_mint), 50), 60);
+    assert_eq!(instruction.accounts[8].pubkey, fee_mint);
+    env.process(&instruction).unwrap();
+
+    assert_eq!(env.token_account(&receiver_associated).owner, receiver);
+    assert_eq!(env.token_balance(&receiver_associated), 60);
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 40);
+}
+
+#[test]
+fn withdraw_to_existing_receiver_associated_account() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let receiver = env.new_funded();
+    let receiver_associated = env.create_associated_token_account(&fee_mint, &receiver, 5);
+
+    let created = withdraw_ft(&env, &bridge, &commission, &Pubkey::new_unique(), &fee_mint, token(CommissionToken::FT(fee_mint), 50), 60);
+    let instruction = withdraw_ft(&env, &bridge, &commission, &receiver, &fee_mint, token(CommissionToken::FT(fee_mint), 50), 60);
+    // Same layout as the withdrawal that creates the receiver associated account
+    assert_eq!(instruction.accounts.len(), created.accounts.len());
+    env.process(&instruction).unwrap();
+
+    assert_eq!(env.token_balance(&receiver_associated), 65);
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 40);
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 1);
+}
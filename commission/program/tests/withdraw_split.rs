@@ -0,0 +1,121 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+mod fixture;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Bridge, Env};
+use fixture::{token, withdraw_split_ft, withdraw_split_native, Commission};
+use lib::error::LibError;
+use lib::instructions::commission::{charge_commission_ft, charge_commission_native, CommissionInstruction};
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+// Commission collected 2000 lamports and 100 fee tokens
+fn setup() -> (Env, Bridge, Commission, Pubkey) {
+    let mut env = Env::new();
+    let bridge = Bridge::new(&mut env);
+    let fee_mint = Pubkey::new_unique();
+    env.create_mint(&fee_mint, &Pubkey::new_unique(), 6, None);
+    let commission = Commission::new(&mut env, &bridge, vec![
+        token(CommissionToken::Native, 1000),
+        token(CommissionToken::FT(fee_mint), 50),
+    ]);
+
+    let owner = env.new_funded();
+    env.create_associated_token_account(&fee_mint, &owner, 100);
+    for nonce in 0..2 {
+        env.process(&charge_commission_native(
+            commission.program_id, commission.admin, bridge.admin, owner, CommissionToken::Native, TokenType::Native, 1, nonce,
+        )).unwrap();
+        env.process(&charge_commission_ft(
+            commission.program_id, commission.admin, bridge.admin, owner, fee_mint, CommissionToken::FT(fee_mint), TokenType::FT, 1, 2 + nonce,
+        )).unwrap();
+    }
+    (env, bridge, commission, fee_mint)
+}
+
+#[test]
+fn native_withdraw_is_split_70_30() {
+    let (mut env, bridge, commission, _) = setup();
+    let receiver = env.new_funded();
+    let (treasury, dao) = (env.new_funded(), env.new_funded());
+    let (treasury_lamports, dao_lamports, receiver_lamports) = (env.lamports(&treasury), env.lamports(&dao), env.lamports(&receiver));
+
+    env.process(&withdraw_split_native(
+        &env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1500, vec![(treasury, 7000), (dao, 3000)],
+    )).unwrap();
+    assert_eq!(env.lamports(&treasury), treasury_lamports + 1050);
+    assert_eq!(env.lamports(&dao), dao_lamports + 450);
+    assert_eq!(env.lamports(&receiver), receiver_lamports);
+}
+
+#[test]
+fn ft_withdraw_is_split_70_30() {
+    let (mut env, bridge, commission, fee_mint) = setup();
+    let receiver = env.new_funded();
+    let (treasury, dao) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    env.process(&withdraw_split_ft(
+        &env, &bridge, &commission, &receiver, &fee_mint, token(CommissionToken::FT(fee_mint), 50), 99, vec![(treasury, 7000), (dao, 3000)],
+    )).unwrap();
+    // The last receiver takes the rounding remainder
+    assert_eq!(env.token_balance(&get_associated_token_address(&treasury, &fee_mint)), 69);
+    assert_eq!(env.token_balance(&get_associated_token_address(&dao, &fee_mint)), 30);
+    assert_eq!(env.token_balance(&get_associated_token_address(&commission.admin, &fee_mint)), 1);
+}
+
+#[test]
+fn split_not_summing_to_total_is_rejected() {
+    let (mut env, bridge, commission, _) = setup();
+    let receiver = env.new_funded();
+    let (treasury, dao) = (env.new_funded(), env.new_funded());
+
+    for split in [vec![(treasury, 7000), (dao, 2000)], vec![(treasury, 7000), (dao, 3001)], vec![(treasury, 10_000), (dao, 0)]] {
+        assert_eq!(
+            env.process(&withdraw_split_native(&env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1500, split)),
+            Err(LibError::WrongArgsSize.into()),
+        );
+    }
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 0);
+}
+
+#[test]
+fn unsigned_split_is_rejected() {
+    let (mut env, bridge, commission, _) = setup();
+    let receiver = env.new_funded();
+    let (treasury, dao) = (env.new_funded(), env.new_funded());
+
+    // Split receivers are bound into the signed content
+    let attacker = env.new_funded();
+    let mut instruction = withdraw_split_native(
+        &env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 1500, vec![(treasury, 7000), (dao, 3000)],
+    );
+    let mut args = match CommissionInstruction::try_from_slice(&instruction.data).unwrap() {
+        CommissionInstruction::Withdraw(args) => args,
+        _ => unreachable!(),
+    };
+    args.split[0].0 = attacker;
+    instruction.data = CommissionInstruction::Withdraw(args).try_to_vec().unwrap();
+    instruction.accounts[5].pubkey = attacker;
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+}
+
+#[test]
+fn unsigned_amount_is_rejected() {
+    let (mut env, bridge, commission, _) = setup();
+    let receiver = env.new_funded();
+
+    // Withdraw amount is the operation data of the signed content
+    let mut instruction = withdraw_split_native(
+        &env, &bridge, &commission, &receiver, token(CommissionToken::Native, 1000), 500, vec![],
+    );
+    let mut args = match CommissionInstruction::try_from_slice(&instruction.data).unwrap() {
+        CommissionInstruction::Withdraw(args) => args,
+        _ => unreachable!(),
+    };
+    args.withdraw_amount = 1500;
+    instruction.data = CommissionInstruction::Withdraw(args).try_to_vec().unwrap();
+    assert_eq!(env.process(&instruction), Err(LibError::WrongSignature.into()));
+    assert_eq!(commission.state(&env).withdraw_token_nonce, 0);
+}
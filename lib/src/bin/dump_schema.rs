@@ -0,0 +1,26 @@
+//! Writes the Borsh schema containers of the bridge, commission and upgrade instructions:
+//! `<dir>/<program>_instruction.borsh` with the Borsh-encoded `BorshSchemaContainer`, and prints them in the readable form.
+//!
+//! cargo run -p solana-bridge-lib --features schema --bin dump_schema -- <dir>
+
+use borsh::schema::BorshSchemaContainer;
+use borsh::{BorshSchema, BorshSerialize};
+use lib::instructions::bridge::BridgeInstruction;
+use lib::instructions::commission::CommissionInstruction;
+use lib::instructions::upgrade::UpgradeInstruction;
+
+fn main() {
+    let dir = std::env::args().nth(1).unwrap_or_else(|| String::from("."));
+
+    let containers: [(&str, BorshSchemaContainer); 3] = [
+        ("bridge", BridgeInstruction::schema_container()),
+        ("commission", CommissionInstruction::schema_container()),
+        ("upgrade", UpgradeInstruction::schema_container()),
+    ];
+
+    for (name, container) in containers.iter() {
+        let path = std::path::Path::new(&dir).join(format!("{}_instruction.borsh", name));
+        std::fs::write(&path, container.try_to_vec().unwrap()).unwrap();
+        println!("{}: {:#?}", path.display(), container);
+    }
+}
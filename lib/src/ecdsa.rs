@@ -1,21 +1,51 @@
-use solana_program::secp256k1_recover::{SECP256K1_PUBLIC_KEY_LENGTH, secp256k1_recover, Secp256k1Pubkey};
+use solana_program::secp256k1_recover::{SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH, secp256k1_recover, Secp256k1Pubkey};
 use solana_program::{
     entrypoint::ProgramResult, hash,
-    msg,
 };
 use solana_program::program_error::ProgramError;
 use crate::error::LibError;
 
-pub fn verify_ecdsa_signature(hash: &[u8], sig: &[u8], reid: u8, target_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> ProgramResult {
-    let recovered_key = secp256k1_recover(hash, reid, sig);
-    if recovered_key.is_err() {
-        return ProgramResult::Err(LibError::InvalidSignature.into());
+// secp256k1 curve order / 2 (big-endian), signatures with the bigger S are malleable copies of the low-S ones
+pub const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+// Ethereum address is the last 20 bytes of keccak256(public key)
+pub const ETH_ADDRESS_LENGTH: usize = 20;
+
+/// Derives the Ethereum address from the 64-byte public key: `keccak256(public_key)[12..]`.
+pub fn eth_address(public_key: &[u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> [u8; ETH_ADDRESS_LENGTH] {
+    let hash = solana_program::keccak::hash(public_key.as_slice()).to_bytes();
+    let mut address = [0u8; ETH_ADDRESS_LENGTH];
+    address.copy_from_slice(&hash[32 - ETH_ADDRESS_LENGTH..]);
+    address
+}
+
+/// Checks that S part of the `r | s` signature is not above the half of the curve order.
+pub fn is_low_s(sig: &[u8]) -> bool {
+    sig.len() == SECP256K1_SIGNATURE_LENGTH && sig[32..] <= SECP256K1_HALF_ORDER[..]
+}
+
+fn recover_key(hash: &[u8], sig: &[u8], reid: u8) -> Result<[u8; SECP256K1_PUBLIC_KEY_LENGTH], ProgramError> {
+    if !is_low_s(sig) {
+        return Err(LibError::InvalidSignature.into());
     }
 
-    let key =  recovered_key.unwrap().0;
+    match secp256k1_recover(hash, reid, sig) {
+        Ok(key) => Ok(key.0),
+        Err(_) => Err(LibError::InvalidSignature.into()),
+    }
+}
 
-    msg!("Recovered public key from signature: {}", bs58::encode(key.as_ref()).into_string().as_str());
-    msg!("Required public key: {}", bs58::encode(target_key.as_ref()).into_string().as_str());
+pub fn verify_ecdsa_signature(hash: &[u8], sig: &[u8], reid: u8, target_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> ProgramResult {
+    let key = recover_key(hash, sig, reid)?;
+
+    #[cfg(feature = "debug-logs")]
+    {
+        solana_program::msg!("Recovered public key from signature: {}", bs58::encode(key.as_ref()).into_string().as_str());
+        solana_program::msg!("Required public key: {}", bs58::encode(target_key.as_ref()).into_string().as_str());
+    }
 
     if key != target_key {
         return ProgramResult::Err(LibError::WrongSignature.into());
@@ -24,3 +54,28 @@ pub fn verify_ecdsa_signature(hash: &[u8], sig: &[u8], reid: u8, target_key: [u8
     Ok(())
 }
 
+/// Same as `verify_ecdsa_signature`, but compares the Ethereum address of the recovered key
+/// for the signers that distribute the address instead of the full public key.
+pub fn verify_ecdsa_signature_eth_address(hash: &[u8], sig: &[u8], reid: u8, target_address: [u8; ETH_ADDRESS_LENGTH]) -> ProgramResult {
+    let key = recover_key(hash, sig, reid)?;
+
+    if eth_address(&key) != target_address {
+        return ProgramResult::Err(LibError::WrongSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Verifies the signature against the Ethereum address if it is set, otherwise against the full public key.
+pub fn verify_ecdsa_signer(
+    hash: &[u8],
+    sig: &[u8],
+    reid: u8,
+    public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    eth_address: Option<[u8; ETH_ADDRESS_LENGTH]>,
+) -> ProgramResult {
+    match eth_address {
+        Some(address) => verify_ecdsa_signature_eth_address(hash, sig, reid, address),
+        None => verify_ecdsa_signature(hash, sig, reid, public_key),
+    }
+}
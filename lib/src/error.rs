@@ -1,7 +1,8 @@
 //! Error types
 
+use borsh::{BorshDeserialize, BorshSerialize};
 use num_derive::FromPrimitive;
-use solana_program::{decode_error::DecodeError, msg, program_error::{PrintProgramError, ProgramError}};
+use solana_program::{decode_error::DecodeError, msg, program::set_return_data, program_error::{PrintProgramError, ProgramError}};
 use thiserror::Error;
 
 /// Errors that may be returned by the Token program.
@@ -100,6 +101,78 @@ pub enum LibError {
     /// 30 Token is not supported yet
     #[error("Not supported")]
     NotSupported,
+    /// 31 Deposit amount is below the configured minimum
+    #[error("Below minimum")]
+    BelowMinimum,
+    /// 32 Bridge admin is not the mint authority
+    #[error("Wrong mint authority")]
+    WrongMintAuthority,
+    /// 33 Withdrawal delay has not passed since the announcement
+    #[error("Withdraw delayed")]
+    WithdrawDelayed,
+    /// 34 Upgrade buffer authority is not the upgrade admin
+    #[error("Wrong buffer authority")]
+    WrongBufferAuthority,
+    /// 35 Merkle proof is empty or too long
+    #[error("Wrong Merkle proof")]
+    WrongMerkleProof,
+    /// 36 Instruction received less accounts than expected
+    #[error("Not enough accounts")]
+    NotEnoughAccounts,
+    /// 37 Deposit target network is not in the supported networks list
+    #[error("Unsupported network")]
+    UnsupportedNetwork,
+    /// 38 Token amount calculation overflowed
+    #[error("Amount overflow")]
+    AmountOverflow,
+    /// 39 Deposits or withdrawals of the token are disabled
+    #[error("Token disabled")]
+    TokenDisabled,
+    /// 40 Unsupported version of the state account layout
+    #[error("Unsupported state version")]
+    UnsupportedVersion,
+    /// 41 Mint supply is not zero
+    #[error("Mint supply is not zero")]
+    NonZeroSupply,
+    /// 42 Bridge is not approved as the collection authority
+    #[error("Bridge is not the collection authority")]
+    NotCollectionAuthority,
+    /// 43 Required account signature was not provided
+    #[error("Missing signer")]
+    MissingSigner,
+    /// 44 Minting would exceed the mint supply cap
+    #[error("Max supply exceeded")]
+    MaxSupplyExceeded,
+    /// 45 Token account is frozen
+    #[error("Account frozen")]
+    AccountFrozen,
+    /// 46 Withdrawal amount exceeds the single withdrawal limit
+    #[error("Rate limited")]
+    RateLimited,
+    /// 47 Instruction before the deposit is not ChargeCommission
+    #[error("Not a commission charge")]
+    NotACommissionCharge,
+    /// 48 Receiver address does not match the address format of the target network
+    #[error("Wrong address format")]
+    WrongAddressFormat,
+    /// 49 Amount can not be represented with the target decimals
+    #[error("Wrong amount precision")]
+    WrongAmountPrecision,
+    /// 50 Timelock of the backup key promotion has not passed since the request
+    #[error("Promotion timelocked")]
+    PromotionTimelocked,
+    /// 51 Backup key promotion was not requested
+    #[error("No pending promotion")]
+    NoPendingPromotion,
+    /// 52 Collection of the token is not verified
+    #[error("Unverified collection")]
+    UnverifiedCollection,
+    /// 53 Announced withdrawal was vetoed
+    #[error("Withdraw vetoed")]
+    WithdrawVetoed,
+    /// 54 Authorized root was revoked or signed by the replaced admin key
+    #[error("Root revoked")]
+    RootRevoked,
 }
 
 
@@ -109,6 +182,37 @@ impl From<LibError> for ProgramError {
     }
 }
 
+// Program codes of the ErrorInfo
+pub const BRIDGE_PROGRAM_CODE: u8 = 0;
+pub const COMMISSION_PROGRAM_CODE: u8 = 1;
+pub const UPGRADE_PROGRAM_CODE: u8 = 2;
+
+/// Set by the program entrypoints as the return data of the failed instruction,
+/// so clients can tell which program of the bridge returned the error.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ErrorInfo {
+    // One of the *_PROGRAM_CODE constants
+    pub program: u8,
+    // LibError code for the custom errors, builtin ProgramError code otherwise
+    pub code: u32,
+    pub builtin: bool,
+}
+
+impl ErrorInfo {
+    pub fn new(program: u8, error: &ProgramError) -> Self {
+        match error {
+            ProgramError::Custom(code) => ErrorInfo { program, code: *code, builtin: false },
+            // Builtin errors are encoded in the upper 32 bits
+            _ => ErrorInfo { program, code: (u64::from(error.clone()) >> 32) as u32, builtin: true },
+        }
+    }
+}
+
+pub fn set_error_info(program: u8, error: &ProgramError) {
+    set_return_data(&ErrorInfo::new(program, error).try_to_vec().unwrap());
+}
+
 impl PrintProgramError for LibError {
     fn print<E>(&self) {
         msg!(&self.to_string());
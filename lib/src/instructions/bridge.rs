@@ -11,25 +11,49 @@ use spl_associated_token_account::get_associated_token_address;
 use crate::instructions::InstructionValidation;
 use std::mem::size_of;
 use crate::error::LibError;
-use crate::TokenType;
+use crate::{CommissionToken, Network, NetworkFormat, TokenType};
+use crate::merkle::{HashAlgo, MerkleProof, MAX_MERKLE_DEPTH};
+use crate::ecdsa::ETH_ADDRESS_LENGTH;
 
+#[cfg(not(feature = "large-addresses"))]
 pub const MAX_NETWORKS_SIZE: usize = 20;
+#[cfg(not(feature = "large-addresses"))]
 pub const MAX_ADDRESS_SIZE: usize = 100;
+
+// Limits for the deployments bridging to the networks with long names or addresses
+#[cfg(feature = "large-addresses")]
+pub const MAX_NETWORKS_SIZE: usize = 64;
+#[cfg(feature = "large-addresses")]
+pub const MAX_ADDRESS_SIZE: usize = 256;
 pub const MAX_TOKEN_ID_SIZE: usize = 100;
+// Max count of networks in the supported networks list
+pub const MAX_SUPPORTED_NETWORKS: usize = 32;
+// Max count of NFTs deposited by one DepositNFTBatch instruction, limited by compute units
+pub const MAX_NFT_BATCH_SIZE: usize = 5;
 pub const MAX_TX_SIZE: usize = 100;
+// Max capacity of the StoredRoots ring buffer, limited by the account realloc size per instruction
+pub const MAX_STORED_ROOTS: usize = 256;
+// Max decimals of the fungible token minted by the withdrawal, NFT mints always have zero decimals
+pub const MAX_FT_DECIMALS: u8 = 9;
+// Withdrawal fee is set in basis points, 10000 is the whole amount
+pub const MAX_WITHDRAW_FEE_BPS: u16 = 10000;
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct InitializeAdminArgs {
     // ECDSA public key
     pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     // Admin account seeds (also public)
     pub seeds: [u8; 32],
     pub commission_program: Pubkey,
+    // Hash function of the withdrawal leaves and Merkle trees
+    pub hash_algo: HashAlgo,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct TransferOwnershipArgs {
     // New ECDSA public key
     pub new_public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
@@ -42,65 +66,150 @@ pub struct TransferOwnershipArgs {
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct DepositBumps {
+    // Bump of the SupportedNetworks account
+    pub networks: u8,
+    // Bump of the TokenConfig account (FT and NFT only)
+    pub token_config: u8,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct DepositNativeArgs {
     pub amount: u64,
-    pub network_to: String,
+    pub network_to: Network,
+    pub receiver_address: String,
+    pub seeds: [u8; 32],
+    pub bundle_data: Option<Vec<u8>>,
+    pub bundle_seed: Option<[u8; 32]>,
+    // Bumps of the existing config accounts, saves the address derivation (see DepositBumps)
+    pub bumps: Option<DepositBumps>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct DepositNativeWithCommissionArgs {
+    pub amount: u64,
+    pub network_to: Network,
     pub receiver_address: String,
     pub seeds: [u8; 32],
     pub bundle_data: Option<Vec<u8>>,
     pub bundle_seed: Option<[u8; 32]>,
+    // Bumps of the existing config accounts, saves the address derivation (see DepositBumps)
+    pub bumps: Option<DepositBumps>,
+    // Token to pay the commission in, passed to the ChargeCommission instruction
+    pub commission_token: CommissionToken,
+    pub deposit_nonce: u64,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct DepositFTArgs {
     pub amount: u64,
-    pub network_to: String,
+    pub network_to: Network,
     pub receiver_address: String,
     pub seeds: [u8; 32],
     pub token_seed: Option<[u8; 32]>,
     pub bundle_data: Option<Vec<u8>>,
     pub bundle_seed: Option<[u8; 32]>,
+    // Bumps of the existing config accounts, saves the address derivation (see DepositBumps)
+    pub bumps: Option<DepositBumps>,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct DepositNFTArgs {
-    pub network_to: String,
+    pub network_to: Network,
     pub receiver_address: String,
     pub seeds: [u8; 32],
     pub token_seed: Option<[u8; 32]>,
     pub bundle_data: Option<Vec<u8>>,
     pub bundle_seed: Option<[u8; 32]>,
+    // Bumps of the existing config accounts, saves the address derivation (see DepositBumps)
+    pub bumps: Option<DepositBumps>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct DepositNFTBatchArgs {
+    pub network_to: Network,
+    pub receiver_address: String,
+    pub seeds: [u8; 32],
+    pub mints: Vec<Pubkey>,
+    // Token seed for every mint, the token is burned if the seed is provided
+    pub token_seeds: Vec<Option<[u8; 32]>>,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct SignedMetadata {
     pub name: String,
     pub symbol: String,
     pub uri: String,
     pub decimals: u8,
+    // Supply cap of the FT mint created by the withdrawal, unlimited if None
+    pub max_supply: Option<u64>,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct WithdrawArgs {
     // Default: hash of tx | event_id | network_from
     pub origin: [u8; 32],
+    // Total signed amount
     pub amount: u64,
+    // Amount to withdraw in the current call (FT only), remaining amount if None
+    pub claim_amount: Option<u64>,
     // Signature for the Merkle root
     pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     pub recovery_id: u8,
     // Merkle path
-    pub path: Vec<[u8; 32]>,
+    pub path: MerkleProof,
     pub seeds: [u8; 32],
     pub token_seed: Option<[u8; 32]>,
     pub signed_meta: Option<SignedMetadata>,
+    // EIP-712 domain separator if the leaf is signed as typed data, raw content hash is used if None
+    pub eip712_domain_separator: Option<[u8; 32]>,
+    // Fail instead of creating the missing owner token associated account (FT and NFT only)
+    pub strict_ata: bool,
+    // Structured origin, checked against `origin` if provided (raw origin is accepted if None)
+    pub origin_preimage: Option<OriginPreimage>,
+    // Leaf is built from mint, amount and decimals only, for the tokens without metadata (FT only)
+    pub no_metadata: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct OriginPreimage {
+    // Deposit transaction hash on the source network
+    pub tx_hash: Vec<u8>,
+    pub event_id: String,
+    pub network_from: String,
+}
+
+impl OriginPreimage {
+    /// Default origin scheme: keccak256(tx_hash | event_id | network_from)
+    pub fn hash(&self) -> [u8; 32] {
+        solana_program::keccak::hashv(&[
+            self.tx_hash.as_slice(),
+            self.event_id.as_bytes(),
+            self.network_from.as_bytes(),
+        ]).to_bytes()
+    }
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct MintCollectionArgs {
     pub data: SignedMetadata,
     pub seeds: [u8; 32],
@@ -115,476 +224,2468 @@ pub struct MintNFTArgs {
     pub verify: bool,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Clone)]
-pub enum BridgeInstruction {
-    /// Initialize new BridgeAdmin that will store ECDSA publick key
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[writable]` The BridgeAdmin account to initialize
-    ///   1. `[writable,signer]` The fee payer
-    ///   2. `[]` System program
-    ///   3. `[]` Rent sysvar
-    InitializeAdmin(InitializeAdminArgs),
-
-    /// Change admin in BridgeAdmin.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[writable]` The BridgeAdmin account
-    ///
-    TransferOwnership(TransferOwnershipArgs),
-
-    /// Make SOL deposit on bridge.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[writable]` The BridgeAdmin account
-    ///   1. `[writable,signer]` The owner account
-    ///   2. `[]` System program
-    ///   3. `[]` Rent sysvar
-    ///   5. `[]` Sysvar
-    DepositNative(DepositNativeArgs),
-
-    /// Make FT deposit on bridge.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[]` The BridgeAdmin account
-    ///   1. `[writable]` The token mint account
-    ///   2. `[writable]` The owner token associated account
-    ///   3. `[writable]` The bridge token account
-    ///   4. `[writable,signer]` The token owner account
-    ///   5. `[]` Token program id
-    ///   6. `[]` System program
-    ///   7. `[]` Rent sysvar
-    ///   8. `[]` Sysvar
-    ///   9. `[]` Associated token program
-    DepositFT(DepositFTArgs),
-
-    /// Make NFT deposit on bridge.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[]` The BridgeAdmin account
-    ///   1. `[writable]` The token mint account
-    ///   2. `[writable]` The owner token associated account
-    ///   3. `[writable]` The bridge token account
-    ///   4. `[writable,signer]` The token owner account
-    ///   5. `[]` Token program id
-    ///   6. `[]` System program
-    ///   7. `[]` Rent sysvar
-    ///   8. `[]` Sysvar
-    ///   9. `[]` Associated token program
-    DepositNFT(DepositNFTArgs),
-
-    /// Make NFT withdraw from bridge.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[writable]` The BridgeAdmin account
-    ///   1. `[writable,signer]` The owner account
-    ///   2. `[writable]` The new Withdraw account
-    ///   3. `[]` System program
-    ///   4. `[]` Rent sysvar
-    WithdrawNative(WithdrawArgs),
-
-    /// Make FT withdraw from bridge.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[]` The BridgeAdmin account
-    ///   1. `[writable]` The token mint account
-    ///   2. `[writable]` The token metadata account
-    ///   3. `[writable,signer]` The owner account
-    ///   4. `[writable]` The owner token associated account
-    ///   5. `[writable]` The bridge token account
-    ///   6. `[writable]` The new Withdraw account
-    ///   7. `[]` Token program id
-    ///   8. `[]` System program
-    ///   9. `[]` Rent sysvar
-    ///   10. `[]` Metadata program
-    ///   11. `[]` Associated token program
-    WithdrawFT(WithdrawArgs),
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetMinDepositArgs {
+    // Lamports of the native deposits, fungible token minimums are set by SetTokenConfig
+    pub min_deposit: u64,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
 
-    /// Make NFT withdraw from bridge.
-    ///
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[]` The BridgeAdmin account
-    ///   1. `[writable]` The token mint account
-    ///   2. `[writable]` The token metadata account
-    ///   3. `[writable,signer]` The owner account
-    ///   4. `[writable]` The owner token associated account
-    ///   5. `[writable]` The bridge token account
-    ///   6. `[writable]` The new Withdraw account
-    ///   7. `[]` Token program id
-    ///   8. `[]` System program
-    ///   9. `[]` Rent sysvar
-    ///   10. `[]` Metadata program
-    ///   11. `[]` Associated token program
-    ///   12. `[]` Collection metadata account (Optional)
-    WithdrawNFT(WithdrawArgs),
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct InitBridgeTokenAccountArgs {
+    pub seeds: [u8; 32],
+}
 
-    /// Create collection NFT owned by brisge
-    /// Accounts expected by this instruction:
-    ///
-    ///   0. `[writable]` The BridgeAdmin account
-    ///   1. `[writable,signed]` The token mint account
-    ///   2. `[writable]` The bridge token account
-    ///   3. `[writable]` The new metadata account
-    ///   4. `[writable,signer]` The payer account
-    ///   5. `[]` Token program id
-    ///   6. `[]` Token metadata program id
-    ///   7. `[]` Rent sysvar
-    ///   8. `[]` System program
-    ///   9. `[]` Associated token program
-    MintCollection(MintCollectionArgs),
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetStrictTokenAccountsArgs {
+    pub strict: bool,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetSponsorTokenAccountsArgs {
+    pub sponsor: bool,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
 
-impl InstructionValidation for DepositNativeArgs {
-    fn validate(&self) -> ProgramResult {
-        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE ||
-            self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE || self.amount <= 0 {
-            return Err(LibError::WrongArgsSize.into());
-        }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetNetworkFormatsArgs {
+    // Receiver address formats replacing the stored ones, networks without format accept any address
+    pub formats: Vec<NetworkFormat>,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
 
-        Ok(())
-    }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetMaxSingleWithdrawArgs {
+    // Zero disables the limit
+    pub max_single_withdraw: u64,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
 }
 
-impl InstructionValidation for DepositFTArgs {
-    fn validate(&self) -> ProgramResult {
-        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE ||
-            self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE || self.amount <= 0 {
-            return Err(LibError::WrongArgsSize.into());
-        }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetBackupKeyArgs {
+    // Public key that can replace the admin key after the timelock, None removes it
+    pub backup_key: Option<[u8; SECP256K1_PUBLIC_KEY_LENGTH]>,
+    // Delay in slots between the promotion request and confirmation
+    pub promote_timelock: u64,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
 
-        Ok(())
-    }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct PromoteBackupArgs {
+    // false requests the promotion, true confirms the requested one after the timelock
+    pub confirm: bool,
+    // Backup key signature of keccak256(BRIDGE_DOMAIN | "promote_backup" | bridge admin | nonce | confirm)
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    pub seeds: [u8; 32],
 }
 
-impl InstructionValidation for DepositNFTArgs {
-    fn validate(&self) -> ProgramResult {
-        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE || self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE {
-            return Err(LibError::WrongArgsSize.into());
-        }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct CancelPromotionArgs {
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
 
-        Ok(())
-    }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetWithdrawDelayArgs {
+    // Delay in slots between the announcement and the withdrawal
+    pub delay: u64,
+    // Withdrawals with the bigger amount should be announced first
+    pub threshold: u64,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
 }
 
-impl InstructionValidation for WithdrawArgs {
-    fn validate(&self) -> ProgramResult {
-        if self.amount <= 0 {
-            return Err(LibError::WrongArgsSize.into());
-        }
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetWithdrawFeeArgs {
+    // Fee in basis points kept by the bridge from every native and fungible token withdrawal
+    pub withdraw_fee_bps: u16,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetEthAddressArgs {
+    // Ethereum address of the signer, None switches back to the full public key verification
+    pub eth_address: Option<[u8; ETH_ADDRESS_LENGTH]>,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct AnnounceWithdrawArgs {
+    // Hash of deposit tx info. See spec in core for more information.
+    pub origin: [u8; 32],
+    pub amount: u64,
+    // Leaf hash of the withdraw content, the same that is verified by the withdrawal
+    pub leaf: [u8; 32],
+    // Signature for the Merkle root of the withdrawal
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path from the leaf
+    pub path: MerkleProof,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct VetoWithdrawArgs {
+    // Signature for the Merkle root, the operation data is the announcement address
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct UpdateTokenMetadataArgs {
+    pub token_seed: [u8; 32],
+    // Decimals are ignored, only name, symbol and uri are updated
+    pub data: SignedMetadata,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct EmergencyWithdrawArgs {
+    // Mints of the bridge token accounts to sweep
+    pub mints: Vec<Pubkey>,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetNetworksArgs {
+    // Networks supported as the deposit target
+    pub networks: Vec<Network>,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetTokenConfigArgs {
+    pub mint: Pubkey,
+    pub deposit_enabled: bool,
+    pub withdraw_enabled: bool,
+    // Decimals of the deposit amount in the cross-chain message, None keeps the mint decimals
+    pub target_decimals: Option<u8>,
+    // Minimal deposit amount in the mint base units, zero disables the check
+    pub min_deposit: u64,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ReclaimMintArgs {
+    // Seed of the bridged mint
+    pub token_seed: [u8; 32],
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SweepDustArgs {
+    // Mints of the bridge token accounts to close if empty
+    pub mints: Vec<Pubkey>,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct RegisterCollectionDelegateArgs {
+    // Mint of the collection that approved bridge as the collection authority
+    pub collection_mint: Pubkey,
+    // Signature for the Merkle root
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct MigrateAdminArgs {
+    pub commission_program: Pubkey,
+    // Signature of keccak256(BRIDGE_DOMAIN | bridge admin | commission_program) by the stored public key
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct AuthorizeOriginsArgs {
+    // Merkle root of the withdraw contents that can be claimed without the signature
+    pub root: [u8; 32],
+    // Signature of keccak256(BRIDGE_DOMAIN | "authorized_origins" | bridge admin | root)
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ClaimAuthorizedArgs {
+    pub token_type: TokenType,
+    // Withdraw arguments, the path should lead to the authorized root, signature is not checked
+    pub withdraw: WithdrawArgs,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct RevokeRootArgs {
+    // Authorized Merkle root, the operation data of the signed content
+    pub root: [u8; 32],
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    // Merkle path
+    pub path: Vec<[u8; 32]>,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct StoreRootsArgs {
+    // Merkle roots of the withdraw contents, the oldest stored roots are evicted when the buffer is full
+    pub roots: Vec<[u8; 32]>,
+    // Capacity of the ring buffer, the newest roots are kept when it changes
+    pub capacity: u16,
+    // Signature of keccak256(BRIDGE_DOMAIN | "stored_roots" | bridge admin | capacity | batches | keccak256(roots)),
+    // where batches is the count of the batches stored before
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+    pub seeds: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ClaimStoredArgs {
+    pub token_type: TokenType,
+    // One of the roots in the StoredRoots account
+    pub root: [u8; 32],
+    // Withdraw arguments, the path should lead to the root, signature is not checked
+    pub withdraw: WithdrawArgs,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ValidateWithdrawArgs {
+    pub token_type: TokenType,
+    pub withdraw: WithdrawArgs,
+}
+
+// Returned by ValidateWithdraw instruction via return data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct WithdrawValidation {
+    pub origin: [u8; 32],
+    pub valid: bool,
+    // Program error code if the withdrawal would fail
+    pub error: Option<u64>,
+}
+
+// Returned by withdraw instructions via return data, so CPI callers can confirm the delivered amount
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct WithdrawReceipt {
+    pub origin: [u8; 32],
+    // Amount delivered to the receiver, the withdrawal fee is excluded
+    pub amount: u64,
+    // Withdrawal fee kept by the bridge
+    pub fee: u64,
+    pub mint: Option<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub enum BridgeInstruction {
+    /// Initialize new BridgeAdmin that will store ECDSA publick key
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account to initialize
+    ///   1. `[writable,signer]` The fee payer
+    ///   2. `[]` System program
+    ///   3. `[]` Rent sysvar
+    InitializeAdmin(InitializeAdminArgs),
+
+    /// Change admin in BridgeAdmin.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///
+    TransferOwnership(TransferOwnershipArgs),
+
+    /// Make SOL deposit on bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable,signer]` The owner account
+    ///   2. `[]` System program
+    ///   3. `[]` Rent sysvar
+    ///   4. `[]` Sysvar
+    ///   5. `[]` The SupportedNetworks account
+    DepositNative(DepositNativeArgs),
+
+    /// Make FT deposit on bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account (pays the bridge token account rent in the sponsored mode)
+    ///   1. `[writable]` The token mint account
+    ///   2. `[writable]` The owner token associated account
+    ///   3. `[writable]` The bridge token account
+    ///   4. `[writable,signer]` The token owner account
+    ///   5. `[]` Token program id
+    ///   6. `[]` System program
+    ///   7. `[]` Rent sysvar
+    ///   8. `[]` Sysvar
+    ///   9. `[]` Associated token program
+    ///   10. `[]` The SupportedNetworks account
+    ///   11. `[]` The TokenConfig account
+    DepositFT(DepositFTArgs),
+
+    /// Make NFT deposit on bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account (pays the bridge token account rent in the sponsored mode)
+    ///   1. `[writable]` The token mint account
+    ///   2. `[writable]` The owner token associated account
+    ///   3. `[writable]` The bridge token account
+    ///   4. `[writable,signer]` The token owner account
+    ///   5. `[]` Token program id
+    ///   6. `[]` System program
+    ///   7. `[]` Rent sysvar
+    ///   8. `[]` Sysvar
+    ///   9. `[]` Associated token program
+    ///   10. `[]` The SupportedNetworks account
+    ///   11. `[]` The TokenConfig account
+    DepositNFT(DepositNFTArgs),
+
+    /// Make deposit of several NFTs on bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account (pays the bridge token account rent in the sponsored mode)
+    ///   1. `[writable,signer]` The token owner account
+    ///   2. `[]` Token program id
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    ///   5. `[]` Sysvar
+    ///   6. `[]` Associated token program
+    ///   7. `[]` The SupportedNetworks account
+    ///      For every mint in the provided order:
+    ///      8+4i. `[writable]` The token mint account
+    ///      9+4i. `[writable]` The owner token associated account
+    ///      10+4i. `[writable]` The bridge token account
+    ///      11+4i. `[]` The TokenConfig account
+    DepositNFTBatch(DepositNFTBatchArgs),
+
+    /// Make NFT withdraw from bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable,signer]` The owner account
+    ///   2. `[writable]` The new Withdraw account
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    ///   5. `[]` The WithdrawAnnouncement account (Optional, if amount exceeds the delay threshold)
+    WithdrawNative(WithdrawArgs),
+
+    /// Make FT withdraw from bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account (`[writable]` for the wSOL mint, the lacking lamports are wrapped from it)
+    ///   1. `[writable]` The token mint account
+    ///   2. `[writable]` The token metadata account
+    ///   3. `[writable,signer]` The owner account
+    ///   4. `[writable]` The owner token associated account
+    ///   5. `[writable]` The bridge token account
+    ///   6. `[writable]` The new Withdraw account
+    ///   7. `[]` Token program id
+    ///   8. `[]` System program
+    ///   9. `[]` Rent sysvar
+    ///   10. `[]` Metadata program
+    ///   11. `[]` Associated token program
+    ///   12. `[]` The TokenConfig account
+    ///   13. `[writable]` The MintCap account
+    ///   14. `[]` Collection metadata account (Optional, if the leaf uses the name and symbol of the verified collection)
+    ///   15. `[]` The WithdrawAnnouncement account (Optional, if amount exceeds the delay threshold)
+    WithdrawFT(WithdrawArgs),
+
+    /// Make NFT withdraw from bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account
+    ///   1. `[writable]` The token mint account
+    ///   2. `[writable]` The token metadata account
+    ///   3. `[writable,signer]` The owner account
+    ///   4. `[writable]` The owner token associated account
+    ///   5. `[writable]` The bridge token account
+    ///   6. `[writable]` The new Withdraw account
+    ///   7. `[]` Token program id
+    ///   8. `[]` System program
+    ///   9. `[]` Rent sysvar
+    ///   10. `[]` Metadata program
+    ///   11. `[]` Associated token program
+    ///   12. `[]` The TokenConfig account
+    ///   13. `[writable]` Collection metadata account (Optional, required if the token has a collection)
+    ///   14. `[]` The WithdrawAnnouncement account (Optional, if amount exceeds the delay threshold)
+    ///       Optional accounts to verify the token in the collection delegated to bridge (see RegisterCollectionDelegate):
+    ///   15. `[]` The CollectionDelegate account
+    ///   16. `[]` The collection mint account
+    ///   17. `[]` The collection master edition account
+    ///   18. `[]` The collection authority record of the bridge
+    WithdrawNFT(WithdrawArgs),
+
+    /// Create collection NFT owned by brisge
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable,signed]` The token mint account
+    ///   2. `[writable]` The bridge token account
+    ///   3. `[writable]` The new metadata account
+    ///   4. `[writable,signer]` The payer account
+    ///   5. `[]` Token program id
+    ///   6. `[]` Token metadata program id
+    ///   7. `[]` Rent sysvar
+    ///   8. `[]` System program
+    ///   9. `[]` Associated token program
+    MintCollection(MintCollectionArgs),
+
+    /// Change minimal deposit amount in BridgeAdmin.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetMinDeposit(SetMinDepositArgs),
+
+    /// Create bridge token associated account for the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account
+    ///   1. `[]` The token mint account
+    ///   2. `[writable]` The bridge token account
+    ///   3. `[writable,signer]` The payer account
+    ///   4. `[]` Token program id
+    ///   5. `[]` System program
+    ///   6. `[]` Rent sysvar
+    ///   7. `[]` Associated token program
+    InitBridgeTokenAccount(InitBridgeTokenAccountArgs),
+
+    /// Enable or disable strict mode, that requires bridge token accounts to be created by InitBridgeTokenAccount.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetStrictTokenAccounts(SetStrictTokenAccountsArgs),
+
+    /// Change the delay and the amount threshold for withdrawals that should be announced.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetWithdrawDelay(SetWithdrawDelayArgs),
+
+    /// Announce the signed withdrawal that exceeds the delay threshold.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account
+    ///   1. `[writable,signer]` The payer account
+    ///   2. `[writable]` The new WithdrawAnnouncement account
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    AnnounceWithdraw(AnnounceWithdrawArgs),
+
+    /// Update name, symbol and uri of the token minted by bridge.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[]` The token mint account
+    ///   2. `[writable]` The token metadata account
+    ///   3. `[]` Metadata program
+    UpdateTokenMetadata(UpdateTokenMetadataArgs),
+
+    /// Move all SOL (above rent-exempt minimum) and all tokens of the listed mints from bridge to the destination.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The destination account
+    ///   2. `[]` Token program id
+    ///   3. `[]` The first token mint account
+    ///   4. `[writable]` The bridge token account for the first mint
+    ///   5. `[writable]` The destination token associated account for the first mint
+    ///      ... the same three accounts for every next mint
+    EmergencyWithdraw(EmergencyWithdrawArgs),
+
+    /// Replace the list of networks supported as the deposit target.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The SupportedNetworks account
+    ///   2. `[writable,signer]` The payer account
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    SetNetworks(SetNetworksArgs),
+
+    /// Enable or disable deposits and withdrawals of the certain token.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The TokenConfig account
+    ///   2. `[writable,signer]` The payer account
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    SetTokenConfig(SetTokenConfigArgs),
+
+    /// Check the withdrawal without executing it. Result is returned as `WithdrawValidation` return data.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account
+    ///   1. `[]` The owner account
+    ///   2. `[]` The Withdraw account
+    ///   3. `[]` The token mint account (FT and NFT only)
+    ///   4. `[]` The token metadata account (FT and NFT only)
+    ///   5. `[]` The bridge token account (FT and NFT only)
+    ///   6. `[]` The TokenConfig account (FT and NFT only)
+    ///   7. `[]` Collection metadata account (Optional)
+    ///   8. `[]` The WithdrawAnnouncement account (Optional, if amount exceeds the delay threshold)
+    ValidateWithdraw(ValidateWithdrawArgs),
+
+    /// Move the BridgeAdmin or Withdraw account of the deployed layout without the version byte to the current layout.
+    /// Accounts of the current version are left as is.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin or Withdraw account
+    ///   1. `[writable,signer]` The payer account for the additional rent
+    ///   2. `[]` System program
+    MigrateState,
+
+    /// Close the bridge token account of the bridged mint with zero supply (all tokens were returned to the source chain).
+    /// The mint account itself is kept, because the token program does not allow to close mints.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[]` The token mint account
+    ///   2. `[writable]` The bridge token account
+    ///   3. `[writable]` The receiver of the token account rent
+    ///   4. `[]` Token program id
+    ReclaimMint(ReclaimMintArgs),
+
+    /// Close the bridge token accounts of the listed mints with zero balance and move their rent to the receiver.
+    /// Missing and non-empty accounts are skipped.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The receiver of the token accounts rent
+    ///   2. `[]` Token program id
+    ///   3. `[writable]` The bridge token account for the first mint
+    ///      ... the bridge token account for every next mint
+    SweepDust(SweepDustArgs),
+
+    /// Register the external collection that approved the BridgeAdmin as the collection authority
+    /// (token metadata `ApproveCollectionAuthority`), so NFT withdrawals can verify the tokens of that collection.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The CollectionDelegate account
+    ///   2. `[]` The collection authority record of the BridgeAdmin
+    ///   3. `[writable,signer]` The payer account
+    ///   4. `[]` System program
+    ///   5. `[]` Rent sysvar
+    RegisterCollectionDelegate(RegisterCollectionDelegateArgs),
+
+    /// Move the BridgeAdmin created by the first program version (`public_key | is_initialized`)
+    /// or by the deployed one (`public_key | commission_program | is_initialized`) to the current layout,
+    /// keeping the public key and setting the provided commission program.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable,signer]` The payer account for the additional rent
+    ///   2. `[]` System program
+    MigrateAdmin(MigrateAdminArgs),
+
+    /// Set the fee in basis points that is kept by the bridge from native and fungible token withdrawals.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetWithdrawFee(SetWithdrawFeeArgs),
+
+    /// Set the Ethereum address (`keccak256(public key)[12..]`) of the signer. If it is set, 
+    /// all admin signatures are verified against the address instead of the stored public key.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetEthAddress(SetEthAddressArgs),
+
+    /// Store the signed Merkle root of the withdraw contents, that can be claimed later by ClaimAuthorized
+    /// without the signature for every claim.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account
+    ///   1. `[writable]` The new AuthorizedOrigins account
+    ///   2. `[writable,signer]` The payer account
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    AuthorizeOrigins(AuthorizeOriginsArgs),
+
+    /// Make the Native, FT or NFT withdrawal, which content is included in the authorized root.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The AuthorizedOrigins account
+    ///      1+. Accounts of the WithdrawNative, WithdrawFT or WithdrawNFT instruction
+    ClaimAuthorized(ClaimAuthorizedArgs),
+
+    /// Enable or disable the sponsored mode, in which the rent of the bridge token accounts created by deposits
+    /// is paid from the BridgeAdmin lamports instead of the depositor.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetSponsorTokenAccounts(SetSponsorTokenAccountsArgs),
+
+    /// Make SOL deposit on bridge charging the commission by the ChargeCommission CPI in the same instruction.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable,signer]` The owner account
+    ///   2. `[]` System program
+    ///   3. `[]` Rent sysvar
+    ///   4. `[]` The SupportedNetworks account
+    ///   5. `[]` Commission program
+    ///   6. `[writable]` The CommissionAdmin account
+    ///   7. `[writable]` The new Charge account
+    ///   8. `[]` SPL token program
+    ///   9. `[writable]` Commission token owner associated account (Optional)
+    ///   10. `[writable]` Commission token admin associated account (Optional)
+    ///   11. `[]` Commission token mint account (Optional)
+    DepositNativeWithCommission(DepositNativeWithCommissionArgs),
+
+    /// Set the max amount of a single native or FT withdrawal.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetMaxSingleWithdraw(SetMaxSingleWithdrawArgs),
+
+    /// Set the receiver address formats of the deposit target networks.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The SupportedNetworks account
+    ///   2. `[writable,signer]` The fee payer (extends the SupportedNetworks account created before)
+    ///   3. `[]` System program
+    SetNetworkFormats(SetNetworkFormatsArgs),
+
+    /// Store the batch of signed Merkle roots of the withdraw contents in the ring buffer,
+    /// the withdrawals included in any stored root can be claimed later by ClaimStored.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The BridgeAdmin account
+    ///   1. `[writable]` The StoredRoots account (created on the first call)
+    ///   2. `[writable,signer]` The payer account
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    StoreRoots(StoreRootsArgs),
+
+    /// Make the Native, FT or NFT withdrawal, which content is included in one of the stored roots.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The StoredRoots account
+    ///      1+. Accounts of the WithdrawNative, WithdrawFT or WithdrawNFT instruction
+    ClaimStored(ClaimStoredArgs),
+
+    /// Set the backup key, that can replace the admin key by PromoteBackup after the timelock.
+    /// Cancels the pending promotion.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    SetBackupKey(SetBackupKeyArgs),
+
+    /// Request the promotion of the backup key or confirm the requested one after the timelock,
+    /// signed by the backup key.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    PromoteBackup(PromoteBackupArgs),
+
+    /// Cancel the pending promotion of the backup key.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    CancelPromotion(CancelPromotionArgs),
+
+    /// Veto the announced withdrawal by marking the WithdrawAnnouncement account as vetoed.
+    /// The vetoed withdrawal can not be announced again.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The WithdrawAnnouncement account
+    VetoWithdraw(VetoWithdrawArgs),
+
+    /// Revoke the authorized or stored root, the withdrawals included in it can not be claimed anymore.
+    /// The AuthorizedOrigins account is created as revoked if the root was not authorized yet,
+    /// the root is removed from the StoredRoots account if it exists.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The BridgeAdmin account
+    ///   1. `[writable]` The AuthorizedOrigins account
+    ///   2. `[writable]` The StoredRoots account
+    ///   3. `[writable,signer]` The payer account
+    ///   4. `[]` System program
+    ///   5. `[]` Rent sysvar
+    RevokeRoot(RevokeRootArgs),
+}
+
+
+impl InstructionValidation for DepositNativeArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE ||
+            self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE || self.amount <= 0 {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for DepositNativeWithCommissionArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE ||
+            self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE || self.amount == 0 {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for DepositFTArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE ||
+            self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE || self.amount <= 0 {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for DepositNFTArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE || self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for DepositNFTBatchArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.receiver_address.as_bytes().len() > MAX_ADDRESS_SIZE || self.network_to.as_bytes().len() > MAX_NETWORKS_SIZE {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        if self.mints.len() == 0 || self.mints.len() > MAX_NFT_BATCH_SIZE || self.mints.len() != self.token_seeds.len() {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        for i in 0..self.mints.len() {
+            for j in i + 1..self.mints.len() {
+                if self.mints[i] == self.mints[j] {
+                    return Err(LibError::WrongArgsSize.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for ClaimAuthorizedArgs {
+    fn validate(&self) -> ProgramResult {
+        self.withdraw.validate()?;
+        self.withdraw.validate_token_type(&self.token_type)
+    }
+}
+
+impl InstructionValidation for StoreRootsArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.capacity == 0 || self.capacity as usize > MAX_STORED_ROOTS ||
+            self.roots.len() == 0 || self.roots.len() > self.capacity as usize {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for ClaimStoredArgs {
+    fn validate(&self) -> ProgramResult {
+        self.withdraw.validate()?;
+        self.withdraw.validate_token_type(&self.token_type)
+    }
+}
+
+impl InstructionValidation for ValidateWithdrawArgs {
+    fn validate(&self) -> ProgramResult {
+        self.withdraw.validate()?;
+        self.withdraw.validate_token_type(&self.token_type)
+    }
+}
+
+impl InstructionValidation for SetNetworkFormatsArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.formats.len() > MAX_SUPPORTED_NETWORKS {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        for format in &self.formats {
+            if format.network.as_bytes().len() > MAX_NETWORKS_SIZE {
+                return Err(LibError::WrongArgsSize.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for SetNetworksArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.networks.len() > MAX_SUPPORTED_NETWORKS {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        for network in &self.networks {
+            if network.as_bytes().len() > MAX_NETWORKS_SIZE {
+                return Err(LibError::WrongArgsSize.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for WithdrawArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.amount == 0 || self.claim_amount == Some(0) {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        if self.path.len() > MAX_MERKLE_DEPTH {
+            return Err(LibError::WrongMerklePath.into());
+        }
+
+        if let Some(preimage) = &self.origin_preimage {
+            if preimage.network_from.as_bytes().len() > MAX_NETWORKS_SIZE {
+                return Err(LibError::WrongArgsSize.into());
+            }
+
+            if preimage.hash() != self.origin {
+                return Err(LibError::WrongContentHash.into());
+            }
+        }
+
+        // Token created by the withdrawal always gets the metadata
+        if self.no_metadata && (self.token_seed.is_some() || self.signed_meta.is_some()) {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for SetWithdrawFeeArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.withdraw_fee_bps > MAX_WITHDRAW_FEE_BPS {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for MintCollectionArgs {
+    fn validate(&self) -> ProgramResult {
+        self.data.validate()
+    }
+}
+
+impl InstructionValidation for UpdateTokenMetadataArgs {
+    fn validate(&self) -> ProgramResult {
+        self.data.validate()
+    }
+}
+
+impl WithdrawArgs {
+    /// Checks the arguments that depend on the withdrawn token type.
+    pub fn validate_token_type(&self, token_type: &TokenType) -> ProgramResult {
+        if let Some(signed_meta) = &self.signed_meta {
+            signed_meta.validate_decimals(token_type)?;
+            signed_meta.validate_max_supply(token_type)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SignedMetadata {
+    /// NFT mint should have zero decimals, FT mint - not more than `MAX_FT_DECIMALS`.
+    pub fn validate_decimals(&self, token_type: &TokenType) -> ProgramResult {
+        let valid = match token_type {
+            TokenType::NFT => self.decimals == 0,
+            TokenType::FT => self.decimals <= MAX_FT_DECIMALS,
+            TokenType::Native => true,
+        };
+
+        if !valid {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl SignedMetadata {
+    /// Supply cap is available only for FT and should allow at least one minted unit.
+    pub fn validate_max_supply(&self, token_type: &TokenType) -> ProgramResult {
+        match (token_type, self.max_supply) {
+            (_, None) => Ok(()),
+            (TokenType::FT, Some(max_supply)) if max_supply > 0 => Ok(()),
+            _ => Err(LibError::WrongArgsSize.into()),
+        }
+    }
+}
+
+impl InstructionValidation for SignedMetadata {
+    fn validate(&self) -> ProgramResult {
+        if self.name.as_bytes().len() > mpl_token_metadata::state::MAX_NAME_LENGTH ||
+            self.symbol.as_bytes().len() > mpl_token_metadata::state::MAX_SYMBOL_LENGTH ||
+            self.uri.as_bytes().len() > mpl_token_metadata::state::MAX_URI_LENGTH {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        // Null bytes are trimmed from the stored metadata, so the leaf of such URI could not match the signed one
+        if self.uri.chars().any(char::is_control) {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// WithdrawAnnouncement address with the bump. The signed leaf is the part of the address,
+/// so only the announcement of the withdrawn content is accepted.
+pub fn find_withdraw_announcement(program_id: &Pubkey, bridge_admin: &Pubkey, origin: [u8; 32], amount: u64, leaf: [u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[crate::WITHDRAW_ANNOUNCEMENT_PDA_SEED.as_bytes(), bridge_admin.as_ref(), origin.as_slice(), amount.to_be_bytes().as_slice(), leaf.as_slice()],
+        program_id,
+    )
+}
+
+pub fn initialize_admin(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    fee_payer: Pubkey,
+    commission_program: Pubkey,
+    public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    seeds: [u8; 32],
+    hash_algo: HashAlgo,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(fee_payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: BridgeInstruction::InitializeAdmin(InitializeAdminArgs {
+            public_key,
+            seeds,
+            commission_program,
+            hash_algo,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn transfer_ownership(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    new_public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    seeds: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::TransferOwnership(TransferOwnershipArgs {
+            signature,
+            new_public_key,
+            seeds,
+            recovery_id,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn get_networks_address(program_id: &Pubkey, bridge_admin: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[crate::NETWORKS_PDA_SEED.as_bytes(), bridge_admin.as_ref()], program_id).0
+}
+
+pub fn get_token_config_address(program_id: &Pubkey, bridge_admin: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[crate::TOKEN_CONFIG_PDA_SEED.as_bytes(), bridge_admin.as_ref(), mint.as_ref()], program_id).0
+}
+
+pub fn get_mint_cap_address(program_id: &Pubkey, bridge_admin: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[crate::MINT_CAP_PDA_SEED.as_bytes(), bridge_admin.as_ref(), mint.as_ref()], program_id).0
+}
+
+pub fn get_collection_delegate_address(program_id: &Pubkey, bridge_admin: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[crate::COLLECTION_DELEGATE_PDA_SEED.as_bytes(), bridge_admin.as_ref(), collection_mint.as_ref()], program_id).0
+}
+
+pub fn get_authorized_origins_address(program_id: &Pubkey, bridge_admin: &Pubkey, root: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[crate::AUTHORIZED_ORIGINS_PDA_SEED.as_bytes(), bridge_admin.as_ref(), root.as_slice()], program_id).0
+}
+
+pub fn get_stored_roots_address(program_id: &Pubkey, bridge_admin: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[crate::STORED_ROOTS_PDA_SEED.as_bytes(), bridge_admin.as_ref()], program_id).0
+}
+
+pub fn get_deposit_bumps(program_id: &Pubkey, bridge_admin: &Pubkey, mint: Option<&Pubkey>) -> DepositBumps {
+    let (_, networks) = Pubkey::find_program_address(&[crate::NETWORKS_PDA_SEED.as_bytes(), bridge_admin.as_ref()], program_id);
+    let token_config = match mint {
+        Some(mint) => Pubkey::find_program_address(&[crate::TOKEN_CONFIG_PDA_SEED.as_bytes(), bridge_admin.as_ref(), mint.as_ref()], program_id).1,
+        None => 0,
+    };
+
+    DepositBumps { networks, token_config }
+}
+
+pub fn deposit_native(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    amount: u64,
+    receiver_address: String,
+    bundle_data: Option<Vec<u8>>,
+    bundle_seed: Option<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(get_networks_address(&program_id, &bridge_admin), false),
+        ],
+        data: BridgeInstruction::DepositNative(DepositNativeArgs {
+            amount,
+            network_to,
+            receiver_address,
+            seeds,
+            bundle_data,
+            bundle_seed,
+            bumps: Some(get_deposit_bumps(&program_id, &bridge_admin, None)),
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn deposit_native_with_commission(
+    program_id: Pubkey,
+    commission_program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    amount: u64,
+    receiver_address: String,
+    bundle_data: Option<Vec<u8>>,
+    bundle_seed: Option<[u8; 32]>,
+    commission_token: CommissionToken,
+    deposit_nonce: u64,
+) -> Instruction {
+    let commission_admin = crate::instructions::commission::commission_admin_address(&commission_program_id, &bridge_admin).unwrap();
+
+    let mut accounts = vec![
+        AccountMeta::new(bridge_admin, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(get_networks_address(&program_id, &bridge_admin), false),
+        AccountMeta::new_readonly(commission_program_id, false),
+        AccountMeta::new(commission_admin, false),
+        AccountMeta::new(crate::instructions::commission::get_charge_address(&commission_program_id, &owner, deposit_nonce), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    if let CommissionToken::FT(mint) = commission_token {
+        accounts.push(AccountMeta::new(get_associated_token_address(&owner, &mint), false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&commission_admin, &mint), false));
+        accounts.push(AccountMeta::new_readonly(mint, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::DepositNativeWithCommission(DepositNativeWithCommissionArgs {
+            amount,
+            network_to,
+            receiver_address,
+            seeds,
+            bundle_data,
+            bundle_seed,
+            bumps: Some(get_deposit_bumps(&program_id, &bridge_admin, None)),
+            commission_token,
+            deposit_nonce,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn deposit_ft(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    receiver_address: String,
+    amount: u64,
+    token_seed: Option<[u8; 32]>,
+    bundle_data: Option<Vec<u8>>,
+    bundle_seed: Option<[u8; 32]>,
+) -> Instruction {
+    let owner_associated = get_associated_token_address(&owner, &mint);
+    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(owner_associated, false),
+            AccountMeta::new(bridge_associated, false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(get_networks_address(&program_id, &bridge_admin), false),
+            AccountMeta::new_readonly(get_token_config_address(&program_id, &bridge_admin, &mint), false),
+        ],
+        data: BridgeInstruction::DepositFT(DepositFTArgs {
+            amount,
+            network_to,
+            receiver_address,
+            seeds,
+            token_seed,
+            bundle_data,
+            bundle_seed,
+            bumps: Some(get_deposit_bumps(&program_id, &bridge_admin, Some(&mint))),
+        }).try_to_vec().unwrap(),
+    }
+}
+
+// Charge instruction for the deposit, pays the commission in the native or fungible token
+fn charge_commission_for_deposit(
+    commission_program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    commission_token: CommissionToken,
+    deposit_token: TokenType,
+    deposit_token_amount: u64,
+    deposit_nonce: u64,
+) -> Instruction {
+    let commission_admin = crate::instructions::commission::commission_admin_address(&commission_program_id, &bridge_admin).unwrap();
+
+    match commission_token {
+        CommissionToken::FT(mint) => crate::instructions::commission::charge_commission_ft(
+            commission_program_id,
+            commission_admin,
+            bridge_admin,
+            owner,
+            mint,
+            commission_token,
+            deposit_token,
+            deposit_token_amount,
+            deposit_nonce,
+        ),
+        _ => crate::instructions::commission::charge_commission_native(
+            commission_program_id,
+            commission_admin,
+            bridge_admin,
+            owner,
+            commission_token,
+            deposit_token,
+            deposit_token_amount,
+            deposit_nonce,
+        ),
+    }
+}
+
+/// Returns the `ChargeCommission` and `DepositNative` instructions in the order required by the bridge.
+/// The instructions should be sent in one transaction without other commission charges or deposits between them.
+pub fn build_deposit_native_tx(
+    program_id: Pubkey,
+    commission_program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    amount: u64,
+    receiver_address: String,
+    bundle_data: Option<Vec<u8>>,
+    bundle_seed: Option<[u8; 32]>,
+    commission_token: CommissionToken,
+    deposit_nonce: u64,
+) -> Vec<Instruction> {
+    vec![
+        charge_commission_for_deposit(commission_program_id, bridge_admin, owner, commission_token, TokenType::Native, amount, deposit_nonce),
+        deposit_native(program_id, bridge_admin, owner, seeds, network_to, amount, receiver_address, bundle_data, bundle_seed),
+    ]
+}
+
+/// Returns the `ChargeCommission` and `DepositFT` instructions in the order required by the bridge.
+/// The instructions should be sent in one transaction without other commission charges or deposits between them.
+pub fn build_deposit_ft_tx(
+    program_id: Pubkey,
+    commission_program_id: Pubkey,
+    bridge_admin: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    receiver_address: String,
+    amount: u64,
+    token_seed: Option<[u8; 32]>,
+    bundle_data: Option<Vec<u8>>,
+    bundle_seed: Option<[u8; 32]>,
+    commission_token: CommissionToken,
+    deposit_nonce: u64,
+) -> Vec<Instruction> {
+    vec![
+        charge_commission_for_deposit(commission_program_id, bridge_admin, owner, commission_token, TokenType::FT, amount, deposit_nonce),
+        deposit_ft(program_id, bridge_admin, mint, owner, seeds, network_to, receiver_address, amount, token_seed, bundle_data, bundle_seed),
+    ]
+}
+
+pub fn deposit_nft(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    receiver_address: String,
+    token_seed: Option<[u8; 32]>,
+    bundle_data: Option<Vec<u8>>,
+    bundle_seed: Option<[u8; 32]>,
+) -> Instruction {
+    let owner_associated = get_associated_token_address(&owner, &mint);
+    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(owner_associated, false),
+            AccountMeta::new(bridge_associated, false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(get_networks_address(&program_id, &bridge_admin), false),
+            AccountMeta::new_readonly(get_token_config_address(&program_id, &bridge_admin, &mint), false),
+        ],
+        data: BridgeInstruction::DepositNFT(DepositNFTArgs {
+            network_to,
+            receiver_address,
+            seeds,
+            token_seed,
+            bundle_data,
+            bundle_seed,
+            bumps: Some(get_deposit_bumps(&program_id, &bridge_admin, Some(&mint))),
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn deposit_nft_batch(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    seeds: [u8; 32],
+    network_to: Network,
+    receiver_address: String,
+    mints: Vec<Pubkey>,
+    token_seeds: Vec<Option<[u8; 32]>>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(bridge_admin, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(get_networks_address(&program_id, &bridge_admin), false),
+    ];
+
+    for mint in &mints {
+        accounts.push(AccountMeta::new(*mint, false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&owner, mint), false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&bridge_admin, mint), false));
+        accounts.push(AccountMeta::new_readonly(get_token_config_address(&program_id, &bridge_admin, mint), false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::DepositNFTBatch(DepositNFTBatchArgs {
+            network_to,
+            receiver_address,
+            seeds,
+            mints,
+            token_seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn withdraw_native(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    withdraw: Pubkey,
+    seeds: [u8; 32],
+    origin: [u8; 32],
+    amount: u64,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+    token_seed: Option<[u8; 32]>,
+    signed_meta: Option<SignedMetadata>,
+    eip712_domain_separator: Option<[u8; 32]>,
+    strict_ata: bool,
+    origin_preimage: Option<OriginPreimage>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new(withdraw, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: BridgeInstruction::WithdrawNative(WithdrawArgs {
+            origin,
+            amount,
+            claim_amount: None,
+            signature,
+            recovery_id,
+            path: MerkleProof(path),
+            seeds,
+            token_seed,
+            signed_meta,
+            eip712_domain_separator,
+            strict_ata,
+            origin_preimage,
+            no_metadata: false,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn withdraw_ft(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    withdraw: Pubkey,
+    seeds: [u8; 32],
+    origin: [u8; 32],
+    amount: u64,
+    claim_amount: Option<u64>,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+    token_seed: Option<[u8; 32]>,
+    signed_meta: Option<SignedMetadata>,
+    eip712_domain_separator: Option<[u8; 32]>,
+    strict_ata: bool,
+    origin_preimage: Option<OriginPreimage>,
+    no_metadata: bool,
+    collection_metadata: Option<Pubkey>,
+) -> Instruction {
+    let owner_associated = get_associated_token_address(&owner, &mint);
+    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
+
+    let mut accounts = vec![
+        AccountMeta { pubkey: bridge_admin, is_signer: false, is_writable: mint == spl_token::native_mint::id() },
+        AccountMeta::new(mint, false),
+        AccountMeta::new(mpl_token_metadata::pda::find_metadata_account(&mint).0, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new(owner_associated, false),
+        AccountMeta::new(bridge_associated, false),
+        AccountMeta::new(withdraw, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(get_token_config_address(&program_id, &bridge_admin, &mint), false),
+        AccountMeta::new(get_mint_cap_address(&program_id, &bridge_admin, &mint), false),
+    ];
+
+    // Only if the signed leaf uses the collection name and symbol
+    if let Some(collection_metadata) = collection_metadata {
+        accounts.push(AccountMeta::new_readonly(collection_metadata, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::WithdrawFT(WithdrawArgs {
+            origin,
+            amount,
+            claim_amount,
+            signature,
+            recovery_id,
+            path: MerkleProof(path),
+            seeds,
+            token_seed,
+            signed_meta,
+            eip712_domain_separator,
+            strict_ata,
+            origin_preimage,
+            no_metadata,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn withdraw_nft(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    mint: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    withdraw: Pubkey,
+    seeds: [u8; 32],
+    origin: [u8; 32],
+    amount: u64,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+    token_seed: Option<[u8; 32]>,
+    signed_meta: Option<SignedMetadata>,
+    eip712_domain_separator: Option<[u8; 32]>,
+    strict_ata: bool,
+    origin_preimage: Option<OriginPreimage>,
+    collection_metadata: Option<Pubkey>,
+) -> Instruction {
+    let owner_associated = get_associated_token_address(&owner, &mint);
+    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(bridge_admin, false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new(metadata, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new(owner_associated, false),
+        AccountMeta::new(bridge_associated, false),
+        AccountMeta::new(withdraw, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(get_token_config_address(&program_id, &bridge_admin, &mint), false),
+    ];
+
+    // Required if the token has a collection, writable for the sized collection verification
+    if let Some(collection_metadata) = collection_metadata {
+        accounts.push(AccountMeta::new(collection_metadata, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::WithdrawNFT(WithdrawArgs {
+            origin,
+            amount,
+            claim_amount: None,
+            signature,
+            recovery_id,
+            path: MerkleProof(path),
+            seeds,
+            token_seed,
+            signed_meta,
+            eip712_domain_separator,
+            strict_ata,
+            origin_preimage,
+            no_metadata: false,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_min_deposit(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    min_deposit: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::SetMinDeposit(SetMinDepositArgs {
+            min_deposit,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn init_bridge_token_account(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    mint: Pubkey,
+    payer: Pubkey,
+    seeds: [u8; 32],
+) -> Instruction {
+    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(bridge_admin, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(bridge_associated, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: BridgeInstruction::InitBridgeTokenAccount(InitBridgeTokenAccountArgs {
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_strict_token_accounts(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    strict: bool,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::SetStrictTokenAccounts(SetStrictTokenAccountsArgs {
+            strict,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_sponsor_token_accounts(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    sponsor: bool,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::SetSponsorTokenAccounts(SetSponsorTokenAccountsArgs {
+            sponsor,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_max_single_withdraw(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    max_single_withdraw: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::SetMaxSingleWithdraw(SetMaxSingleWithdrawArgs {
+            max_single_withdraw,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_backup_key(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    backup_key: Option<[u8; SECP256K1_PUBLIC_KEY_LENGTH]>,
+    promote_timelock: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::SetBackupKey(SetBackupKeyArgs {
+            backup_key,
+            promote_timelock,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn promote_backup(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    confirm: bool,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::PromoteBackup(PromoteBackupArgs {
+            confirm,
+            signature,
+            recovery_id,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn cancel_promotion(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::CancelPromotion(CancelPromotionArgs {
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_withdraw_delay(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    delay: u64,
+    threshold: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+        ],
+        data: BridgeInstruction::SetWithdrawDelay(SetWithdrawDelayArgs {
+            delay,
+            threshold,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn announce_withdraw(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    payer: Pubkey,
+    origin: [u8; 32],
+    amount: u64,
+    leaf: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: MerkleProof,
+) -> Instruction {
+    let (announcement, _) = find_withdraw_announcement(&program_id, &bridge_admin, origin, amount, leaf);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(bridge_admin, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new(announcement, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: BridgeInstruction::AnnounceWithdraw(AnnounceWithdrawArgs {
+            origin,
+            amount,
+            leaf,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn veto_withdraw(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    announcement: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(announcement, false),
+        ],
+        data: BridgeInstruction::VetoWithdraw(VetoWithdrawArgs {
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn update_token_metadata(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    token_seed: [u8; 32],
+    data: SignedMetadata,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    let (mint, _) = Pubkey::find_program_address(&[token_seed.as_slice()], &program_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(mpl_token_metadata::pda::find_metadata_account(&mint).0, false),
+            AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+        ],
+        data: BridgeInstruction::UpdateTokenMetadata(UpdateTokenMetadataArgs {
+            token_seed,
+            data,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn emergency_withdraw(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    destination: Pubkey,
+    mints: Vec<Pubkey>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(bridge_admin, false),
+        AccountMeta::new(destination, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    for mint in &mints {
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&bridge_admin, mint), false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&destination, mint), false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::EmergencyWithdraw(EmergencyWithdrawArgs {
+            mints,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_networks(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    payer: Pubkey,
+    networks: Vec<Network>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(get_networks_address(&program_id, &bridge_admin), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: BridgeInstruction::SetNetworks(SetNetworksArgs {
+            networks,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_network_formats(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    payer: Pubkey,
+    formats: Vec<NetworkFormat>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(get_networks_address(&program_id, &bridge_admin), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: BridgeInstruction::SetNetworkFormats(SetNetworkFormatsArgs {
+            formats,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn set_token_config(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    payer: Pubkey,
+    mint: Pubkey,
+    deposit_enabled: bool,
+    withdraw_enabled: bool,
+    target_decimals: Option<u8>,
+    min_deposit: u64,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(get_token_config_address(&program_id, &bridge_admin, &mint), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: BridgeInstruction::SetTokenConfig(SetTokenConfigArgs {
+            mint,
+            deposit_enabled,
+            withdraw_enabled,
+            target_decimals,
+            min_deposit,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn validate_withdraw(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
+    owner: Pubkey,
+    withdraw: Pubkey,
+    mint: Option<Pubkey>,
+    token_type: TokenType,
+    args: WithdrawArgs,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(bridge_admin, false),
+        AccountMeta::new_readonly(owner, false),
+        AccountMeta::new_readonly(withdraw, false),
+    ];
 
-        Ok(())
+    if let Some(mint) = mint {
+        accounts.push(AccountMeta::new_readonly(mint, false));
+        accounts.push(AccountMeta::new_readonly(mpl_token_metadata::pda::find_metadata_account(&mint).0, false));
+        accounts.push(AccountMeta::new_readonly(get_associated_token_address(&bridge_admin, &mint), false));
+        accounts.push(AccountMeta::new_readonly(get_token_config_address(&program_id, &bridge_admin, &mint), false));
     }
-}
 
-impl InstructionValidation for MintCollectionArgs {
-    fn validate(&self) -> ProgramResult {
-        self.data.validate()
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::ValidateWithdraw(ValidateWithdrawArgs {
+            token_type,
+            withdraw: args,
+        }).try_to_vec().unwrap(),
     }
 }
 
-impl InstructionValidation for SignedMetadata {
-    fn validate(&self) -> ProgramResult {
-        if self.name.as_bytes().len() > mpl_token_metadata::state::MAX_NAME_LENGTH ||
-            self.symbol.as_bytes().len() > mpl_token_metadata::state::MAX_SYMBOL_LENGTH ||
-            self.uri.as_bytes().len() > mpl_token_metadata::state::MAX_URI_LENGTH {
-            return Err(LibError::WrongArgsSize.into());
-        }
-
-        Ok(())
+pub fn migrate_state(
+    program_id: Pubkey,
+    account: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(account, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: BridgeInstruction::MigrateState.try_to_vec().unwrap(),
     }
 }
 
-pub fn initialize_admin(
+pub fn reclaim_mint(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    fee_payer: Pubkey,
-    commission_program: Pubkey,
-    public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     seeds: [u8; 32],
+    token_seed: [u8; 32],
+    receiver: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
 ) -> Instruction {
+    let (mint, _) = Pubkey::find_program_address(&[token_seed.as_slice()], &program_id);
+
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(bridge_admin, false),
-            AccountMeta::new(fee_payer, true),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(get_associated_token_address(&bridge_admin, &mint), false),
+            AccountMeta::new(receiver, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
         ],
-        data: BridgeInstruction::InitializeAdmin(InitializeAdminArgs {
-            public_key,
+        data: BridgeInstruction::ReclaimMint(ReclaimMintArgs {
+            token_seed,
+            signature,
+            recovery_id,
+            path,
             seeds,
-            commission_program,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn transfer_ownership(
+pub fn sweep_dust(
     program_id: Pubkey,
     bridge_admin: Pubkey,
+    seeds: [u8; 32],
+    receiver: Pubkey,
+    mints: Vec<Pubkey>,
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
-    new_public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(bridge_admin, false),
+        AccountMeta::new(receiver, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    for mint in &mints {
+        accounts.push(AccountMeta::new(get_associated_token_address(&bridge_admin, mint), false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::SweepDust(SweepDustArgs {
+            mints,
+            signature,
+            recovery_id,
+            path,
+            seeds,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn register_collection_delegate(
+    program_id: Pubkey,
+    bridge_admin: Pubkey,
     seeds: [u8; 32],
+    payer: Pubkey,
+    collection_mint: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
 ) -> Instruction {
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(get_collection_delegate_address(&program_id, &bridge_admin, &collection_mint), false),
+            AccountMeta::new_readonly(mpl_token_metadata::pda::find_collection_authority_account(&collection_mint, &bridge_admin).0, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
         ],
-        data: BridgeInstruction::TransferOwnership(TransferOwnershipArgs {
+        data: BridgeInstruction::RegisterCollectionDelegate(RegisterCollectionDelegateArgs {
+            collection_mint,
             signature,
-            new_public_key,
-            seeds,
             recovery_id,
+            path,
+            seeds,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn deposit_native(
+pub fn migrate_admin(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    owner: Pubkey,
     seeds: [u8; 32],
-    network_to: String,
-    amount: u64,
-    receiver_address: String,
-    bundle_data: Option<Vec<u8>>,
-    bundle_seed: Option<[u8; 32]>,
+    payer: Pubkey,
+    commission_program: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
 ) -> Instruction {
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(bridge_admin, false),
-            AccountMeta::new(owner, true),
+            AccountMeta::new(payer, true),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
         ],
-        data: BridgeInstruction::DepositNative(DepositNativeArgs {
-            amount,
-            network_to,
-            receiver_address,
+        data: BridgeInstruction::MigrateAdmin(MigrateAdminArgs {
+            commission_program,
+            signature,
+            recovery_id,
             seeds,
-            bundle_data,
-            bundle_seed,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn deposit_ft(
+pub fn set_withdraw_fee(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    mint: Pubkey,
-    owner: Pubkey,
     seeds: [u8; 32],
-    network_to: String,
-    receiver_address: String,
-    amount: u64,
-    token_seed: Option<[u8; 32]>,
-    bundle_data: Option<Vec<u8>>,
-    bundle_seed: Option<[u8; 32]>,
+    withdraw_fee_bps: u16,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
 ) -> Instruction {
-    let owner_associated = get_associated_token_address(&owner, &mint);
-    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
-
     Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new_readonly(bridge_admin, false),
-            AccountMeta::new(mint, false),
-            AccountMeta::new(owner_associated, false),
-            AccountMeta::new(bridge_associated, false),
-            AccountMeta::new(owner, true),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(bridge_admin, false),
         ],
-        data: BridgeInstruction::DepositFT(DepositFTArgs {
-            amount,
-            network_to,
-            receiver_address,
+        data: BridgeInstruction::SetWithdrawFee(SetWithdrawFeeArgs {
+            withdraw_fee_bps,
+            signature,
+            recovery_id,
+            path,
             seeds,
-            token_seed,
-            bundle_data,
-            bundle_seed,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn deposit_nft(
+pub fn set_eth_address(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    mint: Pubkey,
-    owner: Pubkey,
     seeds: [u8; 32],
-    network_to: String,
-    receiver_address: String,
-    token_seed: Option<[u8; 32]>,
-    bundle_data: Option<Vec<u8>>,
-    bundle_seed: Option<[u8; 32]>,
+    eth_address: Option<[u8; ETH_ADDRESS_LENGTH]>,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
 ) -> Instruction {
-    let owner_associated = get_associated_token_address(&owner, &mint);
-    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
-
     Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new_readonly(bridge_admin, false),
-            AccountMeta::new(mint, false),
-            AccountMeta::new(owner_associated, false),
-            AccountMeta::new(bridge_associated, false),
-            AccountMeta::new(owner, true),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(bridge_admin, false),
         ],
-        data: BridgeInstruction::DepositNFT(DepositNFTArgs {
-            network_to,
-            receiver_address,
+        data: BridgeInstruction::SetEthAddress(SetEthAddressArgs {
+            eth_address,
+            signature,
+            recovery_id,
+            path,
             seeds,
-            token_seed,
-            bundle_data,
-            bundle_seed,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn withdraw_native(
+pub fn authorize_origins(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    owner: Pubkey,
-    withdraw: Pubkey,
     seeds: [u8; 32],
-    origin: [u8; 32],
-    amount: u64,
-    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    payer: Pubkey,
+    root: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
-    path: Vec<[u8; 32]>,
-    token_seed: Option<[u8; 32]>,
-    signed_meta: Option<SignedMetadata>,
 ) -> Instruction {
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new_readonly(bridge_admin, false),
-            AccountMeta::new(owner, true),
-            AccountMeta::new(withdraw, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_authorized_origins_address(&program_id, &bridge_admin, &root), false),
+            AccountMeta::new(payer, true),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
         ],
-        data: BridgeInstruction::WithdrawNative(WithdrawArgs {
-            origin,
-            amount,
+        data: BridgeInstruction::AuthorizeOrigins(AuthorizeOriginsArgs {
+            root,
             signature,
             recovery_id,
-            path,
             seeds,
-            token_seed,
-            signed_meta,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn withdraw_ft(
+/// Converts the instruction built by `withdraw_native`, `withdraw_ft` or `withdraw_nft` (signature can be zeroed)
+/// to the claim of the withdrawal included in the authorized root.
+pub fn claim_authorized(
+    withdraw: Instruction,
+    bridge_admin: Pubkey,
+    root: [u8; 32],
+) -> Instruction {
+    let (token_type, args) = match BridgeInstruction::try_from_slice(withdraw.data.as_slice()).unwrap() {
+        BridgeInstruction::WithdrawNative(args) => (TokenType::Native, args),
+        BridgeInstruction::WithdrawFT(args) => (TokenType::FT, args),
+        BridgeInstruction::WithdrawNFT(args) => (TokenType::NFT, args),
+        _ => panic!("not a withdraw instruction"),
+    };
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(get_authorized_origins_address(&withdraw.program_id, &bridge_admin, &root), false),
+    ];
+    accounts.extend(withdraw.accounts);
+
+    Instruction {
+        program_id: withdraw.program_id,
+        accounts,
+        data: BridgeInstruction::ClaimAuthorized(ClaimAuthorizedArgs {
+            token_type,
+            withdraw: args,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn revoke_root(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    mint: Pubkey,
-    owner: Pubkey,
-    withdraw: Pubkey,
     seeds: [u8; 32],
-    origin: [u8; 32],
-    amount: u64,
-    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    payer: Pubkey,
+    root: [u8; 32],
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
     path: Vec<[u8; 32]>,
-    token_seed: Option<[u8; 32]>,
-    signed_meta: Option<SignedMetadata>,
 ) -> Instruction {
-    let owner_associated = get_associated_token_address(&owner, &mint);
-    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
-
     Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new_readonly(bridge_admin, false),
-            AccountMeta::new_readonly(mint, false),
-            AccountMeta::new(owner, true),
-            AccountMeta::new(owner_associated, false),
-            AccountMeta::new(bridge_associated, false),
-            AccountMeta::new(withdraw, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(bridge_admin, false),
+            AccountMeta::new(get_authorized_origins_address(&program_id, &bridge_admin, &root), false),
+            AccountMeta::new(get_stored_roots_address(&program_id, &bridge_admin), false),
+            AccountMeta::new(payer, true),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
         ],
-        data: BridgeInstruction::WithdrawFT(WithdrawArgs {
-            origin,
-            amount,
+        data: BridgeInstruction::RevokeRoot(RevokeRootArgs {
+            root,
             signature,
             recovery_id,
             path,
             seeds,
-            token_seed,
-            signed_meta,
         }).try_to_vec().unwrap(),
     }
 }
 
-pub fn withdraw_nft(
+pub fn store_roots(
     program_id: Pubkey,
     bridge_admin: Pubkey,
-    mint: Pubkey,
-    metadata: Pubkey,
-    owner: Pubkey,
-    withdraw: Pubkey,
     seeds: [u8; 32],
-    origin: [u8; 32],
-    amount: u64,
-    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    payer: Pubkey,
+    roots: Vec<[u8; 32]>,
+    capacity: u16,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
-    path: Vec<[u8; 32]>,
-    token_seed: Option<[u8; 32]>,
-    signed_meta: Option<SignedMetadata>,
 ) -> Instruction {
-    let owner_associated = get_associated_token_address(&owner, &mint);
-    let bridge_associated = get_associated_token_address(&bridge_admin, &mint);
-
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new_readonly(bridge_admin, false),
-            AccountMeta::new(mint, false),
-            AccountMeta::new(metadata, false),
-            AccountMeta::new(owner, true),
-            AccountMeta::new(owner_associated, false),
-            AccountMeta::new(bridge_associated, false),
-            AccountMeta::new(withdraw, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(get_stored_roots_address(&program_id, &bridge_admin), false),
+            AccountMeta::new(payer, true),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
-            AccountMeta::new_readonly(mpl_token_metadata::id(), false),
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
         ],
-        data: BridgeInstruction::WithdrawNFT(WithdrawArgs {
-            origin,
-            amount,
+        data: BridgeInstruction::StoreRoots(StoreRootsArgs {
+            roots,
+            capacity,
             signature,
             recovery_id,
-            path,
             seeds,
-            token_seed,
-            signed_meta,
         }).try_to_vec().unwrap(),
     }
-}
\ No newline at end of file
+}
+
+/// Converts the instruction built by `withdraw_native`, `withdraw_ft` or `withdraw_nft` (signature can be zeroed)
+/// to the claim of the withdrawal included in the stored root.
+pub fn claim_stored(
+    withdraw: Instruction,
+    bridge_admin: Pubkey,
+    root: [u8; 32],
+) -> Instruction {
+    let (token_type, args) = match BridgeInstruction::try_from_slice(withdraw.data.as_slice()).unwrap() {
+        BridgeInstruction::WithdrawNative(args) => (TokenType::Native, args),
+        BridgeInstruction::WithdrawFT(args) => (TokenType::FT, args),
+        BridgeInstruction::WithdrawNFT(args) => (TokenType::NFT, args),
+        _ => panic!("not a withdraw instruction"),
+    };
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(get_stored_roots_address(&withdraw.program_id, &bridge_admin), false),
+    ];
+    accounts.extend(withdraw.accounts);
+
+    Instruction {
+        program_id: withdraw.program_id,
+        accounts,
+        data: BridgeInstruction::ClaimStored(ClaimStoredArgs {
+            token_type,
+            root,
+            withdraw: args,
+        }).try_to_vec().unwrap(),
+    }
+}
@@ -2,19 +2,28 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     entrypoint::ProgramResult,
     instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
     pubkey::Pubkey,
     sysvar,
 };
 use solana_program::secp256k1_recover::SECP256K1_PUBLIC_KEY_LENGTH;
 use crate::{CommissionToken, CommissionArgs, TokenType};
+use crate::error::LibError;
+use crate::merkle::{MerkleProof, MAX_MERKLE_DEPTH};
+use crate::instructions::InstructionValidation;
 use std::mem::size_of;
 use spl_associated_token_account::get_associated_token_address;
 
 pub const MAX_TOKENS_COUNT: usize = 10;
+// Max count of the receivers the withdrawn commission can be split between
+pub const MAX_SPLIT_RECEIVERS: usize = 5;
+// Sum of the split shares in basis points
+pub const SPLIT_TOTAL_BPS: u16 = 10_000;
 pub const MAX_TOKEN_SIZE: usize = size_of::<CommissionToken>() + 32;
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct CommissionTokenArg {
     pub token: CommissionToken,
     pub amount: u64,
@@ -22,12 +31,14 @@ pub struct CommissionTokenArg {
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct InitializeAdminArgs {
     pub acceptable_tokens: Vec<CommissionTokenArg>,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct FeeTokenArgs {
     pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     pub recovery_id: u8,
@@ -37,15 +48,80 @@ pub struct FeeTokenArgs {
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct WithdrawArgs {
     pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     pub recovery_id: u8,
-    pub path: Vec<[u8; 32]>,
+    pub path: MerkleProof,
     pub token: CommissionTokenArg,
     pub withdraw_amount: u64,
+    // Receivers with their shares in basis points, the whole amount goes to the receiver account if empty
+    pub split: Vec<(Pubkey, u16)>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct SetFeeTokensArgs {
+    pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub recovery_id: u8,
+    pub path: Vec<[u8; 32]>,
+    pub tokens: Vec<CommissionTokenArg>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct MigrateBridgeAdminArgs {
+    pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub recovery_id: u8,
+    pub path: Vec<[u8; 32]>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct CloseAdminArgs {
+    pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub recovery_id: u8,
+    pub path: Vec<[u8; 32]>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ResetNoncesArgs {
+    pub add_token_nonce: u64,
+    pub remove_token_nonce: u64,
+    pub update_token_nonce: u64,
+    pub withdraw_token_nonce: u64,
+    pub set_tokens_nonce: u64,
+    pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub recovery_id: u8,
+    pub path: Vec<[u8; 32]>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct GetCollectedArgs {
+    pub token: CommissionToken,
+}
+
+// Returned by GetCollected instruction via return data
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct CollectedCommission {
+    pub token: CommissionToken,
+    // Total charged amount
+    pub collected: u64,
+    // Total withdrawn amount
+    pub withdrawn: u64,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub enum CommissionInstruction {
     /// Initialize new CommissionAdmin that will store acceptable token
     ///
@@ -65,12 +141,13 @@ pub enum CommissionInstruction {
     ///   0. `[writable]` The CommissionAdmin account
     ///   1. `[]` The BridgeAdmin account
     ///   2. `[writable,signer]` The owner account
-    ///   3. `[]` System program
-    ///   4. `[]` Rent sysvar
-    ///   5. `[]` SPL token program
-    ///   6. `[writable]` Commission token owner associated account (Optional)
-    ///   7. `[writable]` Commission token admin associated account (Optional)
-    ///   8. `[]` Commission token mint account (Optional)
+    ///   3. `[writable]` The new Charge account
+    ///   4. `[]` System program
+    ///   5. `[]` Rent sysvar
+    ///   6. `[]` SPL token program
+    ///   7. `[writable]` Commission token owner associated account (Optional)
+    ///   8. `[writable]` Commission token admin associated account (Optional)
+    ///   9. `[]` Commission token mint account (Optional)
     ChargeCommission(CommissionArgs),
 
     /// Add new acceptable commission token
@@ -101,16 +178,191 @@ pub enum CommissionInstruction {
     ///
     /// Accounts expected by this instruction:
     ///
-    ///   0. `[]` The CommissionAdmin account
+    ///   0. `[writable]` The CommissionAdmin account
     ///   1. `[]` The BridgeAdmin account
     ///   2. `[writable, signer]` The receiver account (also fee payer)
     ///   3. `[]` System program
     ///   4. `[]` Rent sysvar
-    ///   5. `[]` SPL token program
-    ///   6. `[]` Commission token receiver associated account (Optional)
-    ///   7. `[]` Commission token admin associated account (Optional)
-    ///   8. `[]` Commission token mint account (Optional)
+    ///   5. `[]` SPL token program (FT only)
+    ///   6. `[writable]` Commission token receiver associated account (FT only)
+    ///   7. `[writable]` Commission token admin associated account (FT only)
+    ///   8. `[]` Commission token mint account (FT only)
+    ///      For every split receiver in the provided order (if split is not empty):
+    ///      5+i. `[writable]` Split receiver account (Native only)
+    ///      9+2i. `[]` Split receiver account (FT only)
+    ///      10+2i. `[writable]` Split receiver associated account (FT only)
     Withdraw(WithdrawArgs),
+
+    /// Replace the whole acceptable commission tokens list
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The CommissionAdmin account
+    ///   1. `[]` The BridgeAdmin account
+    SetFeeTokens(SetFeeTokensArgs),
+
+    /// Move collected tokens to the CommissionAdmin of the new BridgeAdmin
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The old CommissionAdmin account
+    ///   1. `[]` The old BridgeAdmin account
+    ///   2. `[writable]` The new CommissionAdmin account to initialize
+    ///   3. `[]` The new BridgeAdmin account
+    ///   4. `[writable,signer]` The fee payer
+    ///   5. `[]` System program
+    ///   6. `[]` Rent sysvar
+    ///   7. `[]` SPL token program
+    ///      For every acceptable FT and NFT token in the stored order (sorted by `CommissionToken::sort_key`):
+    ///      8+3i. `[writable]` Old commission admin associated account
+    ///      9+3i. `[writable]` New commission admin associated account
+    ///      10+3i. `[]` Commission token mint account
+    MigrateBridgeAdmin(MigrateBridgeAdminArgs),
+
+    /// Get collected and withdrawn amounts of the acceptable token via return data
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The CommissionAdmin account
+    ///   1. `[]` The BridgeAdmin account
+    GetCollected(GetCollectedArgs),
+
+    /// Move the CommissionAdmin account of the deployed layout without the version byte to the current layout.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The CommissionAdmin account
+    ///   1. `[writable,signer]` The payer account for the additional rent
+    ///   2. `[]` System program
+    MigrateState,
+
+    /// Close the CommissionAdmin of the retired bridge and move its rent to the receiver.
+    /// All acceptable tokens should be withdrawn. Requires the admin signature.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The CommissionAdmin account
+    ///   1. `[]` The BridgeAdmin account
+    ///   2. `[writable]` The receiver account
+    ///   3. `[]` SPL token program
+    ///      For every acceptable FT token in the stored order (sorted by `CommissionToken::sort_key`):
+    ///      4+i. `[writable]` Commission admin associated account
+    CloseAdmin(CloseAdminArgs),
+
+    /// Set all operation nonces of the CommissionAdmin, used to resynchronize the off-chain signers after a failed deploy.
+    /// Requires the admin signature.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The CommissionAdmin account
+    ///   1. `[]` The BridgeAdmin account
+    ResetNonces(ResetNoncesArgs),
+}
+
+impl InstructionValidation for FeeTokenArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.path.len() > MAX_MERKLE_DEPTH {
+            return Err(LibError::WrongMerklePath.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for CloseAdminArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.path.len() > MAX_MERKLE_DEPTH {
+            return Err(LibError::WrongMerklePath.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for ResetNoncesArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.path.len() > MAX_MERKLE_DEPTH {
+            return Err(LibError::WrongMerklePath.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for InitializeAdminArgs {
+    fn validate(&self) -> ProgramResult {
+        // Admin without acceptable tokens can not charge any commission, so the deposits will be blocked
+        if self.acceptable_tokens.is_empty() || self.acceptable_tokens.len() > MAX_TOKENS_COUNT {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for SetFeeTokensArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.tokens.len() > MAX_TOKENS_COUNT {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        for i in 0..self.tokens.len() {
+            for j in i + 1..self.tokens.len() {
+                if self.tokens[i].token == self.tokens[j].token {
+                    return Err(LibError::WrongArgsSize.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionValidation for WithdrawArgs {
+    fn validate(&self) -> ProgramResult {
+        if self.split.is_empty() {
+            return Ok(());
+        }
+
+        if self.split.len() > MAX_SPLIT_RECEIVERS {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        let mut total: u32 = 0;
+        for (_, bps) in &self.split {
+            if *bps == 0 {
+                return Err(LibError::WrongArgsSize.into());
+            }
+
+            total += *bps as u32;
+        }
+
+        if total != SPLIT_TOTAL_BPS as u32 {
+            return Err(LibError::WrongArgsSize.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Seeds of the CommissionAdmin account for the bridge admin.
+/// The account is created without the bump seed, so the seeds are used as is for signing.
+pub fn commission_admin_seeds(bridge_admin: &Pubkey) -> [&[u8]; 2] {
+    [crate::COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin.as_ref()]
+}
+
+/// Canonical CommissionAdmin address derivation, the same that is used for the account creation.
+/// The scheme does not include the bump seed (changing it would move the existing accounts),
+/// so the derivation fails for the bridge admins without the off-curve address.
+pub fn commission_admin_address(program_id: &Pubkey, bridge_admin: &Pubkey) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(&commission_admin_seeds(bridge_admin), program_id)?)
+}
+
+pub fn get_charge_address(program_id: &Pubkey, owner: &Pubkey, deposit_nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[crate::CHARGE_PDA_SEED.as_bytes(), owner.as_ref(), deposit_nonce.to_be_bytes().as_slice()],
+        program_id,
+    ).0
 }
 
 pub fn charge_commission_native(
@@ -121,13 +373,17 @@ pub fn charge_commission_native(
     token: CommissionToken,
     deposit_token: TokenType,
     deposit_token_amount: u64,
+    deposit_nonce: u64,
 ) -> Instruction {
+    let charge = get_charge_address(&program_id, &owner, deposit_nonce);
+
     Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(commission_admin, false),
             AccountMeta::new_readonly(bridge_admin, false),
             AccountMeta::new(owner, true),
+            AccountMeta::new(charge, false),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(spl_token::id(), false),
@@ -136,6 +392,7 @@ pub fn charge_commission_native(
             token,
             deposit_token,
             deposit_token_amount,
+            deposit_nonce,
         }).try_to_vec().unwrap(),
     }
 }
@@ -149,7 +406,10 @@ pub fn charge_commission_ft(
     token: CommissionToken,
     deposit_token: TokenType,
     deposit_token_amount: u64,
+    deposit_nonce: u64,
 ) -> Instruction {
+    let charge = get_charge_address(&program_id, &owner, deposit_nonce);
+
     let owner_associated = get_associated_token_address(&owner, &mint);
     let commission_associated = get_associated_token_address(&commission_admin, &mint);
 
@@ -159,6 +419,7 @@ pub fn charge_commission_ft(
             AccountMeta::new(commission_admin, false),
             AccountMeta::new_readonly(bridge_admin, false),
             AccountMeta::new(owner, true),
+            AccountMeta::new(charge, false),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(spl_token::id(), false),
@@ -170,6 +431,145 @@ pub fn charge_commission_ft(
             token,
             deposit_token,
             deposit_token_amount,
+            deposit_nonce,
         }).try_to_vec().unwrap(),
     }
-}
\ No newline at end of file
+}
+
+pub fn migrate_bridge_admin(
+    program_id: Pubkey,
+    commission_admin: Pubkey,
+    bridge_admin: Pubkey,
+    new_commission_admin: Pubkey,
+    new_bridge_admin: Pubkey,
+    fee_payer: Pubkey,
+    mints: Vec<Pubkey>,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(commission_admin, false),
+        AccountMeta::new_readonly(bridge_admin, false),
+        AccountMeta::new(new_commission_admin, false),
+        AccountMeta::new_readonly(new_bridge_admin, false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    for mint in mints {
+        accounts.push(AccountMeta::new(get_associated_token_address(&commission_admin, &mint), false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&new_commission_admin, &mint), false));
+        accounts.push(AccountMeta::new_readonly(mint, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: CommissionInstruction::MigrateBridgeAdmin(MigrateBridgeAdminArgs {
+            signature,
+            recovery_id,
+            path,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn get_collected(
+    program_id: Pubkey,
+    commission_admin: Pubkey,
+    bridge_admin: Pubkey,
+    token: CommissionToken,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(commission_admin, false),
+            AccountMeta::new_readonly(bridge_admin, false),
+        ],
+        data: CommissionInstruction::GetCollected(GetCollectedArgs {
+            token,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn migrate_state(
+    program_id: Pubkey,
+    commission_admin: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(commission_admin, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: CommissionInstruction::MigrateState.try_to_vec().unwrap(),
+    }
+}
+
+pub fn close_admin(
+    program_id: Pubkey,
+    commission_admin: Pubkey,
+    bridge_admin: Pubkey,
+    receiver: Pubkey,
+    mints: Vec<Pubkey>,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(commission_admin, false),
+        AccountMeta::new_readonly(bridge_admin, false),
+        AccountMeta::new(receiver, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    for mint in mints {
+        accounts.push(AccountMeta::new(get_associated_token_address(&commission_admin, &mint), false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: CommissionInstruction::CloseAdmin(CloseAdminArgs {
+            signature,
+            recovery_id,
+            path,
+        }).try_to_vec().unwrap(),
+    }
+}
+
+pub fn reset_nonces(
+    program_id: Pubkey,
+    commission_admin: Pubkey,
+    bridge_admin: Pubkey,
+    add_token_nonce: u64,
+    remove_token_nonce: u64,
+    update_token_nonce: u64,
+    withdraw_token_nonce: u64,
+    set_tokens_nonce: u64,
+    signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    recovery_id: u8,
+    path: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(commission_admin, false),
+            AccountMeta::new_readonly(bridge_admin, false),
+        ],
+        data: CommissionInstruction::ResetNonces(ResetNoncesArgs {
+            add_token_nonce,
+            remove_token_nonce,
+            update_token_nonce,
+            withdraw_token_nonce,
+            set_tokens_nonce,
+            signature,
+            recovery_id,
+            path,
+        }).try_to_vec().unwrap(),
+    }
+}
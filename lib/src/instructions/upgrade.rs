@@ -12,6 +12,7 @@ use std::mem::size_of;
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct InitializeAdminArgs {
     // ECDSA public key
     pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
@@ -20,6 +21,7 @@ pub struct InitializeAdminArgs {
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct TransferOwnershipArgs {
     // New ECDSA public key
     pub new_public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
@@ -30,13 +32,37 @@ pub struct TransferOwnershipArgs {
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct UpgradeArgs {
     pub signature: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     pub recovery_id: u8,
     pub path: Vec<[u8; 32]>,
+    // Upgrade of the upgrade program itself is rejected unless this flag is signed
+    pub allow_self_upgrade: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ChangeContractArgs {
+    pub new_contract: Pubkey,
+    // Signature of keccak256(UPGRADE_DOMAIN | "change_contract" | contract | new_contract | nonce) by the admin public key
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct ReleaseAuthorityArgs {
+    pub new_authority: Pubkey,
+    // Signature of keccak256(UPGRADE_DOMAIN | "release_authority" | contract | new_authority | nonce) by the admin public key
+    pub signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    pub recovery_id: u8,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub enum UpgradeInstruction {
     /// Initialize new UpgradeAdmin that will store acceptable token
     ///
@@ -71,4 +97,37 @@ pub enum UpgradeInstruction {
     ///   6. `[]` Clock sysvar.
     ///   7. `[]` BPFLoaderUpgradable program
     Upgrade(UpgradeArgs),
+
+    /// Move UpgradeAdmin to the new managed contract.
+    /// UpgradeAdmin address is derived from the contract, so the state is moved to the new account
+    /// and the old one can not be used anymore.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The UpgradeAdmin account
+    ///   1. `[writable]` The new UpgradeAdmin account for the new contract
+    ///   2. `[writable,signer]` The fee payer
+    ///   3. `[]` System program
+    ///   4. `[]` Rent sysvar
+    ChangeContract(ChangeContractArgs),
+
+    /// Move the UpgradeAdmin account of the deployed layout without the version byte to the current layout.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The UpgradeAdmin account
+    ///   1. `[writable,signer]` The payer account for the additional rent
+    ///   2. `[]` System program
+    MigrateState,
+
+    /// Transfer the contract upgrade authority from the UpgradeAdmin to the plain key.
+    /// After that the contract can not be upgraded by the UpgradeAdmin anymore.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The UpgradeAdmin account
+    ///   1. `[writable]` The ProgramData account of the contract stored in UpgradeAdmin
+    ///   2. `[]` The new upgrade authority account
+    ///   3. `[]` BPFLoaderUpgradable program
+    ReleaseAuthority(ReleaseAuthorityArgs),
 }
\ No newline at end of file
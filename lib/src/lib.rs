@@ -2,43 +2,492 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult, msg,
-    program::{invoke, invoke_signed}, pubkey::Pubkey, system_instruction,
+    program::{invoke, invoke_signed}, program_error::ProgramError, pubkey::Pubkey, system_instruction,
     sysvar::{rent::Rent, Sysvar},
 };
-use spl_associated_token_account::create_associated_token_account;
+use solana_program::log::sol_log_data;
+use solana_program::secp256k1_recover::SECP256K1_PUBLIC_KEY_LENGTH;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 
 pub mod merkle;
 pub mod ecdsa;
 pub mod error;
 pub mod instructions;
+#[cfg(feature = "schema")]
+pub mod schema;
 
 pub const SOLANA_NETWORK: &str = "Solana";
 
+// Signed content domains. Every program prepends its own domain to the hashed content,
+// so the signature for one program can not be used in another one with the same admin key.
+pub const BRIDGE_DOMAIN: &[u8] = b"RARIMO_BRIDGE";
+pub const COMMISSION_DOMAIN: &[u8] = b"RARIMO_COMMISSION";
+pub const UPGRADE_DOMAIN: &[u8] = b"RARIMO_UPGRADE";
+// Operation tag of the PromoteBackup signed by the backup key
+pub const PROMOTE_BACKUP_TAG: &str = "promote_backup";
+// Operation tag of the upgrade ChangeContract, separates its signed content from the other upgrade operations
+pub const CHANGE_CONTRACT_TAG: &str = "change_contract";
+// Operation tag of the upgrade ReleaseAuthority
+pub const RELEASE_AUTHORITY_TAG: &str = "release_authority";
+
 pub const COMMISSION_ADMIN_PDA_SEED: &str = "commission_admin";
 pub const UPGRADE_ADMIN_PDA_SEED: &str = "upgrade_admin";
+pub const WITHDRAW_ANNOUNCEMENT_PDA_SEED: &str = "withdraw_announcement";
+pub const CHARGE_PDA_SEED: &str = "charge";
+pub const NETWORKS_PDA_SEED: &str = "networks";
+pub const TOKEN_CONFIG_PDA_SEED: &str = "token_config";
+pub const COLLECTION_DELEGATE_PDA_SEED: &str = "collection_delegate";
+pub const AUTHORIZED_ORIGINS_PDA_SEED: &str = "authorized_origins";
+pub const MINT_CAP_PDA_SEED: &str = "mint_cap";
+pub const STORED_ROOTS_PDA_SEED: &str = "stored_roots";
+
+// Version of the state accounts layout, stored as the leading byte of BridgeAdmin, Withdraw, CommissionAdmin and UpgradeAdmin.
+// Should be increased by every layout change of these accounts, MigrateState moves the accounts of the previous versions.
+pub const STATE_VERSION: u8 = 1;
+// Accounts created by the deployed program versions before the layout versioning, they have no version byte
+pub const BASELINE_STATE_VERSION: u8 = 0;
+
+// Prefix of the program log with the created Withdraw account address, parsed by relayers
+pub const WITHDRAW_PDA_LOG_PREFIX: &str = "WITHDRAW_PDA";
+
+// Prefix of the program data log with the Borsh-encoded KeyRotated event
+pub const KEY_ROTATED_LOG_PREFIX: &str = "KEY_ROTATED";
+
+/// Emitted by the bridge and upgrade TransferOwnership as `sol_log_data([KEY_ROTATED_LOG_PREFIX, Borsh(KeyRotated)])`.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct KeyRotated {
+    // Admin account that changed the key
+    pub admin: Pubkey,
+    pub old_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+    pub new_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
+}
+
+impl KeyRotated {
+    pub fn log(&self) {
+        sol_log_data(&[KEY_ROTATED_LOG_PREFIX.as_bytes(), self.try_to_vec().unwrap().as_slice()]);
+    }
+}
+
+// Prefix of the program data log with the Borsh-encoded Deposited event
+pub const DEPOSITED_LOG_PREFIX: &str = "DEPOSITED";
+
+/// Emitted by the bridge FT deposit as `sol_log_data([DEPOSITED_LOG_PREFIX, Borsh(Deposited)])`.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct Deposited {
+    pub mint: Pubkey,
+    // Amount transferred or burned in the mint decimals
+    pub amount: u64,
+    // Amount in the target decimals of the token config, used by the cross-chain message
+    pub normalized_amount: u64,
+    pub network_to: Network,
+    pub receiver_address: String,
+}
+
+impl Deposited {
+    pub fn log(&self) {
+        sol_log_data(&[DEPOSITED_LOG_PREFIX.as_bytes(), self.try_to_vec().unwrap().as_slice()]);
+    }
+}
+
+/// Converts the amount from `decimals` to `target_decimals`. Scaling down fails if the amount has
+/// the non-zero digits below the target precision, scaling up fails on overflow.
+pub fn normalize_amount(amount: u64, decimals: u8, target_decimals: u8) -> Result<u64, ProgramError> {
+    let scale = |diff: u8| 10u64.checked_pow(diff as u32).ok_or(error::LibError::WrongAmountPrecision);
+
+    if target_decimals >= decimals {
+        let factor = scale(target_decimals - decimals)?;
+        return amount.checked_mul(factor).ok_or(error::LibError::WrongAmountPrecision.into());
+    }
+
+    let factor = scale(decimals - target_decimals)?;
+    if amount % factor != 0 {
+        return Err(error::LibError::WrongAmountPrecision.into());
+    }
+
+    Ok(amount / factor)
+}
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub enum TokenType {
     Native,
     FT,
     NFT,
 }
 
+impl TokenType {
+    /// Stable token class used in the signed content: Native = 0, FT = 1, NFT = 2.
+    /// Does not depend on the enum variants order.
+    pub fn class(&self) -> u8 {
+        match self {
+            TokenType::Native => 0,
+            TokenType::FT => 1,
+            TokenType::NFT => 2,
+        }
+    }
+}
+
+/// Network name of the deposit target and the supported networks list.
+/// Known networks are compared and hashed without the string allocation, the other names are kept as `Custom`.
+/// Borsh encoding is the plain name string for all variants, so it is the same as for the `String` field
+/// it replaces: the instruction data and the stored lists are unchanged.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Network {
+    Solana,
+    Ethereum,
+    Polygon,
+    BSC,
+    Avalanche,
+    Near,
+    Custom(String),
+}
+
+impl Network {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Network::Solana => SOLANA_NETWORK,
+            Network::Ethereum => "Ethereum",
+            Network::Polygon => "Polygon",
+            Network::BSC => "BSC",
+            Network::Avalanche => "Avalanche",
+            Network::Near => "Near",
+            Network::Custom(name) => name.as_str(),
+        }
+    }
+
+    /// Name bytes, used for the hashing and the size limits.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+}
+
+impl From<&str> for Network {
+    /// Known names are case-sensitive, so `Custom` never holds the name of a known network.
+    fn from(name: &str) -> Self {
+        match name {
+            SOLANA_NETWORK => Network::Solana,
+            "Ethereum" => Network::Ethereum,
+            "Polygon" => Network::Polygon,
+            "BSC" => Network::BSC,
+            "Avalanche" => Network::Avalanche,
+            "Near" => Network::Near,
+            _ => Network::Custom(String::from(name)),
+        }
+    }
+}
+
+impl From<String> for Network {
+    fn from(name: String) -> Self {
+        match Network::from(name.as_str()) {
+            Network::Custom(_) => Network::Custom(name),
+            known => known,
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl BorshSerialize for Network {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(self.as_str(), writer)
+    }
+}
+
+impl BorshDeserialize for Network {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let name: String = BorshDeserialize::deserialize(buf)?;
+        Ok(Network::from(name))
+    }
+}
+
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// Format of the deposit receiver address on the target network.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub enum AddressFormat {
+    // Not checked, only the length limit of the deposit args
+    Any,
+    // 0x prefixed 20 bytes hex (EVM networks)
+    Hex,
+    // 32 bytes base58 (Solana)
+    Base58,
+    // Bech32 or bech32m with the valid checksum (Cosmos networks)
+    Bech32,
+}
+
+impl AddressFormat {
+    pub fn validate(&self, address: &str) -> ProgramResult {
+        let valid = match self {
+            AddressFormat::Any => true,
+            AddressFormat::Hex => is_hex_address(address),
+            AddressFormat::Base58 => matches!(bs58::decode(address).into_vec(), Ok(key) if key.len() == 32),
+            AddressFormat::Bech32 => is_bech32_address(address),
+        };
+
+        if !valid {
+            msg!("Address {} does not match {:?} format", address, self);
+            return Err(error::LibError::WrongAddressFormat.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Address format of the network in the supported networks list.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct NetworkFormat {
+    pub network: Network,
+    pub format: AddressFormat,
+}
+
+fn is_hex_address(address: &str) -> bool {
+    match address.strip_prefix("0x") {
+        Some(hex) => hex.len() == 40 && hex.bytes().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+fn is_bech32_address(address: &str) -> bool {
+    if address.len() < 8 || address.len() > 90 {
+        return false;
+    }
+
+    // Mixed case is not allowed, the checksum is computed over the lowercase string
+    let lowercase = address.to_ascii_lowercase();
+    if address != lowercase && address != address.to_ascii_uppercase() {
+        return false;
+    }
+
+    let (hrp, data) = match lowercase.rfind('1') {
+        Some(pos) if pos > 0 && lowercase.len() - pos > 6 => (&lowercase[..pos], &lowercase[pos + 1..]),
+        _ => return false,
+    };
+
+    if !hrp.bytes().all(|c| (33..=126).contains(&c)) {
+        return false;
+    }
+
+    let mut values: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|c| c & 31));
+
+    for c in data.bytes() {
+        match BECH32_CHARSET.iter().position(|&v| v == c) {
+            Some(v) => values.push(v as u8),
+            None => return false,
+        }
+    }
+
+    let checksum = bech32_polymod(&values);
+    checksum == BECH32_CONST || checksum == BECH32M_CONST
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    let mut checksum: u32 = 1;
+    for v in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ (*v as u32);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= g;
+            }
+        }
+    }
+
+    checksum
+}
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub enum CommissionToken {
     Native,
     FT(Pubkey),
     NFT(Pubkey),
+    // Flat native fee, accepted for the commission charged in a token missing from the acceptable list
+    Any,
+}
+
+impl CommissionToken {
+    /// Stable token class used in the signed content: Native = 0, FT = 1, NFT = 2, Any = 3.
+    /// Does not depend on the enum variants order.
+    pub fn class(&self) -> u8 {
+        match self {
+            CommissionToken::Native => 0,
+            CommissionToken::FT(_) => 1,
+            CommissionToken::NFT(_) => 2,
+            CommissionToken::Any => 3,
+        }
+    }
+
+    /// Canonical ordering key: token class, then mint bytes (zero for Native and Any).
+    /// Commission acceptable tokens list is kept sorted by it.
+    pub fn sort_key(&self) -> (u8, [u8; 32]) {
+        match self {
+            CommissionToken::Native | CommissionToken::Any => (self.class(), [0u8; 32]),
+            CommissionToken::FT(mint) | CommissionToken::NFT(mint) => (self.class(), mint.to_bytes()),
+        }
+    }
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
 pub struct CommissionArgs {
     pub token: CommissionToken,
     pub deposit_token: TokenType,
     pub deposit_token_amount: u64,
+    // Unique per owner deposit nonce, the commission is charged only once for it
+    pub deposit_nonce: u64,
+}
+
+/// Checks that at least `count` accounts were passed to the instruction.
+/// Logs the instruction name, so the missing accounts error is not a bare `NotEnoughAccountKeys`.
+pub fn expect_accounts(accounts: &[AccountInfo], count: usize, instruction: &str) -> ProgramResult {
+    if accounts.len() < count {
+        msg!("{} expects at least {} accounts, got {}", instruction, count, accounts.len());
+        return Err(error::LibError::NotEnoughAccounts.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that the account signed the transaction.
+/// Transfers from the unsigned account fail inside the CPI anyway, the early check gives the clear error.
+pub fn expect_signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer {
+        msg!("Account {} should be a signer", account.key);
+        return Err(error::LibError::MissingSigner.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that the admin PDA does not exist yet before creating it.
+/// Pre-funded by system transfer account makes `create_account` fail with the unclear error, so it is reported explicitly.
+pub fn expect_empty_admin(account: &AccountInfo) -> ProgramResult {
+    if !account.data_is_empty() {
+        msg!("Admin account {} is already initialized", account.key);
+        return Err(error::LibError::AlreadyInUse.into());
+    }
+
+    if account.lamports() > 0 {
+        msg!("Admin account {} is pre-funded with {} lamports", account.key, account.lamports());
+        return Err(error::LibError::AlreadyInUse.into());
+    }
+
+    Ok(())
+}
+
+/// Serializes the state with variable-length data (vectors, strings) into the account.
+/// Fails with `WrongDataLen` if the serialized state does not fit the account data, so nothing is written partially.
+pub fn serialize_checked<T: BorshSerialize>(state: &T, account: &AccountInfo) -> ProgramResult {
+    let data = state.try_to_vec()?;
+    let mut account_data = account.data.borrow_mut();
+    if data.len() > account_data.len() {
+        msg!("Serialized state of {} bytes does not fit account {} of {} bytes", data.len(), account.key, account_data.len());
+        return Err(error::LibError::WrongDataLen.into());
+    }
+
+    account_data[..data.len()].copy_from_slice(data.as_slice());
+    Ok(())
+}
+
+/// Checks that the state account layout version is supported by the program.
+pub fn check_version(version: u8) -> ProgramResult {
+    match version {
+        STATE_VERSION => Ok(()),
+        BASELINE_STATE_VERSION => {
+            msg!("State account should be moved to the version {} by MigrateState", STATE_VERSION);
+            Err(error::LibError::UnsupportedVersion.into())
+        }
+        _ => {
+            msg!("Unsupported state version {}", version);
+            Err(error::LibError::UnsupportedVersion.into())
+        }
+    }
+}
+
+/// Layout version of the program state account. The accounts of the deployed baseline layout have no version byte,
+/// they are recognized by `baseline_size` and reported as `BASELINE_STATE_VERSION`.
+pub fn state_version(account: &AccountInfo, baseline_size: usize) -> Result<u8, ProgramError> {
+    if account.data_len() == baseline_size {
+        return Ok(BASELINE_STATE_VERSION);
+    }
+
+    match account.data.borrow().first() {
+        Some(version) => Ok(*version),
+        None => Err(error::LibError::NotInitialized.into()),
+    }
+}
+
+/// Moves the account of the deployed baseline layout to the current one, when the current layout is the version byte
+/// followed by the baseline fields and the appended fields, that are zero by default: reallocates the account
+/// to `versioned_size` and prepends the `STATE_VERSION` byte to the data. The rent is paid by the payer.
+/// Accounts of the current version are left as is.
+pub fn migrate_legacy_account<'a>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    baseline_size: usize,
+    versioned_size: usize,
+) -> ProgramResult {
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    match state_version(account, baseline_size)? {
+        BASELINE_STATE_VERSION => {
+            realloc_with_rent(account, payer, system_program, versioned_size)?;
+
+            let mut data = account.data.borrow_mut();
+            data.copy_within(0..baseline_size, 1);
+            data[0] = STATE_VERSION;
+            Ok(())
+        }
+        STATE_VERSION => {
+            msg!("Account {} is already of the current version", account.key);
+            Ok(())
+        }
+        version => check_version(version),
+    }
+}
+
+/// Reallocates the program account to the new size (new bytes are zeroed),
+/// the payer tops up the lamports to stay rent-exempt.
+pub fn realloc_with_rent<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_size: usize,
+) -> ProgramResult {
+    let required = Rent::get()?.minimum_balance(new_size);
+    if account.lamports() < required {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required - account.lamports()),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    account.realloc(new_size, true)
 }
 
 pub fn call_create_account<'a>(
@@ -73,6 +522,9 @@ pub fn call_create_account<'a>(
     }
 }
 
+/// Creates the associated token account with the idempotent instruction, so the account
+/// created by another instruction after the caller's emptiness check does not fail the transaction.
+/// The created (or already existing) account is checked to be owned by the token program.
 pub fn call_create_associated_account<'a>(
     payer: &AccountInfo<'a>,
     wallet: &AccountInfo<'a>,
@@ -83,10 +535,11 @@ pub fn call_create_associated_account<'a>(
     spl_token: &AccountInfo<'a>,
 ) -> ProgramResult {
     invoke(
-        &create_associated_token_account(
+        &create_associated_token_account_idempotent(
             payer.key,
             wallet.key,
             mint.key,
+            spl_token.key,
         ),
         &[
             payer.clone(),
@@ -97,5 +550,11 @@ pub fn call_create_associated_account<'a>(
             spl_token.clone(),
             rent_info.clone()
         ],
-    )
+    )?;
+
+    if account.owner != spl_token.key || account.data_is_empty() {
+        return Err(error::LibError::WrongTokenAccount.into());
+    }
+
+    Ok(())
 }
\ No newline at end of file
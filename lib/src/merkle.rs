@@ -1,4 +1,7 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::program_error::ProgramError;
+use crate::{BRIDGE_DOMAIN, SOLANA_NETWORK};
+use crate::error::LibError;
 
 pub fn amount_bytes(amount: u64) -> Vec<u8> {
     let mut result: [u8; 32] = [0; 32];
@@ -11,15 +14,330 @@ pub fn amount_bytes(amount: u64) -> Vec<u8> {
     return Vec::from(result);
 }
 
-pub fn get_merkle_root(mut hash: solana_program::keccak::Hash, path: &Vec<[u8; 32]>) -> Result<[u8; 32], ProgramError> {
+// Paths up to that length are hashed in the stack buffer without heap allocations.
+// Short proofs are the common case for the small trees, it saves a `Vec` allocation per path element.
+pub const SHORT_PATH_LENGTH: usize = 4;
+
+/// Hash function of the withdrawal leaves and Merkle trees, selected by the bridge admin at initialization.
+/// Sha256 is used for the interop with the source chains that sign sha256 trees (e.g. Cosmos-based).
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub enum HashAlgo {
+    Keccak,
+    Sha256,
+}
+
+impl HashAlgo {
+    pub fn hashv(&self, vals: &[&[u8]]) -> [u8; 32] {
+        match self {
+            HashAlgo::Keccak => solana_program::keccak::hashv(vals).to_bytes(),
+            HashAlgo::Sha256 => solana_program::hash::hashv(vals).to_bytes(),
+        }
+    }
+
+    pub fn hash(&self, val: &[u8]) -> [u8; 32] {
+        self.hashv(&[val])
+    }
+}
+
+pub fn get_merkle_root(hash: solana_program::keccak::Hash, path: &Vec<[u8; 32]>) -> Result<[u8; 32], ProgramError> {
+    get_merkle_root_with(HashAlgo::Keccak, hash.to_bytes(), path)
+}
+
+/// Same as `get_merkle_root` with the selected hash function.
+///
+/// Every pair is hashed in the sorted order: the greater of the path element and the current hash
+/// (compared as big-endian bytes) goes first, `hash(max | min)`. Equal hashes produce the same
+/// concatenation in any order, so the tree builder needs no tie-break convention for them.
+pub fn get_merkle_root_with(algo: HashAlgo, hash: [u8; 32], path: &Vec<[u8; 32]>) -> Result<[u8; 32], ProgramError> {
+    // Every path element costs a hash call, longer paths are never valid proofs
+    if path.len() > MAX_MERKLE_DEPTH {
+        return Err(LibError::WrongMerklePath.into());
+    }
+
+    let root = if path.len() <= SHORT_PATH_LENGTH {
+        get_short_merkle_root(algo, hash, path)
+    } else {
+        get_long_merkle_root(algo, hash, path)
+    };
+
+    Result::Ok(root)
+}
+
+// Max depth of the signed Merkle trees (2^32 leaves), so the max length of any Merkle path
+pub const MAX_MERKLE_DEPTH: usize = 32;
+
+// Max length of the withdrawal Merkle proof
+pub const MAX_PROOF_LENGTH: usize = MAX_MERKLE_DEPTH;
+
+/// Merkle path from the leaf to the signed root.
+/// Borsh encoding is the same as for `Vec<[u8; 32]>`.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(borsh::BorshSchema))]
+pub struct MerkleProof(pub Vec<[u8; 32]>);
+
+impl MerkleProof {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Computes the root for the leaf. Fails if the proof is empty or longer than `MAX_PROOF_LENGTH`.
+    pub fn root_from_leaf(&self, leaf: solana_program::keccak::Hash) -> Result<[u8; 32], ProgramError> {
+        if self.is_empty() || self.len() > MAX_PROOF_LENGTH {
+            return Err(LibError::WrongMerkleProof.into());
+        }
+
+        get_merkle_root(leaf, &self.0)
+    }
+
+    /// Same as `root_from_leaf` with the selected hash function.
+    pub fn root_from_leaf_with(&self, algo: HashAlgo, leaf: [u8; 32]) -> Result<[u8; 32], ProgramError> {
+        if self.is_empty() || self.len() > MAX_PROOF_LENGTH {
+            return Err(LibError::WrongMerkleProof.into());
+        }
+
+        get_merkle_root_with(algo, leaf, &self.0)
+    }
+}
+
+fn get_short_merkle_root(algo: HashAlgo, mut hash: [u8; 32], path: &Vec<[u8; 32]>) -> [u8; 32] {
+    let mut buffer: [u8; 64] = [0; 64];
+
     for i in 0..path.len() {
-        let leaf = solana_program::keccak::Hash::new_from_array(path[i]);
+        let leaf = path[i];
+        // The order of equal hashes does not change the buffer
         if leaf >= hash {
-            hash = solana_program::keccak::hash([leaf.as_ref(), hash.as_ref()].concat().as_slice());
+            buffer[..32].copy_from_slice(leaf.as_ref());
+            buffer[32..].copy_from_slice(hash.as_ref());
         } else {
-            hash = solana_program::keccak::hash([hash.as_ref(), leaf.as_ref()].concat().as_slice());
+            buffer[..32].copy_from_slice(hash.as_ref());
+            buffer[32..].copy_from_slice(leaf.as_ref());
+        }
+
+        hash = algo.hash(buffer.as_slice());
+    }
+
+    hash
+}
+
+fn get_long_merkle_root(algo: HashAlgo, mut hash: [u8; 32], path: &Vec<[u8; 32]>) -> [u8; 32] {
+    for i in 0..path.len() {
+        let leaf = path[i];
+        // The order of equal hashes does not change the concatenation
+        if leaf >= hash {
+            hash = algo.hash([leaf.as_ref(), hash.as_ref()].concat().as_slice());
+        } else {
+            hash = algo.hash([hash.as_ref(), leaf.as_ref()].concat().as_slice());
+        }
+    }
+
+    hash
+}
+
+// Decimals of SOL. They are implied by the native token, so the native leaf operation does not contain them
+pub const SOLANA_NATIVE_DECIMALS: u8 = 9u8;
+
+// EIP-191 version byte 0x01 prefix of the EIP-712 typed data digest
+pub const EIP712_PREFIX: &[u8] = &[0x19, 0x01];
+
+/// Metadata string as it is included into the leaf: only the trailing null bytes (the padding of the
+/// Metaplex metadata fields) are removed. Leading and embedded nulls and any whitespace are kept.
+pub fn normalize_metadata_field(field: &str) -> String {
+    field.trim_end_matches(char::from(0)).to_string()
+}
+
+/// Operation data that is included into the bridge Merkle leaf.
+pub trait Data {
+    fn get_operation(&self) -> Vec<u8>;
+}
+
+/// Bridge withdrawal Merkle leaf content.
+/// Shared with off-chain relayers so both sides produce exactly the same leaf hash.
+pub struct Content {
+    pub origin: [u8; 32],
+    pub network_to: String,
+    pub receiver: [u8;32],
+    pub program_id: [u8; 32],
+    pub data: Vec<u8>,
+}
+
+impl Content {
+    pub fn new(origin: [u8; 32], receiver: [u8;32], program_id: [u8; 32], data: Box<dyn Data>) -> Self {
+        Content {
+            origin,
+            receiver,
+            network_to: String::from(SOLANA_NETWORK),
+            program_id,
+            data: data.get_operation(),
+        }
+    }
+
+    /// Leaf hash: keccak256(BRIDGE_DOMAIN | data | origin | network_to | receiver | program_id)
+    pub fn hash(self) -> solana_program::keccak::Hash {
+        solana_program::keccak::Hash::new_from_array(self.hash_with(HashAlgo::Keccak))
+    }
+
+    /// Leaf hash with the selected hash function, the hashed data is the same as for `hash`.
+    pub fn hash_with(self, algo: HashAlgo) -> [u8; 32] {
+        let mut data = Vec::from(BRIDGE_DOMAIN);
+        data.append(&mut Vec::from(self.data));
+
+        data.append(&mut Vec::from(self.origin.as_slice()));
+
+        data.append(&mut Vec::from(self.network_to.as_bytes()));
+
+        data.append(&mut Vec::from(self.receiver.as_slice()));
+
+        data.append(&mut Vec::from(self.program_id.as_slice()));
+
+        algo.hash(data.as_slice())
+    }
+
+    /// EIP-712 style leaf hash: keccak256(0x1901 | domain_separator | hash()),
+    /// matches the digest of the typed data signed by the EVM-side relayers.
+    pub fn hash_eip712(self, domain_separator: [u8; 32]) -> solana_program::keccak::Hash {
+        let struct_hash = self.hash();
+        solana_program::keccak::hashv(&[EIP712_PREFIX, domain_separator.as_slice(), struct_hash.as_ref()])
+    }
+
+    /// Leaf hash selected by the withdrawal: EIP-712 style if the domain separator is provided, raw otherwise.
+    pub fn leaf_hash(self, eip712_domain_separator: Option<[u8; 32]>) -> solana_program::keccak::Hash {
+        match eip712_domain_separator {
+            Some(domain_separator) => self.hash_eip712(domain_separator),
+            None => self.hash(),
+        }
+    }
+
+    /// Leaf hash with the selected hash function. EIP-712 digest is defined only for keccak,
+    /// so the domain separator with another function fails with `InvalidMessage`.
+    pub fn leaf_hash_with(self, algo: HashAlgo, eip712_domain_separator: Option<[u8; 32]>) -> Result<[u8; 32], ProgramError> {
+        match (algo, eip712_domain_separator) {
+            (HashAlgo::Keccak, _) => Ok(self.leaf_hash(eip712_domain_separator).to_bytes()),
+            (_, None) => Ok(self.hash_with(algo)),
+            (_, Some(_)) => Err(LibError::InvalidMessage.into()),
+        }
+    }
+}
+
+pub struct TransferData {
+    // Empty line if is native
+    pub address_to: Option<[u8; 32]>,
+    // Empty line if is native or fungible
+    pub token_id_to: Option<[u8; 32]>,
+    pub amount: Option<u64>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub decimals: Option<u8>,
+    // Supply cap of the FT mint, signed only by the withdrawal that creates the mint
+    pub max_supply: Option<u64>,
+}
+
+impl TransferData {
+    pub fn new_ft_transfer(mint: [u8; 32], amount: u64, name: String, symbol: String, uri: String, decimals: u8) -> Self {
+        TransferData {
+            address_to: Some(mint),
+            token_id_to: None,
+            amount: Some(amount),
+            name: Some(name),
+            symbol: Some(symbol),
+            uri: Some(uri),
+            decimals: Some(decimals),
+            max_supply: None,
+        }
+    }
+
+    // For the tokens without metadata: operation bytes are mint | amount | decimals
+    pub fn new_ft_transfer_without_metadata(mint: [u8; 32], amount: u64, decimals: u8) -> Self {
+        TransferData {
+            address_to: Some(mint),
+            token_id_to: None,
+            amount: Some(amount),
+            name: None,
+            symbol: None,
+            uri: None,
+            decimals: Some(decimals),
+            max_supply: None,
         }
     }
 
-    Result::Ok(hash.to_bytes())
+    pub fn new_nft_transfer(mint: [u8; 32], collection: Option<[u8; 32]>, name: String, symbol: String, uri: String) -> Self {
+        TransferData {
+            address_to: collection,
+            token_id_to: Some(mint),
+            amount: None,
+            name: Some(name),
+            symbol: Some(symbol),
+            uri: Some(uri),
+            decimals: None,
+            max_supply: None,
+        }
+    }
+
+    /// Native operation is only the 32 bytes BE amount in lamports, without `SOLANA_NATIVE_DECIMALS`.
+    pub fn new_native_transfer(amount: u64) -> Self {
+        TransferData {
+            amount: Some(amount),
+            address_to: None,
+            token_id_to: None,
+            name: None,
+            symbol: None,
+            uri: None,
+            decimals: None,
+            max_supply: None,
+        }
+    }
+
+    pub fn with_max_supply(mut self, max_supply: Option<u64>) -> Self {
+        self.max_supply = max_supply;
+        self
+    }
+}
+
+// Operation bytes: address_to | name | token_id_to | uri | amount (32 bytes BE) | symbol | decimals | max_supply (32 bytes BE).
+// Absent fields are skipped.
+impl Data for TransferData {
+    fn get_operation(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        if let Some(val) = self.address_to {
+            data.append(&mut Vec::from(val.as_slice()));
+        }
+
+        if let Some(val) = &self.name {
+            data.append(&mut Vec::from(val.as_bytes()));
+        }
+
+        if let Some(val) = self.token_id_to {
+            data.append(&mut Vec::from(val.as_slice()));
+        }
+
+        if let Some(val) = &self.uri {
+            data.append(&mut Vec::from(val.as_bytes()));
+        }
+
+        if let Some(val) = self.amount {
+            data.append(&mut Vec::from(amount_bytes(val)));
+        }
+
+        if let Some(val) = &self.symbol {
+            data.append(&mut Vec::from(val.as_bytes()));
+        }
+
+        if let Some(val) = self.decimals {
+            data.push(val);
+        }
+
+        if let Some(val) = self.max_supply {
+            data.append(&mut Vec::from(amount_bytes(val)));
+        }
+
+        data
+    }
 }
\ No newline at end of file
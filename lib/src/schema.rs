@@ -0,0 +1,84 @@
+//! Borsh schema of the instruction args for the clients in other languages (`schema` feature).
+//! `BorshSchemaContainer` of the instruction enums is dumped by the `dump_schema` binary.
+
+use std::collections::HashMap;
+use borsh::schema::{BorshSchema, Declaration, Definition};
+use crate::Network;
+
+// Network is encoded as the plain name string
+impl BorshSchema for Network {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        String::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        String::declaration()
+    }
+}
+
+impl BorshSchema for crate::instructions::bridge::MintNFTArgs {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        layout::MintNFTArgs::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        layout::MintNFTArgs::declaration()
+    }
+}
+
+// Mirrors of the Metaplex types without the schema, the layout and names should be the same as of the originals
+mod layout {
+    use borsh::BorshSchema;
+    use solana_program::pubkey::Pubkey;
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    pub struct MintNFTArgs {
+        pub data: DataV2,
+        pub seeds: [u8; 32],
+        pub verify: bool,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    pub struct DataV2 {
+        pub name: String,
+        pub symbol: String,
+        pub uri: String,
+        pub seller_fee_basis_points: u16,
+        pub creators: Option<Vec<Creator>>,
+        pub collection: Option<Collection>,
+        pub uses: Option<Uses>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    pub struct Creator {
+        pub address: Pubkey,
+        pub verified: bool,
+        pub share: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    pub struct Collection {
+        pub verified: bool,
+        pub key: Pubkey,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    pub enum UseMethod {
+        Burn,
+        Multiple,
+        Single,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    pub struct Uses {
+        pub use_method: UseMethod,
+        pub remaining: u64,
+        pub total: u64,
+    }
+}
@@ -0,0 +1,55 @@
+use lib::error::LibError;
+use lib::AddressFormat;
+
+fn rejected(format: AddressFormat, address: &str) {
+    assert_eq!(format.validate(address), Err(LibError::WrongAddressFormat.into()), "{:?} {}", format, address);
+}
+
+#[test]
+fn hex_address_is_checked() {
+    assert_eq!(AddressFormat::Hex.validate("0x52908400098527886E0F7030069857D2E4169EE7"), Ok(()));
+    assert_eq!(AddressFormat::Hex.validate("0xde709f2102306220921060314715629080e2fb77"), Ok(()));
+
+    rejected(AddressFormat::Hex, "52908400098527886E0F7030069857D2E4169EE7");
+    rejected(AddressFormat::Hex, "0x52908400098527886E0F7030069857D2E4169EE");
+    rejected(AddressFormat::Hex, "0x52908400098527886E0F7030069857D2E4169EE70");
+    rejected(AddressFormat::Hex, "0x52908400098527886E0F7030069857D2E4169EEG");
+}
+
+#[test]
+fn base58_address_is_checked() {
+    assert_eq!(AddressFormat::Base58.validate("11111111111111111111111111111111"), Ok(()));
+    assert_eq!(AddressFormat::Base58.validate("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"), Ok(()));
+
+    // 0 and l are not in the alphabet
+    rejected(AddressFormat::Base58, "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ50A");
+    // Not 32 bytes
+    rejected(AddressFormat::Base58, "1111111111111111111111111111111");
+    rejected(AddressFormat::Base58, "0x52908400098527886E0F7030069857D2E4169EE7");
+}
+
+#[test]
+fn bech32_address_is_checked() {
+    // BIP-173 and BIP-350 test vectors
+    assert_eq!(AddressFormat::Bech32.validate("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"), Ok(()));
+    assert_eq!(AddressFormat::Bech32.validate("A12UEL5L"), Ok(()));
+    assert_eq!(AddressFormat::Bech32.validate("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw"), Ok(()));
+    assert_eq!(AddressFormat::Bech32.validate("a1lqfn3a"), Ok(()));
+
+    // Wrong checksum
+    rejected(AddressFormat::Bech32, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5");
+    // Mixed case
+    rejected(AddressFormat::Bech32, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kV8f3t4");
+    // No separator, empty human readable part, character outside of the charset
+    rejected(AddressFormat::Bech32, "pzry9x0s0muk");
+    rejected(AddressFormat::Bech32, "1pzry9x0s0muk");
+    rejected(AddressFormat::Bech32, "x1b4n0q5v");
+    rejected(AddressFormat::Bech32, "0x52908400098527886E0F7030069857D2E4169EE7");
+}
+
+#[test]
+fn any_address_is_accepted() {
+    for address in ["", "0x1", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5", "anything"] {
+        assert_eq!(AddressFormat::Any.validate(address), Ok(()));
+    }
+}
@@ -0,0 +1,29 @@
+use lib::instructions::commission::{commission_admin_address, commission_admin_seeds};
+use lib::COMMISSION_ADMIN_PDA_SEED;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn derived_address_matches_creation_seeds() {
+    let program_id = Pubkey::new_unique();
+    let mut derived = 0;
+    for _ in 0..16 {
+        let bridge_admin = Pubkey::new_unique();
+        let created = Pubkey::create_program_address(&[b"commission_admin", bridge_admin.as_ref()], &program_id);
+        match commission_admin_address(&program_id, &bridge_admin) {
+            Ok(key) => {
+                assert_eq!(created, Ok(key));
+                derived += 1;
+            }
+            // The scheme has no bump seed, so some bridge admins have no commission admin
+            Err(_) => assert!(created.is_err()),
+        }
+    }
+    assert!(derived > 0);
+}
+
+#[test]
+fn seeds_are_public() {
+    let bridge_admin = Pubkey::new_unique();
+    assert_eq!(COMMISSION_ADMIN_PDA_SEED, "commission_admin");
+    assert_eq!(commission_admin_seeds(&bridge_admin), [COMMISSION_ADMIN_PDA_SEED.as_bytes(), bridge_admin.as_ref()]);
+}
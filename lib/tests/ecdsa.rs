@@ -0,0 +1,79 @@
+use lib::ecdsa::{eth_address, is_low_s, verify_ecdsa_signature, verify_ecdsa_signature_eth_address, verify_ecdsa_signer, SECP256K1_HALF_ORDER};
+use lib::error::LibError;
+use solana_program::secp256k1_recover::secp256k1_recover;
+
+// secp256k1 curve order (big-endian)
+const ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+// Low-S signature of the hash (libsecp256k1 normalizes S) with the signer public key
+fn sign(hash: &[u8; 32]) -> ([u8; 64], u8, [u8; 64]) {
+    let secret = libsecp256k1::SecretKey::parse(&[1; 32]).unwrap();
+    let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(hash), &secret);
+    let public_key = libsecp256k1::PublicKey::from_secret_key(&secret).serialize();
+    (signature.serialize(), recovery_id.serialize(), public_key[1..].try_into().unwrap())
+}
+
+// Malleable copy of the signature: `r | n - s` recovered with the other recovery id
+fn high_s(signature: &[u8; 64], recovery_id: u8) -> ([u8; 64], u8) {
+    let mut result = *signature;
+    let mut borrow = 0u16;
+    for i in (0..32).rev() {
+        let diff = ORDER[i] as u16 + 0x100 - signature[32 + i] as u16 - borrow;
+        result[32 + i] = diff as u8;
+        borrow = if diff < 0x100 { 1 } else { 0 };
+    }
+    (result, recovery_id ^ 1)
+}
+
+#[test]
+fn low_s_signature_is_accepted() {
+    let hash = solana_program::keccak::hash(b"withdraw").to_bytes();
+    let (signature, recovery_id, public_key) = sign(&hash);
+    assert!(is_low_s(&signature));
+    assert_eq!(verify_ecdsa_signature(&hash, &signature, recovery_id, public_key), Ok(()));
+}
+
+#[test]
+fn high_s_signature_is_rejected() {
+    let hash = solana_program::keccak::hash(b"withdraw").to_bytes();
+    let (signature, recovery_id, public_key) = sign(&hash);
+    let (malleable, malleable_recovery_id) = high_s(&signature, recovery_id);
+
+    // The copy recovers the same key, so only the S check rejects it
+    assert_eq!(secp256k1_recover(&hash, malleable_recovery_id, &malleable).unwrap().to_bytes(), public_key);
+    assert!(!is_low_s(&malleable));
+    assert_eq!(verify_ecdsa_signature(&hash, &malleable, malleable_recovery_id, public_key), Err(LibError::InvalidSignature.into()));
+}
+
+#[test]
+fn half_order_is_the_boundary() {
+    let mut signature = [0u8; 64];
+    signature[32..].copy_from_slice(&SECP256K1_HALF_ORDER);
+    assert!(is_low_s(&signature));
+
+    signature[63] += 1;
+    assert!(!is_low_s(&signature));
+    assert!(!is_low_s(&signature[..63]));
+}
+
+#[test]
+fn signature_is_verified_against_eth_address() {
+    let hash = solana_program::keccak::hash(b"withdraw").to_bytes();
+    let (signature, recovery_id, public_key) = sign(&hash);
+    let address = eth_address(&public_key);
+    assert_eq!(address.as_slice(), &solana_program::keccak::hash(&public_key).to_bytes()[12..]);
+
+    assert_eq!(verify_ecdsa_signature_eth_address(&hash, &signature, recovery_id, address), Ok(()));
+    assert_eq!(verify_ecdsa_signer(&hash, &signature, recovery_id, [0; 64], Some(address)), Ok(()));
+
+    let mut other = address;
+    other[0] ^= 1;
+    assert_eq!(verify_ecdsa_signature_eth_address(&hash, &signature, recovery_id, other), Err(LibError::WrongSignature.into()));
+    assert_eq!(verify_ecdsa_signer(&hash, &signature, recovery_id, public_key, Some(other)), Err(LibError::WrongSignature.into()));
+
+    // Full key mode without the address
+    assert_eq!(verify_ecdsa_signer(&hash, &signature, recovery_id, public_key, None), Ok(()));
+}
@@ -0,0 +1,45 @@
+use lib::merkle::{Content, TransferData, EIP712_PREFIX};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+    }
+    bytes
+}
+
+fn known_native_transfer() -> Content {
+    Content::new([1; 32], [2; 32], [3; 32], Box::new(TransferData::new_native_transfer(1000)))
+}
+
+// "Mail" example of the EIP-712 specification
+const MAIL_DOMAIN_SEPARATOR: &str = "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f";
+const MAIL_STRUCT_HASH: &str = "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e";
+const MAIL_DIGEST: &str = "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2";
+
+#[test]
+fn prefix_matches_eip712_vector() {
+    let digest = solana_program::keccak::hashv(&[EIP712_PREFIX, &from_hex(MAIL_DOMAIN_SEPARATOR), &from_hex(MAIL_STRUCT_HASH)]);
+    assert_eq!(hex(digest.as_ref()), MAIL_DIGEST);
+}
+
+#[test]
+fn eip712_hash_wraps_struct_hash() {
+    let domain_separator = from_hex(MAIL_DOMAIN_SEPARATOR);
+    let struct_hash = known_native_transfer().hash();
+    let expected = solana_program::keccak::hashv(&[&[0x19, 0x01], &domain_separator, struct_hash.as_ref()]);
+    assert_eq!(known_native_transfer().hash_eip712(domain_separator), expected);
+}
+
+#[test]
+fn leaf_hash_is_raw_by_default() {
+    let domain_separator = from_hex(MAIL_DOMAIN_SEPARATOR);
+    assert_eq!(known_native_transfer().leaf_hash(None), known_native_transfer().hash());
+    assert_eq!(known_native_transfer().leaf_hash(Some(domain_separator)), known_native_transfer().hash_eip712(domain_separator));
+    assert_ne!(known_native_transfer().leaf_hash(Some(domain_separator)), known_native_transfer().hash());
+    assert_ne!(known_native_transfer().hash_eip712([0; 32]), known_native_transfer().hash_eip712(domain_separator));
+}
@@ -0,0 +1,48 @@
+use std::sync::Mutex;
+
+use borsh::BorshDeserialize;
+use lib::error::{set_error_info, ErrorInfo, LibError, BRIDGE_PROGRAM_CODE, COMMISSION_PROGRAM_CODE, UPGRADE_PROGRAM_CODE};
+use solana_program::program_error::ProgramError;
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_program::pubkey::Pubkey;
+
+static RETURN_DATA: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+// Keeps the return data set by the program
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_set_return_data(&self, data: &[u8]) {
+        *RETURN_DATA.lock().unwrap() = data.to_vec();
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        Some((Pubkey::default(), RETURN_DATA.lock().unwrap().clone()))
+    }
+}
+
+#[test]
+fn custom_error_is_encoded_with_program() {
+    let error: ProgramError = LibError::AlreadyInUse.into();
+    assert_eq!(ErrorInfo::new(BRIDGE_PROGRAM_CODE, &error), ErrorInfo { program: 0, code: LibError::AlreadyInUse as u32, builtin: false });
+    assert_eq!(ErrorInfo::new(COMMISSION_PROGRAM_CODE, &error), ErrorInfo { program: 1, code: LibError::AlreadyInUse as u32, builtin: false });
+    assert_eq!(ErrorInfo::new(UPGRADE_PROGRAM_CODE, &error).program, 2);
+}
+
+#[test]
+fn builtin_error_is_marked() {
+    let info = ErrorInfo::new(BRIDGE_PROGRAM_CODE, &ProgramError::MissingRequiredSignature);
+    assert!(info.builtin);
+    // MissingRequiredSignature is the builtin error 8
+    assert_eq!(info.code, 8);
+}
+
+#[test]
+fn error_info_is_set_as_return_data() {
+    set_syscall_stubs(Box::new(Stubs));
+
+    set_error_info(COMMISSION_PROGRAM_CODE, &LibError::NotAcceptable.into());
+    let (_, data) = solana_program::program::get_return_data().unwrap();
+    let info = ErrorInfo::deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(info, ErrorInfo { program: COMMISSION_PROGRAM_CODE, code: LibError::NotAcceptable as u32, builtin: false });
+}
@@ -0,0 +1,168 @@
+use lib::error::LibError;
+use lib::merkle::{amount_bytes, get_merkle_root, get_merkle_root_with, normalize_metadata_field, Content, Data, HashAlgo, MerkleProof, TransferData, MAX_MERKLE_DEPTH, MAX_PROOF_LENGTH, SHORT_PATH_LENGTH};
+use lib::BRIDGE_DOMAIN;
+use solana_program::keccak;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn known_ft_transfer_data() -> TransferData {
+    TransferData::new_ft_transfer([4; 32], 1_000_000, String::from("Rarimo"), String::from("RMO"), String::from("https://rarimo.com/token.json"), 6)
+}
+
+fn known_ft_transfer() -> Content {
+    Content::new([1; 32], [2; 32], [3; 32], Box::new(known_ft_transfer_data()))
+}
+
+#[test]
+fn known_ft_transfer_leaf_is_stable() {
+    // Documented in lib/README.md, should never change
+    assert_eq!(hex(known_ft_transfer().hash().as_ref()), "e7b60d1dab6c2b036330a8330200a4b8a962728c96ce2e2ae13b550daa1add11");
+}
+
+#[test]
+fn ft_transfer_leaf_matches_documented_layout() {
+    let operation = [
+        [4u8; 32].as_slice(),
+        b"Rarimo",
+        b"https://rarimo.com/token.json",
+        amount_bytes(1_000_000).as_slice(),
+        b"RMO",
+        &[6],
+    ].concat();
+    assert_eq!(known_ft_transfer_data().get_operation(), operation);
+
+    let leaf = solana_program::keccak::hashv(&[BRIDGE_DOMAIN, operation.as_slice(), &[1; 32], b"Solana", &[2; 32], &[3; 32]]);
+    assert_eq!(known_ft_transfer().hash(), leaf);
+}
+
+#[test]
+fn ft_transfer_without_metadata_layout() {
+    let operation = [[4u8; 32].as_slice(), amount_bytes(1_000_000).as_slice(), &[6]].concat();
+    assert_eq!(TransferData::new_ft_transfer_without_metadata([4; 32], 1_000_000, 6).get_operation(), operation);
+}
+
+#[test]
+fn native_transfer_leaf_omits_decimals() {
+    // Only the amount in lamports, SOLANA_NATIVE_DECIMALS is implied by the native token
+    let operation = TransferData::new_native_transfer(1_000_000).get_operation();
+    assert_eq!(operation, amount_bytes(1_000_000));
+    assert_eq!(operation.len(), 32);
+
+    let leaf = solana_program::keccak::hashv(&[BRIDGE_DOMAIN, operation.as_slice(), &[1; 32], b"Solana", &[2; 32], &[3; 32]]);
+    let content = Content::new([1; 32], [2; 32], [3; 32], Box::new(TransferData::new_native_transfer(1_000_000)));
+    assert_eq!(content.hash(), leaf);
+    assert_eq!(hex(leaf.as_ref()), "a0888642241372848f7589deaa010b00d934b1dd146bbe0086cc79b222940ccc");
+}
+
+#[test]
+fn metadata_field_trailing_nulls_are_trimmed() {
+    assert_eq!(normalize_metadata_field("Rarimo\0\0\0"), "Rarimo");
+    assert_eq!(normalize_metadata_field("\0\0"), "");
+    assert_eq!(normalize_metadata_field("Rarimo"), "Rarimo");
+}
+
+#[test]
+fn metadata_field_leading_and_embedded_nulls_are_kept() {
+    assert_eq!(normalize_metadata_field("\0\0Rarimo"), "\0\0Rarimo");
+    assert_eq!(normalize_metadata_field("Rari\0mo\0"), "Rari\0mo");
+    assert_eq!(normalize_metadata_field("\0Rari\0mo\0\0"), "\0Rari\0mo");
+}
+
+#[test]
+fn metadata_field_whitespace_is_kept() {
+    assert_eq!(normalize_metadata_field(" Rarimo \0"), " Rarimo ");
+    assert_eq!(normalize_metadata_field("Rarimo\0 "), "Rarimo\0 ");
+}
+
+// Root computation of the original implementation: a concatenated pair is allocated per path element
+fn reference_merkle_root(algo: HashAlgo, mut hash: [u8; 32], path: &Vec<[u8; 32]>) -> [u8; 32] {
+    for leaf in path {
+        let pair = if *leaf >= hash { [leaf.as_ref(), hash.as_ref()].concat() } else { [hash.as_ref(), leaf.as_ref()].concat() };
+        hash = algo.hash(pair.as_slice());
+    }
+
+    hash
+}
+
+#[test]
+fn short_and_long_paths_match_reference_root() {
+    let leaf = solana_program::keccak::hash(b"leaf").to_bytes();
+    // Siblings both below and above the current hash
+    let path: Vec<[u8; 32]> = (0..SHORT_PATH_LENGTH as u8 + 3)
+        .map(|i| if i == 2 { [0; 32] } else { solana_program::keccak::hash(&[i]).to_bytes() })
+        .collect();
+
+    for algo in [HashAlgo::Keccak, HashAlgo::Sha256] {
+        for length in 0..=path.len() {
+            let path = path[..length].to_vec();
+            assert_eq!(get_merkle_root_with(algo, leaf, &path).unwrap(), reference_merkle_root(algo, leaf, &path), "{:?} path of {}", algo, length);
+        }
+
+        // Sibling equal to the current hash
+        let equal = vec![leaf; SHORT_PATH_LENGTH + 1];
+        assert_eq!(get_merkle_root_with(algo, leaf, &equal[..1].to_vec()).unwrap(), reference_merkle_root(algo, leaf, &equal[..1].to_vec()));
+        assert_eq!(get_merkle_root_with(algo, leaf, &equal).unwrap(), reference_merkle_root(algo, leaf, &equal));
+    }
+}
+
+// Off-chain builders hash the sorted pair, the equal siblings give the same pair in any order
+#[test]
+fn equal_siblings_are_hashed_as_duplicate_pair() {
+    let (a, b) = (solana_program::keccak::hash(b"a").to_bytes(), solana_program::keccak::hash(b"b").to_bytes());
+    let pair = |x: [u8; 32], y: [u8; 32]| if x >= y { keccak::hashv(&[&x, &y]).to_bytes() } else { keccak::hashv(&[&y, &x]).to_bytes() };
+
+    // Equal sibling of the leaf
+    assert_eq!(get_merkle_root(keccak::Hash::new_from_array(a), &vec![a]).unwrap(), keccak::hashv(&[&a, &a]).to_bytes());
+
+    // Tree of [a, b, a, b]: both subtrees are hash(a, b), so the sibling is equal on the second level
+    let ab = pair(a, b);
+    let root = keccak::hashv(&[&ab, &ab]).to_bytes();
+    for (leaf, sibling) in [(a, b), (b, a)] {
+        assert_eq!(get_merkle_root(keccak::Hash::new_from_array(leaf), &vec![sibling, ab]).unwrap(), root);
+        assert_eq!(MerkleProof(vec![sibling, ab]).root_from_leaf(keccak::Hash::new_from_array(leaf)).unwrap(), root);
+    }
+
+    // Tree of [a, a, b, b]: the leaves are equal on the first level
+    let root = pair(keccak::hashv(&[&a, &a]).to_bytes(), keccak::hashv(&[&b, &b]).to_bytes());
+    assert_eq!(get_merkle_root(keccak::Hash::new_from_array(a), &vec![a, keccak::hashv(&[&b, &b]).to_bytes()]).unwrap(), root);
+    assert_eq!(get_merkle_root(keccak::Hash::new_from_array(b), &vec![b, keccak::hashv(&[&a, &a]).to_bytes()]).unwrap(), root);
+}
+
+#[test]
+fn merkle_proof_root_matches_reference_root() {
+    let leaf = solana_program::keccak::hash(b"leaf");
+    for length in [1, SHORT_PATH_LENGTH, MAX_PROOF_LENGTH] {
+        let path: Vec<[u8; 32]> = (0..length as u8).map(|i| solana_program::keccak::hash(&[i]).to_bytes()).collect();
+        let proof = MerkleProof(path.clone());
+        assert_eq!(proof.len(), length);
+        assert_eq!(proof.root_from_leaf(leaf).unwrap(), reference_merkle_root(HashAlgo::Keccak, leaf.to_bytes(), &path));
+        assert_eq!(proof.root_from_leaf_with(HashAlgo::Sha256, leaf.to_bytes()).unwrap(), reference_merkle_root(HashAlgo::Sha256, leaf.to_bytes(), &path));
+    }
+}
+
+#[test]
+fn empty_merkle_proof_is_rejected() {
+    let proof = MerkleProof(vec![]);
+    assert!(proof.is_empty());
+    assert_eq!(proof.root_from_leaf(solana_program::keccak::hash(b"leaf")), Err(LibError::WrongMerkleProof.into()));
+    assert_eq!(proof.root_from_leaf_with(HashAlgo::Sha256, [1; 32]), Err(LibError::WrongMerkleProof.into()));
+}
+
+#[test]
+fn over_long_merkle_proof_is_rejected() {
+    let proof = MerkleProof(vec![[1; 32]; MAX_PROOF_LENGTH + 1]);
+    assert_eq!(proof.root_from_leaf(solana_program::keccak::hash(b"leaf")), Err(LibError::WrongMerkleProof.into()));
+    assert_eq!(proof.root_from_leaf_with(HashAlgo::Keccak, [1; 32]), Err(LibError::WrongMerkleProof.into()));
+}
+
+#[test]
+fn merkle_path_depth_is_limited() {
+    let leaf = solana_program::keccak::hash(b"leaf");
+    let path = vec![[1; 32]; MAX_MERKLE_DEPTH];
+    assert_eq!(get_merkle_root(leaf, &path), Ok(reference_merkle_root(HashAlgo::Keccak, leaf.to_bytes(), &path)));
+
+    let path = vec![[1; 32]; MAX_MERKLE_DEPTH + 1];
+    assert_eq!(get_merkle_root(leaf, &path), Err(LibError::WrongMerklePath.into()));
+}
@@ -0,0 +1,47 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use lib::Network;
+
+// Network names are stored and hashed off-chain, the encoding should never change
+
+#[test]
+fn known_network_encoding_is_stable() {
+    let known = [
+        (Network::Solana, "Solana"),
+        (Network::Ethereum, "Ethereum"),
+        (Network::Polygon, "Polygon"),
+        (Network::BSC, "BSC"),
+        (Network::Avalanche, "Avalanche"),
+        (Network::Near, "Near"),
+    ];
+
+    for (network, name) in known {
+        assert_eq!(network.as_bytes(), name.as_bytes());
+        assert_eq!(network.to_string(), name);
+        // The same data as the `String` field replaced by `Network`
+        assert_eq!(network.try_to_vec().unwrap(), String::from(name).try_to_vec().unwrap());
+        assert_eq!(Network::try_from_slice(&String::from(name).try_to_vec().unwrap()).unwrap(), network);
+        assert_eq!(Network::from(name), network);
+    }
+}
+
+#[test]
+fn custom_network_encoding_is_stable() {
+    let network = Network::from("Cosmos");
+    assert_eq!(network, Network::Custom(String::from("Cosmos")));
+    assert_eq!(network.as_bytes(), b"Cosmos");
+
+    let data = network.try_to_vec().unwrap();
+    assert_eq!(data, [&6u32.to_le_bytes()[..], b"Cosmos"].concat());
+    assert_eq!(Network::try_from_slice(&data).unwrap(), network);
+
+    // Known names are case-sensitive
+    assert_eq!(Network::from("ethereum"), Network::Custom(String::from("ethereum")));
+}
+
+#[test]
+fn custom_network_with_known_name_is_decoded_as_known() {
+    let data = Network::Custom(String::from("Ethereum")).try_to_vec().unwrap();
+    assert_eq!(data, Network::Ethereum.try_to_vec().unwrap());
+    assert_eq!(Network::try_from_slice(&data).unwrap(), Network::Ethereum);
+    assert_eq!(Network::from(String::from("Ethereum")), Network::Ethereum);
+}
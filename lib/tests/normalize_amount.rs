@@ -0,0 +1,34 @@
+use lib::error::LibError;
+use lib::normalize_amount;
+
+#[test]
+fn same_decimals_are_unchanged() {
+    assert_eq!(normalize_amount(1234, 6, 6), Ok(1234));
+    assert_eq!(normalize_amount(u64::MAX, 9, 9), Ok(u64::MAX));
+}
+
+#[test]
+fn amount_is_scaled_up() {
+    assert_eq!(normalize_amount(1234, 6, 9), Ok(1_234_000));
+    assert_eq!(normalize_amount(1, 0, 18), Ok(10u64.pow(18)));
+}
+
+#[test]
+fn scaling_up_overflow_is_rejected() {
+    assert_eq!(normalize_amount(u64::MAX / 10 + 1, 8, 9), Err(LibError::WrongAmountPrecision.into()));
+    // 10^20 does not fit in u64
+    assert_eq!(normalize_amount(1, 0, 20), Err(LibError::WrongAmountPrecision.into()));
+    assert_eq!(normalize_amount(0, 0, 20), Err(LibError::WrongAmountPrecision.into()));
+}
+
+#[test]
+fn amount_is_scaled_down() {
+    assert_eq!(normalize_amount(1_234_000, 9, 6), Ok(1234));
+    assert_eq!(normalize_amount(0, 9, 0), Ok(0));
+}
+
+#[test]
+fn scaling_down_with_lost_digits_is_rejected() {
+    assert_eq!(normalize_amount(1_234_001, 9, 6), Err(LibError::WrongAmountPrecision.into()));
+    assert_eq!(normalize_amount(999, 3, 0), Err(LibError::WrongAmountPrecision.into()));
+}
@@ -0,0 +1,69 @@
+//! Run with `--features schema`.
+
+#![cfg(feature = "schema")]
+
+use borsh::schema::{BorshSchema, BorshSchemaContainer, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSerialize};
+use lib::instructions::bridge::{BridgeInstruction, WithdrawArgs};
+
+fn named_fields(container: &BorshSchemaContainer, declaration: &str) -> Vec<(String, String)> {
+    match container.definitions.get(declaration) {
+        Some(Definition::Struct { fields: Fields::NamedFields(fields) }) => fields.clone(),
+        definition => panic!("{} is not a struct with named fields: {:?}", declaration, definition),
+    }
+}
+
+fn fields(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+    fields.iter().map(|(name, declaration)| (name.to_string(), declaration.to_string())).collect()
+}
+
+// Clients generate the (de)serializers from the schema, the layout should never change silently
+#[test]
+fn withdraw_args_schema_is_stable() {
+    let container = WithdrawArgs::schema_container();
+    assert_eq!(container.declaration, "WithdrawArgs");
+    assert_eq!(named_fields(&container, "WithdrawArgs"), fields(&[
+        ("origin", "Array<u8, 32>"),
+        ("amount", "u64"),
+        ("claim_amount", "Option<u64>"),
+        ("signature", "Array<u8, 64>"),
+        ("recovery_id", "u8"),
+        ("path", "MerkleProof"),
+        ("seeds", "Array<u8, 32>"),
+        ("token_seed", "Option<Array<u8, 32>>"),
+        ("signed_meta", "Option<SignedMetadata>"),
+        ("eip712_domain_separator", "Option<Array<u8, 32>>"),
+        ("strict_ata", "bool"),
+        ("origin_preimage", "Option<OriginPreimage>"),
+        ("no_metadata", "bool"),
+    ]));
+    assert_eq!(named_fields(&container, "SignedMetadata"), fields(&[
+        ("name", "string"),
+        ("symbol", "string"),
+        ("uri", "string"),
+        ("decimals", "u8"),
+        ("max_supply", "Option<u64>"),
+    ]));
+    assert_eq!(named_fields(&container, "OriginPreimage"), fields(&[
+        ("tx_hash", "Vec<u8>"),
+        ("event_id", "string"),
+        ("network_from", "string"),
+    ]));
+    assert_eq!(
+        container.definitions.get("MerkleProof"),
+        Some(&Definition::Struct { fields: Fields::UnnamedFields(vec![String::from("Vec<Array<u8, 32>>")]) }),
+    );
+    assert_eq!(container.definitions.len(), 12);
+}
+
+#[test]
+fn instruction_schema_container_round_trips() {
+    let container = BridgeInstruction::schema_container();
+    let encoded = container.try_to_vec().unwrap();
+    assert_eq!(BorshSchemaContainer::try_from_slice(&encoded).unwrap(), container);
+
+    match container.definitions.get("BridgeInstruction") {
+        Some(Definition::Enum { variants }) => assert!(variants.iter().any(|(name, _)| name == "WithdrawFT")),
+        definition => panic!("BridgeInstruction is not an enum: {:?}", definition),
+    }
+}
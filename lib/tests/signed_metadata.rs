@@ -0,0 +1,28 @@
+use lib::error::LibError;
+use lib::instructions::bridge::SignedMetadata;
+use lib::instructions::InstructionValidation;
+
+fn metadata(uri: &str) -> SignedMetadata {
+    SignedMetadata { name: String::from("Rarimo"), symbol: String::from("RMO"), uri: String::from(uri), decimals: 6, max_supply: None }
+}
+
+#[test]
+fn valid_uri_is_accepted() {
+    assert_eq!(metadata("https://rarimo.com/token.json").validate(), Ok(()));
+    assert_eq!(metadata("ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi?name=Ü").validate(), Ok(()));
+    assert_eq!(metadata("").validate(), Ok(()));
+}
+
+#[test]
+fn uri_with_control_characters_is_rejected() {
+    for uri in ["https://rarimo.com/token.json\0", "https://rarimo.com/\0token.json", "https://rarimo.com/token.json\n", "\u{7f}https://rarimo.com"] {
+        assert_eq!(metadata(uri).validate(), Err(LibError::WrongArgsSize.into()), "{:?}", uri);
+    }
+}
+
+#[test]
+fn uri_length_is_limited() {
+    let max = "a".repeat(mpl_token_metadata::state::MAX_URI_LENGTH);
+    assert_eq!(metadata(&max).validate(), Ok(()));
+    assert_eq!(metadata(&(max + "a")).validate(), Err(LibError::WrongArgsSize.into()));
+}
@@ -0,0 +1,20 @@
+use lib::{CommissionToken, TokenType};
+use solana_program::pubkey::Pubkey;
+
+// Token classes are hashed off-chain, the values should never change
+
+#[test]
+fn token_type_classes_are_stable() {
+    assert_eq!(TokenType::Native.class(), 0);
+    assert_eq!(TokenType::FT.class(), 1);
+    assert_eq!(TokenType::NFT.class(), 2);
+}
+
+#[test]
+fn commission_token_classes_are_stable() {
+    let mint = Pubkey::new_unique();
+    assert_eq!(CommissionToken::Native.class(), 0);
+    assert_eq!(CommissionToken::FT(mint).class(), 1);
+    assert_eq!(CommissionToken::NFT(mint).class(), 2);
+    assert_eq!(CommissionToken::Any.class(), 3);
+}
@@ -10,7 +10,7 @@ use solana_program::{
 use solana_program::program_error::PrintProgramError;
 
 use crate::processor;
-use lib::error::LibError;
+use lib::error::{LibError, set_error_info, UPGRADE_PROGRAM_CODE};
 
 entrypoint!(process_instruction);
 
@@ -24,6 +24,7 @@ fn process_instruction<'a>(
         Err(e) => {
             // catch the error so we can print it
             e.print::<LibError>();
+            set_error_info(UPGRADE_PROGRAM_CODE, &e);
             return Err(e);
         }
     }
@@ -1,30 +1,50 @@
 use lib::merkle::{amount_bytes};
 use solana_program::pubkey::Pubkey;
-use lib::SOLANA_NETWORK;
+use lib::{SOLANA_NETWORK, UPGRADE_DOMAIN};
 
+/// Signed content of the upgrade operation.
+/// Hash: keccak256(UPGRADE_DOMAIN | network | nonce (32 bytes) | upgrade program id | upgraded contract | buffer [| 0x01])
+/// The trailing byte is present only for the allowed self upgrade.
 pub struct Content {
     pub network: String,
     pub nonce: u64,
+    // Upgrade program that verifies the signature
+    pub program: Pubkey,
+    // Program to be upgraded
     pub contract: Pubkey,
+    // Buffer with the new program data
     pub buffer: Pubkey,
+    // Upgrade program is allowed to upgrade itself
+    pub allow_self_upgrade: bool,
 }
 
 impl Content {
-    pub fn new(nonce: u64, contract: Pubkey, buffer: Pubkey) -> Self {
+    pub fn new(nonce: u64, program: Pubkey, contract: Pubkey, buffer: Pubkey) -> Self {
         Content {
             network: String::from(SOLANA_NETWORK),
             nonce,
+            program,
             contract,
             buffer,
+            allow_self_upgrade: false,
         }
     }
 
+    pub fn with_allow_self_upgrade(mut self, allow_self_upgrade: bool) -> Self {
+        self.allow_self_upgrade = allow_self_upgrade;
+        self
+    }
+
     pub fn hash(self) -> solana_program::keccak::Hash {
-        let mut data = Vec::new();
+        let mut data = Vec::from(UPGRADE_DOMAIN);
         data.append(&mut Vec::from(self.network.as_bytes()));
         data.append(&mut Vec::from(amount_bytes(self.nonce)));
+        data.append(&mut Vec::from(self.program.as_ref()));
         data.append(&mut Vec::from(self.contract.as_ref()));
         data.append(&mut Vec::from(self.buffer.as_ref()));
+        if self.allow_self_upgrade {
+            data.push(1);
+        }
         solana_program::keccak::hash(data.as_slice())
     }
 }
@@ -1,19 +1,21 @@
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult, msg,
-    program::{invoke, invoke_signed}, pubkey::Pubkey, system_instruction,
+    program::{invoke, invoke_signed}, program_error::ProgramError, pubkey::Pubkey, system_instruction,
     sysvar::{rent::Rent, Sysvar},
 };
-use crate::state::{MAX_ADMIN_SIZE, UpgradeAdmin};
+use crate::state::{MAX_ADMIN_SIZE, BASELINE_ADMIN_SIZE, UpgradeAdmin};
 use borsh::{
     BorshDeserialize, BorshSerialize,
 };
+use solana_program::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_program::program_utils::limited_deserialize;
 use solana_program::secp256k1_recover::{SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH};
 use lib::ecdsa::verify_ecdsa_signature;
 use lib::error::LibError;
 use lib::instructions::upgrade::UpgradeInstruction;
 use crate::merkle::Content;
-use lib::merkle::get_merkle_root;
+use lib::merkle::{amount_bytes, get_merkle_root};
 
 pub fn process_instruction<'a>(
     program_id: &'a Pubkey,
@@ -32,7 +34,19 @@ pub fn process_instruction<'a>(
         }
         UpgradeInstruction::Upgrade(args) => {
             msg!("Instruction: Upgrade");
-            process_upgrade(program_id, accounts, args.signature, args.recovery_id, args.path)
+            process_upgrade(program_id, accounts, args.signature, args.recovery_id, args.path, args.allow_self_upgrade)
+        }
+        UpgradeInstruction::ChangeContract(args) => {
+            msg!("Instruction: Change contract");
+            process_change_contract(program_id, accounts, args.new_contract, args.signature, args.recovery_id)
+        }
+        UpgradeInstruction::MigrateState => {
+            msg!("Instruction: Migrate state");
+            process_migrate_state(program_id, accounts)
+        }
+        UpgradeInstruction::ReleaseAuthority(args) => {
+            msg!("Instruction: Release authority");
+            process_release_authority(program_id, accounts, args.new_authority, args.signature, args.recovery_id)
         }
     }
 }
@@ -44,6 +58,7 @@ pub fn process_init_admin<'a>(
     public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     upgrade_program: Pubkey,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 4, "InitializeAdmin")?;
     let account_info_iter = &mut accounts.iter();
 
     let upgrade_admin_info = next_account_info(account_info_iter)?;
@@ -56,6 +71,8 @@ pub fn process_init_admin<'a>(
         return Err(LibError::WrongAdmin.into());
     }
 
+    lib::expect_empty_admin(upgrade_admin_info)?;
+
     lib::call_create_account(
         fee_payer_info,
         upgrade_admin_info,
@@ -73,6 +90,7 @@ pub fn process_init_admin<'a>(
 
     upgrade_admin.contract = upgrade_program;
     upgrade_admin.public_key = public_key;
+    upgrade_admin.version = lib::STATE_VERSION;
     upgrade_admin.is_initialized = true;
     upgrade_admin.serialize(&mut *upgrade_admin_info.data.borrow_mut())?;
     Ok(())
@@ -86,6 +104,7 @@ pub fn process_transfer_ownership<'a>(
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 1, "TransferOwnership")?;
     let account_info_iter = &mut accounts.iter();
     let upgrade_admin_info = next_account_info(account_info_iter)?;
 
@@ -94,15 +113,25 @@ pub fn process_transfer_ownership<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(upgrade_admin.version)?;
+
     let upgrade_admin_key = Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), upgrade_admin.contract.as_ref()], &program_id)?;
     if upgrade_admin_key != *upgrade_admin_info.key {
         return Err(LibError::WrongSeeds.into());
     }
 
-    verify_ecdsa_signature(solana_program::keccak::hash(new_public_key.as_slice()).as_ref(), signature.as_slice(), recovery_id, upgrade_admin.public_key)?;
+    verify_ecdsa_signature(solana_program::keccak::hash([lib::UPGRADE_DOMAIN, new_public_key.as_slice()].concat().as_slice()).as_ref(), signature.as_slice(), recovery_id, upgrade_admin.public_key)?;
+
+    let old_public_key = upgrade_admin.public_key;
 
     upgrade_admin.public_key = new_public_key;
     upgrade_admin.serialize(&mut *upgrade_admin_info.data.borrow_mut())?;
+
+    lib::KeyRotated {
+        admin: upgrade_admin_key,
+        old_key: old_public_key,
+        new_key: new_public_key,
+    }.log();
     Ok(())
 }
 
@@ -112,7 +141,9 @@ pub fn process_upgrade<'a>(
     signature: [u8; SECP256K1_SIGNATURE_LENGTH],
     recovery_id: u8,
     path: Vec<[u8; 32]>,
+    allow_self_upgrade: bool,
 ) -> ProgramResult {
+    lib::expect_accounts(accounts, 7, "Upgrade")?;
     let account_info_iter = &mut accounts.iter();
     let upgrade_admin_info = next_account_info(account_info_iter)?;
     let upgrade_program_data = next_account_info(account_info_iter)?;
@@ -132,6 +163,29 @@ pub fn process_upgrade<'a>(
         return Err(LibError::NotInitialized.into());
     }
 
+    lib::check_version(upgrade_admin.version)?;
+
+    // The broken upgrade program could not upgrade anything anymore, including itself
+    if *upgrade_program.key == *program_id && !allow_self_upgrade {
+        msg!("Self upgrade requires the signed allow_self_upgrade flag");
+        return Err(LibError::InvalidKey.into());
+    }
+
+    if *upgrade_buffer.owner != solana_program::bpf_loader_upgradeable::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Check the buffer authority here to fail with the clear error instead of the loader one
+    let buffer: UpgradeableLoaderState = limited_deserialize(
+        upgrade_buffer.data.borrow().as_ref(),
+        UpgradeableLoaderState::size_of_buffer_metadata() as u64,
+    ).map_err(|_| ProgramError::InvalidAccountData)?;
+
+    match buffer {
+        UpgradeableLoaderState::Buffer { authority_address } if authority_address == Some(upgrade_admin_key) => {}
+        _ => return Err(LibError::WrongBufferAuthority.into()),
+    }
+
     let instruction =  solana_program::bpf_loader_upgradeable::upgrade(
         upgrade_program.key,
         upgrade_buffer.key,
@@ -141,7 +195,8 @@ pub fn process_upgrade<'a>(
 
     msg!("Current nonce: {}", upgrade_admin.nonce);
 
-    let content = Content::new(upgrade_admin.nonce, upgrade_admin.contract, *upgrade_buffer.key);
+    let content = Content::new(upgrade_admin.nonce, *program_id, upgrade_admin.contract, *upgrade_buffer.key)
+        .with_allow_self_upgrade(allow_self_upgrade);
     let root = get_merkle_root(content.hash(), &path)?;
 
     verify_ecdsa_signature(root.as_ref(), signature.as_slice(), recovery_id, upgrade_admin.public_key)?;
@@ -164,4 +219,158 @@ pub fn process_upgrade<'a>(
     upgrade_admin.nonce = upgrade_admin.nonce + 1;
     upgrade_admin.serialize(&mut *upgrade_admin_info.data.borrow_mut())?;
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn process_change_contract<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    new_contract: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 5, "ChangeContract")?;
+    let account_info_iter = &mut accounts.iter();
+    let upgrade_admin_info = next_account_info(account_info_iter)?;
+    let new_upgrade_admin_info = next_account_info(account_info_iter)?;
+    let fee_payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let mut upgrade_admin: UpgradeAdmin = BorshDeserialize::deserialize(&mut upgrade_admin_info.data.borrow_mut().as_ref())?;
+    if !upgrade_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(upgrade_admin.version)?;
+
+    let upgrade_admin_key = Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), upgrade_admin.contract.as_ref()], &program_id)?;
+    if upgrade_admin_key != *upgrade_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if new_contract == Pubkey::default() || new_contract == upgrade_admin.contract {
+        return Err(LibError::InvalidKey.into());
+    }
+
+    let new_upgrade_admin_key = Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), new_contract.as_ref()], &program_id)?;
+    if new_upgrade_admin_key != *new_upgrade_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    msg!("Current nonce: {}", upgrade_admin.nonce);
+
+    // Current contract is included, so the signature can not be replayed for another UpgradeAdmin with the same key
+    let hash = solana_program::keccak::hashv(&[
+        lib::UPGRADE_DOMAIN,
+        lib::CHANGE_CONTRACT_TAG.as_bytes(),
+        upgrade_admin.contract.as_ref(),
+        new_contract.as_ref(),
+        amount_bytes(upgrade_admin.nonce).as_slice(),
+    ]);
+
+    verify_ecdsa_signature(hash.as_ref(), signature.as_slice(), recovery_id, upgrade_admin.public_key)?;
+
+    lib::expect_empty_admin(new_upgrade_admin_info)?;
+
+    lib::call_create_account(
+        fee_payer_info,
+        new_upgrade_admin_info,
+        rent_info,
+        system_program,
+        MAX_ADMIN_SIZE,
+        program_id,
+        &[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), new_contract.as_ref()],
+    )?;
+
+    let new_upgrade_admin = UpgradeAdmin {
+        version: lib::STATE_VERSION,
+        public_key: upgrade_admin.public_key,
+        contract: new_contract,
+        nonce: upgrade_admin.nonce + 1,
+        is_initialized: true,
+    };
+    new_upgrade_admin.serialize(&mut *new_upgrade_admin_info.data.borrow_mut())?;
+
+    upgrade_admin.contract = new_contract;
+    upgrade_admin.nonce = upgrade_admin.nonce + 1;
+    upgrade_admin.is_initialized = false;
+    upgrade_admin.serialize(&mut *upgrade_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_release_authority<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    new_authority: Pubkey,
+    signature: [u8; SECP256K1_SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 4, "ReleaseAuthority")?;
+    let account_info_iter = &mut accounts.iter();
+    let upgrade_admin_info = next_account_info(account_info_iter)?;
+    let upgrade_program_data = next_account_info(account_info_iter)?;
+    let new_authority_info = next_account_info(account_info_iter)?;
+
+    let mut upgrade_admin: UpgradeAdmin = BorshDeserialize::deserialize(&mut upgrade_admin_info.data.borrow_mut().as_ref())?;
+    if !upgrade_admin.is_initialized {
+        return Err(LibError::NotInitialized.into());
+    }
+
+    lib::check_version(upgrade_admin.version)?;
+
+    let upgrade_admin_key = Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), upgrade_admin.contract.as_ref()], &program_id)?;
+    if upgrade_admin_key != *upgrade_admin_info.key {
+        return Err(LibError::WrongSeeds.into());
+    }
+
+    if new_authority == Pubkey::default() || new_authority != *new_authority_info.key {
+        return Err(LibError::InvalidKey.into());
+    }
+
+    msg!("Current nonce: {}", upgrade_admin.nonce);
+
+    // The tag separates the content from ChangeContract, which signs the same keys and nonce
+    let hash = solana_program::keccak::hashv(&[
+        lib::UPGRADE_DOMAIN,
+        lib::RELEASE_AUTHORITY_TAG.as_bytes(),
+        upgrade_admin.contract.as_ref(),
+        new_authority.as_ref(),
+        amount_bytes(upgrade_admin.nonce).as_slice(),
+    ]);
+
+    verify_ecdsa_signature(hash.as_ref(), signature.as_slice(), recovery_id, upgrade_admin.public_key)?;
+
+    let instruction = solana_program::bpf_loader_upgradeable::set_upgrade_authority(
+        &upgrade_admin.contract,
+        &upgrade_admin_key,
+        Some(&new_authority),
+    );
+
+    invoke_signed(
+        &instruction,
+        &[
+            upgrade_program_data.clone(),
+            upgrade_admin_info.clone(),
+            new_authority_info.clone(),
+        ],
+        &[&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), upgrade_admin.contract.as_ref()]],
+    )?;
+
+    upgrade_admin.nonce = upgrade_admin.nonce + 1;
+    upgrade_admin.serialize(&mut *upgrade_admin_info.data.borrow_mut())?;
+    Ok(())
+}
+
+pub fn process_migrate_state<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+) -> ProgramResult {
+    lib::expect_accounts(accounts, 3, "MigrateState")?;
+    let account_info_iter = &mut accounts.iter();
+
+    let account_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    lib::migrate_legacy_account(program_id, account_info, payer_info, system_program, BASELINE_ADMIN_SIZE, MAX_ADMIN_SIZE)
+}
@@ -4,11 +4,15 @@ use lib::instructions::commission::{MAX_TOKENS_COUNT, MAX_TOKEN_SIZE};
 use std::mem::size_of;
 use solana_program::secp256k1_recover::SECP256K1_PUBLIC_KEY_LENGTH;
 
-pub const MAX_ADMIN_SIZE: usize = SECP256K1_PUBLIC_KEY_LENGTH + (32 as usize) + (8 as usize) + (1 as usize);
+pub const MAX_ADMIN_SIZE: usize = 1 + SECP256K1_PUBLIC_KEY_LENGTH + (32 as usize) + (8 as usize) + (1 as usize);
+// UpgradeAdmin of the deployed version without the version byte, see MigrateState
+pub const BASELINE_ADMIN_SIZE: usize = SECP256K1_PUBLIC_KEY_LENGTH + (32 as usize) + (8 as usize) + (1 as usize);
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct UpgradeAdmin {
+    // Layout version, see lib::STATE_VERSION
+    pub version: u8,
     // ECDSA public key
     pub public_key: [u8; SECP256K1_PUBLIC_KEY_LENGTH],
     pub contract: Pubkey,
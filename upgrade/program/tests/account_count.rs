@@ -0,0 +1,41 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::Account;
+use lib::error::LibError;
+use lib::instructions::upgrade::{
+    ChangeContractArgs, InitializeAdminArgs, ReleaseAuthorityArgs, TransferOwnershipArgs, UpgradeArgs, UpgradeInstruction,
+};
+use solana_program::account_info::AccountInfo;
+use solana_program::pubkey::Pubkey;
+
+const SIGNATURE: [u8; 64] = [0; 64];
+
+// Processes the instruction with one account less than expected
+fn assert_expects(name: &str, instruction: UpgradeInstruction, count: usize) {
+    let program_id = Pubkey::new_unique();
+    let mut accounts: Vec<Account> = (1..count).map(|_| Account::new(Pubkey::new_unique(), vec![], program_id)).collect();
+    let infos: Vec<AccountInfo> = accounts.iter_mut().map(Account::info).collect();
+    assert_eq!(
+        upgrade::processor::process_instruction(&program_id, &infos, &instruction.try_to_vec().unwrap()),
+        Err(LibError::NotEnoughAccounts.into()),
+        "{}", name,
+    );
+}
+
+#[test]
+fn upgrade_instructions_check_account_count() {
+    let contract = Pubkey::new_unique();
+    let cases = vec![
+        ("InitializeAdmin", UpgradeInstruction::InitializeAdmin(InitializeAdminArgs { public_key: [1; 64], contract }), 4),
+        ("TransferOwnership", UpgradeInstruction::TransferOwnership(TransferOwnershipArgs { new_public_key: [1; 64], signature: SIGNATURE, recovery_id: 0 }), 1),
+        ("Upgrade", UpgradeInstruction::Upgrade(UpgradeArgs { signature: SIGNATURE, recovery_id: 0, path: vec![], allow_self_upgrade: false }), 7),
+        ("ChangeContract", UpgradeInstruction::ChangeContract(ChangeContractArgs { new_contract: contract, signature: SIGNATURE, recovery_id: 0 }), 5),
+        ("MigrateState", UpgradeInstruction::MigrateState, 3),
+        ("ReleaseAuthority", UpgradeInstruction::ReleaseAuthority(ReleaseAuthorityArgs { new_authority: contract, signature: SIGNATURE, recovery_id: 0 }), 4),
+    ];
+
+    for (name, instruction, count) in cases {
+        assert_expects(name, instruction, count);
+    }
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::Upgrade;
+use lib::error::LibError;
+use solana_program::bpf_loader_upgradeable;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+fn signed_upgrade(upgrade: &mut Upgrade) -> Result<(), ProgramError> {
+    let (signature, recovery_id) = upgrade.sign(upgrade.content());
+    upgrade.process(signature, recovery_id, false)
+}
+
+#[test]
+fn buffer_of_other_authority_is_rejected() {
+    let mut upgrade = Upgrade::new();
+    upgrade.set_buffer(bpf_loader_upgradeable::id(), Some(Pubkey::new_unique()));
+    assert_eq!(signed_upgrade(&mut upgrade), Err(LibError::WrongBufferAuthority.into()));
+    assert_eq!(upgrade.state().nonce, 0);
+}
+
+#[test]
+fn immutable_buffer_is_rejected() {
+    let mut upgrade = Upgrade::new();
+    upgrade.set_buffer(bpf_loader_upgradeable::id(), None);
+    assert_eq!(signed_upgrade(&mut upgrade), Err(LibError::WrongBufferAuthority.into()));
+}
+
+#[test]
+fn buffer_of_other_program_is_rejected() {
+    let mut upgrade = Upgrade::new();
+    let admin = upgrade.admin;
+    upgrade.set_buffer(Pubkey::new_unique(), Some(admin));
+    assert_eq!(signed_upgrade(&mut upgrade), Err(ProgramError::IncorrectProgramId));
+}
+
+#[test]
+fn buffer_of_upgrade_admin_is_accepted() {
+    let mut upgrade = Upgrade::new();
+    signed_upgrade(&mut upgrade).unwrap();
+    assert_eq!(upgrade.state().nonce, 1);
+}
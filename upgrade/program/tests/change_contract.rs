@@ -0,0 +1,119 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Env, Signer};
+use lib::error::LibError;
+use lib::instructions::upgrade::{ChangeContractArgs, UpgradeInstruction};
+use lib::merkle::amount_bytes;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+use upgrade::state::{UpgradeAdmin, MAX_ADMIN_SIZE};
+
+const NONCE: u64 = 3;
+
+// Contract with its upgrade admin address, derived without the bump, so not every contract has it
+fn contract(program_id: &Pubkey) -> (Pubkey, Pubkey) {
+    std::iter::repeat_with(Pubkey::new_unique)
+        .find_map(|contract| admin_address(program_id, &contract).ok().map(|admin| (contract, admin)))
+        .unwrap()
+}
+
+fn admin_address(program_id: &Pubkey, contract: &Pubkey) -> Result<Pubkey, solana_program::pubkey::PubkeyError> {
+    Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), contract.as_ref()], program_id)
+}
+
+struct Setup {
+    env: Env,
+    program_id: Pubkey,
+    contract: Pubkey,
+    admin: Pubkey,
+    signer: Signer,
+}
+
+fn setup() -> Setup {
+    let mut env = Env::new();
+    let program_id = Pubkey::new_unique();
+    env.add_program(program_id, upgrade::processor::process_instruction);
+
+    let signer = Signer::new(1);
+    let (contract, admin) = contract(&program_id);
+    let state = UpgradeAdmin { version: lib::STATE_VERSION, public_key: signer.public_key(), contract, nonce: NONCE, is_initialized: true };
+    env.set_state(&admin, &program_id, &state, MAX_ADMIN_SIZE);
+    Setup { env, program_id, contract, admin, signer }
+}
+
+impl Setup {
+    fn state(&self, admin: &Pubkey) -> UpgradeAdmin {
+        UpgradeAdmin::deserialize(&mut self.env.data(admin).as_slice()).unwrap()
+    }
+
+    fn sign(&self, new_contract: &Pubkey, nonce: u64) -> ([u8; 64], u8) {
+        let hash = solana_program::keccak::hashv(&[lib::UPGRADE_DOMAIN, lib::CHANGE_CONTRACT_TAG.as_bytes(), self.contract.as_ref(), new_contract.as_ref(), amount_bytes(nonce).as_slice()]);
+        self.signer.sign(&hash.to_bytes())
+    }
+
+    fn change_contract(&mut self, new_contract: Pubkey, new_admin: Pubkey, signature: [u8; 64], recovery_id: u8) -> Result<(), ProgramError> {
+        let payer = self.env.new_funded();
+        self.env.process(&Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.admin, false),
+                AccountMeta::new(new_admin, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+            data: UpgradeInstruction::ChangeContract(ChangeContractArgs { new_contract, signature, recovery_id }).try_to_vec().unwrap(),
+        })
+    }
+}
+
+#[test]
+fn contract_is_changed() {
+    let mut setup = setup();
+    let (new_contract, new_admin) = contract(&setup.program_id);
+    let (signature, recovery_id) = setup.sign(&new_contract, NONCE);
+    setup.change_contract(new_contract, new_admin, signature, recovery_id).unwrap();
+
+    let state = setup.state(&new_admin);
+    assert!(state.is_initialized);
+    assert_eq!(state.contract, new_contract);
+    assert_eq!(state.nonce, NONCE + 1);
+    assert_eq!(state.public_key, setup.signer.public_key());
+    assert_eq!(setup.env.account(&new_admin).unwrap().owner, setup.program_id);
+
+    // Old admin can not be used anymore
+    let old = setup.state(&setup.admin);
+    assert!(!old.is_initialized);
+    assert_eq!(old.nonce, NONCE + 1);
+}
+
+#[test]
+fn bad_signature_is_rejected() {
+    let mut setup = setup();
+    let (new_contract, new_admin) = contract(&setup.program_id);
+
+    // Signed for other contract and for the stale nonce
+    let (other, _) = contract(&setup.program_id);
+    let (signature, recovery_id) = setup.sign(&other, NONCE);
+    assert_eq!(setup.change_contract(new_contract, new_admin, signature, recovery_id), Err(LibError::WrongSignature.into()));
+    let (signature, recovery_id) = setup.sign(&new_contract, NONCE - 1);
+    assert_eq!(setup.change_contract(new_contract, new_admin, signature, recovery_id), Err(LibError::WrongSignature.into()));
+
+    assert!(!setup.env.exists(&new_admin));
+    assert!(setup.state(&setup.admin).is_initialized);
+}
+
+#[test]
+fn zero_and_same_contract_are_rejected() {
+    let mut setup = setup();
+    let (signature, recovery_id) = setup.sign(&Pubkey::default(), NONCE);
+    assert_eq!(setup.change_contract(Pubkey::default(), Pubkey::new_unique(), signature, recovery_id), Err(LibError::InvalidKey.into()));
+
+    let (contract, admin) = (setup.contract, setup.admin);
+    let (signature, recovery_id) = setup.sign(&contract, NONCE);
+    assert_eq!(setup.change_contract(contract, admin, signature, recovery_id), Err(LibError::InvalidKey.into()));
+}
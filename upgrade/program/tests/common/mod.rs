@@ -0,0 +1,130 @@
+//! Upgrade instruction processed with the account infos built in place.
+//!
+//! The loader invocation is not emulated: on the host `invoke_signed` only logs the call, so the processing
+//! succeeds once the buffer authority and the signature are verified.
+
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lib::instructions::upgrade::{UpgradeArgs, UpgradeInstruction};
+use solana_program::account_info::AccountInfo;
+use solana_program::bpf_loader_upgradeable;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+use upgrade::merkle::Content;
+use upgrade::state::{UpgradeAdmin, MAX_ADMIN_SIZE};
+
+pub struct Account {
+    pub key: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+}
+
+impl Account {
+    pub fn new(key: Pubkey, data: Vec<u8>, owner: Pubkey) -> Self {
+        Account { key, lamports: 1_000_000, data, owner }
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(&self.key, false, true, &mut self.lamports, &mut self.data, &self.owner, false, 0)
+    }
+}
+
+// UpgradeableLoaderState::Buffer in the loader (bincode) encoding: the variant index, then the optional authority
+pub fn buffer_data(authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = 1u32.to_le_bytes().to_vec();
+    match authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend_from_slice(authority.as_ref());
+        }
+        None => data.push(0),
+    }
+    data
+}
+
+/// Initialized upgrade admin of the contract and the buffer owned by it.
+/// Accounts in the Upgrade instruction order.
+pub struct Upgrade {
+    pub program_id: Pubkey,
+    pub contract: Pubkey,
+    pub admin: Pubkey,
+    pub buffer: Pubkey,
+    secret: libsecp256k1::SecretKey,
+    accounts: Vec<Account>,
+}
+
+impl Upgrade {
+    pub fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        // Upgrade admin address is derived without the bump, so not every contract has it
+        let (contract, admin) = loop {
+            let contract = Pubkey::new_unique();
+            if let Ok(admin) = Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), contract.as_ref()], &program_id) {
+                break (contract, admin);
+            }
+        };
+        Self::with_contract(program_id, contract, admin)
+    }
+
+    /// Upgrade admin of the upgrade program itself
+    pub fn new_self_upgrade() -> Self {
+        let (program_id, admin) = loop {
+            let program_id = Pubkey::new_unique();
+            if let Ok(admin) = Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), program_id.as_ref()], &program_id) {
+                break (program_id, admin);
+            }
+        };
+        Self::with_contract(program_id, program_id, admin)
+    }
+
+    fn with_contract(program_id: Pubkey, contract: Pubkey, admin: Pubkey) -> Self {
+        let secret = libsecp256k1::SecretKey::parse(&[1; 32]).unwrap();
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&secret).serialize()[1..].try_into().unwrap();
+        let state = UpgradeAdmin { version: lib::STATE_VERSION, public_key, contract, nonce: 0, is_initialized: true };
+        let mut admin_data = state.try_to_vec().unwrap();
+        admin_data.resize(MAX_ADMIN_SIZE, 0);
+
+        let buffer = Pubkey::new_unique();
+        let accounts = vec![
+            Account::new(admin, admin_data, program_id),
+            Account::new(Pubkey::new_unique(), vec![], bpf_loader_upgradeable::id()),
+            Account::new(contract, vec![], bpf_loader_upgradeable::id()),
+            Account::new(buffer, buffer_data(Some(admin)), bpf_loader_upgradeable::id()),
+            Account::new(Pubkey::new_unique(), vec![], system_program::id()),
+            Account::new(sysvar::rent::id(), vec![], sysvar::id()),
+            Account::new(sysvar::clock::id(), vec![], sysvar::id()),
+        ];
+
+        Upgrade { program_id, contract, admin, buffer, secret, accounts }
+    }
+
+    pub fn set_buffer(&mut self, owner: Pubkey, authority: Option<Pubkey>) {
+        self.accounts[3] = Account::new(self.buffer, buffer_data(authority), owner);
+    }
+
+    pub fn state(&self) -> UpgradeAdmin {
+        UpgradeAdmin::deserialize(&mut self.accounts[0].data.as_slice()).unwrap()
+    }
+
+    /// Content of the upgrade with the buffer for the current nonce
+    pub fn content(&self) -> Content {
+        Content::new(self.state().nonce, self.program_id, self.contract, self.buffer)
+    }
+
+    /// Signature and recovery id of the content hash (the path is empty, the hash is the root)
+    pub fn sign(&self, content: Content) -> ([u8; 64], u8) {
+        let message = libsecp256k1::Message::parse(&content.hash().to_bytes());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &self.secret);
+        (signature.serialize(), recovery_id.serialize())
+    }
+
+    pub fn process(&mut self, signature: [u8; 64], recovery_id: u8, allow_self_upgrade: bool) -> ProgramResult {
+        let input = UpgradeInstruction::Upgrade(UpgradeArgs { signature, recovery_id, path: vec![], allow_self_upgrade }).try_to_vec().unwrap();
+        let program_id = self.program_id;
+        let infos: Vec<AccountInfo> = self.accounts.iter_mut().map(Account::info).collect();
+        upgrade::processor::process_instruction(&program_id, &infos, &input)
+    }
+}
@@ -0,0 +1,44 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Env, Signer};
+use lib::instructions::upgrade::{TransferOwnershipArgs, UpgradeInstruction};
+use lib::{KeyRotated, KEY_ROTATED_LOG_PREFIX, UPGRADE_DOMAIN};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+use upgrade::state::{UpgradeAdmin, MAX_ADMIN_SIZE};
+
+#[test]
+fn ownership_transfer_emits_key_rotated() {
+    let mut env = Env::new();
+    let program_id = Pubkey::new_unique();
+    env.add_program(program_id, upgrade::processor::process_instruction);
+
+    // Upgrade admin address is derived without the bump, so not every contract has it
+    let (contract, admin) = std::iter::repeat_with(Pubkey::new_unique)
+        .find_map(|contract| {
+            Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), contract.as_ref()], &program_id).ok().map(|admin| (contract, admin))
+        })
+        .unwrap();
+
+    let signer = Signer::new(1);
+    let (old_key, new_key) = (signer.public_key(), Signer::new(2).public_key());
+    let state = UpgradeAdmin { version: lib::STATE_VERSION, public_key: old_key, contract, nonce: 0, is_initialized: true };
+    env.set_state(&admin, &program_id, &state, MAX_ADMIN_SIZE);
+
+    let (signature, recovery_id) = signer.sign(&keccak::hashv(&[UPGRADE_DOMAIN, new_key.as_slice()]).to_bytes());
+    env.process(&Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new(admin, false)],
+        data: UpgradeInstruction::TransferOwnership(TransferOwnershipArgs { new_public_key: new_key, signature, recovery_id }).try_to_vec().unwrap(),
+    }).unwrap();
+    assert_eq!(UpgradeAdmin::deserialize(&mut env.data(&admin).as_slice()).unwrap().public_key, new_key);
+
+    let events: Vec<KeyRotated> = env.data_logs().iter()
+        .filter(|log| log[0] == KEY_ROTATED_LOG_PREFIX.as_bytes())
+        .map(|log| KeyRotated::try_from_slice(&log[1]).unwrap())
+        .collect();
+    assert_eq!(events, vec![KeyRotated { admin, old_key, new_key }]);
+}
@@ -0,0 +1,167 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Account, Env, Signer};
+use lib::error::LibError;
+use lib::instructions::upgrade::{ReleaseAuthorityArgs, UpgradeInstruction};
+use lib::merkle::amount_bytes;
+use solana_program::account_info::AccountInfo;
+use solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::loader_upgradeable_instruction::UpgradeableLoaderInstruction;
+use solana_program::program_error::ProgramError;
+use solana_program::program_utils::limited_deserialize;
+use solana_program::pubkey::Pubkey;
+use upgrade::state::{UpgradeAdmin, MAX_ADMIN_SIZE};
+
+const NONCE: u64 = 3;
+
+// ProgramData in the loader (bincode) encoding: the variant index, the slot, then the optional authority
+fn program_data(authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    match authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend_from_slice(authority.as_ref());
+        }
+        None => data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0),
+    }
+    data
+}
+
+fn program_data_authority(data: &[u8]) -> Option<Pubkey> {
+    match data[12] {
+        1 => Some(Pubkey::try_from(&data[13..45]).unwrap()),
+        _ => None,
+    }
+}
+
+// SetAuthority of the upgradeable loader: the current authority should sign
+fn loader_processor<'a>(_: &'a Pubkey, accounts: &'a [AccountInfo<'a>], input: &[u8]) -> ProgramResult {
+    match limited_deserialize(input, input.len() as u64).map_err(|_| ProgramError::InvalidInstructionData)? {
+        UpgradeableLoaderInstruction::SetAuthority => {
+            let (program_data_info, authority_info, new_authority_info) = (&accounts[0], &accounts[1], &accounts[2]);
+            if program_data_authority(&program_data_info.data.borrow()) != Some(*authority_info.key) || !authority_info.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            program_data_info.data.borrow_mut()[13..45].copy_from_slice(new_authority_info.key.as_ref());
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+struct Setup {
+    env: Env,
+    program_id: Pubkey,
+    contract: Pubkey,
+    admin: Pubkey,
+    program_data: Pubkey,
+    signer: Signer,
+}
+
+fn setup() -> Setup {
+    let mut env = Env::new();
+    let program_id = Pubkey::new_unique();
+    env.add_program(program_id, upgrade::processor::process_instruction);
+    env.add_program(bpf_loader_upgradeable::id(), loader_processor);
+
+    // Upgrade admin address is derived without the bump, so not every contract has it
+    let (contract, admin) = std::iter::repeat_with(Pubkey::new_unique)
+        .find_map(|contract| {
+            Pubkey::create_program_address(&[lib::UPGRADE_ADMIN_PDA_SEED.as_bytes(), contract.as_ref()], &program_id).ok().map(|admin| (contract, admin))
+        })
+        .unwrap();
+
+    let signer = Signer::new(1);
+    let state = UpgradeAdmin { version: lib::STATE_VERSION, public_key: signer.public_key(), contract, nonce: NONCE, is_initialized: true };
+    env.set_state(&admin, &program_id, &state, MAX_ADMIN_SIZE);
+
+    let (data_address, _) = Pubkey::find_program_address(&[contract.as_ref()], &bpf_loader_upgradeable::id());
+    env.set_account(data_address, Account { lamports: 1_000_000, data: program_data(Some(admin)), owner: bpf_loader_upgradeable::id(), executable: false });
+    Setup { env, program_id, contract, admin, program_data: data_address, signer }
+}
+
+impl Setup {
+    fn sign(&self, new_authority: &Pubkey, nonce: u64) -> ([u8; 64], u8) {
+        self.sign_tagged(lib::RELEASE_AUTHORITY_TAG, new_authority, nonce)
+    }
+
+    fn sign_tagged(&self, tag: &str, new_authority: &Pubkey, nonce: u64) -> ([u8; 64], u8) {
+        let hash = solana_program::keccak::hashv(&[lib::UPGRADE_DOMAIN, tag.as_bytes(), self.contract.as_ref(), new_authority.as_ref(), amount_bytes(nonce).as_slice()]);
+        self.signer.sign(&hash.to_bytes())
+    }
+
+    fn release(&mut self, new_authority: Pubkey, signature: [u8; 64], recovery_id: u8) -> ProgramResult {
+        self.env.process(&Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.admin, false),
+                AccountMeta::new(self.program_data, false),
+                AccountMeta::new_readonly(new_authority, false),
+                AccountMeta::new_readonly(bpf_loader_upgradeable::id(), false),
+            ],
+            data: UpgradeInstruction::ReleaseAuthority(ReleaseAuthorityArgs { new_authority, signature, recovery_id }).try_to_vec().unwrap(),
+        })
+    }
+
+    fn authority(&self) -> Option<Pubkey> {
+        program_data_authority(&self.env.data(&self.program_data))
+    }
+
+    fn nonce(&self) -> u64 {
+        UpgradeAdmin::deserialize(&mut self.env.data(&self.admin).as_slice()).unwrap().nonce
+    }
+}
+
+#[test]
+fn upgrade_authority_is_released() {
+    let mut setup = setup();
+    let new_authority = Pubkey::new_unique();
+    let (signature, recovery_id) = setup.sign(&new_authority, NONCE);
+
+    setup.release(new_authority, signature, recovery_id).unwrap();
+    assert_eq!(setup.authority(), Some(new_authority));
+    assert_eq!(setup.nonce(), NONCE + 1);
+
+    // The signature is spent with the nonce
+    assert_eq!(setup.release(new_authority, signature, recovery_id), Err(LibError::WrongSignature.into()));
+}
+
+#[test]
+fn bad_signature_is_rejected() {
+    let mut setup = setup();
+    let new_authority = Pubkey::new_unique();
+
+    let (signature, recovery_id) = setup.sign(&Pubkey::new_unique(), NONCE);
+    assert_eq!(setup.release(new_authority, signature, recovery_id), Err(LibError::WrongSignature.into()));
+    let (signature, recovery_id) = setup.sign(&new_authority, NONCE + 1);
+    assert_eq!(setup.release(new_authority, signature, recovery_id), Err(LibError::WrongSignature.into()));
+
+    assert_eq!(setup.authority(), Some(setup.admin));
+    assert_eq!(setup.nonce(), NONCE);
+}
+
+#[test]
+fn zero_authority_is_rejected() {
+    let mut setup = setup();
+    let (signature, recovery_id) = setup.sign(&Pubkey::default(), NONCE);
+    assert_eq!(setup.release(Pubkey::default(), signature, recovery_id), Err(LibError::InvalidKey.into()));
+    assert_eq!(setup.authority(), Some(setup.admin));
+}
+
+#[test]
+fn change_contract_signature_is_rejected() {
+    let mut setup = setup();
+    let new_authority = Pubkey::new_unique();
+
+    // ChangeContract to the same key at the current nonce
+    let (signature, recovery_id) = setup.sign_tagged(lib::CHANGE_CONTRACT_TAG, &new_authority, NONCE);
+    assert_eq!(setup.release(new_authority, signature, recovery_id), Err(LibError::WrongSignature.into()));
+    assert_eq!(setup.authority(), Some(setup.admin));
+    assert_eq!(setup.nonce(), NONCE);
+}
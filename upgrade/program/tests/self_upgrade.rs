@@ -0,0 +1,31 @@
+mod common;
+
+use common::Upgrade;
+use lib::error::LibError;
+
+#[test]
+fn self_upgrade_without_flag_is_rejected() {
+    let mut upgrade = Upgrade::new_self_upgrade();
+    assert_eq!(upgrade.contract, upgrade.program_id);
+
+    let (signature, recovery_id) = upgrade.sign(upgrade.content());
+    assert_eq!(upgrade.process(signature, recovery_id, false), Err(LibError::InvalidKey.into()));
+    assert_eq!(upgrade.state().nonce, 0);
+}
+
+#[test]
+fn self_upgrade_with_signed_flag_is_accepted() {
+    let mut upgrade = Upgrade::new_self_upgrade();
+    let (signature, recovery_id) = upgrade.sign(upgrade.content().with_allow_self_upgrade(true));
+    upgrade.process(signature, recovery_id, true).unwrap();
+    assert_eq!(upgrade.state().nonce, 1);
+}
+
+#[test]
+fn self_upgrade_with_unsigned_flag_is_rejected() {
+    let mut upgrade = Upgrade::new_self_upgrade();
+    // Signature of the ordinary upgrade content
+    let (signature, recovery_id) = upgrade.sign(upgrade.content());
+    assert_eq!(upgrade.process(signature, recovery_id, true), Err(LibError::WrongSignature.into()));
+    assert_eq!(upgrade.state().nonce, 0);
+}
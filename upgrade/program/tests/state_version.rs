@@ -0,0 +1,67 @@
+#[path = "../../../bridge/program/tests/common/mod.rs"]
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Env, Signer};
+use lib::error::LibError;
+use lib::instructions::upgrade::UpgradeInstruction;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+use upgrade::state::{UpgradeAdmin, BASELINE_ADMIN_SIZE, MAX_ADMIN_SIZE};
+
+// UpgradeAdmin of the deployed version, the same fields without the version byte
+#[derive(BorshSerialize)]
+struct BaselineUpgradeAdmin {
+    public_key: [u8; 64],
+    contract: Pubkey,
+    nonce: u64,
+    is_initialized: bool,
+}
+
+fn migrate_state(env: &mut Env, program_id: &Pubkey, admin: &Pubkey) -> Result<(), solana_program::program_error::ProgramError> {
+    let payer = env.new_funded();
+    env.process(&Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: UpgradeInstruction::MigrateState.try_to_vec().unwrap(),
+    })
+}
+
+fn setup() -> (Env, Pubkey, Pubkey) {
+    let mut env = Env::new();
+    let program_id = Pubkey::new_unique();
+    env.add_program(program_id, upgrade::processor::process_instruction);
+    (env, program_id, Pubkey::new_unique())
+}
+
+#[test]
+fn baseline_admin_is_migrated() {
+    let (mut env, program_id, admin) = setup();
+    let public_key = Signer::new(1).public_key();
+    let contract = Pubkey::new_unique();
+    env.set_state(&admin, &program_id, &BaselineUpgradeAdmin { public_key, contract, nonce: 5, is_initialized: true }, BASELINE_ADMIN_SIZE);
+
+    migrate_state(&mut env, &program_id, &admin).unwrap();
+    assert_eq!(env.data(&admin).len(), MAX_ADMIN_SIZE);
+    let state = UpgradeAdmin::deserialize(&mut env.data(&admin).as_slice()).unwrap();
+    assert_eq!(state, UpgradeAdmin { version: lib::STATE_VERSION, public_key, contract, nonce: 5, is_initialized: true });
+
+    // Current version is left as is
+    let data = env.data(&admin);
+    migrate_state(&mut env, &program_id, &admin).unwrap();
+    assert_eq!(env.data(&admin), data);
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let (mut env, program_id, admin) = setup();
+    let state = UpgradeAdmin { version: lib::STATE_VERSION + 1, public_key: [1; 64], contract: Pubkey::new_unique(), nonce: 0, is_initialized: true };
+    env.set_state(&admin, &program_id, &state, MAX_ADMIN_SIZE);
+
+    assert_eq!(migrate_state(&mut env, &program_id, &admin), Err(LibError::UnsupportedVersion.into()));
+}
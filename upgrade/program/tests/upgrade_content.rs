@@ -0,0 +1,61 @@
+mod common;
+
+use common::Upgrade;
+use lib::error::LibError;
+use lib::merkle::amount_bytes;
+use lib::{SOLANA_NETWORK, UPGRADE_DOMAIN};
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+use upgrade::merkle::Content;
+
+#[test]
+fn content_hash_matches_signed_message() {
+    let (program, contract, buffer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let message = [
+        UPGRADE_DOMAIN,
+        SOLANA_NETWORK.as_bytes(),
+        amount_bytes(5).as_slice(),
+        program.as_ref(),
+        contract.as_ref(),
+        buffer.as_ref(),
+    ].concat();
+
+    assert_eq!(Content::new(5, program, contract, buffer).hash(), keccak::hash(&message));
+    assert_eq!(
+        Content::new(5, program, contract, buffer).with_allow_self_upgrade(true).hash(),
+        keccak::hash(&[message.as_slice(), &[1]].concat()),
+    );
+}
+
+#[test]
+fn upgrade_verifies_content_hash() {
+    let mut upgrade = Upgrade::new();
+    let (signature, recovery_id) = upgrade.sign(upgrade.content());
+    upgrade.process(signature, recovery_id, false).unwrap();
+    assert_eq!(upgrade.state().nonce, 1);
+
+    // Replayed signature is of the previous nonce
+    assert_eq!(upgrade.process(signature, recovery_id, false), Err(LibError::WrongSignature.into()));
+}
+
+#[test]
+fn upgrade_of_other_content_is_rejected() {
+    let mut upgrade = Upgrade::new();
+    let other = Pubkey::new_unique();
+    let contents = [
+        Content::new(0, other, upgrade.contract, upgrade.buffer),
+        Content::new(0, upgrade.program_id, other, upgrade.buffer),
+        Content::new(0, upgrade.program_id, upgrade.contract, other),
+        Content::new(1, upgrade.program_id, upgrade.contract, upgrade.buffer),
+    ];
+
+    for content in contents {
+        let (signature, recovery_id) = upgrade.sign(content);
+        assert_eq!(upgrade.process(signature, recovery_id, false), Err(LibError::WrongSignature.into()));
+    }
+
+    // Self upgrade flag is signed
+    let (signature, recovery_id) = upgrade.sign(upgrade.content());
+    assert_eq!(upgrade.process(signature, recovery_id, true), Err(LibError::WrongSignature.into()));
+    assert_eq!(upgrade.state().nonce, 0);
+}